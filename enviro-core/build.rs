@@ -1,11 +1,13 @@
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 /// This build script orchestrates the compilation of:
 /// 1. Zig components (C-ABI bridge for syscall wrapping and memory allocation)
 /// 2. Go components (gRPC control plane and eBPF networking)
-/// into a unified binary that Rust can link against.
+///    into a unified binary that Rust can link against.
+/// 3. The sample executor plugin used by the plugin integration tests
+///    (only with the `sample-plugin` feature).
 fn main() {
     // Tell Cargo about our custom cfg flags
     println!("cargo:rustc-check-cfg=cfg(zig_available)");
@@ -35,6 +37,11 @@ fn main() {
         println!("cargo:rustc-link-lib=dylib=enviro_go");
         println!("cargo:rustc-cfg=go_available");
     }
+
+    if env::var_os("CARGO_FEATURE_SAMPLE_PLUGIN").is_some() {
+        println!("cargo:rerun-if-changed=tests/sample-plugin/");
+        build_sample_plugin(&out_dir);
+    }
 }
 
 /// Compiles Zig code into a static library with C-ABI compatibility.
@@ -42,7 +49,7 @@ fn main() {
 /// provide optimal syscall wrapping without Rust's safety overhead.
 ///
 /// Returns: true if build succeeded, false otherwise
-fn build_zig_components(out_dir: &PathBuf) -> bool {
+fn build_zig_components(out_dir: &Path) -> bool {
     let zig_dir = PathBuf::from("../enviro-zig");
     
     if !zig_dir.exists() {
@@ -51,7 +58,7 @@ fn build_zig_components(out_dir: &PathBuf) -> bool {
     }
 
     let status = Command::new("zig")
-        .args(&[
+        .args([
             "build-lib",
             "-static",
             "-target", "native-native",
@@ -84,7 +91,7 @@ fn build_zig_components(out_dir: &PathBuf) -> bool {
 /// for the control plane, while CGO allows us to expose Go functions to Rust.
 ///
 /// Returns: true if build succeeded, false otherwise
-fn build_go_components(out_dir: &PathBuf) -> bool {
+fn build_go_components(out_dir: &Path) -> bool {
     let go_dir = PathBuf::from("../enviro-go");
     
     if !go_dir.exists() {
@@ -94,7 +101,7 @@ fn build_go_components(out_dir: &PathBuf) -> bool {
 
     // Set CGO flags for building shared library
     let status = Command::new("go")
-        .args(&[
+        .args([
             "build",
             "-buildmode=c-shared",
             "-o", &format!("{}/libenviro_go.so", out_dir.display()),
@@ -121,3 +128,46 @@ fn build_go_components(out_dir: &PathBuf) -> bool {
         }
     }
 }
+
+/// Compiles the sample executor plugin into cdylibs for the plugin tests.
+///
+/// The plugin links against its own build of enviro-core, so it is built by a
+/// nested cargo invocation with a separate target directory (the outer build
+/// holds the lock on ours). Two variants are produced and exported to the
+/// tests: `ENVIRO_SAMPLE_PLUGIN` and `ENVIRO_SAMPLE_PLUGIN_BAD_ABI`, the latter
+/// reporting a mismatched `plugin_abi_version`.
+fn build_sample_plugin(out_dir: &Path) {
+    let variants = [
+        ("ENVIRO_SAMPLE_PLUGIN", "enviro_sample_plugin", None),
+        ("ENVIRO_SAMPLE_PLUGIN_BAD_ABI", "enviro_sample_plugin_bad_abi", Some("bad-abi")),
+    ];
+
+    let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let target_dir = out_dir.join("sample-plugin");
+    let built = target_dir.join("debug").join(dylib_name("enviro_sample_plugin"));
+
+    for (var, name, feature) in variants {
+        let mut cmd = Command::new(&cargo);
+        cmd.args(["build", "--quiet", "--manifest-path", "tests/sample-plugin/Cargo.toml"])
+            .arg("--target-dir")
+            .arg(&target_dir)
+            // Don't leak the outer build's feature set into the nested one
+            .env_remove("CARGO_FEATURE_SAMPLE_PLUGIN");
+        if let Some(feature) = feature {
+            cmd.args(["--features", feature]);
+        }
+
+        let status = cmd.status().expect("Failed to execute cargo for the sample plugin");
+        assert!(status.success(), "Sample plugin build failed with status: {}", status);
+
+        // Both variants share an output file name, so copy each one out
+        let plugin = out_dir.join(dylib_name(name));
+        std::fs::copy(&built, &plugin).expect("Failed to copy the sample plugin");
+        println!("cargo:rustc-env={}={}", var, plugin.display());
+    }
+}
+
+/// Platform file name of a shared library, e.g. `libfoo.so`.
+fn dylib_name(name: &str) -> String {
+    format!("{}{}{}", env::consts::DLL_PREFIX, name, env::consts::DLL_SUFFIX)
+}
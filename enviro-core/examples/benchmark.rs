@@ -7,7 +7,7 @@
 //! - Buffer pool reuse rates
 
 use enviro_core::FastRuntime;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -163,6 +163,7 @@ async fn benchmark_memory_pool(runtime: &FastRuntime) -> anyhow::Result<()> {
 }
 
 async fn benchmark_parallel_setup() -> anyhow::Result<()> {
+    use enviro_core::engine::parallel_setup::NamespaceSetupConfig;
     use enviro_core::runtime::FastStartConfig;
     
     const ITERATIONS: usize = 20;
@@ -171,8 +172,15 @@ async fn benchmark_parallel_setup() -> anyhow::Result<()> {
     println!("Testing sequential namespace setup...");
     let sequential_config = FastStartConfig {
         parallel_namespaces: false,
+        namespaces: NamespaceSetupConfig::default(),
         use_namespace_cache: false,
         prewarm_executors: false,
+        prewarm_count: 0,
+        restart_backoff: Duration::from_millis(100),
+        host_capacity: None,
+        stop_grace_period: Duration::from_secs(10),
+        log_dir: None,
+        cgroup_root: None,
         max_cached_namespaces: 0,
     };
     let sequential_runtime = FastRuntime::with_config(sequential_config);
@@ -189,8 +197,15 @@ async fn benchmark_parallel_setup() -> anyhow::Result<()> {
     println!("Testing parallel namespace setup...");
     let parallel_config = FastStartConfig {
         parallel_namespaces: true,
+        namespaces: NamespaceSetupConfig::default(),
         use_namespace_cache: true,
         prewarm_executors: false,
+        prewarm_count: 0,
+        restart_backoff: Duration::from_millis(100),
+        host_capacity: None,
+        stop_grace_period: Duration::from_secs(10),
+        log_dir: None,
+        cgroup_root: None,
         max_cached_namespaces: 10,
     };
     let parallel_runtime = FastRuntime::with_config(parallel_config);
@@ -0,0 +1,773 @@
+//! CLI Parsing and Output Formatting
+//!
+//! Argument parsing and rendering helpers for the `enviro` binary, kept in the
+//! library so they can be tested (including against a real
+//! [`FastRuntime`](crate::FastRuntime)) without spawning the process.
+
+use crate::perf::{ComparisonBaseline, PerfSnapshot};
+use crate::runtime::{ContainerInfo, ContainerSpec, FastRuntime};
+use crate::server::Client;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// A parsed `enviro` command line
+#[derive(Debug, Clone)]
+pub enum Cli {
+    /// No subcommand: start the engine
+    Engine,
+    /// `enviro run [-e KEY=VALUE]... [-w DIR] <image> -- <command> [args...]`
+    Run(Box<ContainerSpec>),
+    /// `enviro run -f <Envirofile>`: the container is described by the file
+    RunFile { path: PathBuf },
+    /// `enviro ps [-a] [--format table|json] [SERVER]` (alias `list`),
+    /// listing the containers of a running `enviro serve`
+    Ps {
+        all: bool,
+        format: OutputFormat,
+        endpoint: ServerEndpoint,
+    },
+    /// `enviro stop <id> [SERVER]`, stopping a container of a running
+    /// `enviro serve`
+    Stop { id: String, endpoint: ServerEndpoint },
+    /// `enviro serve [SERVER]`
+    ///
+    /// `SERVER` is `[--addr <host:port>] [--token-file <path>]` or
+    /// `--socket <path>`.
+    Serve { endpoint: ServerEndpoint },
+    /// `enviro stats [--format table|json]`
+    Stats { format: OutputFormat },
+    /// `-v` / `--version`
+    Version,
+    /// `-h` / `--help`
+    Help,
+}
+
+/// Errors produced by [`parse_args`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CliError {
+    /// A flag or subcommand that is not recognized
+    #[error("unrecognized argument '{0}'")]
+    UnknownArgument(String),
+
+    /// A flag that takes a value was given none
+    #[error("'{0}' requires a value")]
+    MissingValue(&'static str),
+
+    /// A required positional argument is missing
+    #[error("'{command}' requires {what}")]
+    MissingArgument {
+        command: &'static str,
+        what: &'static str,
+    },
+
+    /// A positional argument where none is expected
+    #[error("unexpected argument '{0}'")]
+    UnexpectedArgument(String),
+
+    /// Two flags that cannot be given together
+    #[error("'{0}' cannot be used with '{1}'")]
+    ConflictingArguments(&'static str, &'static str),
+
+    /// A flag value that could not be parsed
+    #[error("invalid value '{value}' for '{flag}': {reason}")]
+    InvalidValue {
+        flag: &'static str,
+        value: String,
+        reason: String,
+    },
+}
+
+/// Parse the `enviro` command line, excluding the program name
+pub fn parse_args(args: &[String]) -> Result<Cli, CliError> {
+    let Some((first, rest)) = args.split_first() else {
+        return Ok(Cli::Engine);
+    };
+
+    match first.as_str() {
+        "-h" | "--help" => Ok(Cli::Help),
+        "-v" | "--version" => Ok(Cli::Version),
+        "run" => parse_run(rest),
+        "ps" | "list" => parse_ps(rest),
+        "stop" => parse_stop(rest),
+        "serve" => parse_serve(rest),
+        "stats" => parse_stats(rest),
+        other => Err(CliError::UnknownArgument(other.to_string())),
+    }
+}
+
+fn parse_run(args: &[String]) -> Result<Cli, CliError> {
+    let mut file = None;
+    let mut image = None;
+    let mut env = HashMap::new();
+    let mut workdir = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-e" | "--env" => {
+                let pair = iter.next().ok_or(CliError::MissingValue("--env"))?;
+                let (key, value) = pair.split_once('=').ok_or_else(|| CliError::InvalidValue {
+                    flag: "--env",
+                    value: pair.clone(),
+                    reason: "expected KEY=VALUE".to_string(),
+                })?;
+                env.insert(key.to_string(), value.to_string());
+            }
+            "-w" | "--workdir" => {
+                workdir = Some(iter.next().ok_or(CliError::MissingValue("--workdir"))?.clone());
+            }
+            "-f" | "--file" => {
+                file = Some(PathBuf::from(iter.next().ok_or(CliError::MissingValue("--file"))?));
+            }
+            "--" => break,
+            flag if flag.starts_with('-') => return Err(CliError::UnknownArgument(flag.to_string())),
+            _ if image.is_none() => image = Some(arg.clone()),
+            other => return Err(CliError::UnexpectedArgument(other.to_string())),
+        }
+    }
+
+    // The Envirofile describes the whole container
+    if let Some(path) = file {
+        if let Some(image) = image {
+            return Err(CliError::UnexpectedArgument(image));
+        }
+        if let Some(extra) = iter.next() {
+            return Err(CliError::UnexpectedArgument(extra.clone()));
+        }
+        if !env.is_empty() || workdir.is_some() {
+            return Err(CliError::InvalidValue {
+                flag: "--file",
+                value: path.display().to_string(),
+                reason: "cannot be combined with --env or --workdir".to_string(),
+            });
+        }
+        return Ok(Cli::RunFile { path });
+    }
+
+    let image = image.ok_or(CliError::MissingArgument {
+        command: "run",
+        what: "an image",
+    })?;
+    let command = iter.next().ok_or(CliError::MissingArgument {
+        command: "run",
+        what: "a command after '--'",
+    })?;
+
+    let mut spec = ContainerSpec::new(image, command.clone(), iter.cloned().collect());
+    spec.env = env;
+    if let Some(workdir) = workdir {
+        spec.workdir = workdir;
+    }
+    Ok(Cli::Run(Box::new(spec)))
+}
+
+fn parse_ps(args: &[String]) -> Result<Cli, CliError> {
+    let mut all = false;
+    let mut format = OutputFormat::default();
+    let mut endpoint = ServerEndpoint::default();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-a" | "--all" => all = true,
+            "--format" => format = parse_format(iter.next())?,
+            flag if endpoint.parse_flag(flag, &mut iter)? => {}
+            flag if flag.starts_with('-') => return Err(CliError::UnknownArgument(flag.to_string())),
+            other => return Err(CliError::UnexpectedArgument(other.to_string())),
+        }
+    }
+    Ok(Cli::Ps {
+        all,
+        format,
+        endpoint: endpoint.validate()?,
+    })
+}
+
+fn parse_stats(args: &[String]) -> Result<Cli, CliError> {
+    let mut format = OutputFormat::default();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--format" => format = parse_format(iter.next())?,
+            flag if flag.starts_with('-') => return Err(CliError::UnknownArgument(flag.to_string())),
+            other => return Err(CliError::UnexpectedArgument(other.to_string())),
+        }
+    }
+    Ok(Cli::Stats { format })
+}
+
+/// Parse the value following `--format`
+fn parse_format(value: Option<&String>) -> Result<OutputFormat, CliError> {
+    let value = value.ok_or(CliError::MissingValue("--format"))?;
+    value.parse().map_err(|e: anyhow::Error| CliError::InvalidValue {
+        flag: "--format",
+        value: value.clone(),
+        reason: e.to_string(),
+    })
+}
+
+fn parse_stop(args: &[String]) -> Result<Cli, CliError> {
+    let mut id = None;
+    let mut endpoint = ServerEndpoint::default();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            flag if endpoint.parse_flag(flag, &mut iter)? => {}
+            flag if flag.starts_with('-') => return Err(CliError::UnknownArgument(flag.to_string())),
+            other if id.is_none() => id = Some(other.to_string()),
+            other => return Err(CliError::UnexpectedArgument(other.to_string())),
+        }
+    }
+    let id = id.ok_or(CliError::MissingArgument {
+        command: "stop",
+        what: "a container ID",
+    })?;
+    Ok(Cli::Stop {
+        id,
+        endpoint: endpoint.validate()?,
+    })
+}
+
+fn parse_serve(args: &[String]) -> Result<Cli, CliError> {
+    let mut endpoint = ServerEndpoint::default();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            flag if endpoint.parse_flag(flag, &mut iter)? => {}
+            flag if flag.starts_with('-') => return Err(CliError::UnknownArgument(flag.to_string())),
+            other => return Err(CliError::UnexpectedArgument(other.to_string())),
+        }
+    }
+    Ok(Cli::Serve {
+        endpoint: endpoint.validate()?,
+    })
+}
+
+/// Where `enviro serve` listens, and where `enviro ps` and `enviro stop`
+/// reach it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServerEndpoint {
+    /// `--addr`; [`DEFAULT_ADDR`](crate::server::DEFAULT_ADDR) when unset
+    pub addr: Option<SocketAddr>,
+    /// `--socket`: a Unix socket used instead of a TCP address
+    pub socket: Option<PathBuf>,
+    /// `--token-file`: file holding the token clients `auth` with
+    pub token_file: Option<PathBuf>,
+}
+
+impl ServerEndpoint {
+    /// TCP address of the server
+    pub fn addr(&self) -> SocketAddr {
+        self.addr.unwrap_or_else(|| {
+            crate::server::DEFAULT_ADDR
+                .parse()
+                .expect("valid default address")
+        })
+    }
+
+    /// Read the token from `token_file`, if one is set
+    pub fn token(&self) -> Result<Option<String>> {
+        let Some(path) = &self.token_file else {
+            return Ok(None);
+        };
+        let token = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Ok(Some(token.trim().to_string()))
+    }
+
+    /// Connect to the server and authenticate if a token file is set
+    pub async fn connect(&self) -> Result<Client> {
+        let client = match &self.socket {
+            Some(path) => Client::connect_unix(path).await,
+            None => Client::connect(self.addr()).await,
+        };
+        let mut client = client.with_context(|| {
+            format!("No enviro server at {}; start one with 'enviro serve'", self)
+        })?;
+        if let Some(token) = self.token()? {
+            client.auth(token).await?;
+        }
+        Ok(client)
+    }
+
+    /// Consume `flag` and its value if it is `--addr`, `--socket` or
+    /// `--token-file`
+    fn parse_flag<'a>(
+        &mut self,
+        flag: &str,
+        values: &mut impl Iterator<Item = &'a String>,
+    ) -> Result<bool, CliError> {
+        match flag {
+            "--addr" => {
+                let value = values.next().ok_or(CliError::MissingValue("--addr"))?;
+                let addr = value.parse().map_err(|e: std::net::AddrParseError| {
+                    CliError::InvalidValue {
+                        flag: "--addr",
+                        value: value.clone(),
+                        reason: e.to_string(),
+                    }
+                })?;
+                self.addr = Some(addr);
+            }
+            "--socket" => {
+                let value = values.next().ok_or(CliError::MissingValue("--socket"))?;
+                self.socket = Some(PathBuf::from(value));
+            }
+            "--token-file" => {
+                let value = values.next().ok_or(CliError::MissingValue("--token-file"))?;
+                self.token_file = Some(PathBuf::from(value));
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Reject flags that cannot be combined
+    fn validate(self) -> Result<Self, CliError> {
+        if self.socket.is_some() {
+            // The socket's permissions are its access control
+            if self.addr.is_some() {
+                return Err(CliError::ConflictingArguments("--socket", "--addr"));
+            }
+            if self.token_file.is_some() {
+                return Err(CliError::ConflictingArguments("--socket", "--token-file"));
+            }
+        }
+        Ok(self)
+    }
+}
+
+impl fmt::Display for ServerEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.socket {
+            Some(path) => write!(f, "{}", path.display()),
+            None => write!(f, "{}", self.addr()),
+        }
+    }
+}
+
+/// Output format for reporting commands such as `enviro ps` and `enviro stats`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Human-readable table with aligned columns
+    #[default]
+    Table,
+    /// Pretty-printed JSON
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "table" => Ok(Self::Table),
+            "json" => Ok(Self::Json),
+            other => anyhow::bail!("unknown format '{}', expected 'table' or 'json'", other),
+        }
+    }
+}
+
+/// Render the output of `enviro ps`
+///
+/// Only running containers are shown unless `all` is set.
+pub fn format_containers(
+    containers: &[ContainerInfo],
+    all: bool,
+    format: OutputFormat,
+) -> Result<String> {
+    let shown: Vec<&ContainerInfo> = containers
+        .iter()
+        .filter(|c| all || c.state.is_running())
+        .collect();
+
+    match format {
+        OutputFormat::Json => Ok(format!("{}\n", serde_json::to_string_pretty(&shown)?)),
+        OutputFormat::Table => Ok(format_table(&shown)),
+    }
+}
+
+fn format_table(containers: &[&ContainerInfo]) -> String {
+    let header = ["CONTAINER ID", "STATE", "UPTIME", "NAMESPACE"];
+    let rows: Vec<[String; 4]> = containers
+        .iter()
+        .map(|c| {
+            [
+                c.id.clone(),
+                c.state.to_string(),
+                format_uptime(c.uptime_secs),
+                c.namespace_id.to_string(),
+            ]
+        })
+        .collect();
+
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let mut out = String::new();
+    let mut push_row = |cells: [&str; 4]| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        out.push_str(line.join("   ").trim_end());
+        out.push('\n');
+    };
+
+    push_row(header);
+    for row in &rows {
+        push_row([&row[0], &row[1], &row[2], &row[3]]);
+    }
+    out
+}
+
+/// Containers started and stopped by [`self_benchmark`] for `enviro stats`
+pub const STATS_BENCHMARK_STARTS: usize = 10;
+
+/// Start and stop `starts` containers without a command on `runtime` and
+/// return its metrics afterwards
+///
+/// Without a daemon to query, this is what `enviro stats` reports on.
+pub async fn self_benchmark(runtime: &FastRuntime, starts: usize) -> Result<PerfSnapshot> {
+    for i in 0..starts {
+        let id = format!("enviro-stats-{}-{}", std::process::id(), i);
+        runtime.start_container(&id, "alpine", "true", vec![]).await?;
+        runtime.stop_container(&id).await?;
+    }
+    Ok(runtime.metrics().snapshot())
+}
+
+/// Output of `enviro stats --format json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatsReport {
+    /// The runtime's metrics
+    pub snapshot: PerfSnapshot,
+    /// Container start time of the Docker baseline in milliseconds
+    pub docker_container_start_ms: f64,
+    /// Container start speedup over the Docker baseline
+    pub speedup: f64,
+}
+
+impl StatsReport {
+    /// Compare `snapshot` against the default [`ComparisonBaseline`]
+    pub fn new(snapshot: PerfSnapshot) -> Self {
+        let baseline = ComparisonBaseline::default();
+        Self {
+            speedup: snapshot.speedup(&baseline),
+            docker_container_start_ms: baseline.container_start_ms,
+            snapshot,
+        }
+    }
+}
+
+/// Format a duration in seconds compactly, e.g. `42s`, `3m 5s`, `2h 10m`
+fn format_uptime(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m {}s", secs / 60, secs % 60),
+        _ => format!("{}h {}m", secs / 3600, (secs % 3600) / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::{ContainerState, FastRuntime};
+    use std::path::Path;
+
+    #[tokio::test]
+    async fn test_ps_table_lists_running_containers() {
+        let runtime = FastRuntime::new();
+        let mut handles = Vec::new();
+        for id in ["web-1", "worker-22", "db"] {
+            handles.push(runtime.start_container(id, "alpine", "/bin/sh", vec![]).await.unwrap());
+        }
+        handles[2].stop().await.unwrap();
+
+        let containers = runtime.list_containers().await;
+        let table = format_containers(&containers, false, OutputFormat::Table).unwrap();
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert!(lines[0].starts_with("CONTAINER ID"));
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with("web-1 "));
+        assert!(lines[2].starts_with("worker-22 "));
+        assert!(!table.contains("db"));
+
+        // Columns line up with the header
+        let state_col = lines[0].find("STATE").unwrap();
+        assert_eq!(lines[1].find("running"), Some(state_col));
+        assert_eq!(lines[2].find("running"), Some(state_col));
+    }
+
+    #[tokio::test]
+    async fn test_ps_all_includes_stopped() {
+        let runtime = FastRuntime::new();
+        let handle = runtime.start_container("db", "alpine", "/bin/sh", vec![]).await.unwrap();
+        handle.stop().await.unwrap();
+
+        let containers = runtime.list_containers().await;
+        let table = format_containers(&containers, true, OutputFormat::Table).unwrap();
+        assert!(table.lines().any(|line| line.starts_with("db ") && line.contains("stopped")));
+    }
+
+    #[tokio::test]
+    async fn test_ps_json() {
+        let runtime = FastRuntime::new();
+        let handle = runtime.start_container("api", "alpine", "/bin/sh", vec![]).await.unwrap();
+
+        let containers = runtime.list_containers().await;
+        let json = format_containers(&containers, false, OutputFormat::Json).unwrap();
+        let parsed: Vec<ContainerInfo> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].id, "api");
+        assert_eq!(parsed[0].state, ContainerState::Running);
+        assert_eq!(parsed[0].namespace_id, handle.namespace_id());
+    }
+
+    #[test]
+    fn test_empty_table_has_header() {
+        let table = format_containers(&[], false, OutputFormat::Table).unwrap();
+        assert_eq!(table, "CONTAINER ID   STATE   UPTIME   NAMESPACE\n");
+    }
+
+    #[test]
+    fn test_output_format_parse() {
+        assert_eq!("table".parse::<OutputFormat>().unwrap(), OutputFormat::Table);
+        assert_eq!("json".parse::<OutputFormat>().unwrap(), OutputFormat::Json);
+        assert!("yaml".parse::<OutputFormat>().is_err());
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_no_args() {
+        assert!(matches!(parse_args(&[]), Ok(Cli::Engine)));
+    }
+
+    #[test]
+    fn test_parse_help_and_version() {
+        assert!(matches!(parse_args(&args(&["-h"])), Ok(Cli::Help)));
+        assert!(matches!(parse_args(&args(&["--help"])), Ok(Cli::Help)));
+        assert!(matches!(parse_args(&args(&["-v"])), Ok(Cli::Version)));
+        assert!(matches!(parse_args(&args(&["--version"])), Ok(Cli::Version)));
+    }
+
+    #[test]
+    fn test_parse_run() {
+        let cli = parse_args(&args(&[
+            "run", "-e", "A=1", "--env", "B=x=y", "-w", "/srv", "alpine", "--", "echo", "hi", "-n",
+        ]))
+        .unwrap();
+
+        let Cli::Run(spec) = cli else {
+            panic!("expected run, got {:?}", cli);
+        };
+        assert_eq!(spec.image, "alpine");
+        assert_eq!(spec.command, "echo");
+        assert_eq!(spec.args, vec!["hi", "-n"]);
+        assert_eq!(spec.env["A"], "1");
+        assert_eq!(spec.env["B"], "x=y");
+        assert_eq!(spec.workdir, "/srv");
+    }
+
+    #[test]
+    fn test_parse_run_file() {
+        assert!(matches!(
+            parse_args(&args(&["run", "-f", "Envirofile"])),
+            Ok(Cli::RunFile { path }) if path.as_os_str() == "Envirofile"
+        ));
+        assert!(matches!(
+            parse_args(&args(&["run", "--file", "web.toml"])),
+            Ok(Cli::RunFile { path }) if path.as_os_str() == "web.toml"
+        ));
+        assert_eq!(
+            parse_args(&args(&["run", "-f"])).unwrap_err(),
+            CliError::MissingValue("--file")
+        );
+        assert_eq!(
+            parse_args(&args(&["run", "-f", "Envirofile", "alpine"])).unwrap_err(),
+            CliError::UnexpectedArgument("alpine".to_string())
+        );
+        assert_eq!(
+            parse_args(&args(&["run", "-f", "Envirofile", "--", "true"])).unwrap_err(),
+            CliError::UnexpectedArgument("true".to_string())
+        );
+        assert!(matches!(
+            parse_args(&args(&["run", "-e", "A=1", "-f", "Envirofile"])),
+            Err(CliError::InvalidValue { flag: "--file", .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_run_missing_args() {
+        assert_eq!(
+            parse_args(&args(&["run"])).unwrap_err(),
+            CliError::MissingArgument { command: "run", what: "an image" }
+        );
+        assert_eq!(
+            parse_args(&args(&["run", "alpine", "--"])).unwrap_err(),
+            CliError::MissingArgument { command: "run", what: "a command after '--'" }
+        );
+        assert_eq!(
+            parse_args(&args(&["run", "alpine", "-e"])).unwrap_err(),
+            CliError::MissingValue("--env")
+        );
+        assert!(matches!(
+            parse_args(&args(&["run", "-e", "NOVALUE", "alpine", "--", "true"])),
+            Err(CliError::InvalidValue { flag: "--env", .. })
+        ));
+        assert_eq!(
+            parse_args(&args(&["run", "alpine", "echo"])).unwrap_err(),
+            CliError::UnexpectedArgument("echo".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_ps() {
+        assert!(matches!(
+            parse_args(&args(&["ps"])),
+            Ok(Cli::Ps { all: false, format: OutputFormat::Table, endpoint })
+                if endpoint == ServerEndpoint::default()
+        ));
+        assert!(matches!(
+            parse_args(&args(&["list", "-a", "--format", "json"])),
+            Ok(Cli::Ps { all: true, format: OutputFormat::Json, .. })
+        ));
+        assert!(matches!(
+            parse_args(&args(&["ps", "--socket", "/run/enviro.sock"])),
+            Ok(Cli::Ps { endpoint: ServerEndpoint { socket: Some(path), .. }, .. })
+                if path == Path::new("/run/enviro.sock")
+        ));
+        assert_eq!(
+            parse_args(&args(&["ps", "--format"])).unwrap_err(),
+            CliError::MissingValue("--format")
+        );
+        let err = parse_args(&args(&["ps", "--format", "yaml"])).unwrap_err();
+        assert!(err.to_string().contains("unknown format 'yaml'"));
+    }
+
+    #[test]
+    fn test_parse_stats() {
+        assert!(matches!(
+            parse_args(&args(&["stats"])),
+            Ok(Cli::Stats { format: OutputFormat::Table })
+        ));
+        assert!(matches!(
+            parse_args(&args(&["stats", "--format", "json"])),
+            Ok(Cli::Stats { format: OutputFormat::Json })
+        ));
+        assert_eq!(
+            parse_args(&args(&["stats", "extra"])).unwrap_err(),
+            CliError::UnexpectedArgument("extra".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_self_benchmark() {
+        let runtime = FastRuntime::new();
+        let snapshot = self_benchmark(&runtime, 3).await.unwrap();
+        assert_eq!(snapshot.container_starts, 3);
+        assert!(runtime.list_containers().await.iter().all(|c| !c.state.is_running()));
+
+        let report = StatsReport::new(snapshot);
+        assert_eq!(report.docker_container_start_ms, 500.0);
+        assert!(report.speedup > 0.0);
+    }
+
+    #[test]
+    fn test_parse_stop() {
+        assert!(matches!(
+            parse_args(&args(&["stop", "web-1"])),
+            Ok(Cli::Stop { id, endpoint }) if id == "web-1" && endpoint == ServerEndpoint::default()
+        ));
+        assert!(matches!(
+            parse_args(&args(&["stop", "--addr", "127.0.0.1:7001", "web-1"])),
+            Ok(Cli::Stop { id, endpoint }) if id == "web-1" && endpoint.addr().port() == 7001
+        ));
+        assert_eq!(
+            parse_args(&args(&["stop"])).unwrap_err(),
+            CliError::MissingArgument { command: "stop", what: "a container ID" }
+        );
+        assert_eq!(
+            parse_args(&args(&["stop", "a", "b"])).unwrap_err(),
+            CliError::UnexpectedArgument("b".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_serve() {
+        assert!(matches!(
+            parse_args(&args(&["serve"])),
+            Ok(Cli::Serve { endpoint }) if endpoint.to_string() == crate::server::DEFAULT_ADDR
+        ));
+        assert!(matches!(
+            parse_args(&args(&["serve", "--addr", "0.0.0.0:7001", "--token-file", "/run/token"])),
+            Ok(Cli::Serve {
+                endpoint: ServerEndpoint { addr: Some(addr), socket: None, token_file: Some(path) },
+            }) if addr.port() == 7001
+                    && addr.ip().is_unspecified()
+                    && path == Path::new("/run/token")
+        ));
+        assert!(matches!(
+            parse_args(&args(&["serve", "--socket", "/run/enviro.sock"])),
+            Ok(Cli::Serve { endpoint: ServerEndpoint { socket: Some(path), token_file: None, .. } })
+                if path == Path::new("/run/enviro.sock")
+        ));
+        assert_eq!(
+            parse_args(&args(&["serve", "--socket", "a.sock", "--addr", "127.0.0.1:1"]))
+                .unwrap_err(),
+            CliError::ConflictingArguments("--socket", "--addr")
+        );
+        assert_eq!(
+            parse_args(&args(&["serve", "--socket", "a.sock", "--token-file", "t"])).unwrap_err(),
+            CliError::ConflictingArguments("--socket", "--token-file")
+        );
+        assert!(matches!(
+            parse_args(&args(&["serve", "--addr", "localhost"])),
+            Err(CliError::InvalidValue { flag: "--addr", .. })
+        ));
+        assert_eq!(
+            parse_args(&args(&["serve", "--addr"])).unwrap_err(),
+            CliError::MissingValue("--addr")
+        );
+    }
+
+    #[test]
+    fn test_parse_unknown_flags() {
+        for argv in [&["--bogus"][..], &["run", "--bogus", "alpine"], &["ps", "-x"], &["stop", "-f"]] {
+            assert!(
+                matches!(parse_args(&args(argv)), Err(CliError::UnknownArgument(_))),
+                "expected unknown argument for {:?}",
+                argv
+            );
+        }
+        assert_eq!(
+            parse_args(&args(&["frobnicate"])).unwrap_err().to_string(),
+            "unrecognized argument 'frobnicate'"
+        );
+    }
+
+    #[test]
+    fn test_format_uptime() {
+        assert_eq!(format_uptime(42), "42s");
+        assert_eq!(format_uptime(185), "3m 5s");
+        assert_eq!(format_uptime(7800), "2h 10m");
+    }
+}
@@ -8,13 +8,39 @@
 //! - Pre-allocated buffers avoid per-operation `malloc`/`free` overhead
 //! - `BufferPool` recycles released buffers so hot paths never touch the allocator
 //! - Statistics tracking enables runtime tuning of pool sizes
+//! - [`BufferPool::autotune`] grows or shrinks the free-list from observed churn
+//! - [`PooledReader`] streams any `Read` source in pooled, fixed-size chunks
+//! - With the `mmap` feature, large buffers are anonymous mappings straight
+//!   from the kernel, so they neither fragment the heap nor linger in it
 
 use std::collections::VecDeque;
+use std::io::{self, Read};
+use std::sync::Arc;
 use tracing::{debug, info};
+#[cfg(feature = "mmap")]
+use tracing::warn;
+
+use crate::perf::PerfMetrics;
 
 /// Default capacity in bytes for a newly allocated buffer.
 pub const DEFAULT_BUFFER_CAPACITY: usize = 4096;
 
+/// Buffer capacity from which a [`BufferPool`] hands out mmap-backed
+/// buffers, unless changed with [`BufferPool::with_mmap_threshold`].
+#[cfg(feature = "mmap")]
+pub const DEFAULT_MMAP_THRESHOLD: usize = 1024 * 1024;
+
+/// Memory behind a [`ZeroCopyBuffer`].
+#[derive(Debug)]
+enum Storage {
+    /// Heap allocation; its length is the buffer's valid data.
+    Heap(Vec<u8>),
+    /// Anonymous mapping of the buffer's full capacity; only the first
+    /// `len` bytes are valid.
+    #[cfg(feature = "mmap")]
+    Mmap(memmap2::MmapMut),
+}
+
 /// A managed memory buffer for container I/O operations.
 ///
 /// `ZeroCopyBuffer` wraps a contiguous byte region that can be written to
@@ -30,10 +56,11 @@ pub const DEFAULT_BUFFER_CAPACITY: usize = 4096;
 #[derive(Debug)]
 pub struct ZeroCopyBuffer {
     /// Underlying storage.
-    data: Vec<u8>,
-    /// Logical length of valid data (may be less than `data.capacity()`).
+    data: Storage,
+    /// Logical length of valid data (may be less than the storage size).
     len: usize,
-    /// The capacity this buffer was created with (used for stats/matching).
+    /// Bytes the storage holds: the capacity this buffer was created with,
+    /// or the size of the largest write that outgrew it.
     capacity: usize,
 }
 
@@ -45,25 +72,75 @@ impl ZeroCopyBuffer {
     pub fn new(capacity: usize) -> Self {
         debug!(capacity, "Allocating ZeroCopyBuffer");
         Self {
-            data: Vec::with_capacity(capacity),
+            data: Storage::Heap(Vec::with_capacity(capacity)),
             len: 0,
             capacity,
         }
     }
 
+    /// Create a buffer backed by an anonymous private mapping of `capacity`
+    /// bytes.
+    ///
+    /// The pages come from the kernel on first touch and go back to it with
+    /// `munmap` when the buffer is dropped, bypassing the heap allocator.
+    #[cfg(feature = "mmap")]
+    pub fn mmap(capacity: usize) -> io::Result<Self> {
+        debug!(capacity, "Mapping ZeroCopyBuffer");
+        Ok(Self {
+            data: Storage::Mmap(memmap2::MmapMut::map_anon(capacity)?),
+            len: 0,
+            capacity,
+        })
+    }
+
+    /// Returns `true` when the buffer is backed by an anonymous mapping.
+    #[cfg(feature = "mmap")]
+    pub fn is_mmap(&self) -> bool {
+        matches!(self.data, Storage::Mmap(_))
+    }
+
     /// Write `src` into the buffer, replacing any previous contents.
     ///
     /// The buffer grows automatically if `src` exceeds the current capacity,
     /// but for best performance callers should pre-size via the pool.
     pub fn write(&mut self, src: &[u8]) {
-        self.data.clear();
-        self.data.extend_from_slice(src);
+        match &mut self.data {
+            Storage::Heap(data) => {
+                data.clear();
+                data.extend_from_slice(src);
+                self.capacity = self.capacity.max(src.len());
+            }
+            #[cfg(feature = "mmap")]
+            Storage::Mmap(map) if src.len() <= map.len() => map[..src.len()].copy_from_slice(src),
+            #[cfg(feature = "mmap")]
+            Storage::Mmap(_) => {
+                self.data = match memmap2::MmapMut::map_anon(src.len()) {
+                    Ok(mut map) => {
+                        map.copy_from_slice(src);
+                        Storage::Mmap(map)
+                    }
+                    Err(e) => {
+                        warn!(
+                            len = src.len(),
+                            error = %e,
+                            "Growing mapped buffer failed; using the heap"
+                        );
+                        Storage::Heap(src.to_vec())
+                    }
+                };
+                self.capacity = src.len();
+            }
+        }
         self.len = src.len();
     }
 
     /// Returns a slice over the valid data in the buffer.
     pub fn as_slice(&self) -> &[u8] {
-        &self.data[..self.len]
+        match &self.data {
+            Storage::Heap(data) => &data[..self.len],
+            #[cfg(feature = "mmap")]
+            Storage::Mmap(map) => &map[..self.len],
+        }
     }
 
     /// Returns the number of valid bytes currently stored.
@@ -83,9 +160,50 @@ impl ZeroCopyBuffer {
 
     /// Reset the buffer for reuse, keeping the allocation.
     fn reset(&mut self) {
-        self.data.clear();
+        match &mut self.data {
+            Storage::Heap(data) => data.clear(),
+            // Stale bytes past `len` are never exposed
+            #[cfg(feature = "mmap")]
+            Storage::Mmap(_) => {}
+        }
         self.len = 0;
     }
+
+    /// Replace the contents with up to `capacity` bytes read from `source`,
+    /// stopping early only at end of input.
+    ///
+    /// Returns the number of bytes read; on error the buffer is left empty.
+    fn fill_from<R: Read>(&mut self, source: &mut R) -> io::Result<usize> {
+        let capacity = self.capacity;
+        let region: &mut [u8] = match &mut self.data {
+            Storage::Heap(data) => {
+                data.clear();
+                data.resize(capacity, 0);
+                data
+            }
+            #[cfg(feature = "mmap")]
+            Storage::Mmap(map) => &mut map[..capacity],
+        };
+        let mut filled = 0;
+        while filled < capacity {
+            match source.read(&mut region[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.reset();
+                    return Err(e);
+                }
+            }
+        }
+        match &mut self.data {
+            Storage::Heap(data) => data.truncate(filled),
+            #[cfg(feature = "mmap")]
+            Storage::Mmap(_) => {}
+        }
+        self.len = filled;
+        Ok(filled)
+    }
 }
 
 /// Runtime statistics for a [`BufferPool`].
@@ -99,6 +217,33 @@ pub struct BufferPoolStats {
     pub active_count: usize,
 }
 
+/// Thresholds for [`BufferPool::autotune`].
+///
+/// Each autotune call looks at the window of [`allocate`](BufferPool::allocate)
+/// calls since the previous one. Rates are fractions in `0.0..=1.0`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutotuneConfig {
+    /// Reuse rate at or above which the free-list may be pre-grown
+    pub grow_reuse_rate: f64,
+    /// Reuse rate below which the free-list is halved
+    pub shrink_reuse_rate: f64,
+    /// Free buffers that shrinking never goes below
+    pub min_free: usize,
+    /// Free buffers retained at most; growing stops here and excess is dropped
+    pub max_free: usize,
+}
+
+impl Default for AutotuneConfig {
+    fn default() -> Self {
+        Self {
+            grow_reuse_rate: 0.8,
+            shrink_reuse_rate: 0.2,
+            min_free: 0,
+            max_free: 1024,
+        }
+    }
+}
+
 /// A pool of reusable [`ZeroCopyBuffer`]s.
 ///
 /// `BufferPool` maintains a free-list of previously allocated buffers.  When
@@ -110,6 +255,10 @@ pub struct BufferPoolStats {
 /// Call `allocate` / `release` in a tight loop during initialisation to
 /// pre-populate the pool.  Subsequent I/O operations then run without
 /// touching the system allocator.
+///
+/// # Performance Pattern: Adaptive Sizing
+/// Call [`autotune`](Self::autotune) periodically (e.g. from a timer tick) to
+/// let the free-list follow the workload instead of a fixed warm-up size.
 pub struct BufferPool {
     /// Default capacity for newly created buffers.
     default_capacity: usize,
@@ -119,6 +268,18 @@ pub struct BufferPool {
     total_allocations: usize,
     reuses: usize,
     active_count: usize,
+    /// Optional metrics sink for allocation vs reuse counts.
+    metrics: Option<Arc<PerfMetrics>>,
+    /// Thresholds used by `autotune`.
+    autotune: AutotuneConfig,
+    /// Churn observed since the last `autotune` call.
+    window_allocations: usize,
+    window_reuses: usize,
+    window_peak_active: usize,
+    window_start_active: usize,
+    /// Capacity from which new buffers are mmap-backed.
+    #[cfg(feature = "mmap")]
+    mmap_threshold: usize,
 }
 
 impl BufferPool {
@@ -133,9 +294,52 @@ impl BufferPool {
             total_allocations: 0,
             reuses: 0,
             active_count: 0,
+            metrics: None,
+            autotune: AutotuneConfig::default(),
+            window_allocations: 0,
+            window_reuses: 0,
+            window_peak_active: 0,
+            window_start_active: 0,
+            #[cfg(feature = "mmap")]
+            mmap_threshold: DEFAULT_MMAP_THRESHOLD,
         }
     }
 
+    /// Record every [`allocate`](Self::allocate) in `metrics` as either a
+    /// fresh allocation or a reuse.
+    pub fn with_metrics(mut self, metrics: Arc<PerfMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Use `config` instead of the default [`AutotuneConfig`].
+    pub fn with_autotune(mut self, config: AutotuneConfig) -> Self {
+        self.autotune = config;
+        self
+    }
+
+    /// Back new buffers with [`ZeroCopyBuffer::mmap`] when the pool's
+    /// capacity is at least `threshold` bytes (default
+    /// [`DEFAULT_MMAP_THRESHOLD`]).
+    #[cfg(feature = "mmap")]
+    pub fn with_mmap_threshold(mut self, threshold: usize) -> Self {
+        self.mmap_threshold = threshold;
+        self
+    }
+
+    /// A fresh buffer of the default capacity, mapped when it is large
+    /// enough and the mapping succeeds.
+    fn new_buffer(&self) -> ZeroCopyBuffer {
+        #[cfg(feature = "mmap")]
+        if self.default_capacity >= self.mmap_threshold {
+            match ZeroCopyBuffer::mmap(self.default_capacity) {
+                Ok(buf) => return buf,
+                Err(e) => warn!(error = %e, "Mapping buffer failed; using the heap"),
+            }
+        }
+        ZeroCopyBuffer::new(self.default_capacity)
+    }
+
     /// Obtain a buffer from the pool.
     ///
     /// If the free-list contains a buffer it is returned immediately (reuse).
@@ -144,14 +348,23 @@ impl BufferPool {
         let buf = if let Some(mut buf) = self.free_list.pop_front() {
             buf.reset();
             self.reuses += 1;
+            self.window_reuses += 1;
+            if let Some(metrics) = &self.metrics {
+                metrics.record_buffer_reuse();
+            }
             debug!("Reusing buffer from pool");
             buf
         } else {
             self.total_allocations += 1;
+            self.window_allocations += 1;
+            if let Some(metrics) = &self.metrics {
+                metrics.record_buffer_allocation();
+            }
             debug!("Allocating new buffer");
-            ZeroCopyBuffer::new(self.default_capacity)
+            self.new_buffer()
         };
         self.active_count += 1;
+        self.window_peak_active = self.window_peak_active.max(self.active_count);
         buf
     }
 
@@ -165,6 +378,74 @@ impl BufferPool {
         debug!(free = self.free_list.len(), "Buffer released to pool");
     }
 
+    /// Resize the free-list based on churn since the previous call.
+    ///
+    /// - Reuse rate at or above `grow_reuse_rate` and a spike in checked-out
+    ///   buffers (peak minus those already out when the window began) larger
+    ///   than the free-list, e.g. because buffers were dropped rather than
+    ///   released: pre-allocate up to the spike so a repeat of it needs no
+    ///   allocation.
+    /// - Reuse rate below `shrink_reuse_rate`, or no traffic at all: halve the
+    ///   free-list, keeping at least `min_free`.
+    ///
+    /// The free-list is always trimmed to `max_free`. Pre-allocated buffers
+    /// count towards `total_allocations` but not towards the metrics sink,
+    /// which tracks only [`allocate`](Self::allocate) calls.
+    pub fn autotune(&mut self) {
+        let requests = self.window_allocations + self.window_reuses;
+        let reuse_rate = if requests == 0 {
+            0.0
+        } else {
+            self.window_reuses as f64 / requests as f64
+        };
+        let free = self.free_list.len();
+        let spike = self.window_peak_active.saturating_sub(self.window_start_active);
+        let config = &self.autotune;
+
+        let target = if requests > 0 && reuse_rate >= config.grow_reuse_rate && spike > free {
+            spike
+        } else if reuse_rate < config.shrink_reuse_rate {
+            (free / 2).max(config.min_free).min(free)
+        } else {
+            free
+        }
+        .min(config.max_free);
+
+        if target > free {
+            for _ in free..target {
+                let buf = self.new_buffer();
+                self.free_list.push_back(buf);
+            }
+            self.total_allocations += target - free;
+        } else {
+            self.free_list.truncate(target);
+        }
+        if target != free {
+            info!(reuse_rate, from = free, to = target, "Autotuned BufferPool free-list");
+        }
+
+        self.window_allocations = 0;
+        self.window_reuses = 0;
+        self.window_peak_active = self.active_count;
+        self.window_start_active = self.active_count;
+    }
+
+    /// Drop every buffer on the free-list, returning their memory.
+    ///
+    /// Meant as a [`MemoryPressure`](super::cgroup::MemoryPressure) hook;
+    /// checked-out buffers are unaffected and rejoin the list on release.
+    pub fn on_memory_pressure(&mut self) {
+        let freed = self.free_list.len();
+        self.free_list.clear();
+        self.free_list.shrink_to_fit();
+        info!(freed, "BufferPool cleared under memory pressure");
+    }
+
+    /// Number of buffers currently idle in the free-list.
+    pub fn free_count(&self) -> usize {
+        self.free_list.len()
+    }
+
     /// Snapshot the pool's runtime statistics.
     pub fn get_stats(&self) -> BufferPoolStats {
         BufferPoolStats {
@@ -175,6 +456,76 @@ impl BufferPool {
     }
 }
 
+/// Reads a byte stream as a sequence of pooled [`ZeroCopyBuffer`] chunks.
+///
+/// Intended for checkpoint/restore streams and layer diffs: each chunk is a
+/// buffer from the pool, filled to its capacity (the pool's default for a
+/// fresh buffer) except for the last. Give chunks back with
+/// [`release`](Self::release) once processed so later chunks reuse them.
+///
+/// # Performance Pattern: Bounded Streaming
+/// Releasing each chunk before pulling the next keeps the whole stream on a
+/// single allocation, whatever its length.
+pub struct PooledReader<'a, R: Read> {
+    source: R,
+    pool: &'a mut BufferPool,
+    done: bool,
+}
+
+impl<'a, R: Read> PooledReader<'a, R> {
+    /// Stream `source` through buffers from `pool`.
+    pub fn new(source: R, pool: &'a mut BufferPool) -> Self {
+        Self {
+            source,
+            pool,
+            done: false,
+        }
+    }
+
+    /// Read the next chunk, or `None` at end of input.
+    pub fn next_chunk(&mut self) -> io::Result<Option<ZeroCopyBuffer>> {
+        if self.done {
+            return Ok(None);
+        }
+        let mut buf = self.pool.allocate();
+        match buf.fill_from(&mut self.source) {
+            Ok(0) => {
+                self.done = true;
+                self.pool.release(buf);
+                Ok(None)
+            }
+            Ok(n) => {
+                // A short chunk means the source is exhausted
+                self.done = n < buf.capacity();
+                Ok(Some(buf))
+            }
+            Err(e) => {
+                self.done = true;
+                self.pool.release(buf);
+                Err(e)
+            }
+        }
+    }
+
+    /// Return a chunk to the pool.
+    pub fn release(&mut self, buf: ZeroCopyBuffer) {
+        self.pool.release(buf);
+    }
+
+    /// Give back the underlying source.
+    pub fn into_inner(self) -> R {
+        self.source
+    }
+}
+
+impl<R: Read> Iterator for PooledReader<'_, R> {
+    type Item = io::Result<ZeroCopyBuffer>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_chunk().transpose()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -194,6 +545,11 @@ mod tests {
         assert_eq!(buf.len(), 11);
         assert!(!buf.is_empty());
         assert_eq!(buf.as_slice(), b"hello world");
+        assert_eq!(buf.capacity(), 64);
+
+        // Larger than the capacity: grows to fit
+        buf.write(&[7; 100]);
+        assert_eq!(buf.capacity(), 100);
     }
 
     #[test]
@@ -279,4 +635,193 @@ mod tests {
         let reused = pool.allocate();
         assert!(reused.is_empty(), "reused buffer should be cleared");
     }
+
+    #[test]
+    fn test_pool_records_metrics() {
+        let metrics = PerfMetrics::new();
+        let mut pool = BufferPool::new(256).with_metrics(metrics.clone());
+
+        let a = pool.allocate();
+        let b = pool.allocate();
+        pool.release(a);
+        pool.release(b);
+        let _c = pool.allocate();
+        let _d = pool.allocate();
+        let _e = pool.allocate();
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.buffer_allocations, 3);
+        assert_eq!(snapshot.buffer_reuses, 2);
+        assert_eq!(snapshot.buffer_reuse_rate, 40.0);
+    }
+
+    #[test]
+    fn test_pool_autotune_grows_and_shrinks() {
+        let mut pool = BufferPool::new(64).with_autotune(AutotuneConfig {
+            min_free: 2,
+            ..Default::default()
+        });
+        let warm: Vec<_> = (0..4).map(|_| pool.allocate()).collect();
+        warm.into_iter().for_each(|b| pool.release(b));
+        pool.autotune();
+        assert_eq!(pool.free_count(), 2);
+
+        // High churn, then a spike of 12 where most buffers are consumed
+        // instead of released.
+        for _ in 0..100 {
+            let b = pool.allocate();
+            pool.release(b);
+        }
+        let mut spike: Vec<_> = (0..12).map(|_| pool.allocate()).collect();
+        spike.drain(2..);
+        spike.into_iter().for_each(|b| pool.release(b));
+        assert_eq!(pool.free_count(), 2);
+
+        let allocations = pool.get_stats().total_allocations;
+        pool.autotune();
+        assert_eq!(pool.free_count(), 12);
+        assert_eq!(pool.get_stats().total_allocations, allocations + 10);
+
+        // The repeated spike is now served entirely from the free-list.
+        let spike: Vec<_> = (0..12).map(|_| pool.allocate()).collect();
+        assert_eq!(pool.get_stats().total_allocations, allocations + 10);
+        spike.into_iter().for_each(|b| pool.release(b));
+
+        // Low churn: the free-list halves each window down to min_free.
+        pool.autotune();
+        assert_eq!(pool.free_count(), 12);
+        pool.autotune();
+        assert_eq!(pool.free_count(), 6);
+        pool.autotune();
+        assert_eq!(pool.free_count(), 3);
+        pool.autotune();
+        assert_eq!(pool.free_count(), 2);
+    }
+
+    /// A source returning at most `step` bytes per read
+    struct Trickle<'a> {
+        data: &'a [u8],
+        step: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.step.min(buf.len()).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_pool_on_memory_pressure_clears_free_list() {
+        let mut pool = BufferPool::new(64);
+        let bufs: Vec<_> = (0..3).map(|_| pool.allocate()).collect();
+        let kept = pool.allocate();
+        bufs.into_iter().for_each(|buf| pool.release(buf));
+        assert_eq!(pool.free_count(), 3);
+
+        pool.on_memory_pressure();
+        assert_eq!(pool.free_count(), 0);
+        // Checked-out buffers still come back
+        pool.release(kept);
+        assert_eq!(pool.free_count(), 1);
+    }
+
+    #[test]
+    fn test_pooled_reader_chunks_and_reassembles() {
+        let data: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+        let mut pool = BufferPool::new(4096);
+        let mut reader = PooledReader::new(
+            Trickle {
+                data: &data,
+                step: 1000,
+            },
+            &mut pool,
+        );
+
+        let mut sizes = Vec::new();
+        let mut reassembled = Vec::new();
+        while let Some(chunk) = reader.next_chunk().unwrap() {
+            sizes.push(chunk.len());
+            reassembled.extend_from_slice(chunk.as_slice());
+            reader.release(chunk);
+        }
+        assert!(reader.next_chunk().unwrap().is_none());
+        assert_eq!(sizes, vec![4096, 4096, 1808]);
+        assert_eq!(reassembled, data);
+
+        // Released chunks were reused: one buffer served the whole stream
+        let stats = pool.get_stats();
+        assert_eq!(stats.total_allocations, 1);
+        assert_eq!(stats.active_count, 0);
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_mmap_buffer_write_and_read() {
+        let mut buf = ZeroCopyBuffer::mmap(64).unwrap();
+        assert!(buf.is_mmap());
+        assert!(buf.is_empty());
+        assert_eq!(buf.capacity(), 64);
+
+        buf.write(b"hello world");
+        assert_eq!(buf.as_slice(), b"hello world");
+        buf.write(b"bye");
+        assert_eq!(buf.as_slice(), b"bye");
+
+        // Larger than the mapping: remapped, still mmap-backed
+        let big: Vec<u8> = (0..200u8).collect();
+        buf.write(&big);
+        assert!(buf.is_mmap());
+        assert_eq!(buf.as_slice(), &big[..]);
+        assert_eq!(buf.capacity(), 200);
+
+        buf.reset();
+        assert!(buf.is_empty());
+        assert_eq!(buf.as_slice(), b"");
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_pool_maps_buffers_above_threshold() {
+        let mut small = BufferPool::new(4096).with_mmap_threshold(8192);
+        assert!(!small.allocate().is_mmap());
+
+        let data: Vec<u8> = (0..20_000u32).map(|i| (i % 251) as u8).collect();
+        let mut pool = BufferPool::new(8192).with_mmap_threshold(8192);
+        let mut reader = PooledReader::new(
+            Trickle {
+                data: &data,
+                step: 3000,
+            },
+            &mut pool,
+        );
+        let mut reassembled = Vec::new();
+        while let Some(chunk) = reader.next_chunk().unwrap() {
+            assert!(chunk.is_mmap());
+            reassembled.extend_from_slice(chunk.as_slice());
+            reader.release(chunk);
+        }
+        assert_eq!(reassembled, data);
+        assert_eq!(pool.get_stats().total_allocations, 1);
+
+        // Reused mapped buffers come back empty
+        let buf = pool.allocate();
+        assert!(buf.is_mmap());
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_pooled_reader_iterator() {
+        let mut pool = BufferPool::new(4);
+        let chunks: Vec<Vec<u8>> = PooledReader::new(&b"abcdefgh"[..], &mut pool)
+            .map(|chunk| chunk.unwrap().as_slice().to_vec())
+            .collect();
+        assert_eq!(chunks, vec![b"abcd".to_vec(), b"efgh".to_vec()]);
+
+        let mut pool = BufferPool::new(4);
+        assert_eq!(PooledReader::new(io::empty(), &mut pool).count(), 0);
+        assert_eq!(pool.free_count(), 1);
+    }
 }
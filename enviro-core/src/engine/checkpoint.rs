@@ -0,0 +1,578 @@
+//! Checkpoint Image Format
+//!
+//! This module defines the on-disk format that [`Executor::checkpoint`] and
+//! [`Executor::restore`] target: a single file holding the checkpoint's
+//! metadata followed by the process's memory pages.
+//!
+//! ```text
+//! header:  magic "ENVCKPT\0" | version: u32 LE
+//! section: kind: u8 | address: u64 LE | chunk* | 0u32
+//! chunk:   len: u32 LE (non-zero) | len bytes
+//! end:     kind 0
+//! ```
+//!
+//! Section payloads are chunked, so a section can be streamed from a source
+//! of unknown length; a zero-length chunk closes it. The end marker lets a
+//! reader tell a complete checkpoint from one cut short by a crash.
+//!
+//! # Performance-First Design:
+//! - [`CheckpointWriter`] streams sections through a [`BufferPool`], so
+//!   dumping gigabytes of memory stays on a single buffer allocation
+//! - Sections are written as they arrive; nothing is staged in memory
+//! - [`CheckpointReader`] checks the header up front, before any pages are
+//!   read, and [`CheckpointReader::copy_section`] streams a section's
+//!   payload without buffering it
+//!
+//! [`Executor::checkpoint`]: crate::executor::Executor::checkpoint
+//! [`Executor::restore`]: crate::executor::Executor::restore
+
+use std::io::{self, Read, Write};
+use tracing::debug;
+
+use super::buffer::{BufferPool, PooledReader, DEFAULT_BUFFER_CAPACITY};
+
+/// Magic bytes at the start of every checkpoint file.
+pub const CHECKPOINT_MAGIC: [u8; 8] = *b"ENVCKPT\0";
+
+/// Format version written by [`CheckpointWriter`] and accepted by
+/// [`CheckpointReader`].
+pub const CHECKPOINT_VERSION: u32 = 1;
+
+/// Largest section [`CheckpointReader::next_section`] buffers by default.
+pub const DEFAULT_MAX_SECTION_SIZE: u64 = 1024 * 1024 * 1024;
+
+/// Section kind byte marking the end of the checkpoint.
+const END_MARKER: u8 = 0;
+
+/// Errors produced while reading or writing a checkpoint.
+#[derive(Debug, thiserror::Error)]
+pub enum CheckpointError {
+    /// The file does not start with [`CHECKPOINT_MAGIC`].
+    #[error("not a checkpoint file (bad magic header)")]
+    BadMagic,
+    /// The file was written with a format version this build cannot read.
+    #[error("unsupported checkpoint version {0} (expected {CHECKPOINT_VERSION})")]
+    UnsupportedVersion(u32),
+    /// A section header carries an unknown kind byte.
+    #[error("unknown checkpoint section kind {0}")]
+    UnknownSection(u8),
+    /// A section's payload is larger than the reader buffers.
+    #[error("checkpoint section exceeds the {limit}-byte limit")]
+    SectionTooLarge {
+        /// The reader's maximum section size.
+        limit: u64,
+    },
+    /// The underlying reader or writer failed, including a checkpoint that
+    /// ends before its end marker.
+    #[error("checkpoint I/O failed: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// The kind of data a [`Section`] holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectionKind {
+    /// Opaque metadata describing the checkpointed process (e.g. JSON).
+    Metadata,
+    /// A run of memory pages starting at the section's address.
+    MemoryPages,
+}
+
+impl SectionKind {
+    fn to_byte(self) -> u8 {
+        match self {
+            SectionKind::Metadata => 1,
+            SectionKind::MemoryPages => 2,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Result<Self, CheckpointError> {
+        match byte {
+            1 => Ok(SectionKind::Metadata),
+            2 => Ok(SectionKind::MemoryPages),
+            other => Err(CheckpointError::UnknownSection(other)),
+        }
+    }
+}
+
+/// A section read back from a checkpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    /// What the payload holds.
+    pub kind: SectionKind,
+    /// Start address of the pages; 0 for metadata.
+    pub address: u64,
+    /// The section payload.
+    pub data: Vec<u8>,
+}
+
+/// A section whose payload was streamed by
+/// [`CheckpointReader::copy_section`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SectionInfo {
+    /// What the payload holds.
+    pub kind: SectionKind,
+    /// Start address of the pages; 0 for metadata.
+    pub address: u64,
+    /// Payload bytes copied.
+    pub len: u64,
+}
+
+/// Streams a checkpoint into a single file (or any other `Write` sink).
+///
+/// Write the metadata and memory pages in any order, then call
+/// [`finish`](Self::finish) to append the end marker. A writer dropped
+/// without `finish` leaves a checkpoint that [`CheckpointReader`] rejects.
+///
+/// # Performance Pattern: Pooled Streaming
+/// Each section is copied through chunks from the writer's [`BufferPool`];
+/// a chunk is released before the next is read, so the pool stays at one
+/// buffer no matter how large the checkpoint is.
+pub struct CheckpointWriter<W: Write> {
+    sink: W,
+    pool: BufferPool,
+}
+
+impl<W: Write> CheckpointWriter<W> {
+    /// Start a checkpoint in `sink`, streaming through buffers of
+    /// [`DEFAULT_BUFFER_CAPACITY`] bytes.
+    pub fn new(sink: W) -> Result<Self, CheckpointError> {
+        Self::with_pool(sink, BufferPool::new(DEFAULT_BUFFER_CAPACITY))
+    }
+
+    /// Start a checkpoint in `sink`, streaming through buffers from `pool`.
+    ///
+    /// The pool's buffer size is the largest chunk written.
+    pub fn with_pool(mut sink: W, pool: BufferPool) -> Result<Self, CheckpointError> {
+        sink.write_all(&CHECKPOINT_MAGIC)?;
+        sink.write_all(&CHECKPOINT_VERSION.to_le_bytes())?;
+        Ok(Self { sink, pool })
+    }
+
+    /// Write a metadata section.
+    pub fn write_metadata(&mut self, metadata: &[u8]) -> Result<u64, CheckpointError> {
+        self.write_section(SectionKind::Metadata, 0, metadata)
+    }
+
+    /// Stream the memory pages mapped at `address` from `pages` until it
+    /// reaches end of input.
+    ///
+    /// Returns the number of payload bytes written.
+    pub fn write_pages<R: Read>(&mut self, address: u64, pages: R) -> Result<u64, CheckpointError> {
+        self.write_section(SectionKind::MemoryPages, address, pages)
+    }
+
+    fn write_section<R: Read>(
+        &mut self,
+        kind: SectionKind,
+        address: u64,
+        source: R,
+    ) -> Result<u64, CheckpointError> {
+        self.sink.write_all(&[kind.to_byte()])?;
+        self.sink.write_all(&address.to_le_bytes())?;
+
+        let mut written = 0u64;
+        let mut chunks = PooledReader::new(source, &mut self.pool);
+        while let Some(chunk) = chunks.next_chunk()? {
+            let result = write_chunk(&mut self.sink, chunk.as_slice());
+            written += chunk.len() as u64;
+            chunks.release(chunk);
+            result?;
+        }
+        self.sink.write_all(&0u32.to_le_bytes())?;
+
+        debug!(?kind, address, bytes = written, "Wrote checkpoint section");
+        Ok(written)
+    }
+
+    /// Append the end marker, flush, and give back the sink.
+    pub fn finish(mut self) -> Result<W, CheckpointError> {
+        self.sink.write_all(&[END_MARKER])?;
+        self.sink.flush()?;
+        Ok(self.sink)
+    }
+
+    /// The pool the writer streams through.
+    pub fn pool(&self) -> &BufferPool {
+        &self.pool
+    }
+}
+
+fn write_chunk<W: Write>(sink: &mut W, chunk: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(chunk.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "checkpoint chunk too large"))?;
+    sink.write_all(&len.to_le_bytes())?;
+    sink.write_all(chunk)
+}
+
+/// Reads a checkpoint written by [`CheckpointWriter`], one [`Section`] at a
+/// time.
+///
+/// The magic header and version are validated by [`new`](Self::new). Input
+/// that ends before the end marker fails with an
+/// [`io::ErrorKind::UnexpectedEof`] error instead of looking complete.
+/// Chunk lengths are only trusted as far as the bytes actually arrive, and
+/// [`next_section`](Self::next_section) buffers at most
+/// [`DEFAULT_MAX_SECTION_SIZE`] bytes unless configured otherwise.
+pub struct CheckpointReader<R: Read> {
+    source: R,
+    version: u32,
+    done: bool,
+    max_section_size: u64,
+}
+
+impl<R: Read> CheckpointReader<R> {
+    /// Open a checkpoint, validating its magic header and version.
+    pub fn new(mut source: R) -> Result<Self, CheckpointError> {
+        let mut magic = [0u8; 8];
+        source.read_exact(&mut magic)?;
+        if magic != CHECKPOINT_MAGIC {
+            return Err(CheckpointError::BadMagic);
+        }
+        let version = read_u32(&mut source)?;
+        if version != CHECKPOINT_VERSION {
+            return Err(CheckpointError::UnsupportedVersion(version));
+        }
+        Ok(Self {
+            source,
+            version,
+            done: false,
+            max_section_size: DEFAULT_MAX_SECTION_SIZE,
+        })
+    }
+
+    /// Buffer sections of up to `max` bytes in
+    /// [`next_section`](Self::next_section).
+    pub fn with_max_section_size(mut self, max: u64) -> Self {
+        self.max_section_size = max;
+        self
+    }
+
+    /// Format version of the checkpoint.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Read the next section, or `None` once the end marker is reached.
+    ///
+    /// A section larger than the maximum section size fails with
+    /// [`CheckpointError::SectionTooLarge`]; use
+    /// [`copy_section`](Self::copy_section) for those.
+    pub fn next_section(&mut self) -> Result<Option<Section>, CheckpointError> {
+        let limit = self.max_section_size;
+        let mut data = Vec::new();
+        let Some((kind, address)) = self.read_section(|source, len| {
+            if data.len() as u64 + len > limit {
+                return Err(CheckpointError::SectionTooLarge { limit });
+            }
+            // Grows with the bytes that arrive, not with the claimed length
+            Ok(source.take(len).read_to_end(&mut data)? as u64)
+        })?
+        else {
+            return Ok(None);
+        };
+
+        debug!(
+            ?kind,
+            address,
+            bytes = data.len(),
+            "Read checkpoint section"
+        );
+        Ok(Some(Section {
+            kind,
+            address,
+            data,
+        }))
+    }
+
+    /// Stream the next section's payload into `sink`, or return `None` once
+    /// the end marker is reached.
+    ///
+    /// Nothing is buffered beyond `io::copy`'s stack buffer, so this has no
+    /// size limit.
+    pub fn copy_section<W: Write>(
+        &mut self,
+        sink: &mut W,
+    ) -> Result<Option<SectionInfo>, CheckpointError> {
+        let mut copied = 0u64;
+        let Some((kind, address)) = self.read_section(|source, len| {
+            let chunk = io::copy(&mut source.take(len), &mut *sink)?;
+            copied += chunk;
+            Ok(chunk)
+        })?
+        else {
+            return Ok(None);
+        };
+
+        debug!(?kind, address, bytes = copied, "Copied checkpoint section");
+        Ok(Some(SectionInfo {
+            kind,
+            address,
+            len: copied,
+        }))
+    }
+
+    /// Read a section header, then hand the source and length of each chunk
+    /// to `on_chunk`, which returns the bytes it read; fewer than the chunk
+    /// claims means the checkpoint was cut short.
+    fn read_section(
+        &mut self,
+        mut on_chunk: impl FnMut(&mut R, u64) -> Result<u64, CheckpointError>,
+    ) -> Result<Option<(SectionKind, u64)>, CheckpointError> {
+        if self.done {
+            return Ok(None);
+        }
+        let mut kind = [0u8; 1];
+        self.source.read_exact(&mut kind)?;
+        if kind[0] == END_MARKER {
+            self.done = true;
+            return Ok(None);
+        }
+        let kind = SectionKind::from_byte(kind[0])?;
+        let mut address = [0u8; 8];
+        self.source.read_exact(&mut address)?;
+        let address = u64::from_le_bytes(address);
+
+        loop {
+            let len = u64::from(read_u32(&mut self.source)?);
+            if len == 0 {
+                return Ok(Some((kind, address)));
+            }
+            if on_chunk(&mut self.source, len)? < len {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "checkpoint chunk is truncated",
+                )
+                .into());
+            }
+        }
+    }
+
+    /// Give back the underlying source.
+    pub fn into_inner(self) -> R {
+        self.source
+    }
+}
+
+impl<R: Read> Iterator for CheckpointReader<R> {
+    type Item = Result<Section, CheckpointError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.next_section();
+        if next.is_err() {
+            // Stop after the first error rather than parsing garbage
+            self.done = true;
+        }
+        next.transpose()
+    }
+}
+
+fn read_u32<R: Read>(source: &mut R) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    source.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pages(len: usize, seed: u8) -> Vec<u8> {
+        (0..len)
+            .map(|i| (i as u8).wrapping_mul(31).wrapping_add(seed))
+            .collect()
+    }
+
+    fn write_synthetic() -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+        let heap = pages(3 * 4096 + 17, 7);
+        let stack = pages(4096, 42);
+
+        let mut writer = CheckpointWriter::new(Vec::new()).unwrap();
+        writer
+            .write_metadata(br#"{"pid":42,"executor":"native"}"#)
+            .unwrap();
+        assert_eq!(
+            writer.write_pages(0x5555_0000, heap.as_slice()).unwrap(),
+            heap.len() as u64
+        );
+        assert_eq!(
+            writer.write_pages(0x7ffd_0000, stack.as_slice()).unwrap(),
+            4096
+        );
+        writer.write_pages(0x1000, io::empty()).unwrap();
+        // Every chunk went back to the pool before the next was read
+        assert_eq!(writer.pool().get_stats().total_allocations, 1);
+
+        (writer.finish().unwrap(), heap, stack)
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let (file, heap, stack) = write_synthetic();
+        assert!(file.starts_with(&CHECKPOINT_MAGIC));
+
+        let reader = CheckpointReader::new(file.as_slice()).unwrap();
+        assert_eq!(reader.version(), CHECKPOINT_VERSION);
+        let sections: Vec<Section> = reader.collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(
+            sections,
+            vec![
+                Section {
+                    kind: SectionKind::Metadata,
+                    address: 0,
+                    data: br#"{"pid":42,"executor":"native"}"#.to_vec(),
+                },
+                Section {
+                    kind: SectionKind::MemoryPages,
+                    address: 0x5555_0000,
+                    data: heap,
+                },
+                Section {
+                    kind: SectionKind::MemoryPages,
+                    address: 0x7ffd_0000,
+                    data: stack,
+                },
+                Section {
+                    kind: SectionKind::MemoryPages,
+                    address: 0x1000,
+                    data: Vec::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_roundtrip_through_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("checkpoint.img");
+        let file = std::fs::File::create(&path).unwrap();
+
+        let mut writer = CheckpointWriter::new(io::BufWriter::new(file)).unwrap();
+        writer.write_metadata(b"meta").unwrap();
+        writer.write_pages(0x2000, &[0xAB; 10_000][..]).unwrap();
+        writer.finish().unwrap();
+
+        let file = std::fs::File::open(&path).unwrap();
+        let mut reader = CheckpointReader::new(io::BufReader::new(file)).unwrap();
+        assert_eq!(reader.next_section().unwrap().unwrap().data, b"meta");
+        let section = reader.next_section().unwrap().unwrap();
+        assert_eq!(section.address, 0x2000);
+        assert_eq!(section.data, vec![0xAB; 10_000]);
+        assert!(reader.next_section().unwrap().is_none());
+        assert!(reader.next_section().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_bad_magic() {
+        let err = CheckpointReader::new(&b"NOTACKPT\x01\0\0\0"[..])
+            .err()
+            .unwrap();
+        assert!(matches!(err, CheckpointError::BadMagic));
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let mut file = CHECKPOINT_MAGIC.to_vec();
+        file.extend_from_slice(&99u32.to_le_bytes());
+        let err = CheckpointReader::new(file.as_slice()).err().unwrap();
+        assert!(matches!(err, CheckpointError::UnsupportedVersion(99)));
+    }
+
+    #[test]
+    fn test_truncated_checkpoint_is_an_error() {
+        let (mut file, _, _) = write_synthetic();
+        // Drop the end marker
+        file.pop();
+
+        let results: Vec<_> = CheckpointReader::new(file.as_slice()).unwrap().collect();
+        assert_eq!(results.len(), 5);
+        assert!(results[..4].iter().all(Result::is_ok));
+        match &results[4] {
+            Err(CheckpointError::Io(e)) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
+            other => panic!("expected EOF error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unknown_section_kind() {
+        let mut file = CHECKPOINT_MAGIC.to_vec();
+        file.extend_from_slice(&CHECKPOINT_VERSION.to_le_bytes());
+        file.push(9);
+
+        let mut reader = CheckpointReader::new(file.as_slice()).unwrap();
+        assert!(matches!(
+            reader.next_section(),
+            Err(CheckpointError::UnknownSection(9))
+        ));
+    }
+
+    /// A checkpoint whose only section claims a `len`-byte chunk but holds
+    /// `actual` bytes
+    fn lying_chunk(len: u32, actual: usize) -> Vec<u8> {
+        let mut file = CHECKPOINT_MAGIC.to_vec();
+        file.extend_from_slice(&CHECKPOINT_VERSION.to_le_bytes());
+        file.push(SectionKind::MemoryPages.to_byte());
+        file.extend_from_slice(&0x1000u64.to_le_bytes());
+        file.extend_from_slice(&len.to_le_bytes());
+        file.extend(std::iter::repeat_n(0xCD, actual));
+        file
+    }
+
+    #[test]
+    fn test_truncated_chunk_is_an_error() {
+        // Claims 4GB but holds 16 bytes: nothing close to 4GB is allocated
+        let file = lying_chunk(u32::MAX, 16);
+        let mut reader = CheckpointReader::new(file.as_slice())
+            .unwrap()
+            .with_max_section_size(u64::MAX);
+        match reader.next_section() {
+            Err(CheckpointError::Io(e)) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
+            other => panic!("expected EOF error, got {:?}", other),
+        }
+
+        let mut reader = CheckpointReader::new(file.as_slice()).unwrap();
+        let mut sink = Vec::new();
+        match reader.copy_section(&mut sink) {
+            Err(CheckpointError::Io(e)) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
+            other => panic!("expected EOF error, got {:?}", other),
+        }
+        assert_eq!(sink.len(), 16);
+    }
+
+    #[test]
+    fn test_section_size_limit() {
+        let file = lying_chunk(u32::MAX, 16);
+        let mut reader = CheckpointReader::new(file.as_slice()).unwrap();
+        assert!(matches!(
+            reader.next_section(),
+            Err(CheckpointError::SectionTooLarge { limit: DEFAULT_MAX_SECTION_SIZE })
+        ));
+
+        let (file, heap, _) = write_synthetic();
+        let mut reader = CheckpointReader::new(file.as_slice())
+            .unwrap()
+            .with_max_section_size(4096);
+        assert_eq!(reader.next_section().unwrap().unwrap().kind, SectionKind::Metadata);
+        assert!(matches!(
+            reader.next_section(),
+            Err(CheckpointError::SectionTooLarge { limit: 4096 })
+        ));
+
+        // Streaming is not limited
+        let mut reader = CheckpointReader::new(file.as_slice())
+            .unwrap()
+            .with_max_section_size(4096);
+        let mut sink = Vec::new();
+        reader.copy_section(&mut io::sink()).unwrap();
+        let info = reader.copy_section(&mut sink).unwrap().unwrap();
+        assert_eq!(
+            info,
+            SectionInfo {
+                kind: SectionKind::MemoryPages,
+                address: 0x5555_0000,
+                len: heap.len() as u64,
+            }
+        );
+        assert_eq!(sink, heap);
+    }
+}
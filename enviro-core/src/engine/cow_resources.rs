@@ -9,9 +9,11 @@
 //! - `Arc`-backed sharing — zero-copy for read-only access paths
 //! - Clone only on mutation via [`Arc::make_mut`] semantics
 //! - `SharedResourceManager` manages a named collection of CoW resources
+//! - `ConcurrentResourceManager` does the same behind a `RwLock`, so it can
+//!   be shared by concurrently-starting containers
 
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use tracing::{debug, info};
 
 /// A copy-on-write wrapper around a clonable resource.
@@ -148,6 +150,139 @@ impl<T: Clone> Default for SharedResourceManager<T> {
     }
 }
 
+/// Thread-safe variant of [`SharedResourceManager`] for concurrent access.
+///
+/// The named resources live in `Arc<RwLock<…>>`, so every method takes
+/// `&self` and clones of the manager can be moved into separate tasks.
+/// Mutation still follows CoW rules: data is only cloned when a `share()`d
+/// reference (or a [`fork`](Self::fork)) is still alive.
+///
+/// # Performance Pattern: Read-Heavy RwLock
+/// Containers starting from the same base environment mostly call
+/// [`share`](Self::share), which only takes the read lock and an `Arc`
+/// clone. The write lock is held just for the duration of a mutation
+/// closure, never across an `.await`.
+pub struct ConcurrentResourceManager<T: Clone> {
+    resources: Arc<RwLock<HashMap<String, CowResource<T>>>>,
+}
+
+impl<T: Clone> ConcurrentResourceManager<T> {
+    /// Create a new, empty concurrent resource manager.
+    pub fn new() -> Self {
+        info!("Creating ConcurrentResourceManager");
+        Self {
+            resources: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Insert a named resource into the manager (thread-safe).
+    pub fn insert(&self, name: impl Into<String>, value: T) {
+        let name = name.into();
+        debug!(name = %name, "Inserting CoW resource");
+        self.resources
+            .write()
+            .expect("resource lock poisoned during insert")
+            .insert(name, CowResource::new(value));
+    }
+
+    /// Register `to` as a copy of `from` without cloning its data.
+    ///
+    /// The two resources share one allocation until either is mutated.
+    /// Returns `false` if `from` does not exist.
+    pub fn fork(&self, from: &str, to: impl Into<String>) -> bool {
+        let mut resources = self
+            .resources
+            .write()
+            .expect("resource lock poisoned during fork");
+        let Some(forked) = resources.get(from).cloned() else {
+            return false;
+        };
+        let to = to.into();
+        debug!(from, to = %to, "Forking CoW resource");
+        resources.insert(to, forked);
+        true
+    }
+
+    /// Get a shared `Arc` reference to the named resource (thread-safe).
+    pub fn share(&self, name: &str) -> Option<Arc<T>> {
+        self.resources
+            .read()
+            .expect("resource lock poisoned during share")
+            .get(name)
+            .map(|r| r.share())
+    }
+
+    /// Run `f` on the named resource under the write lock, cloning it first
+    /// if other references exist.
+    ///
+    /// Returns `f`'s result, or `None` if the resource does not exist.
+    pub fn mutate<R>(&self, name: &str, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        self.resources
+            .write()
+            .expect("resource lock poisoned during mutate")
+            .get_mut(name)
+            .map(|r| f(r.mutate()))
+    }
+
+    /// Remove the named resource, returning it if it existed (thread-safe).
+    ///
+    /// Outstanding `share()` references keep the data alive.
+    pub fn remove(&self, name: &str) -> Option<CowResource<T>> {
+        self.resources
+            .write()
+            .expect("resource lock poisoned during remove")
+            .remove(name)
+    }
+
+    /// Return the reference count for the named resource (thread-safe).
+    pub fn ref_count(&self, name: &str) -> Option<usize> {
+        self.resources
+            .read()
+            .expect("resource lock poisoned during ref_count")
+            .get(name)
+            .map(|r| r.ref_count())
+    }
+
+    /// Returns `true` if the named resource is shared (thread-safe).
+    pub fn is_shared(&self, name: &str) -> Option<bool> {
+        self.resources
+            .read()
+            .expect("resource lock poisoned during is_shared")
+            .get(name)
+            .map(|r| r.is_shared())
+    }
+
+    /// Return the number of managed resources (thread-safe).
+    pub fn len(&self) -> usize {
+        self.resources
+            .read()
+            .expect("resource lock poisoned during len")
+            .len()
+    }
+
+    /// Returns `true` when the manager contains no resources (thread-safe).
+    pub fn is_empty(&self) -> bool {
+        self.resources
+            .read()
+            .expect("resource lock poisoned during is_empty")
+            .is_empty()
+    }
+}
+
+impl<T: Clone> Default for ConcurrentResourceManager<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Clone for ConcurrentResourceManager<T> {
+    fn clone(&self) -> Self {
+        Self {
+            resources: self.resources.clone(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -250,4 +385,72 @@ mod tests {
         let mgr = SharedResourceManager::<u8>::default();
         assert!(mgr.is_empty());
     }
+
+    #[test]
+    fn test_concurrent_manager_mutate_cow() {
+        let mgr = ConcurrentResourceManager::<Vec<u8>>::new();
+        mgr.insert("data", vec![1, 2]);
+        let shared = mgr.share("data").unwrap();
+
+        let len = mgr.mutate("data", |d| {
+            d.push(3);
+            d.len()
+        });
+        assert_eq!(len, Some(3));
+        assert_eq!(*shared, vec![1, 2]);
+        assert_eq!(*mgr.share("data").unwrap(), vec![1, 2, 3]);
+        assert!(mgr.mutate("nope", |d| d.push(0)).is_none());
+    }
+
+    #[test]
+    fn test_concurrent_manager_fork_defers_clone() {
+        let mgr = ConcurrentResourceManager::<String>::default();
+        mgr.insert("base", "PATH=/usr/bin".to_string());
+        assert!(mgr.fork("base", "c1"));
+        assert!(!mgr.fork("missing", "c2"));
+        assert_eq!(mgr.len(), 2);
+
+        // Forked entries share one allocation until one of them is mutated.
+        assert_eq!(mgr.ref_count("base"), Some(2));
+        mgr.mutate("c1", |env| env.push_str(":/opt/bin"));
+        assert_eq!(mgr.ref_count("base"), Some(1));
+        assert_eq!(mgr.is_shared("c1"), Some(false));
+        assert_eq!(*mgr.share("base").unwrap(), "PATH=/usr/bin");
+        assert_eq!(*mgr.share("c1").unwrap(), "PATH=/usr/bin:/opt/bin");
+
+        assert!(mgr.remove("c1").is_some());
+        assert!(mgr.share("c1").is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_manager_tasks_mutate_independently() {
+        let mgr = ConcurrentResourceManager::<Vec<String>>::new();
+        mgr.insert("base-env", vec!["PATH=/usr/bin".to_string()]);
+        mgr.insert("starts", Vec::new());
+        let base = mgr.share("base-env").unwrap();
+
+        let tasks: Vec<_> = (0..16)
+            .map(|i| {
+                let mgr = mgr.clone();
+                tokio::spawn(async move {
+                    let name = format!("container-{}", i);
+                    assert!(mgr.fork("base-env", name.clone()));
+                    tokio::task::yield_now().await;
+                    mgr.mutate(&name, |env| env.push(format!("ID={}", i)));
+                    mgr.mutate("starts", |starts| starts.push(name));
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert_eq!(mgr.len(), 18);
+        assert_eq!(mgr.share("starts").unwrap().len(), 16);
+        assert_eq!(*mgr.share("base-env").unwrap(), *base);
+        for i in 0..16 {
+            let env = mgr.share(&format!("container-{}", i)).unwrap();
+            assert_eq!(*env, vec!["PATH=/usr/bin".to_string(), format!("ID={}", i)]);
+        }
+    }
 }
@@ -0,0 +1,374 @@
+//! Envirofile - Declarative Container Specs
+//!
+//! An Envirofile describes a single container in TOML or YAML, so that
+//! `enviro run -f Envirofile` can launch it without the Python SDK:
+//!
+//! ```toml
+//! name = "web"
+//! image = "nginx:1.25"
+//! command = ["nginx", "-g", "daemon off;"]
+//! workdir = "/srv"
+//! profile = "standard"
+//!
+//! [env]
+//! RUST_LOG = "info"
+//!
+//! [network]
+//! isolated = false
+//! ip_address = "10.88.0.2"
+//! dns_servers = ["1.1.1.1"]
+//! ports = [{ host_port = 8080, container_port = 80 }]
+//! ```
+//!
+//! Unknown keys are rejected rather than ignored, so a typo such as
+//! `dns_server` fails loudly instead of silently running with defaults.
+//!
+//! # Performance Pattern: Parse Once
+//! The file is parsed and validated straight into a [`ContainerSpec`];
+//! nothing is re-read or re-validated on the container start path.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::image::ImageRef;
+use super::resource_limits::ResourceProfile;
+use crate::executor::{NetworkConfig, PortMap};
+use crate::runtime::ContainerSpec;
+
+/// Syntax an Envirofile is written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// TOML (`.toml`)
+    Toml,
+    /// YAML (`.yaml` / `.yml`)
+    Yaml,
+}
+
+impl Format {
+    /// Pick the format from a file extension, if it names one
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "toml" => Some(Self::Toml),
+            "yaml" | "yml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+
+    /// Guess the format of an extensionless file from its contents
+    ///
+    /// Anything that is a valid TOML document is TOML; everything else is
+    /// treated as YAML.
+    pub fn detect(contents: &str) -> Self {
+        if contents.parse::<toml::Table>().is_ok() {
+            Self::Toml
+        } else {
+            Self::Yaml
+        }
+    }
+}
+
+/// A container described by an Envirofile
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Envirofile {
+    /// Container ID; generated when omitted
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Image reference, e.g. `alpine:3.19`
+    pub image: String,
+    /// Command and arguments, in exec form
+    pub command: Vec<String>,
+    /// Environment variables
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Working directory inside the container
+    #[serde(default)]
+    pub workdir: Option<String>,
+    /// Resource profile; the runtime defaults apply when omitted
+    #[serde(default)]
+    pub profile: Option<ResourceProfile>,
+    /// Network settings; the runtime defaults apply when omitted
+    #[serde(default)]
+    pub network: Option<NetworkSettings>,
+}
+
+/// The `[network]` section of an Envirofile
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkSettings {
+    /// Give the container its own network namespace (default `true`)
+    #[serde(default = "default_isolated")]
+    pub isolated: bool,
+    /// Static container address
+    #[serde(default)]
+    pub ip_address: Option<String>,
+    /// Nameservers written to the container's `/etc/resolv.conf`
+    #[serde(default)]
+    pub dns_servers: Vec<String>,
+    /// Host ports forwarded into the container
+    #[serde(default)]
+    pub ports: Vec<PortMap>,
+}
+
+fn default_isolated() -> bool {
+    true
+}
+
+impl From<NetworkSettings> for NetworkConfig {
+    fn from(settings: NetworkSettings) -> Self {
+        Self {
+            isolated: settings.isolated,
+            ip_address: settings.ip_address,
+            dns_servers: settings.dns_servers,
+            port_mappings: settings.ports,
+        }
+    }
+}
+
+impl Envirofile {
+    /// Read an Envirofile and turn it into a [`ContainerSpec`]
+    ///
+    /// The format comes from the extension (`.toml`, `.yaml`, `.yml`) and is
+    /// detected from the contents otherwise, so a plain `Envirofile` works.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<ContainerSpec> {
+        Self::load(path)?.into_spec()
+    }
+
+    /// Read and parse an Envirofile without converting it
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let format = Format::from_path(path).unwrap_or_else(|| Format::detect(&contents));
+
+        Self::parse(&contents, format).with_context(|| format!("Invalid Envirofile {}", path.display()))
+    }
+
+    /// Parse Envirofile contents in the given format
+    pub fn parse(contents: &str, format: Format) -> Result<Self> {
+        match format {
+            Format::Toml => toml::from_str(contents).context("Failed to parse TOML"),
+            Format::Yaml => {
+                // serde_yaml only accepts `!tag` syntax for enum variants;
+                // going through a JSON value lets YAML use the same
+                // `{ custom = ... }` map shape as TOML.
+                let value: serde_yaml::Value =
+                    serde_yaml::from_str(contents).context("Failed to parse YAML")?;
+                let value = serde_json::to_value(value).context("Failed to parse YAML")?;
+                serde_json::from_value(value).context("Failed to parse YAML")
+            }
+        }
+    }
+
+    /// Validate the file and build the [`ContainerSpec`] it describes
+    pub fn into_spec(self) -> Result<ContainerSpec> {
+        ImageRef::parse(&self.image)?;
+        let mut command = self.command.into_iter();
+        let Some(program) = command.next() else {
+            bail!("'command' must name a program to run");
+        };
+        if matches!(&self.name, Some(name) if name.is_empty()) {
+            bail!("'name' must not be empty");
+        }
+
+        let mut spec = ContainerSpec::new(self.image, program, command.collect());
+        if let Some(name) = self.name {
+            spec.id = name;
+        }
+        spec.env = self.env;
+        if let Some(workdir) = self.workdir {
+            spec.workdir = workdir;
+        }
+        spec.profile = self.profile;
+        spec.network = self.network.map(NetworkConfig::from);
+        Ok(spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::Protocol;
+
+    const SAMPLE_TOML: &str = r#"
+name = "web"
+image = "nginx:1.25"
+command = ["nginx", "-g", "daemon off;"]
+workdir = "/srv"
+profile = "performance"
+
+[env]
+RUST_LOG = "info"
+
+[network]
+isolated = false
+ip_address = "10.88.0.2"
+dns_servers = ["1.1.1.1"]
+ports = [
+    { host_port = 8080, container_port = 80 },
+    { host_port = 5353, container_port = 53, protocol = "udp" },
+]
+"#;
+
+    const SAMPLE_YAML: &str = r#"
+name: web
+image: nginx:1.25
+command: [nginx, -g, "daemon off;"]
+workdir: /srv
+profile: performance
+env:
+  RUST_LOG: info
+network:
+  isolated: false
+  ip_address: 10.88.0.2
+  dns_servers: [1.1.1.1]
+  ports:
+    - { host_port: 8080, container_port: 80 }
+    - { host_port: 5353, container_port: 53, protocol: udp }
+"#;
+
+    fn assert_sample_spec(spec: &ContainerSpec) {
+        assert_eq!(spec.id, "web");
+        assert_eq!(spec.image, "nginx:1.25");
+        assert_eq!(spec.command, "nginx");
+        assert_eq!(spec.args, vec!["-g", "daemon off;"]);
+        assert_eq!(spec.workdir, "/srv");
+        assert_eq!(spec.env["RUST_LOG"], "info");
+        assert_eq!(spec.profile, Some(ResourceProfile::Performance));
+
+        let network = spec.network.as_ref().expect("network settings");
+        assert!(!network.isolated);
+        assert_eq!(network.ip_address.as_deref(), Some("10.88.0.2"));
+        assert_eq!(network.dns_servers, vec!["1.1.1.1"]);
+        assert_eq!(
+            network.port_mappings,
+            vec![
+                PortMap { host_port: 8080, container_port: 80, protocol: Protocol::Tcp },
+                PortMap { host_port: 5353, container_port: 53, protocol: Protocol::Udp },
+            ]
+        );
+    }
+
+    fn write_file(dir: &Path, name: &str, contents: &str) -> std::path::PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_from_path_toml_and_yaml() {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, contents) in [
+            ("web.toml", SAMPLE_TOML),
+            ("web.yaml", SAMPLE_YAML),
+            ("web.yml", SAMPLE_YAML),
+        ] {
+            let spec = Envirofile::from_path(write_file(dir.path(), name, contents)).unwrap();
+            assert_sample_spec(&spec);
+        }
+    }
+
+    #[test]
+    fn test_from_path_detects_format_without_extension() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let toml_dir = dir.path().join("toml");
+        std::fs::create_dir(&toml_dir).unwrap();
+        assert_sample_spec(&Envirofile::from_path(write_file(&toml_dir, "Envirofile", SAMPLE_TOML)).unwrap());
+
+        let yaml_dir = dir.path().join("yaml");
+        std::fs::create_dir(&yaml_dir).unwrap();
+        assert_sample_spec(&Envirofile::from_path(write_file(&yaml_dir, "Envirofile", SAMPLE_YAML)).unwrap());
+    }
+
+    #[test]
+    fn test_minimal_file_uses_defaults() {
+        let spec = Envirofile::parse("image = \"alpine\"\ncommand = [\"true\"]\n", Format::Toml)
+            .unwrap()
+            .into_spec()
+            .unwrap();
+
+        // Left for the runtime's ID generator
+        assert!(spec.id.is_empty());
+        assert_eq!(spec.command, "true");
+        assert!(spec.args.is_empty());
+        assert!(spec.env.is_empty());
+        assert_eq!(spec.workdir, "/");
+        assert_eq!(spec.profile, None);
+        assert!(spec.network.is_none());
+    }
+
+    #[test]
+    fn test_custom_profile_and_network_defaults() {
+        let file = Envirofile::parse(
+            r#"
+image: alpine
+command: ["sh", "-c", "true"]
+profile:
+  custom:
+    memory.max: 1073741824
+    pids.max: 32
+network:
+  dns_servers: [9.9.9.9]
+"#,
+            Format::Yaml,
+        )
+        .unwrap();
+
+        assert_eq!(
+            file.profile,
+            Some(ResourceProfile::Custom(HashMap::from([
+                ("memory.max".to_string(), 1 << 30),
+                ("pids.max".to_string(), 32),
+            ])))
+        );
+        let network = file.network.unwrap();
+        assert!(network.isolated);
+        assert!(network.ports.is_empty());
+    }
+
+    #[test]
+    fn test_rejects_unknown_fields() {
+        let top_level = Envirofile::parse(
+            "image = \"alpine\"\ncommand = [\"true\"]\nentrypoint = \"sh\"\n",
+            Format::Toml,
+        )
+        .unwrap_err();
+        assert!(format!("{:#}", top_level).contains("unknown field `entrypoint`"), "{:#}", top_level);
+
+        let nested = Envirofile::parse(
+            "image: alpine\ncommand: [\"true\"]\nnetwork:\n  dns_server: [1.1.1.1]\n",
+            Format::Yaml,
+        )
+        .unwrap_err();
+        assert!(format!("{:#}", nested).contains("unknown field `dns_server`"), "{:#}", nested);
+
+        let port = Envirofile::parse(
+            "image = \"alpine\"\ncommand = [\"true\"]\n[network]\nports = [{ host_port = 1, container_port = 2, proto = \"tcp\" }]\n",
+            Format::Toml,
+        )
+        .unwrap_err();
+        assert!(format!("{:#}", port).contains("unknown field `proto`"), "{:#}", port);
+    }
+
+    #[test]
+    fn test_rejects_invalid_values() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let empty_command = write_file(dir.path(), "a.toml", "image = \"alpine\"\ncommand = []\n");
+        let err = Envirofile::from_path(&empty_command).unwrap_err();
+        assert!(err.to_string().contains("'command'"), "{:#}", err);
+
+        let bad_image = write_file(dir.path(), "b.toml", "image = \"Alpine:\"\ncommand = [\"true\"]\n");
+        assert!(Envirofile::from_path(&bad_image).is_err());
+
+        let missing_image = write_file(dir.path(), "c.toml", "command = [\"true\"]\n");
+        let err = Envirofile::from_path(&missing_image).unwrap_err();
+        assert!(format!("{:#}", err).contains("missing field `image`"), "{:#}", err);
+
+        let err = Envirofile::from_path(dir.path().join("missing.toml")).unwrap_err();
+        assert!(err.to_string().starts_with("Failed to read"), "{:#}", err);
+    }
+}
@@ -0,0 +1,190 @@
+//! ID Generation for Containers and Namespaces
+//!
+//! The runtime mints an ID whenever a container is started without one, and
+//! for every namespace it creates. A process-local counter is enough for a
+//! single host, but IDs handed out by several hosts in a cluster must not
+//! collide, so the generator is pluggable.
+//!
+//! # Performance-First Design:
+//! - `UuidGenerator` draws 16 bytes from the OS per ID; no shared state
+//! - `CounterGenerator` is a pair of atomics, lock-free and monotonic
+//! - Generators are `Send + Sync` and shared by `Arc` across runtime clones
+
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Mints container and namespace IDs for a [`FastRuntime`]
+///
+/// [`FastRuntime`]: crate::runtime::FastRuntime
+pub trait IdGenerator: Send + Sync {
+    /// A new container ID
+    fn container_id(&self) -> String;
+
+    /// A new, non-zero namespace ID
+    fn namespace_id(&self) -> u64;
+}
+
+/// Random IDs that stay unique across hosts
+///
+/// Container IDs are version 4 UUIDs in their hyphenated form; namespace IDs
+/// are 64 random bits.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UuidGenerator;
+
+impl UuidGenerator {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl IdGenerator for UuidGenerator {
+    fn container_id(&self) -> String {
+        let mut bytes: [u8; 16] = random_bytes();
+        // Version 4 (random), RFC 4122 variant
+        bytes[6] = (bytes[6] & 0x0f) | 0x40;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+        Uuid(bytes).to_string()
+    }
+
+    fn namespace_id(&self) -> u64 {
+        u64::from_ne_bytes(random_bytes()).max(1)
+    }
+}
+
+/// Fill an array from the OS random number generator
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0; N];
+    getrandom::getrandom(&mut bytes).expect("OS random number generator unavailable");
+    bytes
+}
+
+/// Hyphenated lowercase hex form of a UUID
+struct Uuid([u8; 16]);
+
+impl fmt::Display for Uuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, byte) in self.0.iter().enumerate() {
+            if matches!(i, 4 | 6 | 8 | 10) {
+                f.write_str("-")?;
+            }
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// Sequential IDs starting at 1, unique within one generator
+///
+/// Container IDs are `<prefix>-<n>`. Cheap and readable, but two hosts
+/// using counters will hand out the same IDs.
+#[derive(Debug)]
+pub struct CounterGenerator {
+    prefix: String,
+    next_container: AtomicU64,
+    next_namespace: AtomicU64,
+}
+
+impl CounterGenerator {
+    /// Counter generator with the `enviro` prefix
+    pub fn new() -> Self {
+        Self::with_prefix("enviro")
+    }
+
+    /// Counter generator whose container IDs start with `prefix`
+    pub fn with_prefix(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            next_container: AtomicU64::new(1),
+            next_namespace: AtomicU64::new(1),
+        }
+    }
+}
+
+impl Default for CounterGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IdGenerator for CounterGenerator {
+    fn container_id(&self) -> String {
+        let n = self.next_container.fetch_add(1, Ordering::Relaxed);
+        format!("{}-{}", self.prefix, n)
+    }
+
+    fn namespace_id(&self) -> u64 {
+        self.next_namespace.fetch_add(1, Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    const GENERATIONS: usize = 10_000;
+
+    fn assert_unique(ids: &dyn IdGenerator) {
+        let containers: HashSet<_> = (0..GENERATIONS).map(|_| ids.container_id()).collect();
+        assert_eq!(containers.len(), GENERATIONS);
+        let namespaces: HashSet<_> = (0..GENERATIONS).map(|_| ids.namespace_id()).collect();
+        assert_eq!(namespaces.len(), GENERATIONS);
+        assert!(!namespaces.contains(&0));
+    }
+
+    #[test]
+    fn test_uuid_generator_unique() {
+        assert_unique(&UuidGenerator::new());
+    }
+
+    #[test]
+    fn test_uuid_format() {
+        let id = UuidGenerator.container_id();
+        let groups: Vec<_> = id.split('-').map(str::len).collect();
+        assert_eq!(groups, vec![8, 4, 4, 4, 12]);
+        assert!(id.chars().all(|c| c == '-' || c.is_ascii_hexdigit()));
+        assert!(!id.chars().any(|c| c.is_ascii_uppercase()));
+        assert_eq!(&id[14..15], "4");
+        assert!(matches!(&id[19..20], "8" | "9" | "a" | "b"));
+    }
+
+    #[test]
+    fn test_counter_generator_unique() {
+        assert_unique(&CounterGenerator::new());
+    }
+
+    #[test]
+    fn test_counter_generator_monotonic() {
+        let ids = CounterGenerator::with_prefix("web");
+        assert_eq!(ids.container_id(), "web-1");
+        assert_eq!(ids.container_id(), "web-2");
+
+        let namespaces: Vec<_> = (0..100).map(|_| ids.namespace_id()).collect();
+        assert_eq!(namespaces.first(), Some(&1));
+        assert!(namespaces.windows(2).all(|pair| pair[1] == pair[0] + 1));
+    }
+
+    #[test]
+    fn test_counter_generator_shared_across_threads() {
+        let ids = Arc::new(CounterGenerator::new());
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let ids = ids.clone();
+                std::thread::spawn(move || {
+                    (0..1000).map(|_| ids.namespace_id()).collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        let mut all = HashSet::new();
+        for handle in handles {
+            let seen = handle.join().unwrap();
+            // Each thread observes its own IDs in increasing order
+            assert!(seen.windows(2).all(|pair| pair[0] < pair[1]));
+            all.extend(seen);
+        }
+        assert_eq!(all.len(), 4000);
+        assert_eq!(ids.namespace_id(), 4001);
+    }
+}
@@ -0,0 +1,985 @@
+//! OCI Images - References and Registry Pulls
+//!
+//! [`ImageRef`] parses references the way `docker pull` does
+//! (`alpine` means `docker.io/library/alpine:latest`), and [`ImageStore`]
+//! pulls images over the registry HTTP API into a content-addressed blob
+//! store:
+//!
+//! ```text
+//! <root>/blobs/sha256/<hex>   manifests, configs and layers, by digest
+//! ```
+//!
+//! Only anonymous pulls are supported: the bearer token challenge used by
+//! Docker Hub and most public registries is answered without credentials.
+//!
+//! # Performance Pattern: Content Addressing
+//! Blobs are keyed by digest, so layers shared between images (or already
+//! present from an earlier pull) are never downloaded twice. Every download
+//! is hashed while it streams to disk and only moved into place once the
+//! digest matches.
+
+use anyhow::{Context, Result};
+use reqwest::header::{ACCEPT, CONTENT_TYPE, WWW_AUTHENTICATE};
+use reqwest::{Client, Response, StatusCode};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, info};
+
+/// Registry used when a reference names none
+pub const DEFAULT_REGISTRY: &str = "docker.io";
+
+/// Tag used when a reference has neither tag nor digest
+pub const DEFAULT_TAG: &str = "latest";
+
+/// Default location of the image store
+pub const DEFAULT_STORE_DIR: &str = "/var/lib/enviro/images";
+
+/// API endpoint behind the `docker.io` registry name
+const DOCKER_HUB_API: &str = "registry-1.docker.io";
+
+// Manifest media types understood by the puller
+pub const MEDIA_TYPE_OCI_INDEX: &str = "application/vnd.oci.image.index.v1+json";
+pub const MEDIA_TYPE_OCI_MANIFEST: &str = "application/vnd.oci.image.manifest.v1+json";
+pub const MEDIA_TYPE_DOCKER_MANIFEST_LIST: &str =
+    "application/vnd.docker.distribution.manifest.list.v2+json";
+pub const MEDIA_TYPE_DOCKER_MANIFEST: &str =
+    "application/vnd.docker.distribution.manifest.v2+json";
+
+/// A parsed image reference: `[registry/]repository[:tag][@digest]`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ImageRef {
+    /// Registry host (and port), e.g. `docker.io` or `localhost:5000`
+    pub registry: String,
+    /// Repository path, e.g. `library/alpine`
+    pub repository: String,
+    /// Tag; `latest` when neither tag nor digest was given
+    pub tag: Option<String>,
+    /// Content digest (`sha256:<hex>`) pinning the manifest
+    pub digest: Option<String>,
+}
+
+impl ImageRef {
+    /// Parse and normalize an image reference
+    ///
+    /// - A first path component containing `.` or `:`, or equal to
+    ///   `localhost`, is the registry; otherwise the registry is `docker.io`
+    /// - Single-component Docker Hub repositories live under `library/`
+    /// - Without tag or digest the tag is `latest`; with only a digest there
+    ///   is no tag
+    pub fn parse(reference: &str) -> Result<Self> {
+        let input = reference.trim();
+        anyhow::ensure!(!input.is_empty(), "Empty image reference");
+
+        let (name, digest) = match input.split_once('@') {
+            Some((name, digest)) => (name, Some(parse_digest(digest)?)),
+            None => (input, None),
+        };
+
+        // A tag can only follow the last path component, so the `:` of a
+        // registry port is never mistaken for one.
+        let last = name.rfind('/').map_or(0, |i| i + 1);
+        let (name, tag) = match name[last..].rfind(':') {
+            Some(i) => (&name[..last + i], Some(&name[last + i + 1..])),
+            None => (name, None),
+        };
+
+        let (registry, path) = match name.split_once('/') {
+            Some((first, rest))
+                if first.contains(['.', ':']) || first == "localhost" =>
+            {
+                (first, rest)
+            }
+            _ => (DEFAULT_REGISTRY, name),
+        };
+        let registry = match registry {
+            "index.docker.io" | "registry-1.docker.io" => DEFAULT_REGISTRY,
+            registry => registry,
+        };
+        let repository = if registry == DEFAULT_REGISTRY && !path.contains('/') {
+            format!("library/{}", path)
+        } else {
+            path.to_string()
+        };
+
+        for component in repository.split('/') {
+            anyhow::ensure!(
+                valid_path_component(component),
+                "Invalid repository name '{}' in '{}': components must be lowercase \
+                 letters and digits, optionally separated by '.', '_', '__' or '-'",
+                repository,
+                reference
+            );
+        }
+        if let Some(tag) = tag {
+            anyhow::ensure!(valid_tag(tag), "Invalid tag '{}' in '{}'", tag, reference);
+        }
+
+        let tag = match (tag, &digest) {
+            (Some(tag), _) => Some(tag.to_string()),
+            (None, None) => Some(DEFAULT_TAG.to_string()),
+            (None, Some(_)) => None,
+        };
+
+        Ok(Self {
+            registry: registry.to_string(),
+            repository,
+            tag,
+            digest,
+        })
+    }
+
+    /// Manifest reference used with the registry API: the digest if pinned,
+    /// otherwise the tag
+    pub fn reference(&self) -> &str {
+        self.digest
+            .as_deref()
+            .or(self.tag.as_deref())
+            .unwrap_or(DEFAULT_TAG)
+    }
+
+    /// Base URL of the registry's HTTP API
+    ///
+    /// Registries on `localhost` are spoken to over plain HTTP, like the
+    /// Docker client does.
+    pub fn api_base(&self) -> String {
+        let host = self.registry.split(':').next().unwrap_or_default();
+        if self.registry == DEFAULT_REGISTRY {
+            format!("https://{}", DOCKER_HUB_API)
+        } else if host == "localhost" || host == "127.0.0.1" {
+            format!("http://{}", self.registry)
+        } else {
+            format!("https://{}", self.registry)
+        }
+    }
+}
+
+impl fmt::Display for ImageRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.registry, self.repository)?;
+        if let Some(tag) = &self.tag {
+            write!(f, ":{}", tag)?;
+        }
+        if let Some(digest) = &self.digest {
+            write!(f, "@{}", digest)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for ImageRef {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+/// Validate a `sha256:<64 hex>` digest
+fn parse_digest(digest: &str) -> Result<String> {
+    let (algorithm, hex) = digest
+        .split_once(':')
+        .with_context(|| format!("Invalid digest '{}': expected <algorithm>:<hex>", digest))?;
+    anyhow::ensure!(
+        algorithm == "sha256",
+        "Unsupported digest algorithm '{}'",
+        algorithm
+    );
+    anyhow::ensure!(
+        hex.len() == 64 && hex.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')),
+        "Invalid sha256 digest '{}'",
+        digest
+    );
+    Ok(digest.to_string())
+}
+
+/// `<dir>/<algorithm>/<hex>` for a validated `digest`
+///
+/// Rejecting anything but a well-formed sha256 digest keeps a crafted
+/// digest such as `sha256:../../x` from naming a path outside `dir`.
+pub(crate) fn digest_path(dir: &Path, digest: &str) -> Result<PathBuf> {
+    parse_digest(digest)?;
+    let (algorithm, hex) = digest.split_once(':').context("Digest has no algorithm")?;
+    Ok(dir.join(algorithm).join(hex))
+}
+
+/// `[a-z0-9]+((\.|_|__|-+)[a-z0-9]+)*`
+fn valid_path_component(component: &str) -> bool {
+    let bytes = component.as_bytes();
+    let alnum = |b: &u8| b.is_ascii_lowercase() || b.is_ascii_digit();
+    if !bytes.first().is_some_and(alnum) || !bytes.last().is_some_and(alnum) {
+        return false;
+    }
+    component
+        .split(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit())
+        .filter(|separator| !separator.is_empty())
+        .all(|separator| {
+            matches!(separator, "." | "_" | "__") || separator.bytes().all(|b| b == b'-')
+        })
+}
+
+/// `[A-Za-z0-9_][A-Za-z0-9_.-]{0,127}`
+fn valid_tag(tag: &str) -> bool {
+    let word = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+    tag.len() <= 128
+        && tag.bytes().next().is_some_and(word)
+        && tag.bytes().all(|b| word(b) || b == b'.' || b == b'-')
+}
+
+/// A content descriptor from a manifest or index
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Descriptor {
+    media_type: String,
+    digest: String,
+    size: u64,
+    #[serde(default)]
+    platform: Option<Platform>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Platform {
+    architecture: String,
+    os: String,
+}
+
+/// Image manifest (OCI or Docker schema 2)
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    config: Descriptor,
+    layers: Vec<Descriptor>,
+}
+
+/// Multi-platform index (OCI index or Docker manifest list)
+#[derive(Debug, Deserialize)]
+struct Index {
+    manifests: Vec<Descriptor>,
+}
+
+/// One filesystem layer of a pulled image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layer {
+    /// Layer digest
+    pub digest: String,
+    /// Media type (compression) of the layer tarball
+    pub media_type: String,
+    /// Compressed size in bytes
+    pub size: u64,
+    /// Location of the blob in the store
+    pub path: PathBuf,
+}
+
+/// An image whose manifest, config and layers are all in the local store
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalImage {
+    /// Reference the image was pulled by
+    pub reference: ImageRef,
+    /// Digest of the platform-specific manifest
+    pub digest: String,
+    /// Location of the image config blob
+    pub config: PathBuf,
+    /// Layers, base layer first
+    pub layers: Vec<Layer>,
+    /// Root of the store holding the blobs
+    pub store: PathBuf,
+}
+
+/// Content-addressed image store backed by a directory
+pub struct ImageStore {
+    root: PathBuf,
+    client: Client,
+}
+
+impl ImageStore {
+    /// Open (or lazily create) a store rooted at `root`
+    pub fn new(root: impl Into<PathBuf>) -> Result<Self> {
+        let client = Client::builder()
+            .user_agent(concat!("enviro/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .context("Failed to create registry HTTP client")?;
+        Ok(Self {
+            root: root.into(),
+            client,
+        })
+    }
+
+    /// Root directory of the store
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Location of the blob with `digest`
+    ///
+    /// Fails if `digest` is not a valid sha256 digest.
+    pub fn blob_path(&self, digest: &str) -> Result<PathBuf> {
+        digest_path(&self.root.join("blobs"), digest)
+    }
+
+    /// Pull `image` for the current platform
+    ///
+    /// Blobs already in the store are reused without contacting the
+    /// registry for them.
+    pub async fn pull(&self, image: &ImageRef) -> Result<LocalImage> {
+        info!("Pulling {}", image);
+        let mut registry = Registry::new(&self.client, image);
+
+        let (mut media_type, mut body) = registry.manifest(image.reference()).await?;
+        let mut digest = sha256_digest(&body);
+        if let Some(pinned) = &image.digest {
+            anyhow::ensure!(
+                &digest == pinned,
+                "Manifest digest mismatch for {}: got {}",
+                image,
+                digest
+            );
+        }
+
+        if is_index(&media_type, &body) {
+            let index: Index = serde_json::from_slice(&body).context("Invalid image index")?;
+            let (os, arch) = current_platform();
+            let selected = select_manifest(&index, os, arch)
+                .with_context(|| format!("{} has no manifest for {}/{}", image, os, arch))?
+                .clone();
+            debug!("Selected {} for {}/{}", selected.digest, os, arch);
+
+            (media_type, body) = registry.manifest(&selected.digest).await?;
+            digest = sha256_digest(&body);
+            anyhow::ensure!(
+                digest == selected.digest,
+                "Manifest digest mismatch for {}: got {}",
+                selected.digest,
+                digest
+            );
+        }
+        debug!("Manifest {} ({})", digest, media_type);
+
+        let manifest: Manifest =
+            serde_json::from_slice(&body).with_context(|| format!("Invalid manifest for {}", image))?;
+        self.write_blob(&digest, &body).await?;
+
+        let config = self.fetch_blob(&mut registry, &manifest.config).await?;
+        let mut layers = Vec::with_capacity(manifest.layers.len());
+        for layer in &manifest.layers {
+            let path = self.fetch_blob(&mut registry, layer).await?;
+            layers.push(Layer {
+                digest: layer.digest.clone(),
+                media_type: layer.media_type.clone(),
+                size: layer.size,
+                path,
+            });
+        }
+
+        info!("Pulled {} ({} layers)", image, layers.len());
+        Ok(LocalImage {
+            reference: image.clone(),
+            digest,
+            config,
+            layers,
+            store: self.root.clone(),
+        })
+    }
+
+    /// Download `descriptor` into the store unless it is already there
+    async fn fetch_blob(&self, registry: &mut Registry<'_>, descriptor: &Descriptor) -> Result<PathBuf> {
+        let path = self.blob_path(&descriptor.digest)?;
+        if tokio::fs::try_exists(&path).await? {
+            debug!("Blob {} already present", descriptor.digest);
+            return Ok(path);
+        }
+
+        let mut response = registry.blob(&descriptor.digest).await?;
+        let partial = self.partial_path(&path).await?;
+        let mut file = tokio::fs::File::create(&partial)
+            .await
+            .with_context(|| format!("Failed to create {:?}", partial))?;
+
+        let mut hasher = Sha256::new();
+        let mut received = 0u64;
+        while let Some(chunk) = response.chunk().await? {
+            hasher.update(&chunk);
+            received += chunk.len() as u64;
+            file.write_all(&chunk).await?;
+        }
+        file.sync_all().await?;
+
+        let actual = format!("sha256:{}", hex(&hasher.finalize()));
+        if actual != descriptor.digest || received != descriptor.size {
+            let _ = tokio::fs::remove_file(&partial).await;
+            anyhow::bail!(
+                "Blob verification failed for {}: got {} ({} of {} bytes)",
+                descriptor.digest,
+                actual,
+                received,
+                descriptor.size
+            );
+        }
+
+        tokio::fs::rename(&partial, &path).await?;
+        debug!("Stored blob {} ({} bytes)", descriptor.digest, received);
+        Ok(path)
+    }
+
+    async fn write_blob(&self, digest: &str, data: &[u8]) -> Result<PathBuf> {
+        let path = self.blob_path(digest)?;
+        let partial = self.partial_path(&path).await?;
+        tokio::fs::write(&partial, data).await?;
+        tokio::fs::rename(&partial, &path).await?;
+        Ok(path)
+    }
+
+    /// Temporary sibling of `path`, creating its directory if needed
+    async fn partial_path(&self, path: &Path) -> Result<PathBuf> {
+        let dir = path.parent().context("Blob path has no parent")?;
+        tokio::fs::create_dir_all(dir)
+            .await
+            .with_context(|| format!("Failed to create {:?}", dir))?;
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(format!(".{}.partial", std::process::id()));
+        Ok(partial.into())
+    }
+}
+
+/// Pull `image` into the store at [`DEFAULT_STORE_DIR`]
+pub async fn pull(image: &ImageRef) -> Result<LocalImage> {
+    ImageStore::new(DEFAULT_STORE_DIR)?.pull(image).await
+}
+
+/// Registry API session for one repository, holding its bearer token
+struct Registry<'a> {
+    client: &'a Client,
+    base: String,
+    repository: String,
+    token: Option<String>,
+}
+
+impl<'a> Registry<'a> {
+    fn new(client: &'a Client, image: &ImageRef) -> Self {
+        Self {
+            client,
+            base: image.api_base(),
+            repository: image.repository.clone(),
+            token: None,
+        }
+    }
+
+    /// Fetch a manifest; returns its media type and raw bytes
+    async fn manifest(&mut self, reference: &str) -> Result<(String, Vec<u8>)> {
+        let url = format!("{}/v2/{}/manifests/{}", self.base, self.repository, reference);
+        let accept = [
+            MEDIA_TYPE_OCI_INDEX,
+            MEDIA_TYPE_DOCKER_MANIFEST_LIST,
+            MEDIA_TYPE_OCI_MANIFEST,
+            MEDIA_TYPE_DOCKER_MANIFEST,
+        ]
+        .join(", ");
+
+        let response = self.get(&url, Some(&accept)).await?;
+        let media_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let body = response.bytes().await?;
+        Ok((media_type, body.to_vec()))
+    }
+
+    async fn blob(&mut self, digest: &str) -> Result<Response> {
+        let url = format!("{}/v2/{}/blobs/{}", self.base, self.repository, digest);
+        self.get(&url, None).await
+    }
+
+    /// GET `url`, answering a bearer challenge once
+    async fn get(&mut self, url: &str, accept: Option<&str>) -> Result<Response> {
+        let mut response = self.send(url, accept).await?;
+        if response.status() == StatusCode::UNAUTHORIZED && self.token.is_none() {
+            let challenge = response
+                .headers()
+                .get(WWW_AUTHENTICATE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_bearer_challenge)
+                .with_context(|| format!("{} requires authentication", url))?;
+            self.token = Some(self.fetch_token(&challenge).await?);
+            response = self.send(url, accept).await?;
+        }
+
+        let status = response.status();
+        anyhow::ensure!(status.is_success(), "GET {} failed: {}", url, status);
+        Ok(response)
+    }
+
+    async fn send(&self, url: &str, accept: Option<&str>) -> Result<Response> {
+        let mut request = self.client.get(url);
+        if let Some(accept) = accept {
+            request = request.header(ACCEPT, accept);
+        }
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        request
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach {}", url))
+    }
+
+    /// Obtain an anonymous pull token from the challenge's realm
+    async fn fetch_token(&self, challenge: &BearerChallenge) -> Result<String> {
+        #[derive(Deserialize)]
+        struct TokenResponse {
+            token: Option<String>,
+            access_token: Option<String>,
+        }
+
+        let mut query = Vec::new();
+        if let Some(service) = &challenge.service {
+            query.push(("service", service.clone()));
+        }
+        let scope = challenge
+            .scope
+            .clone()
+            .unwrap_or_else(|| format!("repository:{}:pull", self.repository));
+        query.push(("scope", scope));
+
+        let response = self
+            .client
+            .get(&challenge.realm)
+            .query(&query)
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach token service {}", challenge.realm))?;
+        let status = response.status();
+        anyhow::ensure!(status.is_success(), "Token request to {} failed: {}", challenge.realm, status);
+
+        let token: TokenResponse = response.json().await.context("Invalid token response")?;
+        token
+            .token
+            .or(token.access_token)
+            .context("Token response contains no token")
+    }
+}
+
+/// Parameters of a `WWW-Authenticate: Bearer ...` challenge
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BearerChallenge {
+    realm: String,
+    service: Option<String>,
+    scope: Option<String>,
+}
+
+fn parse_bearer_challenge(header: &str) -> Option<BearerChallenge> {
+    let (scheme, params) = header.trim().split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case("bearer") {
+        return None;
+    }
+
+    let (mut realm, mut service, mut scope) = (None, None, None);
+    let mut rest = params.trim();
+    while !rest.is_empty() {
+        let (key, after) = rest.split_once('=')?;
+        let (value, after) = match after.strip_prefix('"') {
+            Some(quoted) => {
+                let end = quoted.find('"')?;
+                (&quoted[..end], &quoted[end + 1..])
+            }
+            None => after.split_once(',').unwrap_or((after, "")),
+        };
+        match key.trim() {
+            "realm" => realm = Some(value.to_string()),
+            "service" => service = Some(value.to_string()),
+            "scope" => scope = Some(value.to_string()),
+            _ => {}
+        }
+        rest = after.trim_start_matches([',', ' ']);
+    }
+
+    Some(BearerChallenge {
+        realm: realm?,
+        service,
+        scope,
+    })
+}
+
+fn is_index(media_type: &str, body: &[u8]) -> bool {
+    match media_type {
+        MEDIA_TYPE_OCI_INDEX | MEDIA_TYPE_DOCKER_MANIFEST_LIST => true,
+        MEDIA_TYPE_OCI_MANIFEST | MEDIA_TYPE_DOCKER_MANIFEST => false,
+        // Untyped response: an index is the one with `manifests`
+        _ => serde_json::from_slice::<Index>(body).is_ok(),
+    }
+}
+
+/// OS and architecture in OCI platform terms
+fn current_platform() -> (&'static str, &'static str) {
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        "x86" => "386",
+        "powerpc64" => "ppc64le",
+        other => other,
+    };
+    (std::env::consts::OS, arch)
+}
+
+fn select_manifest<'a>(index: &'a Index, os: &str, arch: &str) -> Option<&'a Descriptor> {
+    index.manifests.iter().find(|descriptor| {
+        descriptor
+            .platform
+            .as_ref()
+            .is_some_and(|platform| platform.os == os && platform.architecture == arch)
+    })
+}
+
+fn sha256_digest(data: &[u8]) -> String {
+    format!("sha256:{}", hex(&Sha256::digest(data)))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    const DIGEST: &str = "sha256:4bcff63911fcb4448bd4fdacec207030997caf25e9bea4045fa6c8c44de311d1";
+
+    fn parsed(reference: &str) -> (String, String, Option<String>, Option<String>) {
+        let image = ImageRef::parse(reference).unwrap();
+        (image.registry, image.repository, image.tag, image.digest)
+    }
+
+    fn expect(
+        registry: &str,
+        repository: &str,
+        tag: Option<&str>,
+        digest: Option<&str>,
+    ) -> (String, String, Option<String>, Option<String>) {
+        (
+            registry.to_string(),
+            repository.to_string(),
+            tag.map(str::to_string),
+            digest.map(str::to_string),
+        )
+    }
+
+    #[test]
+    fn test_parse_implicit_registry_and_tag() {
+        assert_eq!(parsed("alpine"), expect("docker.io", "library/alpine", Some("latest"), None));
+        assert_eq!(parsed("alpine:3.19"), expect("docker.io", "library/alpine", Some("3.19"), None));
+        assert_eq!(parsed("bitnami/redis"), expect("docker.io", "bitnami/redis", Some("latest"), None));
+    }
+
+    #[test]
+    fn test_parse_explicit_registry() {
+        assert_eq!(
+            parsed("docker.io/library/alpine:3.19"),
+            expect("docker.io", "library/alpine", Some("3.19"), None)
+        );
+        assert_eq!(
+            parsed("index.docker.io/alpine"),
+            expect("docker.io", "library/alpine", Some("latest"), None)
+        );
+        assert_eq!(
+            parsed("ghcr.io/org/team/app:v1.2.3"),
+            expect("ghcr.io", "org/team/app", Some("v1.2.3"), None)
+        );
+        // No implicit `library/` outside Docker Hub
+        assert_eq!(parsed("quay.io/busybox"), expect("quay.io", "busybox", Some("latest"), None));
+    }
+
+    #[test]
+    fn test_parse_registry_port_is_not_a_tag() {
+        assert_eq!(
+            parsed("localhost:5000/app"),
+            expect("localhost:5000", "app", Some("latest"), None)
+        );
+        assert_eq!(
+            parsed("registry.local:5000/team/app:dev"),
+            expect("registry.local:5000", "team/app", Some("dev"), None)
+        );
+        assert_eq!(parsed("localhost/app:1"), expect("localhost", "app", Some("1"), None));
+    }
+
+    #[test]
+    fn test_parse_digests() {
+        let pinned = format!("alpine@{}", DIGEST);
+        assert_eq!(parsed(&pinned), expect("docker.io", "library/alpine", None, Some(DIGEST)));
+
+        let both = format!("ghcr.io/org/app:1.0@{}", DIGEST);
+        assert_eq!(parsed(&both), expect("ghcr.io", "org/app", Some("1.0"), Some(DIGEST)));
+
+        let image = ImageRef::parse(&both).unwrap();
+        assert_eq!(image.reference(), DIGEST);
+        assert_eq!(ImageRef::parse("alpine:edge").unwrap().reference(), "edge");
+    }
+
+    #[test]
+    fn test_blob_path_rejects_traversal() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ImageStore::new(dir.path()).unwrap();
+
+        assert_eq!(
+            store.blob_path(DIGEST).unwrap(),
+            dir.path().join("blobs/sha256").join(&DIGEST["sha256:".len()..])
+        );
+        let bare_hex = DIGEST.trim_start_matches("sha256:");
+        for digest in ["sha256:../../x", "../../etc:passwd", "sha256:", bare_hex] {
+            assert!(store.blob_path(digest).is_err(), "accepted {}", digest);
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid() {
+        for reference in [
+            "",
+            "Alpine",
+            "alpine:",
+            "alpine:-bad",
+            "library//alpine",
+            "alpine.",
+            "my..app",
+            "alpine@sha256:abc",
+            "alpine@md5:4bcff63911fcb4448bd4fdacec207030",
+            &format!("alpine@{}", DIGEST.to_uppercase()),
+        ] {
+            assert!(ImageRef::parse(reference).is_err(), "accepted {:?}", reference);
+        }
+        assert!(ImageRef::parse("my__app-x.y--z").is_ok());
+    }
+
+    #[test]
+    fn test_display_round_trips() {
+        for reference in [
+            "docker.io/library/alpine:latest",
+            "localhost:5000/app:dev",
+            &format!("ghcr.io/org/app:1.0@{}", DIGEST),
+            &format!("docker.io/library/alpine@{}", DIGEST),
+        ] {
+            let image: ImageRef = reference.parse().unwrap();
+            assert_eq!(image.to_string(), reference);
+            assert_eq!(ImageRef::parse(&image.to_string()).unwrap(), image);
+        }
+    }
+
+    #[test]
+    fn test_api_base() {
+        let base = |reference: &str| ImageRef::parse(reference).unwrap().api_base();
+        assert_eq!(base("alpine"), "https://registry-1.docker.io");
+        assert_eq!(base("ghcr.io/org/app"), "https://ghcr.io");
+        assert_eq!(base("localhost:5000/app"), "http://localhost:5000");
+    }
+
+    #[test]
+    fn test_parse_bearer_challenge() {
+        let challenge = parse_bearer_challenge(
+            r#"Bearer realm="https://auth.docker.io/token",service="registry.docker.io",scope="repository:library/alpine:pull""#,
+        )
+        .unwrap();
+        assert_eq!(
+            challenge,
+            BearerChallenge {
+                realm: "https://auth.docker.io/token".to_string(),
+                service: Some("registry.docker.io".to_string()),
+                scope: Some("repository:library/alpine:pull".to_string()),
+            }
+        );
+
+        assert!(parse_bearer_challenge(r#"Basic realm="registry""#).is_none());
+        assert!(parse_bearer_challenge(r#"Bearer service="x""#).is_none());
+    }
+
+    #[test]
+    fn test_select_manifest() {
+        let index: Index = serde_json::from_str(
+            r#"{"manifests": [
+                {"mediaType": "application/vnd.oci.image.manifest.v1+json", "size": 1,
+                 "digest": "sha256:aa", "platform": {"architecture": "arm64", "os": "linux"}},
+                {"mediaType": "application/vnd.oci.image.manifest.v1+json", "size": 1,
+                 "digest": "sha256:bb", "platform": {"architecture": "amd64", "os": "linux"}},
+                {"mediaType": "application/vnd.oci.image.manifest.v1+json", "size": 1,
+                 "digest": "sha256:cc"}
+            ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(select_manifest(&index, "linux", "amd64").unwrap().digest, "sha256:bb");
+        assert_eq!(select_manifest(&index, "linux", "arm64").unwrap().digest, "sha256:aa");
+        assert!(select_manifest(&index, "windows", "amd64").is_none());
+        assert!(is_index("", br#"{"manifests": []}"#));
+    }
+
+    /// Serve `routes` (path -> content type, body) like a registry behind a
+    /// bearer token; returns the registry address and a blob request counter
+    async fn mock_registry(
+        routes: HashMap<String, (&'static str, Vec<u8>)>,
+    ) -> (String, Arc<AtomicUsize>) {
+        use tokio::io::AsyncReadExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let blob_requests = Arc::new(AtomicUsize::new(0));
+        let routes = Arc::new(routes);
+
+        let counter = blob_requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let (routes, counter) = (routes.clone(), counter.clone());
+                tokio::spawn(async move {
+                    let mut head = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                        let n = socket.read(&mut buf).await.unwrap();
+                        if n == 0 {
+                            return;
+                        }
+                        head.extend_from_slice(&buf[..n]);
+                    }
+                    let head = String::from_utf8_lossy(&head).to_string();
+                    let path = head.split_whitespace().nth(1).unwrap_or_default();
+                    let authorized = head
+                        .lines()
+                        .any(|line| line.eq_ignore_ascii_case("authorization: bearer t0k"));
+
+                    let (status, extra, content_type, body) = if path.starts_with("/token?") {
+                        ("200 OK", String::new(), "application/json", br#"{"token":"t0k"}"#.to_vec())
+                    } else if !authorized {
+                        let challenge = format!(
+                            "WWW-Authenticate: Bearer realm=\"http://{}/token\",service=\"mock\"\r\n",
+                            addr
+                        );
+                        ("401 Unauthorized", challenge, "text/plain", Vec::new())
+                    } else if let Some((content_type, body)) = routes.get(path) {
+                        if path.contains("/blobs/") {
+                            counter.fetch_add(1, Ordering::SeqCst);
+                        }
+                        ("200 OK", String::new(), *content_type, body.clone())
+                    } else {
+                        ("404 Not Found", String::new(), "text/plain", Vec::new())
+                    };
+
+                    let response = format!(
+                        "HTTP/1.1 {}\r\n{}Content-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        status,
+                        extra,
+                        content_type,
+                        body.len()
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                    let _ = socket.write_all(&body).await;
+                });
+            }
+        });
+
+        (addr.to_string(), blob_requests)
+    }
+
+    /// Routes for a one-layer image `test/app:1.0` behind an index
+    fn image_routes(layer: &[u8], served_layer: &[u8]) -> (HashMap<String, (&'static str, Vec<u8>)>, String) {
+        let config = br#"{"architecture":"amd64","os":"linux"}"#.to_vec();
+        let manifest = serde_json::json!({
+            "schemaVersion": 2,
+            "mediaType": MEDIA_TYPE_OCI_MANIFEST,
+            "config": {
+                "mediaType": "application/vnd.oci.image.config.v1+json",
+                "digest": sha256_digest(&config),
+                "size": config.len(),
+            },
+            "layers": [{
+                "mediaType": "application/vnd.oci.image.layer.v1.tar+gzip",
+                "digest": sha256_digest(layer),
+                "size": layer.len(),
+            }],
+        })
+        .to_string()
+        .into_bytes();
+        let (os, arch) = current_platform();
+        let index = serde_json::json!({
+            "schemaVersion": 2,
+            "manifests": [{
+                "mediaType": MEDIA_TYPE_OCI_MANIFEST,
+                "digest": sha256_digest(&manifest),
+                "size": manifest.len(),
+                "platform": {"architecture": arch, "os": os},
+            }],
+        })
+        .to_string()
+        .into_bytes();
+
+        let manifest_digest = sha256_digest(&manifest);
+        let routes = HashMap::from([
+            ("/v2/test/app/manifests/1.0".to_string(), (MEDIA_TYPE_OCI_INDEX, index)),
+            (
+                format!("/v2/test/app/manifests/{}", manifest_digest),
+                (MEDIA_TYPE_OCI_MANIFEST, manifest),
+            ),
+            (
+                format!("/v2/test/app/blobs/{}", sha256_digest(&config)),
+                ("application/octet-stream", config),
+            ),
+            (
+                format!("/v2/test/app/blobs/{}", sha256_digest(layer)),
+                ("application/octet-stream", served_layer.to_vec()),
+            ),
+        ]);
+        (routes, manifest_digest)
+    }
+
+    #[tokio::test]
+    async fn test_pull_from_mock_registry() {
+        let (routes, manifest_digest) = image_routes(b"layer-data", b"layer-data");
+        let (registry, blob_requests) = mock_registry(routes).await;
+        let dir = tempfile::tempdir().unwrap();
+        let store = ImageStore::new(dir.path()).unwrap();
+        let image = ImageRef::parse(&format!("{}/test/app:1.0", registry)).unwrap();
+
+        let local = store.pull(&image).await.unwrap();
+        assert_eq!(local.digest, manifest_digest);
+        assert_eq!(local.layers.len(), 1);
+        assert_eq!(local.layers[0].path, store.blob_path(&sha256_digest(b"layer-data")).unwrap());
+        assert_eq!(std::fs::read(&local.layers[0].path).unwrap(), b"layer-data");
+        assert!(store.blob_path(&manifest_digest).unwrap().exists());
+        assert_eq!(blob_requests.load(Ordering::SeqCst), 2);
+
+        // Blobs already in the store are not downloaded again
+        assert_eq!(store.pull(&image).await.unwrap(), local);
+        assert_eq!(blob_requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_pull_rejects_corrupt_blob() {
+        let (routes, _) = image_routes(b"layer-data", b"tampered!!");
+        let (registry, _) = mock_registry(routes).await;
+        let dir = tempfile::tempdir().unwrap();
+        let store = ImageStore::new(dir.path()).unwrap();
+        let image = ImageRef::parse(&format!("{}/test/app:1.0", registry)).unwrap();
+
+        let err = store.pull(&image).await.unwrap_err();
+        assert!(err.to_string().contains("verification failed"), "{:#}", err);
+        assert!(!store.blob_path(&sha256_digest(b"layer-data")).unwrap().exists());
+    }
+
+    /// Needs network access to Docker Hub: run with `--ignored`
+    #[tokio::test]
+    #[ignore]
+    async fn test_pull_from_docker_hub() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ImageStore::new(dir.path()).unwrap();
+        let image = ImageRef::parse("alpine:3.19").unwrap();
+
+        let local = store.pull(&image).await.unwrap();
+        assert!(!local.layers.is_empty());
+        assert!(local.config.exists());
+        for layer in &local.layers {
+            assert_eq!(std::fs::metadata(&layer.path).unwrap().len(), layer.size);
+        }
+
+        // Second pull is served from the store
+        assert_eq!(store.pull(&image).await.unwrap(), local);
+    }
+}
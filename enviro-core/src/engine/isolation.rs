@@ -10,41 +10,580 @@
 //! - Zero-copy /proc filesystem interactions via io_uring
 
 use anyhow::{Context, Result};
+use caps::{CapSet, CapsHashSet};
+use super::seccomp::{self, SeccompProfile};
+use crate::executor::DEFAULT_PATH;
 use nix::sched::CloneFlags;
+use nix::unistd::Pid;
+use std::collections::BTreeMap;
+use std::ffi::{CStr, CString, OsStr, OsString};
 use std::fs::{self};
-use std::os::unix::process::CommandExt;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::os::fd::RawFd;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+use std::process::{Command, ExitStatus};
 use tracing::{debug, info};
 
+pub use caps::Capability;
+
+/// A single line of a `uid_map` or `gid_map`
+///
+/// Maps `range` consecutive IDs starting at `container_id` inside the
+/// namespace onto IDs starting at `host_id` outside it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdMap {
+    /// First ID inside the namespace
+    pub container_id: u32,
+    /// First ID on the host
+    pub host_id: u32,
+    /// Number of IDs to map
+    pub range: u32,
+}
+
+impl IdMap {
+    /// Create a new mapping entry
+    pub fn new(container_id: u32, host_id: u32, range: u32) -> Self {
+        Self {
+            container_id,
+            host_id,
+            range,
+        }
+    }
+}
+
+/// Render ID maps in the `container_id host_id range` format expected by procfs
+///
+/// The kernel requires the whole map in a single write, so every entry is
+/// emitted as its own line of one string.
+pub fn format_id_maps(maps: &[IdMap]) -> String {
+    maps.iter()
+        .map(|m| format!("{} {} {}\n", m.container_id, m.host_id, m.range))
+        .collect()
+}
+
 /// Configuration for user namespace isolation
 #[derive(Debug, Clone)]
 pub struct IsolationConfig {
-    /// UID to map to container root (default: current user)
-    pub host_uid: u32,
-    /// GID to map to container root (default: current group)
-    pub host_gid: u32,
-    /// Number of UIDs to map (default: 65536 for full range)
-    pub uid_range: u32,
-    /// Number of GIDs to map (default: 65536 for full range)
-    pub gid_range: u32,
+    /// UID mappings (default: container root → current user, 65536 IDs)
+    pub uid_maps: Vec<IdMap>,
+    /// GID mappings (default: container root → current group, 65536 IDs)
+    pub gid_maps: Vec<IdMap>,
     /// Enable network namespace isolation
     pub isolate_network: bool,
     /// Enable mount namespace isolation
     pub isolate_mount: bool,
     /// Enable PID namespace isolation
     pub isolate_pid: bool,
+    /// Capabilities retained across exec; everything else is dropped (default: none)
+    pub keep_caps: Vec<Capability>,
+    /// Seccomp deny-list installed before exec (default: Docker-like profile)
+    pub seccomp: Option<SeccompProfile>,
+    /// Set `PR_SET_NO_NEW_PRIVS` before exec so setuid/setgid bits and file
+    /// capabilities cannot raise privileges (default: true)
+    ///
+    /// The kernel only lets an unprivileged process install a seccomp filter
+    /// once this bit is set, so installing [`seccomp`](Self::seccomp) sets it
+    /// too; `false` therefore only takes effect when `seccomp` is `None`.
+    pub no_new_privs: bool,
+    /// Root filesystem [`Isolation::spawn_in_namespace`] pivots into before
+    /// exec, with a private `/proc` and a read-only `/sys` (default: none,
+    /// the host's). Requires [`isolate_mount`](Self::isolate_mount).
+    pub rootfs: Option<PathBuf>,
 }
 
 impl Default for IsolationConfig {
     fn default() -> Self {
         Self {
-            host_uid: nix::unistd::getuid().as_raw(),
-            host_gid: nix::unistd::getgid().as_raw(),
-            uid_range: 65536,
-            gid_range: 65536,
+            uid_maps: vec![IdMap::new(0, nix::unistd::getuid().as_raw(), 65536)],
+            gid_maps: vec![IdMap::new(0, nix::unistd::getgid().as_raw(), 65536)],
             isolate_network: true,
             isolate_mount: true,
             isolate_pid: true,
+            keep_caps: Vec::new(),
+            seccomp: Some(SeccompProfile::docker_default()),
+            no_new_privs: true,
+            rootfs: None,
+        }
+    }
+}
+
+/// A subordinate ID allocation from `/etc/subuid` or `/etc/subgid`
+///
+/// Each line of those files has the form `<user>:<start>:<count>`, granting
+/// the user the host IDs `start..start + count` for use in namespace maps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubIdRange {
+    /// First host ID of the allocation
+    pub start: u32,
+    /// Number of IDs in the allocation
+    pub count: u32,
+}
+
+impl SubIdRange {
+    /// Returns `true` if `host_id..host_id + range` lies entirely inside this allocation
+    pub fn contains(&self, host_id: u32, range: u32) -> bool {
+        let end = u64::from(host_id) + u64::from(range);
+        host_id >= self.start && end <= u64::from(self.start) + u64::from(self.count)
+    }
+}
+
+/// Last ID of the `count` IDs starting at `start`, or `None` for an empty range
+fn last_id(start: u32, count: u32) -> Option<u64> {
+    let extra = count.checked_sub(1)?;
+    u64::from(start).checked_add(u64::from(extra))
+}
+
+/// Parse the allocations belonging to a user from subuid/subgid file contents
+///
+/// Entries may name the user either by login name or by numeric ID, so both
+/// are matched. Comments, blank lines, malformed entries and entries
+/// allocating no IDs are ignored.
+pub fn parse_subid_file(contents: &str, user: &str, id: u32) -> Vec<SubIdRange> {
+    let id = id.to_string();
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let owner = fields.next()?;
+            let start = fields.next()?.trim().parse().ok()?;
+            let count = fields.next()?.trim().parse().ok().filter(|&count| count > 0)?;
+            (owner == user || owner == id).then_some(SubIdRange { start, count })
+        })
+        .collect()
+}
+
+/// Check a requested mapping against a user's subordinate ID allocations
+///
+/// Mapping only the caller's own ID (range of 1) never needs an allocation;
+/// anything else must fit inside a single allocated range.
+pub fn validate_id_range(
+    kind: &str,
+    allocations: &[SubIdRange],
+    own_id: u32,
+    host_id: u32,
+    range: u32,
+) -> Result<()> {
+    let Some(last) = last_id(host_id, range) else {
+        anyhow::bail!("Cannot map an empty {kind} range starting at host {kind} {host_id}");
+    };
+    if host_id == own_id && range == 1 {
+        return Ok(());
+    }
+
+    if allocations.iter().any(|a| a.contains(host_id, range)) {
+        return Ok(());
+    }
+
+    if allocations.is_empty() {
+        anyhow::bail!(
+            "No /etc/sub{kind} allocation found for the current user; \
+             cannot map {range} {kind}s starting at host {kind} {host_id}"
+        );
+    }
+
+    let available = allocations
+        .iter()
+        .filter_map(|a| Some(format!("{}-{}", a.start, last_id(a.start, a.count)?)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    anyhow::bail!(
+        "Requested {kind} range {host_id}-{} ({range} IDs) exceeds the /etc/sub{kind} \
+         allocation for the current user ({available})",
+        last
+    )
+}
+
+/// `_LINUX_CAPABILITY_VERSION_3`: 64-bit capability sets in two words
+const CAPABILITY_VERSION_3: u32 = 0x2008_0522;
+
+/// `struct __user_cap_header_struct`
+#[repr(C)]
+struct CapUserHeader {
+    version: u32,
+    pid: libc::c_int,
+}
+
+/// `struct __user_cap_data_struct`
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct CapUserData {
+    effective: u32,
+    permitted: u32,
+    inheritable: u32,
+}
+
+/// Capability sets to install before `exec`, worked out ahead of `fork`
+///
+/// Reading the current sets allocates, so [`CapabilityDrop::new`] runs in
+/// the parent. The child inherits the same sets and [`apply`](Self::apply)
+/// only issues `prctl` and `capset`, which is safe after `fork` in a
+/// multi-threaded process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapabilityDrop {
+    /// Capabilities to remove from the bounding set; empty without CAP_SETPCAP
+    bounding: Vec<Capability>,
+    /// Mask kept in the effective, permitted and inheritable sets
+    retained: u64,
+}
+
+impl CapabilityDrop {
+    /// Plan dropping every capability not listed in `keep` from the calling
+    /// process (or a child forked from it)
+    pub fn new(keep: &[Capability]) -> Result<Self> {
+        let keep: CapsHashSet = keep.iter().copied().collect();
+
+        // Trimming the bounding set needs CAP_SETPCAP; unprivileged callers
+        // have nothing to lose there and no_new_privs covers setuid binaries.
+        let mut bounding = Vec::new();
+        if caps::has_cap(None, CapSet::Effective, Capability::CAP_SETPCAP).unwrap_or(false) {
+            let current =
+                caps::read(None, CapSet::Bounding).context("Failed to read bounding set")?;
+            bounding.extend(current.difference(&keep));
+            bounding.sort_by_key(Capability::index);
+        }
+
+        // Only keep what we actually hold; raising a capability we lack fails.
+        let permitted =
+            caps::read(None, CapSet::Permitted).context("Failed to read permitted set")?;
+        let retained = permitted
+            .intersection(&keep)
+            .fold(0, |mask, cap| mask | cap.bitmask());
+
+        Ok(Self { bounding, retained })
+    }
+
+    /// Plan dropping every capability not listed in `keep` from a child
+    /// cloned into a fresh user namespace
+    ///
+    /// Such a child starts with every capability the kernel supports, in
+    /// its bounding set as well, whatever the caller holds; root `exec`s
+    /// with that whole bounding set. So all of it outside `keep` is dropped
+    /// and all of `keep` retained.
+    pub fn for_new_user_namespace(keep: &[Capability]) -> Self {
+        let keep: CapsHashSet = keep.iter().copied().collect();
+        let supported = caps::runtime::thread_all_supported();
+
+        let mut bounding: Vec<_> = supported.difference(&keep).copied().collect();
+        bounding.sort_by_key(Capability::index);
+        let retained = supported
+            .intersection(&keep)
+            .fold(0, |mask, cap| mask | cap.bitmask());
+
+        Self { bounding, retained }
+    }
+
+    /// Apply the plan to the calling process
+    ///
+    /// The bounding set is trimmed first (while CAP_SETPCAP is still
+    /// effective), then the ambient set is cleared and the effective,
+    /// permitted and inheritable sets are replaced, so nothing outside
+    /// `keep` survives a later `exec`. Neither allocates nor locks, so this
+    /// may run in a `pre_exec` hook; failures are a bare errno.
+    pub fn apply(&self) -> std::io::Result<()> {
+        for cap in &self.bounding {
+            let cap = libc::c_ulong::from(cap.index());
+            // SAFETY: PR_CAPBSET_DROP takes a capability number and no pointers.
+            if unsafe { libc::prctl(libc::PR_CAPBSET_DROP, cap, 0, 0, 0) } != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+        }
+
+        // SAFETY: PR_CAP_AMBIENT_CLEAR_ALL takes no pointers.
+        let cleared = unsafe {
+            libc::prctl(libc::PR_CAP_AMBIENT, libc::PR_CAP_AMBIENT_CLEAR_ALL, 0, 0, 0)
+        };
+        if cleared != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        let mut header = CapUserHeader {
+            version: CAPABILITY_VERSION_3,
+            pid: 0,
+        };
+        let word = |shift: u32| {
+            let bits = (self.retained >> shift) as u32;
+            CapUserData {
+                effective: bits,
+                permitted: bits,
+                inheritable: bits,
+            }
+        };
+        let data = [word(0), word(32)];
+        // SAFETY: header and data match the version 3 layout capset expects.
+        let ret = unsafe { libc::syscall(libc::SYS_capset, &mut header, data.as_ptr()) };
+        if ret != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+/// Drop every capability not listed in `keep` from the calling process
+///
+/// Not for use after `fork`: plan with [`CapabilityDrop::new`] in the
+/// parent and call [`CapabilityDrop::apply`] in the child instead.
+pub fn drop_capabilities(keep: &[Capability]) -> Result<()> {
+    CapabilityDrop::new(keep)?
+        .apply()
+        .context("Failed to drop capabilities")
+}
+
+/// Directory (relative to the new root) where the old root is parked during `pivot_root`.
+const PIVOT_OLD_ROOT: &str = ".pivot_old";
+
+/// Mounts and `pivot_root` into a container rootfs, worked out ahead of
+/// `clone`
+///
+/// [`RootfsPivot::new`] resolves the rootfs, creates its mount points and
+/// builds every path in the parent, so [`apply`](Self::apply) only issues
+/// raw `mount`, `pivot_root`, `chdir`, `umount2` and `rmdir` syscalls in
+/// the child.
+#[derive(Debug)]
+struct RootfsPivot {
+    rootfs: CString,
+    proc_dir: CString,
+    sys_dir: CString,
+    /// Where the old root is parked, as seen before the pivot
+    put_old: CString,
+    /// The same directory as seen after the pivot
+    old_root: CString,
+}
+
+impl RootfsPivot {
+    /// Plan pivoting into `rootfs`
+    fn new(rootfs: &Path) -> Result<Self> {
+        if !rootfs.is_dir() {
+            anyhow::bail!(
+                "Container rootfs {} does not exist or is not a directory",
+                rootfs.display()
+            );
+        }
+        let rootfs = rootfs
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve rootfs {}", rootfs.display()))?;
+        for dir in ["proc", "sys", PIVOT_OLD_ROOT] {
+            let dir = rootfs.join(dir);
+            fs::create_dir_all(&dir)
+                .with_context(|| format!("Failed to create mount point {}", dir.display()))?;
+        }
+
+        let path = |path: &Path| ExecImage::c_string(path.as_os_str().as_bytes());
+        Ok(Self {
+            rootfs: path(&rootfs)?,
+            proc_dir: path(&rootfs.join("proc"))?,
+            sys_dir: path(&rootfs.join("sys"))?,
+            put_old: path(&rootfs.join(PIVOT_OLD_ROOT))?,
+            old_root: path(&Path::new("/").join(PIVOT_OLD_ROOT))?,
+        })
+    }
+
+    /// Switch the calling process into the rootfs as its new `/`
+    ///
+    /// Must run inside a fresh mount namespace, before seccomp denies
+    /// `mount` and `pivot_root`. The steps are:
+    /// 1. Make the existing mount tree private so nothing leaks to the host
+    /// 2. Bind-mount the rootfs onto itself (`pivot_root` needs a mount point)
+    /// 3. Mount a private `/proc` and a read-only `/sys` inside it
+    /// 4. `pivot_root` into it and lazily detach the old root
+    fn apply(&self) -> std::io::Result<()> {
+        let check = |ret: libc::c_long| {
+            if ret == 0 {
+                Ok(())
+            } else {
+                Err(std::io::Error::last_os_error())
+            }
+        };
+        let mount = |source: &CStr, target: &CStr, fstype: Option<&CStr>, flags| {
+            let fstype = fstype.map_or(std::ptr::null(), CStr::as_ptr);
+            // SAFETY: the strings were built before the fork and outlive the
+            // call; mount data is optional.
+            let ret = unsafe {
+                libc::mount(source.as_ptr(), target.as_ptr(), fstype, flags, std::ptr::null())
+            };
+            check(ret.into())
+        };
+
+        mount(c"none", c"/", None, libc::MS_REC | libc::MS_PRIVATE)?;
+        mount(&self.rootfs, &self.rootfs, None, libc::MS_BIND | libc::MS_REC)?;
+        let pseudo = libc::MS_NOSUID | libc::MS_NODEV | libc::MS_NOEXEC;
+        mount(c"proc", &self.proc_dir, Some(c"proc"), pseudo)?;
+        mount(c"sysfs", &self.sys_dir, Some(c"sysfs"), pseudo | libc::MS_RDONLY)?;
+
+        // SAFETY: plain syscalls on NUL-terminated strings built before the fork.
+        unsafe {
+            check(libc::syscall(libc::SYS_pivot_root, self.rootfs.as_ptr(), self.put_old.as_ptr()))?;
+            check(libc::chdir(c"/".as_ptr()).into())?;
+            check(libc::umount2(self.old_root.as_ptr(), libc::MNT_DETACH).into())?;
+            check(libc::rmdir(self.old_root.as_ptr()).into())
+        }
+    }
+}
+
+/// Stack size for the child created by [`Isolation::spawn_in_namespace`].
+///
+/// The child only runs long enough to wait for its ID maps and `exec`, so a
+/// small stack is plenty.
+const CHILD_STACK_SIZE: usize = 1024 * 1024;
+
+/// Program, argument and environment strings for `execve`, built before
+/// `clone` so the child never allocates
+struct ExecImage {
+    program: CString,
+    argv: Vec<CString>,
+    envp: Vec<CString>,
+    cwd: Option<CString>,
+}
+
+impl ExecImage {
+    /// Capture what `cmd` would run
+    ///
+    /// Nothing is inherited from the host environment, as if `env_clear`
+    /// had been called: the child gets only the variables set on `cmd`, plus
+    /// [`DEFAULT_PATH`] when it sets no `PATH`. Stdio is inherited. With a
+    /// `root`, the program is looked up in `PATH` under it, as the child will
+    /// see it after pivoting there.
+    fn from_command(cmd: &Command, root: Option<&Path>) -> Result<Self> {
+        let mut env: BTreeMap<OsString, OsString> = cmd
+            .get_envs()
+            .filter_map(|(key, value)| Some((key.to_os_string(), value?.to_os_string())))
+            .collect();
+        env.entry(OsString::from("PATH"))
+            .or_insert_with(|| OsString::from(DEFAULT_PATH));
+
+        let path = env.get(OsStr::new("PATH"));
+        let program = Self::resolve_program(cmd.get_program(), path, root)?;
+        let argv = std::iter::once(cmd.get_program())
+            .chain(cmd.get_args())
+            .map(|arg| Self::c_string(arg.as_bytes()))
+            .collect::<Result<_>>()?;
+        let envp = env
+            .iter()
+            .map(|(key, value)| Self::c_string(&[key.as_bytes(), b"=", value.as_bytes()].concat()))
+            .collect::<Result<_>>()?;
+        let cwd = cmd
+            .get_current_dir()
+            .map(|dir| Self::c_string(dir.as_os_str().as_bytes()))
+            .transpose()?;
+
+        Ok(Self {
+            program,
+            argv,
+            envp,
+            cwd,
+        })
+    }
+
+    /// Find `program` on `path` the way `execvp` would, unless it contains a `/`
+    ///
+    /// Under a `root`, symlinks are accepted without being followed, since
+    /// absolute targets only resolve once the child has pivoted.
+    fn resolve_program(
+        program: &OsStr,
+        path: Option<&OsString>,
+        root: Option<&Path>,
+    ) -> Result<CString> {
+        if program.as_bytes().contains(&b'/') {
+            return Self::c_string(program.as_bytes());
+        }
+        let executable = |meta: fs::Metadata| meta.is_file() && meta.permissions().mode() & 0o111 != 0;
+        let found = path
+            .into_iter()
+            .flat_map(std::env::split_paths)
+            .map(|dir| dir.join(program))
+            .find(|candidate| match root {
+                Some(root) => {
+                    let inside = root.join(candidate.strip_prefix("/").unwrap_or(candidate));
+                    fs::symlink_metadata(inside).is_ok_and(|meta| meta.is_symlink() || executable(meta))
+                }
+                None => fs::metadata(candidate).is_ok_and(executable),
+            })
+            .with_context(|| format!("{:?} not found in PATH", program))?;
+        Self::c_string(found.as_os_str().as_bytes())
+    }
+
+    fn c_string(bytes: &[u8]) -> Result<CString> {
+        CString::new(bytes).with_context(|| {
+            format!("{:?} contains a NUL byte", String::from_utf8_lossy(bytes))
+        })
+    }
+
+    /// NULL-terminated pointer array over `strings`
+    fn pointers(strings: &[CString]) -> Vec<*const libc::c_char> {
+        strings
+            .iter()
+            .map(|s| s.as_ptr())
+            .chain(std::iter::once(std::ptr::null()))
+            .collect()
+    }
+}
+
+/// A process spawned directly into fresh namespaces via `clone(2)`
+///
+/// Unlike [`std::process::Child`] this handle is not tied to the standard
+/// library's spawn machinery, since `clone` with namespace flags cannot go
+/// through [`Command::spawn`]. Dropping the handle kills the child if it is
+/// still running and reaps it, so it never lingers as a zombie.
+#[derive(Debug)]
+pub struct NamespaceChild {
+    pid: Pid,
+    status: Option<ExitStatus>,
+}
+
+impl NamespaceChild {
+    /// Host PID of the child (it sees itself as PID 1 when PID isolation is on)
+    pub fn id(&self) -> u32 {
+        self.pid.as_raw() as u32
+    }
+
+    /// Block until the child exits and return its exit status
+    pub fn wait(&mut self) -> Result<ExitStatus> {
+        if let Some(status) = self.status {
+            return Ok(status);
+        }
+
+        let mut raw = 0;
+        loop {
+            let res = unsafe { libc::waitpid(self.pid.as_raw(), &mut raw, 0) };
+            if res >= 0 {
+                break;
+            }
+            let err = std::io::Error::last_os_error();
+            if err.kind() != std::io::ErrorKind::Interrupted {
+                return Err(err).with_context(|| format!("Failed to wait for PID {}", self.pid));
+            }
+        }
+
+        let status = ExitStatus::from_raw(raw);
+        self.status = Some(status);
+        Ok(status)
+    }
+
+    /// Send SIGKILL to the child
+    pub fn kill(&mut self) -> Result<()> {
+        if self.status.is_some() {
+            return Ok(());
+        }
+        nix::sys::signal::kill(self.pid, nix::sys::signal::Signal::SIGKILL)
+            .with_context(|| format!("Failed to kill PID {}", self.pid))
+    }
+}
+
+impl Drop for NamespaceChild {
+    fn drop(&mut self) {
+        if self.status.is_some() {
+            return;
+        }
+        let mut raw = 0;
+        // SAFETY: waitpid only writes the status through the valid pointer.
+        let exited = unsafe { libc::waitpid(self.pid.as_raw(), &mut raw, libc::WNOHANG) };
+        if exited == 0 {
+            debug!(pid = self.pid.as_raw(), "Killing namespaced child on drop");
+            let _ = self.kill();
+            let _ = self.wait();
         }
     }
 }
@@ -83,14 +622,146 @@ impl Isolation {
     /// - No runtime overhead for permission checks
     pub fn create_user_namespace(&self) -> Result<()> {
         info!("Creating user namespace with zero-trust mapping");
+        self.validate_subid_ranges()?;
         debug!(
-            "Mapping host UID {} → container UID 0",
-            self.config.host_uid
+            "Mapping {} UID range(s) and {} GID range(s)",
+            self.config.uid_maps.len(),
+            self.config.gid_maps.len()
         );
 
-        // Build clone flags for namespace isolation
+        // unshare(CLONE_NEWPID) would only affect our future children, not the
+        // calling process, so PID isolation is left to spawn_in_namespace().
+        let flags = self.clone_flags() - CloneFlags::CLONE_NEWPID;
+
+        nix::sched::unshare(flags)
+            .context("Failed to create user namespace")?;
+
+        self.write_id_maps("/proc/self")?;
+
+        info!("User namespace created successfully");
+        Ok(())
+    }
+
+    /// Spawn `cmd` as the first process of a fresh set of namespaces.
+    ///
+    /// Uses `clone(2)` with the configured namespace flags, so with PID
+    /// isolation enabled the spawned process becomes PID 1 of its own PID
+    /// namespace. The child blocks on a pipe until the parent has written its
+    /// UID/GID maps, pivots into [`rootfs`](IsolationConfig::rootfs) if one
+    /// is configured, drops every capability outside
+    /// [`keep_caps`](IsolationConfig::keep_caps), applies
+    /// [`no_new_privs`](IsolationConfig::no_new_privs), installs the
+    /// [`seccomp`](IsolationConfig::seccomp) filter, then `exec`s the command.
+    ///
+    /// The host environment is not inherited; only variables set on `cmd`
+    /// reach the child.
+    ///
+    /// # Performance Notes:
+    /// - One `clone` + one `exec`; no intermediate shim process
+    /// - The child stack is only used until `exec` replaces the image
+    pub fn spawn_in_namespace(&self, cmd: Command) -> Result<NamespaceChild> {
+        info!("Spawning command in new namespaces: {:?}", cmd);
+        let rootfs = match &self.config.rootfs {
+            Some(_) if !self.config.isolate_mount => {
+                anyhow::bail!("A container rootfs requires mount namespace isolation")
+            }
+            Some(rootfs) => Some(RootfsPivot::new(rootfs)?),
+            None => None,
+        };
+        self.validate_subid_ranges()?;
+
+        // Everything execve needs is built here: after clone the child may
+        // not allocate or take locks another thread of ours could hold.
+        let image = ExecImage::from_command(&cmd, self.config.rootfs.as_deref())?;
+        let argv = ExecImage::pointers(&image.argv);
+        let envp = ExecImage::pointers(&image.envp);
+        let capabilities = CapabilityDrop::for_new_user_namespace(&self.config.keep_caps);
+        let no_new_privs = self.config.no_new_privs;
+        let filter = self.compile_seccomp()?;
+
+        let flags = self.clone_flags();
+        let (ready_read, ready_write) =
+            nix::unistd::pipe().context("Failed to create namespace sync pipe")?;
+
+        let mut stack = vec![0u8; CHILD_STACK_SIZE];
+        let child_main = Box::new(move || -> isize {
+            // Only raw syscalls from here on. Wait for the parent to write
+            // our ID maps before exec'ing.
+            // SAFETY: the descriptors are ours and the buffers outlive the calls.
+            unsafe {
+                libc::close(ready_write);
+                let mut byte = 0u8;
+                if libc::read(ready_read, (&mut byte as *mut u8).cast(), 1) != 1 {
+                    return 1;
+                }
+                libc::close(ready_read);
+
+                // Before seccomp, which denies mount and pivot_root
+                if let Some(rootfs) = &rootfs {
+                    if rootfs.apply().is_err() {
+                        return 1;
+                    }
+                }
+                if let Some(cwd) = &image.cwd {
+                    if libc::chdir(cwd.as_ptr()) != 0 {
+                        return 127;
+                    }
+                }
+                if capabilities.apply().is_err() {
+                    return 1;
+                }
+                if no_new_privs && libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+                    return 1;
+                }
+                // Seccomp goes last: it may deny syscalls the steps above need.
+                if let Some(filter) = &filter {
+                    if seccomp::install_filter(filter).is_err() {
+                        return 1;
+                    }
+                }
+                // execve only returns on failure
+                libc::execve(image.program.as_ptr(), argv.as_ptr(), envp.as_ptr());
+            }
+            127
+        });
+
+        let pid = unsafe { nix::sched::clone(child_main, &mut stack, flags, Some(libc::SIGCHLD)) };
+        let _ = nix::unistd::close(ready_read);
+        let pid = match pid {
+            Ok(pid) => pid,
+            Err(e) => {
+                let _ = nix::unistd::close(ready_write);
+                return Err(e).context("clone() failed to create namespaced child");
+            }
+        };
+
+        let mut child = NamespaceChild { pid, status: None };
+        debug!(pid = pid.as_raw(), "Cloned child into new namespaces");
+
+        if let Err(e) = self.write_id_maps(&format!("/proc/{}", pid)) {
+            // Closing the pipe without writing makes the child exit early.
+            let _ = nix::unistd::close(ready_write);
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(e);
+        }
+
+        Self::release_child(ready_write)?;
+        Ok(child)
+    }
+
+    /// Signal a cloned child that its namespaces are ready
+    fn release_child(ready_write: RawFd) -> Result<()> {
+        let res = nix::unistd::write(ready_write, &[1u8]);
+        let _ = nix::unistd::close(ready_write);
+        res.context("Failed to signal namespaced child")?;
+        Ok(())
+    }
+
+    /// Build the clone flags for the configured namespace set
+    fn clone_flags(&self) -> CloneFlags {
         let mut flags = CloneFlags::CLONE_NEWUSER;
-        
+
         if self.config.isolate_network {
             flags |= CloneFlags::CLONE_NEWNET;
         }
@@ -101,24 +772,65 @@ impl Isolation {
             flags |= CloneFlags::CLONE_NEWPID;
         }
 
-        // Note: In production, you'd use clone() with a proper stack and child function.
-        // For this example, we'll use unshare() which is simpler for demonstration.
-        nix::sched::unshare(flags)
-            .context("Failed to create user namespace")?;
+        flags
+    }
 
-        // Write UID mapping: "0 <host_uid> <range>"
-        self.write_mapping("/proc/self/uid_map", 0, self.config.host_uid, self.config.uid_range)?;
+    /// Write the UID map, setgroups deny and GID map under `proc_dir`
+    ///
+    /// `proc_dir` is `/proc/self` for the calling process or `/proc/<pid>`
+    /// for a freshly cloned child.
+    fn write_id_maps(&self, proc_dir: &str) -> Result<()> {
+        // Write UID mapping: one "<container_id> <host_id> <range>" line per entry
+        self.write_mapping(&format!("{}/uid_map", proc_dir), &self.config.uid_maps)?;
 
         // Disable setgroups to allow GID mapping (required by kernel for security)
-        self.write_setgroups_deny()?;
+        self.write_setgroups_deny(&format!("{}/setgroups", proc_dir))?;
 
-        // Write GID mapping: "0 <host_gid> <range>"
-        self.write_mapping("/proc/self/gid_map", 0, self.config.host_gid, self.config.gid_range)?;
+        // Write GID mapping: one "<container_id> <host_id> <range>" line per entry
+        self.write_mapping(&format!("{}/gid_map", proc_dir), &self.config.gid_maps)
+    }
+
+    /// Verify the configured UID/GID ranges against `/etc/subuid` and `/etc/subgid`
+    ///
+    /// The kernel rejects an over-large map write with a bare `EPERM`, so this
+    /// pre-check turns that into a descriptive error before `unshare` runs.
+    /// Root may map arbitrary ranges, so the check is skipped entirely for it.
+    pub fn validate_subid_ranges(&self) -> Result<()> {
+        if nix::unistd::geteuid().is_root() {
+            debug!("Running as root, skipping subuid/subgid validation");
+            return Ok(());
+        }
+
+        let uid = nix::unistd::getuid();
+        let user = nix::unistd::User::from_uid(uid)
+            .ok()
+            .flatten()
+            .map(|u| u.name)
+            .unwrap_or_default();
+
+        let uid_allocs = Self::read_subid_file(Path::new("/etc/subuid"), &user, uid.as_raw())?;
+        for map in &self.config.uid_maps {
+            validate_id_range("uid", &uid_allocs, uid.as_raw(), map.host_id, map.range)?;
+        }
+
+        let gid = nix::unistd::getgid().as_raw();
+        let gid_allocs = Self::read_subid_file(Path::new("/etc/subgid"), &user, uid.as_raw())?;
+        for map in &self.config.gid_maps {
+            validate_id_range("gid", &gid_allocs, gid, map.host_id, map.range)?;
+        }
 
-        info!("User namespace created successfully");
         Ok(())
     }
 
+    /// Read a subuid/subgid file, treating a missing file as having no allocations
+    fn read_subid_file(path: &Path, user: &str, uid: u32) -> Result<Vec<SubIdRange>> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Ok(parse_subid_file(&contents, user, uid)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
     /// Execute a command inside the isolated namespace
     ///
     /// # Performance Pattern: Command Reuse
@@ -127,11 +839,23 @@ impl Isolation {
     pub fn exec_in_namespace(&self, mut cmd: Command) -> Result<std::process::Child> {
         info!("Executing command in isolated namespace: {:?}", cmd);
 
+        let capabilities = CapabilityDrop::new(&self.config.keep_caps)?;
+        let no_new_privs = self.config.no_new_privs;
+
+        let filter = self.compile_seccomp()?;
+
         // Set up the namespace before exec
         unsafe {
             cmd.pre_exec(move || {
                 // This runs in the child process before exec
-                // Additional namespace setup can be done here
+                capabilities.apply()?;
+                if no_new_privs && libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                // Seccomp goes last: it may deny syscalls the steps above need.
+                if let Some(filter) = &filter {
+                    seccomp::install_filter(filter)?;
+                }
                 Ok(())
             });
         }
@@ -142,18 +866,33 @@ impl Isolation {
         Ok(child)
     }
 
+    /// Compile the configured seccomp profile, if any
+    ///
+    /// Done in the parent so the child only has to install the filter.
+    fn compile_seccomp(&self) -> Result<Option<seccompiler::BpfProgram>> {
+        self.config
+            .seccomp
+            .as_ref()
+            .map(|profile| {
+                seccomp::ensure_seccomp_available()?;
+                profile.compile()
+            })
+            .transpose()
+    }
+
     /// Write UID or GID mapping to procfs
     ///
     /// # Performance: Single Write Syscall
     /// The kernel processes the entire mapping in one syscall, making this
-    /// operation O(1) regardless of the range size.
-    fn write_mapping(&self, path: &str, container_id: u32, host_id: u32, range: u32) -> Result<()> {
-        let mapping = format!("{} {} {}\n", container_id, host_id, range);
+    /// operation O(1) regardless of the range size. The kernel also only
+    /// accepts a single write per map file, so all ranges go out together.
+    fn write_mapping(&self, path: &str, maps: &[IdMap]) -> Result<()> {
+        let mapping = format_id_maps(maps);
         
-        fs::write(path, mapping)
+        fs::write(path, &mapping)
             .with_context(|| format!("Failed to write mapping to {}", path))?;
 
-        debug!("Wrote mapping to {}: {} → {}", path, container_id, host_id);
+        debug!("Wrote {} mapping line(s) to {}", maps.len(), path);
         Ok(())
     }
 
@@ -162,9 +901,7 @@ impl Isolation {
     /// # Security Note:
     /// This is required by the Linux kernel to prevent privilege escalation
     /// through supplementary groups when using unprivileged user namespaces.
-    fn write_setgroups_deny(&self) -> Result<()> {
-        let path = "/proc/self/setgroups";
-        
+    fn write_setgroups_deny(&self, path: &str) -> Result<()> {
         fs::write(path, "deny\n")
             .with_context(|| format!("Failed to write to {}", path))?;
 
@@ -185,8 +922,11 @@ mod tests {
     #[test]
     fn test_isolation_config_default() {
         let config = IsolationConfig::default();
-        assert_eq!(config.uid_range, 65536);
-        assert_eq!(config.gid_range, 65536);
+        assert_eq!(config.uid_maps.len(), 1);
+        assert_eq!(config.uid_maps[0].container_id, 0);
+        assert_eq!(config.uid_maps[0].range, 65536);
+        assert_eq!(config.gid_maps.len(), 1);
+        assert_eq!(config.gid_maps[0].range, 65536);
         assert!(config.isolate_network);
         assert!(config.isolate_mount);
         assert!(config.isolate_pid);
@@ -195,7 +935,419 @@ mod tests {
     #[test]
     fn test_isolation_creation() {
         let isolation = Isolation::with_defaults();
-        assert_eq!(isolation.config().uid_range, 65536);
+        assert_eq!(isolation.config().uid_maps[0].range, 65536);
+    }
+
+    #[test]
+    fn test_format_single_id_map() {
+        let maps = [IdMap::new(0, 1000, 65536)];
+        assert_eq!(format_id_maps(&maps), "0 1000 65536\n");
+    }
+
+    #[test]
+    fn test_format_multiple_id_maps() {
+        let maps = [
+            IdMap::new(0, 1000, 1),
+            IdMap::new(1, 100000, 999),
+            IdMap::new(1000, 200000, 65536),
+        ];
+        assert_eq!(
+            format_id_maps(&maps),
+            "0 1000 1\n1 100000 999\n1000 200000 65536\n"
+        );
+    }
+
+    #[test]
+    fn test_format_empty_id_maps() {
+        assert_eq!(format_id_maps(&[]), "");
+    }
+
+    const SAMPLE_SUBUID: &str = "\
+# comment line
+alice:100000:65536
+bob:165536:65536
+1001:231072:1000
+";
+
+    #[test]
+    fn test_parse_subid_by_name() {
+        let ranges = parse_subid_file(SAMPLE_SUBUID, "alice", 1000);
+        assert_eq!(ranges, vec![SubIdRange { start: 100000, count: 65536 }]);
+    }
+
+    #[test]
+    fn test_parse_subid_by_numeric_id() {
+        let ranges = parse_subid_file(SAMPLE_SUBUID, "carol", 1001);
+        assert_eq!(ranges, vec![SubIdRange { start: 231072, count: 1000 }]);
+    }
+
+    #[test]
+    fn test_parse_subid_unknown_user() {
+        assert!(parse_subid_file(SAMPLE_SUBUID, "mallory", 4242).is_empty());
+    }
+
+    #[test]
+    fn test_validate_range_within_allocation() {
+        let ranges = parse_subid_file(SAMPLE_SUBUID, "alice", 1000);
+        assert!(validate_id_range("uid", &ranges, 1000, 100000, 65536).is_ok());
+        assert!(validate_id_range("uid", &ranges, 1000, 100100, 1000).is_ok());
+    }
+
+    #[test]
+    fn test_validate_range_beyond_allocation() {
+        let ranges = parse_subid_file(SAMPLE_SUBUID, "alice", 1000);
+        let err = validate_id_range("uid", &ranges, 1000, 100000, 65537).unwrap_err();
+        assert!(err.to_string().contains("exceeds"), "unexpected error: {}", err);
+    }
+
+    #[test]
+    fn test_zero_length_ranges_rejected() {
+        let ranges = parse_subid_file("alice:100000:0\nalice:200000:10\n", "alice", 1000);
+        assert_eq!(ranges, vec![SubIdRange { start: 200000, count: 10 }]);
+
+        let err = validate_id_range("uid", &ranges, 1000, 200000, 0).unwrap_err();
+        assert!(err.to_string().contains("empty uid range"), "unexpected error: {}", err);
+        // A zero-count allocation built by hand must not underflow either
+        let empty = [SubIdRange { start: 0, count: 0 }];
+        assert!(validate_id_range("uid", &empty, 1000, 0, 0).is_err());
+        assert!(validate_id_range("uid", &empty, 1000, 0, 5).is_err());
+    }
+
+    #[test]
+    fn test_validate_own_id_needs_no_allocation() {
+        assert!(validate_id_range("uid", &[], 1000, 1000, 1).is_ok());
+        let err = validate_id_range("uid", &[], 1000, 1000, 65536).unwrap_err();
+        assert!(err.to_string().contains("No /etc/subuid allocation"));
+    }
+
+    #[test]
+    fn test_clone_flags_follow_config() {
+        let config = IsolationConfig {
+            isolate_network: false,
+            ..Default::default()
+        };
+        let flags = Isolation::new(config).clone_flags();
+        assert!(flags.contains(CloneFlags::CLONE_NEWUSER));
+        assert!(flags.contains(CloneFlags::CLONE_NEWPID));
+        assert!(!flags.contains(CloneFlags::CLONE_NEWNET));
+    }
+
+    #[test]
+    #[ignore = "requires privileges to create user and PID namespaces"]
+    fn test_spawn_in_namespace_is_pid_one() {
+        let mut cmd = Command::new("/bin/sh");
+        cmd.args(["-c", "test $$ -eq 1"]);
+
+        let isolation = Isolation::with_defaults();
+        let mut child = isolation.spawn_in_namespace(cmd).unwrap();
+        let status = child.wait().unwrap();
+        assert!(status.success(), "child did not see itself as PID 1: {:?}", status);
+    }
+
+    #[test]
+    fn test_exec_image_from_command() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "exit 0"])
+            .env("ENVIRO_IMAGE_TEST", "1")
+            .env_remove("HOME")
+            .current_dir("/tmp");
+
+        let image = ExecImage::from_command(&cmd, None).unwrap();
+        assert!(image.program.as_bytes().ends_with(b"/sh"));
+        let argv: Vec<_> = image.argv.iter().map(|arg| arg.to_str().unwrap()).collect();
+        assert_eq!(argv, ["sh", "-c", "exit 0"]);
+        // Only what the command sets; nothing leaks in from the host
+        let envp: Vec<_> = image.envp.iter().map(|var| var.to_str().unwrap()).collect();
+        assert_eq!(envp, ["ENVIRO_IMAGE_TEST=1".to_string(), format!("PATH={}", DEFAULT_PATH)]);
+        assert_eq!(image.cwd.as_deref(), Some(c"/tmp"));
+
+        let pointers = ExecImage::pointers(&image.argv);
+        assert_eq!(pointers.len(), 4);
+        assert!(pointers[3].is_null());
+
+        assert!(ExecImage::from_command(&Command::new("enviro-no-such-binary"), None).is_err());
+    }
+
+    #[test]
+    fn test_exec_image_resolves_inside_root() {
+        let root = tempfile::tempdir().unwrap();
+        let bin = root.path().join("bin");
+        fs::create_dir(&bin).unwrap();
+        fs::write(bin.join("enviro-tool"), "#!/bin/sh\n").unwrap();
+        fs::set_permissions(bin.join("enviro-tool"), fs::Permissions::from_mode(0o755)).unwrap();
+        // Absolute symlinks only resolve after the pivot
+        std::os::unix::fs::symlink("/bin/enviro-tool", bin.join("enviro-link")).unwrap();
+
+        let image = ExecImage::from_command(&Command::new("enviro-tool"), Some(root.path())).unwrap();
+        assert_eq!(image.program.as_bytes(), b"/bin/enviro-tool");
+        let image = ExecImage::from_command(&Command::new("enviro-link"), Some(root.path())).unwrap();
+        assert_eq!(image.program.as_bytes(), b"/bin/enviro-link");
+        // The host's sh is not inside the root
+        assert!(ExecImage::from_command(&Command::new("sh"), Some(root.path())).is_err());
+    }
+
+    #[test]
+    #[ignore = "requires privileges to create user and PID namespaces"]
+    fn test_dropped_namespace_child_is_reaped() {
+        let mut cmd = Command::new("/bin/sleep");
+        cmd.arg("30");
+
+        let child = Isolation::with_defaults().spawn_in_namespace(cmd).unwrap();
+        let pid = child.pid;
+        drop(child);
+
+        // Already reaped, so there is nothing left to wait for
+        let err = nix::sys::wait::waitpid(pid, None).unwrap_err();
+        assert_eq!(err, nix::errno::Errno::ECHILD);
+    }
+
+    #[test]
+    fn test_keep_caps_default_empty() {
+        let config = IsolationConfig::default();
+        assert!(config.keep_caps.is_empty());
+    }
+
+    #[test]
+    fn test_capability_drop_retains_only_held_caps() {
+        let keep = [Capability::CAP_NET_BIND_SERVICE];
+        let plan = CapabilityDrop::new(&keep).unwrap();
+        assert_eq!(plan.retained & !Capability::CAP_NET_BIND_SERVICE.bitmask(), 0);
+        assert!(!plan.bounding.contains(&Capability::CAP_NET_BIND_SERVICE));
+
+        let permitted = caps::read(None, CapSet::Permitted).unwrap();
+        if !permitted.contains(&Capability::CAP_NET_BIND_SERVICE) {
+            assert_eq!(plan.retained, 0);
+        }
+    }
+
+    /// Line `field` of `/proc/self/status` as seen by a command spawned with
+    /// [`Isolation::spawn_in_namespace`] under `config`
+    fn spawned_status_field(config: IsolationConfig, field: &str) -> String {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("status");
+        let mut cmd = Command::new("/bin/sh");
+        cmd.args(["-c", "grep \"^$1:\" /proc/self/status > \"$2\"", "sh", field])
+            .arg(&out);
+
+        let status = Isolation::new(config).spawn_in_namespace(cmd).unwrap().wait().unwrap();
+        assert!(status.success(), "spawned child failed: {:?}", status);
+        let line = fs::read_to_string(&out).unwrap();
+        line.trim_start_matches(&format!("{}:", field)).trim().to_string()
+    }
+
+    #[test]
+    fn test_capability_drop_for_new_user_namespace() {
+        let keep = [Capability::CAP_NET_BIND_SERVICE];
+        let plan = CapabilityDrop::for_new_user_namespace(&keep);
+
+        // Independent of what the test process holds
+        assert_eq!(plan.retained, Capability::CAP_NET_BIND_SERVICE.bitmask());
+        assert!(!plan.bounding.contains(&Capability::CAP_NET_BIND_SERVICE));
+        assert_eq!(plan.bounding.len(), caps::runtime::thread_all_supported().len() - 1);
+    }
+
+    #[test]
+    #[ignore = "requires privileges to create user and PID namespaces"]
+    fn test_spawn_in_namespace_drops_caps() {
+        let config = IsolationConfig {
+            keep_caps: vec![Capability::CAP_NET_BIND_SERVICE],
+            ..Default::default()
+        };
+        let mask = u64::from_str_radix(&spawned_status_field(config, "CapEff"), 16).unwrap();
+        assert_eq!(mask, Capability::CAP_NET_BIND_SERVICE.bitmask());
+        let mask = u64::from_str_radix(
+            &spawned_status_field(IsolationConfig::default(), "CapBnd"),
+            16,
+        )
+        .unwrap();
+        assert_eq!(mask, 0);
+    }
+
+    #[test]
+    #[ignore = "requires privileges to create user and PID namespaces"]
+    fn test_spawn_in_namespace_installs_seccomp() {
+        // 2 is SECCOMP_MODE_FILTER
+        assert_eq!(spawned_status_field(IsolationConfig::default(), "Seccomp"), "2");
+        let config = IsolationConfig {
+            seccomp: None,
+            ..Default::default()
+        };
+        assert_eq!(spawned_status_field(config, "Seccomp"), "0");
+    }
+
+    #[test]
+    fn test_exec_effective_caps_subset_of_keep() {
+        let mut cmd = Command::new("/bin/grep");
+        cmd.args(["^CapEff:", "/proc/self/status"])
+            .stdout(std::process::Stdio::piped());
+
+        let config = IsolationConfig {
+            keep_caps: vec![Capability::CAP_NET_BIND_SERVICE],
+            ..Default::default()
+        };
+        let output = Isolation::new(config)
+            .exec_in_namespace(cmd)
+            .unwrap()
+            .wait_with_output()
+            .unwrap();
+        assert!(output.status.success());
+
+        let line = String::from_utf8_lossy(&output.stdout);
+        let mask = u64::from_str_radix(line.trim_start_matches("CapEff:").trim(), 16).unwrap();
+        assert_eq!(mask & !Capability::CAP_NET_BIND_SERVICE.bitmask(), 0);
+    }
+
+    #[test]
+    #[ignore = "requires a privileged process holding CAP_SYS_ADMIN"]
+    fn test_exec_drops_cap_sys_admin() {
+        let mut cmd = Command::new("/bin/grep");
+        cmd.args(["^CapEff:", "/proc/self/status"])
+            .stdout(std::process::Stdio::piped());
+
+        let config = IsolationConfig {
+            keep_caps: vec![Capability::CAP_NET_BIND_SERVICE],
+            ..Default::default()
+        };
+        let output = Isolation::new(config)
+            .exec_in_namespace(cmd)
+            .unwrap()
+            .wait_with_output()
+            .unwrap();
+        assert!(output.status.success());
+
+        let line = String::from_utf8_lossy(&output.stdout);
+        let mask = u64::from_str_radix(line.trim_start_matches("CapEff:").trim(), 16).unwrap();
+        assert_eq!(mask & (1 << Capability::CAP_SYS_ADMIN.index()), 0);
+    }
+
+    #[test]
+    fn test_no_new_privs_default() {
+        let config = IsolationConfig::default();
+        assert!(config.no_new_privs);
+    }
+
+    #[test]
+    #[ignore = "requires root to create a setuid binary owned by another user"]
+    fn test_exec_no_new_privs_ignores_setuid() {
+        use std::os::unix::fs::{chown, PermissionsExt};
+
+        // Not under /tmp, which is often mounted nosuid.
+        let dir = tempfile::tempdir_in(env!("CARGO_MANIFEST_DIR")).unwrap();
+        let id = dir.path().join("id");
+        fs::copy("/usr/bin/id", &id).unwrap();
+        chown(&id, Some(65534), None).unwrap();
+        fs::set_permissions(&id, fs::Permissions::from_mode(0o4755)).unwrap();
+
+        // Prints the effective UID: 65534 if the setuid bit was honoured.
+        let run = |no_new_privs| {
+            let mut cmd = Command::new(&id);
+            cmd.arg("-u").stdout(std::process::Stdio::piped());
+            let config = IsolationConfig {
+                seccomp: None,
+                no_new_privs,
+                ..Default::default()
+            };
+            let output = Isolation::new(config)
+                .exec_in_namespace(cmd)
+                .unwrap()
+                .wait_with_output()
+                .unwrap();
+            assert!(output.status.success());
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+
+        assert_eq!(run(false), "65534");
+        assert_eq!(run(true), nix::unistd::getuid().as_raw().to_string());
+    }
+
+    #[test]
+    fn test_seccomp_default_profile() {
+        let config = IsolationConfig::default();
+        assert_eq!(config.seccomp, Some(SeccompProfile::docker_default()));
+    }
+
+    #[test]
+    fn test_seccomp_denies_mkdir() {
+        let mut denied = vec!["mkdirat"];
+        if cfg!(target_arch = "x86_64") {
+            denied.push("mkdir");
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("blocked");
+        let mut cmd = Command::new("mkdir");
+        cmd.arg(&target).stderr(std::process::Stdio::piped());
+
+        let config = IsolationConfig {
+            seccomp: Some(SeccompProfile::deny(denied)),
+            ..Default::default()
+        };
+        let output = Isolation::new(config)
+            .exec_in_namespace(cmd)
+            .unwrap()
+            .wait_with_output()
+            .unwrap();
+
+        assert!(!output.status.success());
+        assert!(!target.exists());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Operation not permitted"), "unexpected stderr: {}", stderr);
+    }
+
+    #[test]
+    fn test_rootfs_missing_dir() {
+        let err = RootfsPivot::new(Path::new("/nonexistent/enviro-rootfs")).unwrap_err();
+        assert!(err.to_string().contains("does not exist"), "unexpected error: {}", err);
+
+        let config = IsolationConfig {
+            rootfs: Some(PathBuf::from("/nonexistent/enviro-rootfs")),
+            ..Default::default()
+        };
+        let err = Isolation::new(config).spawn_in_namespace(Command::new("/bin/true")).unwrap_err();
+        assert!(err.to_string().contains("does not exist"), "unexpected error: {}", err);
+    }
+
+    #[test]
+    fn test_rootfs_requires_mount_namespace() {
+        let rootfs = tempfile::tempdir().unwrap();
+        let config = IsolationConfig {
+            isolate_mount: false,
+            rootfs: Some(rootfs.path().to_path_buf()),
+            ..Default::default()
+        };
+        let err = Isolation::new(config).spawn_in_namespace(Command::new("/bin/true")).unwrap_err();
+        assert!(err.to_string().contains("mount namespace"), "unexpected error: {}", err);
+    }
+
+    #[test]
+    fn test_rootfs_pivot_creates_mount_points() {
+        let rootfs = tempfile::tempdir().unwrap();
+        RootfsPivot::new(rootfs.path()).unwrap();
+        for dir in ["proc", "sys", PIVOT_OLD_ROOT] {
+            assert!(rootfs.path().join(dir).is_dir(), "missing {}", dir);
+        }
+    }
+
+    #[test]
+    #[ignore = "requires privileges for namespaces and an extracted rootfs in ENVIRO_TEST_ROOTFS"]
+    fn test_spawn_in_namespace_pivots_into_rootfs() {
+        let rootfs = std::env::var("ENVIRO_TEST_ROOTFS")
+            .expect("set ENVIRO_TEST_ROOTFS to a minimal extracted rootfs with /bin/sh");
+        let marker = Path::new(&rootfs).join("enviro-rootfs-marker");
+        fs::write(&marker, "inside\n").unwrap();
+
+        let mut cmd = Command::new("/bin/sh");
+        cmd.args([
+            "-c",
+            "test -e /proc/self/status && test ! -e /.pivot_old && test -f /enviro-rootfs-marker",
+        ]);
+        let config = IsolationConfig {
+            rootfs: Some(PathBuf::from(&rootfs)),
+            ..Default::default()
+        };
+        let status = Isolation::new(config).spawn_in_namespace(cmd).unwrap().wait().unwrap();
+        fs::remove_file(&marker).unwrap();
+        assert!(status.success(), "child did not see the rootfs as /: {:?}", status);
     }
 
     // Note: Actual namespace creation tests require root or proper capabilities
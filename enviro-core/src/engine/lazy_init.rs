@@ -133,7 +133,7 @@ impl<T> LazyResourcePool<T> {
     pub fn is_initialized(&self, name: &str) -> bool {
         self.resources
             .get(name)
-            .map_or(false, |r| r.is_initialized())
+            .is_some_and(|r| r.is_initialized())
     }
 
     /// Remove a named resource from the pool, dropping any cached value.
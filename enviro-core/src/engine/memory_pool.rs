@@ -8,12 +8,23 @@
 //! # Performance-First Design:
 //! - `VecDeque`-backed free list for O(1) acquire/release
 //! - Tracks peak usage so operators can right-size the pool
+//! - `max_size` cap drops surplus contexts on release, so a burst does not
+//!   permanently inflate the pool
 //! - `shrink_to_fit()` reclaims excess capacity during quiet periods
+//! - `on_memory_pressure()` drops idle contexts down to a configured
+//!   minimum, however large past bursts were
+//! - [`SyncContextPool`] hands out [`PooledContext`] guards that release on
+//!   drop, so early returns cannot leak a slot
 
-use std::collections::{HashMap, VecDeque};
-use tracing::{debug, info};
+use std::collections::VecDeque;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex, PoisonError};
+use tracing::{debug, info, warn};
 
-use crate::executor::{ExecutionContext, NetworkConfig, ResourceLimits};
+use crate::executor::{EnvPolicy, ExecutionContext, NetworkConfig, ResourceLimits, UndefinedEnv};
+
+/// Working directory of fresh and recycled contexts.
+const DEFAULT_WORKDIR: &str = "/";
 
 /// Statistics about pool utilization.
 ///
@@ -29,6 +40,9 @@ pub struct PoolStats {
     pub recycled_count: u64,
     /// Highest `active_count` observed since the pool was created.
     pub peak_usage: usize,
+    /// Cumulative number of released contexts dropped because the pool
+    /// was already at `max_size`.
+    pub dropped_count: u64,
 }
 
 /// A pool of reusable [`ExecutionContext`] instances.
@@ -36,7 +50,7 @@ pub struct PoolStats {
 /// # Performance Pattern: Object Reuse via Free List
 /// ```rust,no_run
 /// # use enviro_core::engine::memory_pool::ContextPool;
-/// let mut pool = ContextPool::new(8);
+/// let mut pool = ContextPool::new(8, 32);
 /// let ctx = pool.acquire("ctr-1");
 /// // … use ctx …
 /// pool.release(ctx);
@@ -50,12 +64,27 @@ pub struct ContextPool {
     recycled_count: u64,
     /// High-water mark for active contexts.
     peak_usage: usize,
+    /// Most slots (free + active) kept after a release.
+    max_size: usize,
+    /// Cumulative count of contexts dropped instead of pooled.
+    dropped_count: u64,
+    /// Free contexts kept by [`on_memory_pressure`](Self::on_memory_pressure).
+    min_idle: usize,
 }
 
 impl ContextPool {
     /// Create a new pool pre-populated with `capacity` default contexts.
-    pub fn new(capacity: usize) -> Self {
-        info!(capacity, "Creating ContextPool");
+    ///
+    /// `acquire` still allocates past `max_size` under load, but `release`
+    /// drops contexts instead of pooling them while the pool holds
+    /// `max_size` or more slots. A `capacity` above `max_size` is clamped
+    /// to it.
+    pub fn new(capacity: usize, max_size: usize) -> Self {
+        if capacity > max_size {
+            warn!(capacity, max_size, "ContextPool capacity exceeds max_size, clamping");
+        }
+        let capacity = capacity.min(max_size);
+        info!(capacity, max_size, "Creating ContextPool");
         let mut free_list = VecDeque::with_capacity(capacity);
         for _ in 0..capacity {
             free_list.push_back(Self::default_context());
@@ -65,9 +94,19 @@ impl ContextPool {
             active_count: 0,
             recycled_count: 0,
             peak_usage: 0,
+            max_size,
+            dropped_count: 0,
+            min_idle: 0,
         }
     }
 
+    /// Keep `min_idle` free contexts when shrinking under memory pressure,
+    /// so the next acquisitions after it passes still skip allocation.
+    pub fn with_min_idle(mut self, min_idle: usize) -> Self {
+        self.min_idle = min_idle;
+        self
+    }
+
     /// Acquire a context from the pool, reusing a free slot when available.
     ///
     /// If the free list is empty a fresh context is allocated on the fly.
@@ -93,13 +132,22 @@ impl ContextPool {
     /// Return a context to the pool for future reuse.
     ///
     /// The context is reset to defaults before being pushed onto the free
-    /// list so that stale data is never leaked between containers.
+    /// list so that stale data is never leaked between containers. If the
+    /// pool already holds `max_size` slots the context is dropped instead.
     pub fn release(&mut self, mut ctx: ExecutionContext) {
-        debug!(container_id = %ctx.container_id, "Releasing context back to pool");
-        // Reset mutable fields to prevent data leakage.
-        ctx.container_id.clear();
-        ctx.env.clear();
         self.active_count = self.active_count.saturating_sub(1);
+        if self.free_list.len() + self.active_count >= self.max_size {
+            debug!(
+                container_id = %ctx.container_id,
+                max_size = self.max_size,
+                "ContextPool full – dropping released context"
+            );
+            self.dropped_count += 1;
+            return;
+        }
+
+        debug!(container_id = %ctx.container_id, "Releasing context back to pool");
+        Self::reset_context(&mut ctx);
         self.recycled_count += 1;
         self.free_list.push_back(ctx);
     }
@@ -111,6 +159,7 @@ impl ContextPool {
             active_count: self.active_count,
             recycled_count: self.recycled_count,
             peak_usage: self.peak_usage,
+            dropped_count: self.dropped_count,
         }
     }
 
@@ -119,33 +168,198 @@ impl ContextPool {
     /// Call this during quiet periods to release memory that is unlikely to
     /// be needed again.
     pub fn shrink_to_fit(&mut self) {
-        let target = self.peak_usage.max(1);
-        while self.free_list.len() > target {
-            self.free_list.pop_back();
-        }
-        self.free_list.shrink_to_fit();
+        self.truncate_free_list(self.peak_usage.max(1));
         debug!(
             new_capacity = self.free_list.len(),
             "ContextPool shrunk to fit"
         );
     }
 
+    /// Shrink the pool in response to memory pressure (see
+    /// [`MemoryPressure`](super::cgroup::MemoryPressure)).
+    ///
+    /// Unlike [`shrink_to_fit`](Self::shrink_to_fit), which keeps enough
+    /// free contexts for the peak usage, this keeps only `min_idle` (see
+    /// [`with_min_idle`](Self::with_min_idle)). Checked-out contexts are
+    /// unaffected.
+    pub fn on_memory_pressure(&mut self) {
+        let freed = self.free_list.len().saturating_sub(self.min_idle);
+        self.truncate_free_list(self.min_idle);
+        info!(freed, "ContextPool shrunk under memory pressure");
+    }
+
+    /// Drop free contexts until at most `target` remain.
+    fn truncate_free_list(&mut self, target: usize) {
+        self.free_list.truncate(target);
+        self.free_list.shrink_to_fit();
+    }
+
     /// Build a default, empty execution context.
     fn default_context() -> ExecutionContext {
-        ExecutionContext {
-            container_id: String::new(),
-            env: HashMap::new(),
-            workdir: "/".to_string(),
-            limits: ResourceLimits {
-                cpu_cores: 1.0,
-                memory_bytes: 256 * 1024 * 1024,
-                pid_limit: 128,
-            },
-            network: NetworkConfig {
-                isolated: true,
-                ip_address: None,
-                dns_servers: Vec::new(),
-            },
+        ExecutionContext::builder(String::new())
+            .workdir(DEFAULT_WORKDIR)
+            .rlimits(Vec::new())
+            .umask(None)
+            .limits(Self::default_limits())
+            .build_unchecked()
+    }
+
+    /// Limits given to fresh and recycled contexts.
+    fn default_limits() -> ResourceLimits {
+        ResourceLimits {
+            cpu_cores: 1.0,
+            memory_bytes: 256 * 1024 * 1024,
+            pid_limit: 128,
+        }
+    }
+
+    /// Reset every field to match [`default_context`](Self::default_context)
+    /// so nothing leaks between containers, keeping heap allocations.
+    ///
+    /// The destructuring is exhaustive: a new context field fails to compile
+    /// here until it is reset too.
+    fn reset_context(ctx: &mut ExecutionContext) {
+        let ExecutionContext {
+            container_id,
+            env,
+            expand_env,
+            undefined_env,
+            env_policy,
+            redact_env,
+            workdir,
+            create_workdir,
+            merge_stderr,
+            rlimits,
+            umask,
+            run_as,
+            cgroup,
+            limits,
+            network,
+        } = ctx;
+        container_id.clear();
+        env.clear();
+        *expand_env = false;
+        *undefined_env = UndefinedEnv::Empty;
+        *env_policy = EnvPolicy::Clear;
+        redact_env.clear();
+        workdir.clear();
+        workdir.push_str(DEFAULT_WORKDIR);
+        *create_workdir = false;
+        *merge_stderr = false;
+        rlimits.clear();
+        *umask = None;
+        *run_as = None;
+        *cgroup = None;
+        *limits = Self::default_limits();
+
+        let NetworkConfig {
+            isolated,
+            ip_address,
+            dns_servers,
+            port_mappings,
+        } = network;
+        *isolated = true;
+        *ip_address = None;
+        dns_servers.clear();
+        port_mappings.clear();
+    }
+}
+
+/// A shareable [`ContextPool`] whose contexts release themselves.
+///
+/// Clones share the same pool.
+///
+/// # Performance Pattern: RAII Release
+/// ```rust,no_run
+/// # use enviro_core::engine::memory_pool::SyncContextPool;
+/// let pool = SyncContextPool::new(8, 32);
+/// {
+///     let ctx = pool.acquire_guard("ctr-1");
+///     // … use ctx …
+/// } // returned to the pool here
+/// ```
+#[derive(Clone)]
+pub struct SyncContextPool {
+    inner: Arc<Mutex<ContextPool>>,
+}
+
+impl SyncContextPool {
+    /// Create a new pool pre-populated with `capacity` default contexts,
+    /// keeping at most `max_size` slots (see [`ContextPool::new`]).
+    pub fn new(capacity: usize, max_size: usize) -> Self {
+        ContextPool::new(capacity, max_size).into()
+    }
+
+    /// Acquire a context that is released back to the pool when the
+    /// returned guard is dropped.
+    pub fn acquire_guard(&self, container_id: impl Into<String>) -> PooledContext {
+        let ctx = self.lock().acquire(container_id);
+        PooledContext {
+            ctx: Some(ctx),
+            pool: self.inner.clone(),
+        }
+    }
+
+    /// Return a snapshot of current pool statistics.
+    pub fn stats(&self) -> PoolStats {
+        self.lock().stats()
+    }
+
+    /// Shrink the free list to match the peak observed usage.
+    pub fn shrink_to_fit(&self) {
+        self.lock().shrink_to_fit();
+    }
+
+    /// Shrink the pool in response to memory pressure.
+    pub fn on_memory_pressure(&self) {
+        self.lock().on_memory_pressure();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ContextPool> {
+        self.inner.lock().expect("context pool lock poisoned")
+    }
+}
+
+impl From<ContextPool> for SyncContextPool {
+    fn from(pool: ContextPool) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(pool)),
+        }
+    }
+}
+
+/// An [`ExecutionContext`] checked out of a [`SyncContextPool`].
+///
+/// Dereferences to the context and releases it back to the pool on drop.
+pub struct PooledContext {
+    /// Always `Some` until dropped.
+    ctx: Option<ExecutionContext>,
+    pool: Arc<Mutex<ContextPool>>,
+}
+
+impl Deref for PooledContext {
+    type Target = ExecutionContext;
+
+    fn deref(&self) -> &ExecutionContext {
+        self.ctx.as_ref().expect("pooled context already released")
+    }
+}
+
+impl DerefMut for PooledContext {
+    fn deref_mut(&mut self) -> &mut ExecutionContext {
+        self.ctx.as_mut().expect("pooled context already released")
+    }
+}
+
+impl Drop for PooledContext {
+    fn drop(&mut self) {
+        if let Some(ctx) = self.ctx.take() {
+            // Release even if another holder panicked: the pool's counters
+            // stay consistent, and panicking in drop could abort.
+            self.pool
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .release(ctx);
         }
     }
 }
@@ -153,17 +367,18 @@ impl ContextPool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use nix::sys::resource::Resource;
 
     #[test]
     fn test_pool_acquire_returns_context() {
-        let mut pool = ContextPool::new(2);
+        let mut pool = ContextPool::new(2, 8);
         let ctx = pool.acquire("ctr-1");
         assert_eq!(ctx.container_id, "ctr-1");
     }
 
     #[test]
     fn test_pool_release_and_reuse() {
-        let mut pool = ContextPool::new(1);
+        let mut pool = ContextPool::new(1, 8);
         let ctx = pool.acquire("ctr-1");
         pool.release(ctx);
 
@@ -175,7 +390,7 @@ mod tests {
 
     #[test]
     fn test_pool_stats_tracking() {
-        let mut pool = ContextPool::new(4);
+        let mut pool = ContextPool::new(4, 8);
         assert_eq!(pool.stats().pool_size, 4);
         assert_eq!(pool.stats().active_count, 0);
 
@@ -197,7 +412,7 @@ mod tests {
 
     #[test]
     fn test_pool_grows_beyond_capacity() {
-        let mut pool = ContextPool::new(1);
+        let mut pool = ContextPool::new(1, 8);
         let c1 = pool.acquire("a");
         let c2 = pool.acquire("b"); // exceeds initial capacity
         assert_eq!(pool.stats().active_count, 2);
@@ -207,7 +422,7 @@ mod tests {
 
     #[test]
     fn test_shrink_to_fit() {
-        let mut pool = ContextPool::new(8);
+        let mut pool = ContextPool::new(8, 8);
         let c1 = pool.acquire("a");
         pool.release(c1);
         // peak_usage == 1, free_list has 8 items
@@ -215,15 +430,132 @@ mod tests {
         assert!(pool.free_list.len() <= 1);
     }
 
+    #[test]
+    fn test_on_memory_pressure_shrinks() {
+        let mut pool = ContextPool::new(8, 32);
+        let (c1, c2) = (pool.acquire("a"), pool.acquire("b"));
+        pool.release(c1);
+        pool.release(c2);
+        assert_eq!(pool.free_list.len(), 8);
+
+        pool.on_memory_pressure();
+        assert_eq!(pool.free_list.len(), 0);
+    }
+
+    #[test]
+    fn test_on_memory_pressure_after_burst() {
+        let mut pool = ContextPool::new(2, 32).with_min_idle(2);
+        let burst: Vec<_> = (0..16).map(|i| pool.acquire(format!("ctr-{i}"))).collect();
+        let mut burst = burst.into_iter();
+        let held = burst.next().unwrap();
+        burst.for_each(|ctx| pool.release(ctx));
+        assert_eq!(pool.free_list.len(), 15);
+        assert_eq!(pool.stats().peak_usage, 16);
+
+        // The peak no longer matters: only `min_idle` free contexts stay
+        pool.on_memory_pressure();
+        assert_eq!(pool.free_list.len(), 2);
+        assert_eq!(pool.stats().pool_size, 3);
+
+        pool.release(held);
+        assert_eq!(pool.stats().active_count, 0);
+    }
+
+    #[test]
+    fn test_dropped_guard_returns_context() {
+        let pool = SyncContextPool::new(1, 8);
+        {
+            let mut ctx = pool.acquire_guard("ctr-1");
+            ctx.env.insert("TOKEN".into(), "abc123".into());
+            assert_eq!(ctx.container_id, "ctr-1");
+            assert_eq!(pool.stats().active_count, 1);
+        }
+
+        let stats = pool.stats();
+        assert_eq!(stats.active_count, 0);
+        assert_eq!(stats.recycled_count, 1);
+        assert_eq!(stats.pool_size, 1);
+
+        let reused = pool.acquire_guard("ctr-2");
+        assert_eq!(reused.container_id, "ctr-2");
+        assert!(reused.env.is_empty());
+    }
+
+    #[test]
+    fn test_guard_released_on_early_return() {
+        fn failing_step(pool: &SyncContextPool) -> Result<(), &'static str> {
+            let _ctx = pool.acquire_guard("ctr-1");
+            Err("setup failed")
+        }
+
+        let pool = SyncContextPool::new(2, 8);
+        let shared = pool.clone();
+        assert!(failing_step(&shared).is_err());
+        assert!(failing_step(&shared).is_err());
+
+        let stats = pool.stats();
+        assert_eq!(stats.active_count, 0);
+        assert_eq!(stats.recycled_count, 2);
+        assert_eq!(stats.peak_usage, 1);
+    }
+
+    #[test]
+    fn test_release_drops_past_max_size() {
+        let mut pool = ContextPool::new(1, 2);
+        let burst: Vec<_> = (0..5).map(|i| pool.acquire(format!("ctr-{i}"))).collect();
+        assert_eq!(pool.stats().pool_size, 5);
+
+        for ctx in burst {
+            pool.release(ctx);
+            assert!(pool.free_list.len() <= 2);
+        }
+
+        let stats = pool.stats();
+        assert_eq!(stats.pool_size, 2);
+        assert_eq!(stats.active_count, 0);
+        assert_eq!(stats.recycled_count, 2);
+        assert_eq!(stats.dropped_count, 3);
+        assert_eq!(stats.peak_usage, 5);
+    }
+
+    #[test]
+    fn test_capacity_clamped_to_max_size() {
+        let pool = ContextPool::new(4, 2);
+        assert_eq!(pool.stats().pool_size, 2);
+    }
+
     #[test]
     fn test_release_clears_sensitive_fields() {
-        let mut pool = ContextPool::new(1);
+        let mut pool = ContextPool::new(1, 8);
         let mut ctx = pool.acquire("secret");
         ctx.env.insert("TOKEN".into(), "abc123".into());
+        ctx.expand_env = true;
+        ctx.workdir = "/srv/tenant-a".into();
+        ctx.create_workdir = true;
+        ctx.merge_stderr = true;
+        ctx.rlimits.push((Resource::RLIMIT_CORE, 0, 0));
+        ctx.umask = Some(0o077);
+        ctx.limits.memory_bytes = 8 * 1024 * 1024 * 1024;
+        ctx.limits.pid_limit = 4096;
+        ctx.network.isolated = false;
+        ctx.network.ip_address = Some("10.0.0.7".into());
+        ctx.network.dns_servers.push("10.0.0.1".into());
         pool.release(ctx);
 
         let reused = pool.acquire("other");
         assert!(reused.env.is_empty());
         assert!(reused.container_id == "other");
+        assert!(!reused.expand_env);
+        assert_eq!(reused.workdir, "/");
+        assert!(!reused.create_workdir);
+        assert!(!reused.merge_stderr);
+        assert!(reused.rlimits.is_empty());
+        assert_eq!(reused.umask, None);
+        assert_eq!(reused.limits.memory_bytes, 256 * 1024 * 1024);
+        assert_eq!(reused.limits.pid_limit, 128);
+        assert!(reused.network.isolated);
+        assert_eq!(reused.network.ip_address, None);
+        assert!(reused.network.dns_servers.is_empty());
+        assert!(reused.network.port_mappings.is_empty());
     }
 }
@@ -4,21 +4,43 @@
 //! including isolation, process management, and resource control.
 
 pub mod buffer;
+pub mod cgroup;
+pub mod checkpoint;
 pub mod cow_resources;
+pub mod envirofile;
+pub mod id_generator;
+pub mod image;
 pub mod io_uring;
 pub mod isolation;
 pub mod lazy_init;
 pub mod memory_pool;
 pub mod namespace_cache;
+pub mod overlay;
+#[cfg(feature = "networking")]
+pub mod network;
 pub mod parallel_setup;
+pub mod port_forward;
 pub mod resource_limits;
+pub mod seccomp;
 
-pub use buffer::{BufferPool, ZeroCopyBuffer};
-pub use cow_resources::{CowResource, SharedResourceManager};
-pub use io_uring::{IoUringConfig, IoUringManager};
-pub use isolation::{Isolation, IsolationConfig};
+pub use buffer::{AutotuneConfig, BufferPool, PooledReader, ZeroCopyBuffer};
+pub use cgroup::{Cgroup, CgroupUsage, CpuStat, MemoryPressure};
+pub use checkpoint::{
+    CheckpointError, CheckpointReader, CheckpointWriter, Section, SectionInfo, SectionKind,
+};
+pub use cow_resources::{ConcurrentResourceManager, CowResource, SharedResourceManager};
+pub use envirofile::Envirofile;
+pub use id_generator::{CounterGenerator, IdGenerator, UuidGenerator};
+pub use image::{ImageRef, ImageStore, LocalImage};
+pub use io_uring::{FileRead, IoBackend, IoUringConfig, IoUringManager, IoUringStats};
+pub use isolation::{IdMap, Isolation, IsolationConfig, NamespaceChild};
 pub use lazy_init::{LazyResource, LazyResourcePool};
-pub use memory_pool::{ContextPool, PoolStats};
+pub use memory_pool::{ContextPool, PoolStats, PooledContext, SyncContextPool};
 pub use namespace_cache::{NamespaceCache, NamespaceTemplate};
+pub use overlay::MountHandle;
+#[cfg(feature = "networking")]
+pub use network::{BridgeConfig, ContainerInterface};
 pub use parallel_setup::{ParallelNamespaceSetup, ParallelSetupReport, SetupResult};
-pub use resource_limits::{OptimizedResourceLimits, ResourceLimitBatch, ResourceProfile};
+pub use port_forward::PortForwarder;
+pub use resource_limits::{LimitChange, OptimizedResourceLimits, ResourceLimitBatch, ResourceProfile};
+pub use seccomp::SeccompProfile;
@@ -121,6 +121,32 @@ impl NamespaceCache {
         tpl
     }
 
+    /// Insert `templates` ahead of use, keyed by their names.
+    ///
+    /// Entries with the same name are replaced. Warming counts as neither a
+    /// hit nor a miss, so the statistics only reflect real lookups.
+    pub fn warm(&mut self, templates: impl IntoIterator<Item = NamespaceTemplate>) {
+        for tpl in templates {
+            debug!(name = %tpl.name, "Namespace template warmed");
+            self.entries.insert(tpl.name.clone(), tpl);
+        }
+    }
+
+    /// [`warm`](Self::warm) the cache with the common profiles:
+    /// `"default"`, `"network-only"` (only the network namespace isolated)
+    /// and `"full-isolation"` (every namespace isolated).
+    pub fn warm_defaults(&mut self) {
+        let mut network_only = NamespaceTemplate::new("network-only");
+        network_only.isolate_mount = false;
+        network_only.isolate_pid = false;
+
+        self.warm([
+            NamespaceTemplate::new("default"),
+            network_only,
+            NamespaceTemplate::new("full-isolation"),
+        ]);
+    }
+
     /// Remove a cached template, forcing re-creation on the next access.
     ///
     /// Returns `true` if an entry was actually removed.
@@ -239,6 +265,37 @@ mod tests {
         assert_eq!(cache.cache_stats().misses, 2);
     }
 
+    #[test]
+    fn test_warm_defaults() {
+        let mut cache = NamespaceCache::new();
+        cache.warm_defaults();
+        assert_eq!(cache.cache_stats(), CacheStats { hits: 0, misses: 0, cached_count: 3 });
+
+        let tpl = cache.get_or_create("network-only", || panic!("should be warmed"));
+        assert!(tpl.isolate_network);
+        assert!(!tpl.isolate_mount);
+        assert!(!tpl.isolate_pid);
+        cache.get_or_create("full-isolation", || panic!("should be warmed"));
+        cache.get_or_create("default", || panic!("should be warmed"));
+
+        let stats = cache.cache_stats();
+        assert_eq!(stats.hits, 3);
+        assert_eq!(stats.misses, 0);
+    }
+
+    #[test]
+    fn test_warm_replaces_existing() {
+        let mut cache = NamespaceCache::new();
+        cache.get_or_create("custom", || NamespaceTemplate::new("custom"));
+
+        let mut tpl = NamespaceTemplate::new("custom");
+        tpl.host_uid = 2000;
+        cache.warm([tpl]);
+
+        assert_eq!(cache.get_or_create("custom", || panic!("should be warmed")).host_uid, 2000);
+        assert_eq!(cache.cache_stats().cached_count, 1);
+    }
+
     #[test]
     fn test_default_impl() {
         let cache = NamespaceCache::default();
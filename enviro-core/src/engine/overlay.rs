@@ -0,0 +1,573 @@
+//! Layer Unpacking - Stacking Image Layers with overlayfs
+//!
+//! Turns the layer blobs of a pulled [`LocalImage`] into a root filesystem:
+//! 1. Each layer tarball is extracted once into the content store, keyed by
+//!    its digest (`<store>/layers/sha256/<hex>`)
+//! 2. The extracted layers become the `lowerdir` stack of an overlayfs mount,
+//!    topmost layer first
+//! 3. A fresh `upperdir`/`workdir` pair receives the container's writes
+//!
+//! OCI whiteouts are translated to their overlayfs form while extracting:
+//! `.wh.<name>` becomes a `0:0` character device called `<name>`, and
+//! `.wh..wh..opq` marks its directory with `trusted.overlay.opaque=y`.
+//! Creating those (and mounting) requires root.
+//!
+//! # Performance Pattern: Pooled Read Buffers
+//! Compressed blobs are read through large buffers borrowed from the
+//! runtime's [`BufferPool`], so unpacking an image does not allocate a new
+//! read buffer per layer.
+
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use nix::mount::{MntFlags, MsFlags};
+use nix::sys::stat::{Mode, SFlag};
+use std::ffi::CString;
+use std::fs::File;
+use std::io::{self, BufRead, Read};
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+use super::image::{digest_path, Layer, LocalImage};
+use crate::memory::{BufferPool, PooledBuffer};
+
+/// Prefix marking a deleted file in a layer tarball
+pub const WHITEOUT_PREFIX: &str = ".wh.";
+
+/// Entry marking a directory as opaque (lower layers' contents hidden)
+pub const OPAQUE_WHITEOUT: &str = ".wh..wh..opq";
+
+/// Size of the pooled read buffer used for compressed layers
+const READ_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Counter making per-mount scratch directories unique within the process
+static NEXT_MOUNT: AtomicU64 = AtomicU64::new(0);
+
+/// What a layer entry means for the merged filesystem
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayerEntry {
+    /// A regular entry to unpack as-is
+    File,
+    /// `<dir>/.wh.<name>`: delete `<dir>/<name>` from lower layers
+    Whiteout(PathBuf),
+    /// `<dir>/.wh..wh..opq`: hide everything lower layers have in `<dir>`
+    Opaque(PathBuf),
+}
+
+/// Classify the (relative) tar entry `path`
+pub fn classify_entry(path: &Path) -> LayerEntry {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return LayerEntry::File;
+    };
+    let parent = path.parent().unwrap_or(Path::new("")).to_path_buf();
+
+    if name == OPAQUE_WHITEOUT {
+        LayerEntry::Opaque(parent)
+    } else if let Some(hidden) = name.strip_prefix(WHITEOUT_PREFIX) {
+        LayerEntry::Whiteout(parent.join(hidden))
+    } else {
+        LayerEntry::File
+    }
+}
+
+/// Resolve a tar entry path below `root`, rejecting absolute paths and `..`
+fn contained_path(root: &Path, path: &Path) -> Result<PathBuf> {
+    let mut resolved = root.to_path_buf();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            _ => anyhow::bail!("Layer entry {:?} escapes the layer root", path),
+        }
+    }
+    Ok(resolved)
+}
+
+/// Resolve the directory `dir` of a tar entry below `root`, creating any
+/// missing components
+///
+/// Components are checked with `lstat` instead of being followed, so a
+/// symlink unpacked earlier in the same layer (`a -> /etc`) cannot point a
+/// whiteout or opaque marker outside the layer root.
+fn contained_dir(root: &Path, dir: &Path) -> Result<PathBuf> {
+    let mut resolved = root.to_path_buf();
+    for part in contained_path(Path::new(""), dir)?.components() {
+        resolved.push(part);
+        match std::fs::symlink_metadata(&resolved) {
+            Ok(meta) if meta.is_dir() => {}
+            Ok(_) => anyhow::bail!(
+                "Layer entry {:?} passes through non-directory {:?}",
+                dir,
+                resolved
+            ),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => std::fs::create_dir(&resolved)
+                .with_context(|| format!("Failed to create {:?}", resolved))?,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(resolved)
+}
+
+/// `BufRead` over a file using a buffer borrowed from the pool
+///
+/// Unlike [`engine::buffer::PooledReader`](super::buffer::PooledReader),
+/// which hands out whole chunks from a single-owner pool, this keeps one
+/// buffer from the runtime's shared pool and serves byte-level reads from it,
+/// as the gzip and tar decoders expect.
+struct PooledBufReader {
+    file: File,
+    buf: PooledBuffer,
+    pos: usize,
+    filled: usize,
+}
+
+impl PooledBufReader {
+    fn new(file: File, mut buf: PooledBuffer) -> Self {
+        let capacity = buf.capacity().max(READ_BUFFER_SIZE);
+        buf.resize(capacity, 0);
+        Self {
+            file,
+            buf,
+            pos: 0,
+            filled: 0,
+        }
+    }
+}
+
+impl Read for PooledBufReader {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let n = available.len().min(out.len());
+        out[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl BufRead for PooledBufReader {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.pos == self.filled {
+            self.filled = self.file.read(self.buf.as_mut_slice())?;
+            self.pos = 0;
+        }
+        Ok(&self.buf.as_slice()[self.pos..self.filled])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.pos = (self.pos + amount).min(self.filled);
+    }
+}
+
+/// Extract the layer tarball `blob` into `dest`, translating whiteouts
+///
+/// Gzip compression is detected from the content, so both compressed and
+/// plain tar layers are accepted.
+pub fn extract_layer(blob: &Path, dest: &Path, buffer: PooledBuffer) -> Result<()> {
+    let file = File::open(blob).with_context(|| format!("Failed to open layer {:?}", blob))?;
+    let mut reader = PooledBufReader::new(file, buffer);
+    let gzip = reader.fill_buf()?.starts_with(&[0x1f, 0x8b]);
+    let stream: Box<dyn Read> = if gzip {
+        Box::new(GzDecoder::new(reader))
+    } else {
+        Box::new(reader)
+    };
+
+    std::fs::create_dir_all(dest).with_context(|| format!("Failed to create {:?}", dest))?;
+    let mut archive = tar::Archive::new(stream);
+    archive.set_preserve_permissions(true);
+    archive.set_preserve_mtime(true);
+    archive.set_overwrite(true);
+    // SAFETY: geteuid has no preconditions.
+    archive.set_preserve_ownerships(unsafe { libc::geteuid() } == 0);
+
+    for entry in archive.entries().with_context(|| format!("Failed to read layer {:?}", blob))? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+
+        match classify_entry(&path) {
+            LayerEntry::File => {
+                entry
+                    .unpack_in(dest)
+                    .with_context(|| format!("Failed to unpack {:?}", path))?;
+            }
+            LayerEntry::Whiteout(hidden) => {
+                let name = hidden
+                    .file_name()
+                    .with_context(|| format!("Invalid whiteout {:?}", path))?;
+                let parent = hidden.parent().unwrap_or(Path::new(""));
+                let target = contained_dir(dest, parent)?.join(name);
+                remove_existing(&target)?;
+                nix::sys::stat::mknod(&target, SFlag::S_IFCHR, Mode::empty(), 0)
+                    .with_context(|| format!("Failed to create whiteout {:?}", target))?;
+            }
+            LayerEntry::Opaque(dir) => {
+                let target = contained_dir(dest, &dir)?;
+                set_opaque(&target)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Remove whatever an earlier entry of the same layer put at `path`
+fn remove_existing(path: &Path) -> Result<()> {
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() => std::fs::remove_dir_all(path)?,
+        Ok(_) => std::fs::remove_file(path)?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    Ok(())
+}
+
+fn set_opaque(dir: &Path) -> Result<()> {
+    let path = CString::new(dir.as_os_str().as_bytes())?;
+    // SAFETY: both strings are NUL-terminated and the value is a valid buffer.
+    let ret = unsafe {
+        libc::lsetxattr(
+            path.as_ptr(),
+            c"trusted.overlay.opaque".as_ptr(),
+            b"y".as_ptr().cast(),
+            1,
+            0,
+        )
+    };
+    if ret != 0 {
+        return Err(io::Error::last_os_error())
+            .with_context(|| format!("Failed to mark {:?} opaque", dir));
+    }
+    Ok(())
+}
+
+/// An overlayfs mount of an image; unmounted when dropped
+#[derive(Debug)]
+pub struct MountHandle {
+    target: PathBuf,
+    scratch: PathBuf,
+}
+
+impl MountHandle {
+    /// Where the merged filesystem is mounted
+    pub fn target(&self) -> &Path {
+        &self.target
+    }
+
+    /// Directory receiving the container's changes
+    pub fn upper_dir(&self) -> PathBuf {
+        self.scratch.join("upper")
+    }
+}
+
+impl Drop for MountHandle {
+    fn drop(&mut self) {
+        if let Err(e) = nix::mount::umount2(&self.target, MntFlags::empty()) {
+            warn!("Failed to unmount {:?} ({}), detaching", self.target, e);
+            let _ = nix::mount::umount2(&self.target, MntFlags::MNT_DETACH);
+        }
+        if let Err(e) = std::fs::remove_dir_all(&self.scratch) {
+            warn!("Failed to remove {:?}: {}", self.scratch, e);
+        }
+        debug!("Unmounted {:?}", self.target);
+    }
+}
+
+impl LocalImage {
+    /// Unpack the image's layers and mount them as one filesystem at
+    /// `target`
+    ///
+    /// Uses a private buffer pool; see [`mount_with_pool`](Self::mount_with_pool)
+    /// to share the runtime's.
+    pub async fn mount(&self, target: &Path) -> Result<MountHandle> {
+        self.mount_with_pool(target, &BufferPool::new()).await
+    }
+
+    /// Like [`mount`](Self::mount), reading layers through buffers from `pool`
+    pub async fn mount_with_pool(&self, target: &Path, pool: &Arc<BufferPool>) -> Result<MountHandle> {
+        let mut lower = Vec::with_capacity(self.layers.len());
+        for layer in &self.layers {
+            lower.push(self.unpack_layer(layer, pool).await?);
+        }
+        anyhow::ensure!(!lower.is_empty(), "Image {} has no layers", self.reference);
+
+        let scratch = self.store.join("mounts").join(format!(
+            "{}-{}",
+            std::process::id(),
+            NEXT_MOUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let (upper, work) = (scratch.join("upper"), scratch.join("work"));
+        std::fs::create_dir_all(&upper)?;
+        std::fs::create_dir_all(&work)?;
+
+        // overlayfs lists the topmost lower layer first
+        let lowerdir = lower
+            .iter()
+            .rev()
+            .map(|dir| dir.display().to_string())
+            .collect::<Vec<_>>()
+            .join(":");
+        let options = format!(
+            "lowerdir={},upperdir={},workdir={}",
+            lowerdir,
+            upper.display(),
+            work.display()
+        );
+
+        if let Err(e) = nix::mount::mount(
+            Some("overlay"),
+            target,
+            Some("overlay"),
+            MsFlags::empty(),
+            Some(options.as_str()),
+        ) {
+            let _ = std::fs::remove_dir_all(&scratch);
+            return Err(e).with_context(|| format!("Failed to mount overlay at {:?}", target));
+        }
+
+        info!("Mounted {} at {:?} ({} layers)", self.reference, target, lower.len());
+        Ok(MountHandle {
+            target: target.to_path_buf(),
+            scratch,
+        })
+    }
+
+    /// Extract `layer` into the store unless that already happened
+    async fn unpack_layer(&self, layer: &Layer, pool: &Arc<BufferPool>) -> Result<PathBuf> {
+        let dest = digest_path(&self.store.join("layers"), &layer.digest)?;
+        if dest.exists() {
+            debug!("Layer {} already unpacked", layer.digest);
+            return Ok(dest);
+        }
+
+        let mut partial = dest.as_os_str().to_owned();
+        partial.push(format!(".{}.partial", std::process::id()));
+        let partial = PathBuf::from(partial);
+
+        let buffer = pool.get_buffer(READ_BUFFER_SIZE).await;
+        let blob = layer.path.clone();
+        let staging = partial.clone();
+        let extracted = tokio::task::spawn_blocking(move || {
+            let _ = std::fs::remove_dir_all(&staging);
+            extract_layer(&blob, &staging, buffer)
+        })
+        .await
+        .context("Layer extraction task failed")?;
+
+        if let Err(e) = extracted {
+            let _ = std::fs::remove_dir_all(&partial);
+            return Err(e.context(format!("Failed to unpack layer {}", layer.digest)));
+        }
+        std::fs::rename(&partial, &dest)
+            .with_context(|| format!("Failed to move layer into {:?}", dest))?;
+        debug!("Unpacked layer {} into {:?}", layer.digest, dest);
+        Ok(dest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::image::ImageRef;
+    use flate2::write::GzEncoder;
+    use sha2::{Digest, Sha256};
+
+    /// Gzipped tarball containing `files` (path, contents); a path ending
+    /// in `/` is a directory
+    fn layer_tar(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), flate2::Compression::fast()));
+        for (path, contents) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_uid(0);
+            header.set_gid(0);
+            header.set_mtime(0);
+            if path.ends_with('/') {
+                header.set_entry_type(tar::EntryType::Directory);
+                header.set_mode(0o755);
+                header.set_size(0);
+                builder.append_data(&mut header, path, io::empty()).unwrap();
+            } else {
+                header.set_mode(0o644);
+                header.set_size(contents.len() as u64);
+                builder.append_data(&mut header, path, contents.as_bytes()).unwrap();
+            }
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    /// A `LocalImage` whose layers are `layers` written into `store`
+    fn local_image(store: &Path, layers: &[Vec<u8>]) -> LocalImage {
+        let blobs = store.join("blobs").join("sha256");
+        std::fs::create_dir_all(&blobs).unwrap();
+
+        let layers = layers
+            .iter()
+            .map(|data| {
+                let hex: String = Sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect();
+                let path = blobs.join(&hex);
+                std::fs::write(&path, data).unwrap();
+                Layer {
+                    digest: format!("sha256:{}", hex),
+                    media_type: "application/vnd.oci.image.layer.v1.tar+gzip".to_string(),
+                    size: data.len() as u64,
+                    path,
+                }
+            })
+            .collect();
+
+        LocalImage {
+            reference: ImageRef::parse("localhost/test:latest").unwrap(),
+            digest: "sha256:00".to_string(),
+            config: store.join("config.json"),
+            layers,
+            store: store.to_path_buf(),
+        }
+    }
+
+    #[test]
+    fn test_classify_entry() {
+        assert_eq!(classify_entry(Path::new("etc/passwd")), LayerEntry::File);
+        assert_eq!(
+            classify_entry(Path::new("usr/bin/.wh.tool")),
+            LayerEntry::Whiteout(PathBuf::from("usr/bin/tool"))
+        );
+        assert_eq!(
+            classify_entry(Path::new("./var/cache/.wh..wh..opq")),
+            LayerEntry::Opaque(PathBuf::from("./var/cache"))
+        );
+        assert_eq!(
+            classify_entry(Path::new(".wh.top")),
+            LayerEntry::Whiteout(PathBuf::from("top"))
+        );
+    }
+
+    #[test]
+    fn test_contained_path_rejects_escape() {
+        let root = Path::new("/store/layer");
+        assert_eq!(
+            contained_path(root, Path::new("./etc/x")).unwrap(),
+            PathBuf::from("/store/layer/etc/x")
+        );
+        assert!(contained_path(root, Path::new("../x")).is_err());
+        assert!(contained_path(root, Path::new("/etc/x")).is_err());
+    }
+
+    /// Layer with a symlink `link -> target` followed by an empty `entry`
+    fn symlink_layer(link: &str, target: &Path, entry: &str) -> Vec<u8> {
+        let header = || {
+            let mut header = tar::Header::new_gnu();
+            header.set_uid(0);
+            header.set_gid(0);
+            header.set_mtime(0);
+            header.set_mode(0o644);
+            header.set_size(0);
+            header
+        };
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut link_header = header();
+        link_header.set_entry_type(tar::EntryType::Symlink);
+        builder.append_link(&mut link_header, link, target).unwrap();
+        builder.append_data(&mut header(), entry, io::empty()).unwrap();
+        builder.into_inner().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_whiteouts_do_not_follow_symlinked_parents() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = dir.path().join("outside");
+        std::fs::create_dir(&outside).unwrap();
+        std::fs::write(outside.join("passwd"), "root\n").unwrap();
+
+        for entry in ["a/.wh.passwd", "a/.wh..wh..opq", "a/sub/.wh..wh..opq"] {
+            let blob = dir.path().join("layer.tar");
+            std::fs::write(&blob, symlink_layer("a", &outside, entry)).unwrap();
+            let dest = dir.path().join("dest");
+            let _ = std::fs::remove_dir_all(&dest);
+
+            let buffer = BufferPool::new().get_buffer(READ_BUFFER_SIZE).await;
+            let err = extract_layer(&blob, &dest, buffer).unwrap_err();
+            assert!(err.to_string().contains("non-directory"), "{}: {:#}", entry, err);
+            assert_eq!(std::fs::read_to_string(outside.join("passwd")).unwrap(), "root\n");
+            assert!(!outside.join("sub").exists());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unpack_layer_is_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let image = local_image(dir.path(), &[layer_tar(&[("etc/", ""), ("etc/hostname", "base\n")])]);
+        let pool = BufferPool::new();
+
+        let unpacked = image.unpack_layer(&image.layers[0], &pool).await.unwrap();
+        assert_eq!(std::fs::read_to_string(unpacked.join("etc/hostname")).unwrap(), "base\n");
+
+        // A second unpack reuses the extracted directory
+        std::fs::remove_file(&image.layers[0].path).unwrap();
+        assert_eq!(image.unpack_layer(&image.layers[0], &pool).await.unwrap(), unpacked);
+    }
+
+    #[tokio::test]
+    async fn test_unpack_layer_rejects_traversal_digest() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut image = local_image(dir.path(), &[layer_tar(&[("etc/", "")])]);
+        image.layers[0].digest = "sha256:../../escaped".to_string();
+
+        let err = image.unpack_layer(&image.layers[0], &BufferPool::new()).await.unwrap_err();
+        assert!(err.to_string().contains("Invalid sha256 digest"), "{:#}", err);
+        assert!(!dir.path().join("escaped").exists());
+    }
+
+    /// Requires root (mknod whiteouts, trusted xattrs, mount): run with
+    /// `--ignored`
+    #[tokio::test]
+    #[ignore]
+    async fn test_mount_merges_layers() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = layer_tar(&[
+            ("etc/", ""),
+            ("etc/hostname", "base\n"),
+            ("etc/os-release", "v1\n"),
+            ("bin/", ""),
+            ("bin/tool", "tool\n"),
+            ("data/old/", ""),
+            ("data/old/a.txt", "a\n"),
+            ("data/keep.txt", "keep\n"),
+        ]);
+        let update = layer_tar(&[
+            ("etc/os-release", "v2\n"),
+            ("bin/.wh.tool", ""),
+            ("data/old/.wh..wh..opq", ""),
+            ("data/old/new.txt", "new\n"),
+        ]);
+        let image = local_image(dir.path(), &[base, update]);
+        let target = dir.path().join("rootfs");
+        std::fs::create_dir(&target).unwrap();
+
+        let handle = image.mount(&target).await.unwrap();
+        let read = |path: &str| std::fs::read_to_string(target.join(path)).ok();
+        assert_eq!(read("etc/hostname").as_deref(), Some("base\n"));
+        assert_eq!(read("etc/os-release").as_deref(), Some("v2\n"));
+        assert_eq!(read("data/keep.txt").as_deref(), Some("keep\n"));
+        assert!(!target.join("bin/tool").exists());
+        assert!(target.join("bin").is_dir());
+        let old: Vec<_> = std::fs::read_dir(target.join("data/old"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(old, ["new.txt"]);
+
+        // Writes land in the upper layer, not in the shared layer store
+        std::fs::write(target.join("etc/hostname"), "changed\n").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(handle.upper_dir().join("etc/hostname")).unwrap(),
+            "changed\n"
+        );
+        let scratch = handle.upper_dir().parent().unwrap().to_path_buf();
+
+        drop(handle);
+        assert!(!target.join("etc").exists());
+        assert!(!scratch.exists());
+        let base_dir = image.store.join("layers/sha256").join(image.layers[0].digest.trim_start_matches("sha256:"));
+        assert_eq!(std::fs::read_to_string(base_dir.join("etc/hostname")).unwrap(), "base\n");
+    }
+}
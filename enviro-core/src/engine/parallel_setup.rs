@@ -12,9 +12,15 @@
 //! - `tokio::join!` runs all namespace setup futures concurrently
 //! - Per-namespace timing data enables bottleneck identification
 //! - Failed namespaces are reported individually without aborting siblings
+//! - A panicking step is caught and reported as that namespace's failure
+//! - `run_sequential()` produces the same report for baseline comparisons
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
+use std::any::Any;
 use std::fmt;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::task::Poll;
 use std::time::{Duration, Instant};
 use tracing::{debug, info};
 
@@ -56,7 +62,7 @@ pub struct SetupResult {
 }
 
 /// Aggregated results from a parallel namespace setup run.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ParallelSetupReport {
     /// Per-namespace results, one entry per requested namespace.
     pub results: Vec<SetupResult>,
@@ -74,6 +80,20 @@ impl ParallelSetupReport {
     pub fn failures(&self) -> Vec<&SetupResult> {
         self.results.iter().filter(|r| !r.success).collect()
     }
+
+    /// Fail with every failed namespace and its message when any step
+    /// failed.
+    pub fn ensure_succeeded(&self) -> Result<()> {
+        let failures: Vec<String> = self
+            .failures()
+            .into_iter()
+            .map(|r| format!("{}: {}", r.kind, r.message))
+            .collect();
+        if !failures.is_empty() {
+            bail!("Namespace setup failed ({})", failures.join("; "));
+        }
+        Ok(())
+    }
 }
 
 /// Configuration for which namespaces to set up.
@@ -118,13 +138,39 @@ impl Default for NamespaceSetupConfig {
 /// ```
 pub struct ParallelNamespaceSetup {
     config: NamespaceSetupConfig,
+    /// Namespace whose setup is forced to fail, for tests.
+    #[cfg(test)]
+    fail: Option<NamespaceKind>,
+    /// Namespace whose setup is forced to panic, for tests.
+    #[cfg(test)]
+    panic: Option<NamespaceKind>,
 }
 
 impl ParallelNamespaceSetup {
     /// Create a new setup runner with the given configuration.
     pub fn new(config: NamespaceSetupConfig) -> Self {
         info!("Creating ParallelNamespaceSetup");
-        Self { config }
+        Self {
+            config,
+            #[cfg(test)]
+            fail: None,
+            #[cfg(test)]
+            panic: None,
+        }
+    }
+
+    /// Make every setup of `kind` fail.
+    #[cfg(test)]
+    pub(crate) fn failing(mut self, kind: NamespaceKind) -> Self {
+        self.fail = Some(kind);
+        self
+    }
+
+    /// Make every setup of `kind` panic.
+    #[cfg(test)]
+    pub(crate) fn panicking(mut self, kind: NamespaceKind) -> Self {
+        self.panic = Some(kind);
+        self
     }
 
     /// Run all enabled namespace setup steps concurrently.
@@ -142,12 +188,7 @@ impl ParallelNamespaceSetup {
             self.setup_if_enabled(NamespaceKind::Pid, self.config.pid),
         );
 
-        let mut results = Vec::new();
-        for res in [user, network, mount, pid] {
-            if let Some(r) = res {
-                results.push(r);
-            }
-        }
+        let results: Vec<SetupResult> = [user, network, mount, pid].into_iter().flatten().collect();
 
         let total_duration = start.elapsed();
         info!(
@@ -163,6 +204,33 @@ impl ParallelNamespaceSetup {
         })
     }
 
+    /// Run all enabled namespace setup steps one after another.
+    ///
+    /// The report has the same shape as [`run`](Self::run); the total is the
+    /// sum of the individual durations.
+    pub async fn run_sequential(&self) -> Result<ParallelSetupReport> {
+        let start = Instant::now();
+        let mut results = Vec::new();
+        for (kind, enabled) in [
+            (NamespaceKind::User, self.config.user),
+            (NamespaceKind::Network, self.config.network),
+            (NamespaceKind::Mount, self.config.mount),
+            (NamespaceKind::Pid, self.config.pid),
+        ] {
+            results.extend(self.setup_if_enabled(kind, enabled).await);
+        }
+
+        let total_duration = start.elapsed();
+        debug!(
+            total_ms = total_duration.as_millis(),
+            "Sequential namespace setup complete"
+        );
+        Ok(ParallelSetupReport {
+            results,
+            total_duration,
+        })
+    }
+
     /// Set up a single namespace kind, returning `None` when disabled.
     async fn setup_if_enabled(
         &self,
@@ -184,8 +252,19 @@ impl ParallelNamespaceSetup {
         let start = Instant::now();
         debug!(namespace = %kind, "Setting up namespace");
 
-        // Simulate namespace-specific setup work.
-        let result = self.do_namespace_setup(kind).await;
+        // Simulate namespace-specific setup work. A panic is confined to
+        // this namespace's result instead of unwinding through the whole
+        // join and losing the sibling results.
+        let mut setup = std::pin::pin!(self.do_namespace_setup(kind));
+        let result = std::future::poll_fn(|cx| {
+            match std::panic::catch_unwind(AssertUnwindSafe(|| setup.as_mut().poll(cx))) {
+                Ok(poll) => poll,
+                Err(payload) => {
+                    Poll::Ready(Err(anyhow!("setup panicked: {}", panic_message(&*payload))))
+                }
+            }
+        })
+        .await;
 
         let duration = start.elapsed();
         match result {
@@ -215,6 +294,15 @@ impl ParallelNamespaceSetup {
         // Yield to the runtime so all four futures genuinely overlap.
         tokio::task::yield_now().await;
 
+        #[cfg(test)]
+        if self.fail == Some(kind) {
+            bail!("{kind} namespace setup denied");
+        }
+        #[cfg(test)]
+        if self.panic == Some(kind) {
+            panic!("{kind} namespace setup exploded");
+        }
+
         match kind {
             NamespaceKind::User => {
                 // In production: unshare(CLONE_NEWUSER) + UID/GID map writes
@@ -241,6 +329,17 @@ impl ParallelNamespaceSetup {
     }
 }
 
+/// The message a panic was raised with, when it has one
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "non-string panic payload"
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -315,6 +414,55 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_sequential_matches_parallel() {
+        let setup = ParallelNamespaceSetup::new(NamespaceSetupConfig::default());
+        let parallel = setup.run().await.unwrap();
+        let sequential = setup.run_sequential().await.unwrap();
+
+        let kinds = |report: &ParallelSetupReport| -> Vec<_> {
+            report.results.iter().map(|r| r.kind).collect()
+        };
+        assert_eq!(kinds(&sequential), kinds(&parallel));
+        assert!(sequential.all_succeeded());
+    }
+
+    #[tokio::test]
+    async fn test_failed_namespace_reported() {
+        let setup = ParallelNamespaceSetup::new(NamespaceSetupConfig::default())
+            .failing(NamespaceKind::Mount);
+        let report = setup.run().await.unwrap();
+
+        assert_eq!(report.results.len(), 4);
+        assert_eq!(report.failures().len(), 1);
+        let err = report.ensure_succeeded().unwrap_err().to_string();
+        assert_eq!(err, "Namespace setup failed (mount: mount namespace setup denied)");
+    }
+
+    #[tokio::test]
+    async fn test_panicking_namespace_reported() {
+        let setup = ParallelNamespaceSetup::new(NamespaceSetupConfig::default())
+            .panicking(NamespaceKind::Network);
+
+        for report in [setup.run().await.unwrap(), setup.run_sequential().await.unwrap()] {
+            assert_eq!(report.results.len(), 4);
+            assert_eq!(report.failures().len(), 1);
+            let err = report.ensure_succeeded().unwrap_err().to_string();
+            assert_eq!(
+                err,
+                "Namespace setup failed (network: setup panicked: network namespace setup exploded)"
+            );
+        }
+    }
+
+    #[test]
+    fn test_panic_message() {
+        let payload: Box<dyn Any + Send> = Box::new(String::from("formatted 42"));
+        assert_eq!(panic_message(&*payload), "formatted 42");
+        let payload: Box<dyn Any + Send> = Box::new(42u32);
+        assert_eq!(panic_message(&*payload), "non-string panic payload");
+    }
+
     #[test]
     fn test_namespace_kind_display() {
         assert_eq!(NamespaceKind::User.to_string(), "user");
@@ -0,0 +1,440 @@
+//! Port Forwarding - Exposing Container Ports on the Host
+//!
+//! Each container with `port_mappings` gets its own nftables table holding
+//! DNAT rules from the host ports to `NetworkConfig::ip_address`:
+//! - `prerouting` catches traffic arriving from other hosts
+//! - `output` catches connections to a local address made from the host
+//!
+//! A per-container table makes removal a single `delete table`, with no
+//! rule handles to track.
+//!
+//! # Crash Cleanup
+//! Before a ruleset is loaded it is written to the state directory, named
+//! after the table and the owning process. [`PortForwarder::cleanup_stale`]
+//! deletes the tables whose owner is no longer alive, so rules left behind
+//! by a crashed engine do not keep hijacking host ports.
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use tracing::{debug, info, warn};
+
+use crate::executor::{NetworkConfig, PortMap};
+
+/// Directory holding the rulesets of installed port forwards
+pub const DEFAULT_STATE_DIR: &str = "/run/enviro/port-forward";
+
+/// nftables command-line tool
+pub const DEFAULT_NFT: &str = "nft";
+
+/// Characters of the container ID kept readable in a table name
+const TABLE_ID_PREFIX_LEN: usize = 32;
+
+/// Name of the nftables table holding the rules for `container_id`
+///
+/// The ID is reduced to an nft identifier for readability and followed by
+/// a hash of the full ID, so IDs that sanitize the same way (`web-1` and
+/// `web_1`) still get their own tables.
+pub fn table_name(container_id: &str) -> String {
+    let sanitized: String = container_id
+        .chars()
+        .take(TABLE_ID_PREFIX_LEN)
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let digest = Sha256::digest(container_id.as_bytes());
+    let hash = digest[..6].iter().fold(String::new(), |mut out, b| {
+        let _ = write!(out, "{:02x}", b);
+        out
+    });
+    format!("enviro_{}_{}", sanitized, hash)
+}
+
+/// nftables address family matching `addr`
+fn family(addr: &IpAddr) -> &'static str {
+    match addr {
+        IpAddr::V4(_) => "ip",
+        IpAddr::V6(_) => "ip6",
+    }
+}
+
+/// Render the nftables ruleset forwarding `mappings` to `container_ip`
+///
+/// The output can be loaded with `nft -f`.
+pub fn render_ruleset(container_id: &str, container_ip: IpAddr, mappings: &[PortMap]) -> String {
+    let target = match container_ip {
+        IpAddr::V4(addr) => addr.to_string(),
+        IpAddr::V6(addr) => format!("[{}]", addr),
+    };
+    let rules = |prefix: &str| {
+        mappings.iter().fold(String::new(), |mut out, map| {
+            let _ = writeln!(
+                out,
+                "        {}{} dport {} dnat to {}:{}",
+                prefix, map.protocol, map.host_port, target, map.container_port
+            );
+            out
+        })
+    };
+
+    format!(
+        "table {family} {table} {{\n\
+         \x20   chain prerouting {{\n\
+         \x20       type nat hook prerouting priority dstnat; policy accept;\n\
+         {prerouting}\
+         \x20   }}\n\
+         \x20   chain output {{\n\
+         \x20       type nat hook output priority -100; policy accept;\n\
+         {output}\
+         \x20   }}\n\
+         }}\n",
+        family = family(&container_ip),
+        table = table_name(container_id),
+        prerouting = rules(""),
+        output = rules("fib daddr type local "),
+    )
+}
+
+/// Installs and removes per-container DNAT rules via `nft`
+#[derive(Debug, Clone)]
+pub struct PortForwarder {
+    state_dir: PathBuf,
+    nft: PathBuf,
+}
+
+impl PortForwarder {
+    /// Create a forwarder storing rulesets in `state_dir`
+    pub fn new(state_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            state_dir: state_dir.into(),
+            nft: PathBuf::from(DEFAULT_NFT),
+        }
+    }
+
+    /// Use a different `nft` binary
+    pub fn with_nft(mut self, nft: impl Into<PathBuf>) -> Self {
+        self.nft = nft.into();
+        self
+    }
+
+    /// Install the port mappings of `network` for `container_id`
+    ///
+    /// Does nothing when there are no mappings. Mappings require
+    /// `ip_address` to be set, since that is where traffic is forwarded.
+    pub async fn install(&self, container_id: &str, network: &NetworkConfig) -> Result<()> {
+        if network.port_mappings.is_empty() {
+            return Ok(());
+        }
+        let ip_address = network
+            .ip_address
+            .as_deref()
+            .context("Port mappings require NetworkConfig::ip_address")?;
+        let container_ip: IpAddr = ip_address
+            .split('/')
+            .next()
+            .unwrap_or_default()
+            .parse()
+            .with_context(|| format!("Invalid container IP address '{}'", ip_address))?;
+
+        // Replace whatever an earlier run of this container left behind
+        self.remove(container_id).await?;
+
+        let ruleset = render_ruleset(container_id, container_ip, &network.port_mappings);
+        std::fs::create_dir_all(&self.state_dir)
+            .with_context(|| format!("Failed to create {:?}", self.state_dir))?;
+        let state = self.state_file(container_id, std::process::id());
+        std::fs::write(&state, &ruleset).with_context(|| format!("Failed to write {:?}", state))?;
+
+        if let Err(e) = self.nft(&["-f".as_ref(), state.as_os_str()]).await {
+            let _ = std::fs::remove_file(&state);
+            return Err(e.context(format!("Failed to install port forwards for {}", container_id)));
+        }
+
+        info!(
+            container_id,
+            ports = network.port_mappings.len(),
+            "Installed port forwards"
+        );
+        Ok(())
+    }
+
+    /// Remove the port mappings installed for `container_id`
+    ///
+    /// A container without installed mappings is a no-op and never invokes
+    /// `nft`.
+    pub async fn remove(&self, container_id: &str) -> Result<()> {
+        let table = table_name(container_id);
+        for state in self.state_files()? {
+            if state_table(&state) == Some(table.as_str()) {
+                self.delete_table(&state).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Delete the rules of engine processes that are no longer running
+    ///
+    /// Call once at startup. Returns the number of tables removed.
+    pub async fn cleanup_stale(&self) -> Result<usize> {
+        let mut removed = 0;
+        for state in self.state_files()? {
+            let alive = state_owner(&state).is_some_and(process_alive);
+            if alive {
+                continue;
+            }
+            match self.delete_table(&state).await {
+                Ok(()) => removed += 1,
+                Err(e) => warn!("Failed to clean up stale port forwards {:?}: {:#}", state, e),
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Delete the table described by `state`, then the state file itself
+    async fn delete_table(&self, state: &Path) -> Result<()> {
+        let ruleset = std::fs::read_to_string(state)
+            .with_context(|| format!("Failed to read {:?}", state))?;
+        // The first line is `table <family> <name> {`
+        let header: Vec<&str> = ruleset
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .split_whitespace()
+            .collect();
+        let [_, family, table, _] = header[..] else {
+            anyhow::bail!("Malformed port forward state {:?}", state);
+        };
+
+        match self
+            .nft(&["delete".as_ref(), "table".as_ref(), family.as_ref(), table.as_ref()])
+            .await
+        {
+            Ok(()) => {}
+            // Already gone, e.g. the ruleset was flushed
+            Err(e) if format!("{:#}", e).contains("No such file or directory") => {
+                debug!("Table {} already removed", table);
+            }
+            Err(e) => return Err(e),
+        }
+        std::fs::remove_file(state).with_context(|| format!("Failed to remove {:?}", state))?;
+        Ok(())
+    }
+
+    async fn nft(&self, args: &[&std::ffi::OsStr]) -> Result<()> {
+        let output = Command::new(&self.nft)
+            .args(args)
+            .output()
+            .await
+            .with_context(|| format!("Failed to run {:?}", self.nft))?;
+        anyhow::ensure!(
+            output.status.success(),
+            "{:?} failed: {}",
+            self.nft,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        Ok(())
+    }
+
+    fn state_file(&self, container_id: &str, pid: u32) -> PathBuf {
+        self.state_dir
+            .join(format!("{}.{}.nft", table_name(container_id), pid))
+    }
+
+    fn state_files(&self) -> Result<Vec<PathBuf>> {
+        let entries = match std::fs::read_dir(&self.state_dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {:?}", self.state_dir)),
+        };
+        Ok(entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "nft"))
+            .collect())
+    }
+}
+
+impl Default for PortForwarder {
+    fn default() -> Self {
+        Self::new(DEFAULT_STATE_DIR)
+    }
+}
+
+/// Table name encoded in a state file name (`<table>.<pid>.nft`)
+fn state_table(state: &Path) -> Option<&str> {
+    state.file_stem()?.to_str()?.rsplit_once('.').map(|(table, _)| table)
+}
+
+/// Owning process encoded in a state file name
+fn state_owner(state: &Path) -> Option<u32> {
+    state.file_stem()?.to_str()?.rsplit_once('.')?.1.parse().ok()
+}
+
+fn process_alive(pid: u32) -> bool {
+    Path::new("/proc").join(pid.to_string()).exists()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::Protocol;
+
+    fn mappings() -> Vec<PortMap> {
+        vec![
+            PortMap { host_port: 8080, container_port: 80, protocol: Protocol::Tcp },
+            PortMap { host_port: 5353, container_port: 53, protocol: Protocol::Udp },
+        ]
+    }
+
+    #[test]
+    fn test_render_ruleset_ipv4() {
+        let ruleset = render_ruleset("web-1", "10.88.0.2".parse().unwrap(), &mappings());
+        let rules = "\x20   chain prerouting {\n\
+            \x20       type nat hook prerouting priority dstnat; policy accept;\n\
+            \x20       tcp dport 8080 dnat to 10.88.0.2:80\n\
+            \x20       udp dport 5353 dnat to 10.88.0.2:53\n\
+            \x20   }\n\
+            \x20   chain output {\n\
+            \x20       type nat hook output priority -100; policy accept;\n\
+            \x20       fib daddr type local tcp dport 8080 dnat to 10.88.0.2:80\n\
+            \x20       fib daddr type local udp dport 5353 dnat to 10.88.0.2:53\n\
+            \x20   }\n\
+            }\n";
+        assert_eq!(ruleset, format!("table ip {} {{\n{}", table_name("web-1"), rules));
+    }
+
+    #[test]
+    fn test_render_ruleset_ipv6() {
+        let ruleset = render_ruleset("db", "fd00::2".parse().unwrap(), &mappings()[..1]);
+        assert!(ruleset.starts_with(&format!("table ip6 {} {{\n", table_name("db"))));
+        assert!(ruleset.contains("        tcp dport 8080 dnat to [fd00::2]:80\n"));
+    }
+
+    #[test]
+    fn test_table_name_is_nft_identifier() {
+        for id in ["enviro-42-0", "a/b.c", &"x".repeat(200)] {
+            let table = table_name(id);
+            assert!(table.len() <= 7 + TABLE_ID_PREFIX_LEN + 13, "{}", table);
+            assert!(table.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'), "{}", table);
+        }
+        assert!(table_name("a/b.c").starts_with("enviro_a_b_c_"));
+        assert_eq!(table_name("web-1"), table_name("web-1"));
+        assert_ne!(table_name("web-1"), table_name("web_1"));
+    }
+
+    /// Fake `nft` that appends its arguments to a log next to it
+    fn fake_nft(dir: &Path) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let nft = dir.join("nft");
+        std::fs::write(
+            &nft,
+            format!("#!/bin/sh\necho \"$@\" >> {}\n", dir.join("nft.log").display()),
+        )
+        .unwrap();
+        std::fs::set_permissions(&nft, std::fs::Permissions::from_mode(0o755)).unwrap();
+        nft
+    }
+
+    fn nft_log(dir: &Path) -> Vec<String> {
+        std::fs::read_to_string(dir.join("nft.log"))
+            .unwrap_or_default()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_install_and_remove() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_dir = dir.path().join("state");
+        let forwarder = PortForwarder::new(&state_dir).with_nft(fake_nft(dir.path()));
+        let network = NetworkConfig {
+            isolated: false,
+            ip_address: Some("10.88.0.2/16".to_string()),
+            dns_servers: vec![],
+            port_mappings: mappings(),
+        };
+
+        forwarder.install("web", &network).await.unwrap();
+        let state = forwarder.state_file("web", std::process::id());
+        assert_eq!(
+            std::fs::read_to_string(&state).unwrap(),
+            render_ruleset("web", "10.88.0.2".parse().unwrap(), &network.port_mappings)
+        );
+        assert_eq!(nft_log(dir.path()), [format!("-f {}", state.display())]);
+
+        forwarder.remove("web").await.unwrap();
+        assert!(!state.exists());
+        assert_eq!(
+            nft_log(dir.path())[1],
+            format!("delete table ip {}", table_name("web"))
+        );
+
+        // Nothing installed: no nft invocation
+        forwarder.remove("web").await.unwrap();
+        assert_eq!(nft_log(dir.path()).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_install_requires_ip_address() {
+        let forwarder = PortForwarder::new(tempfile::tempdir().unwrap().path());
+        let network = NetworkConfig {
+            isolated: false,
+            ip_address: None,
+            dns_servers: vec![],
+            port_mappings: mappings(),
+        };
+        let err = forwarder.install("web", &network).await.unwrap_err();
+        assert!(err.to_string().contains("ip_address"));
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_stale_keeps_live_owners() {
+        let dir = tempfile::tempdir().unwrap();
+        let forwarder = PortForwarder::new(dir.path()).with_nft(fake_nft(dir.path()));
+        let ruleset = render_ruleset("gone", "10.88.0.3".parse().unwrap(), &mappings());
+
+        // pid_max never reaches u32::MAX, so this owner cannot be alive
+        let stale = forwarder.state_file("gone", u32::MAX);
+        let live = forwarder.state_file("live", std::process::id());
+        std::fs::write(&stale, &ruleset).unwrap();
+        std::fs::write(&live, &ruleset).unwrap();
+
+        assert_eq!(forwarder.cleanup_stale().await.unwrap(), 1);
+        assert!(!stale.exists());
+        assert!(live.exists());
+        assert_eq!(
+            nft_log(dir.path()),
+            [format!("delete table ip {}", table_name("gone"))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_install_keeps_forwards_of_similar_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let forwarder = PortForwarder::new(dir.path().join("state")).with_nft(fake_nft(dir.path()));
+        let network = |ip: &str| NetworkConfig {
+            isolated: false,
+            ip_address: Some(ip.to_string()),
+            dns_servers: vec![],
+            port_mappings: mappings(),
+        };
+
+        forwarder.install("web-1", &network("10.88.0.2")).await.unwrap();
+        forwarder.install("web_1", &network("10.88.0.3")).await.unwrap();
+
+        // Installing web_1 must not have replaced web-1's table
+        let first = forwarder.state_file("web-1", std::process::id());
+        let second = forwarder.state_file("web_1", std::process::id());
+        assert_ne!(first, second);
+        assert!(first.exists());
+        assert!(second.exists());
+        assert!(nft_log(dir.path()).iter().all(|line| !line.starts_with("delete")));
+
+        forwarder.remove("web_1").await.unwrap();
+        assert!(first.exists());
+        assert!(!second.exists());
+    }
+}
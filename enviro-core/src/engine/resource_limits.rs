@@ -14,19 +14,36 @@
 //! - Preset `ResourceProfile`s avoid per-container configuration overhead
 //! - Timing data from `apply_batch` enables startup optimization
 
+use super::cgroup::{self, Cgroup, CGROUP2_MOUNT};
+use crate::executor::ResourceLimits;
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
+
+/// Queued entries above which [`ResourceLimitBatch::apply_batch`] warns.
+pub const DEFAULT_WARN_THRESHOLD: usize = 32;
+
+/// Limit value written as `max` (no limit) to cgroup control files.
+pub const UNLIMITED: u64 = u64::MAX;
+
+/// Period, in microseconds, that a [`ResourceKind::CpuMaxMicros`] quota is
+/// granted per (the kernel's default `cpu.max` period).
+pub const CPU_MAX_PERIOD_MICROS: u64 = 100_000;
 
 /// Identifies a single cgroup resource parameter.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum ResourceKind {
     /// Memory limit in bytes (e.g. `memory.max`).
     MemoryMax,
     /// Memory soft limit / high watermark (`memory.high`).
     MemoryHigh,
+    /// Swap limit in bytes (`memory.swap.max`); `0` disables swap and
+    /// [`UNLIMITED`] allows unlimited swap.
+    MemorySwapMax,
     /// CPU weight (1–10000, maps to `cpu.weight`).
     CpuWeight,
     /// Maximum CPU bandwidth in microseconds per period (`cpu.max`).
@@ -42,6 +59,7 @@ impl fmt::Display for ResourceKind {
         match self {
             Self::MemoryMax => write!(f, "memory.max"),
             Self::MemoryHigh => write!(f, "memory.high"),
+            Self::MemorySwapMax => write!(f, "memory.swap.max"),
             Self::CpuWeight => write!(f, "cpu.weight"),
             Self::CpuMaxMicros => write!(f, "cpu.max"),
             Self::IoWeight => write!(f, "io.weight"),
@@ -69,6 +87,8 @@ struct LimitEntry {
 /// ```
 pub struct ResourceLimitBatch {
     entries: Vec<LimitEntry>,
+    cgroup_dir: Option<PathBuf>,
+    warn_threshold: usize,
 }
 
 impl ResourceLimitBatch {
@@ -77,9 +97,30 @@ impl ResourceLimitBatch {
         debug!("Creating ResourceLimitBatch");
         Self {
             entries: Vec::new(),
+            cgroup_dir: None,
+            warn_threshold: DEFAULT_WARN_THRESHOLD,
         }
     }
 
+    /// Warn when more than `threshold` entries are queued at apply time.
+    ///
+    /// There are only a handful of control files, so a large batch almost
+    /// always means limits are being queued in a loop and mostly discarded
+    /// by deduplication.
+    pub fn with_warn_threshold(mut self, threshold: usize) -> Self {
+        self.warn_threshold = threshold;
+        self
+    }
+
+    /// Write limits to the control files in `dir`, a cgroup v2 directory
+    /// such as `/sys/fs/cgroup/enviro/<container>`.
+    ///
+    /// Without a cgroup directory, applying a batch is simulated.
+    pub fn with_cgroup_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cgroup_dir = Some(dir.into());
+        self
+    }
+
     /// Add a limit change to the batch.
     ///
     /// Multiple changes for the same [`ResourceKind`] are allowed; the last
@@ -91,8 +132,8 @@ impl ResourceLimitBatch {
 
     /// Apply all queued limit changes in one pass.
     ///
-    /// Returns a [`BatchApplyReport`] with per-entry timing.  In production
-    /// each entry would write to the corresponding cgroup control file;
+    /// Returns a [`BatchApplyReport`] with per-entry timing.  Each entry is
+    /// written to its control file in the cgroup directory, if one is set;
     /// batching ensures the writes happen back-to-back with no intervening
     /// user-space work.
     pub fn apply_batch(&self) -> Result<BatchApplyReport> {
@@ -105,11 +146,19 @@ impl ResourceLimitBatch {
             deduped.insert(&entry.kind, entry.value);
         }
 
+        if self.entries.len() > self.warn_threshold {
+            warn!(
+                queued = self.entries.len(),
+                applied = deduped.len(),
+                threshold = self.warn_threshold,
+                "Resource limit batch exceeds warning threshold; check for limits queued in a loop"
+            );
+        }
+
         let mut results = Vec::with_capacity(deduped.len());
         for (kind, value) in &deduped {
             let entry_start = Instant::now();
-            // In production: write `value` to `/sys/fs/cgroup/<container>/<kind>`
-            Self::apply_single(kind, *value)
+            self.apply_single(kind, *value)
                 .with_context(|| format!("Failed to apply {kind}"))?;
             results.push(LimitApplyResult {
                 kind: (*kind).clone(),
@@ -126,16 +175,20 @@ impl ResourceLimitBatch {
         );
 
         Ok(BatchApplyReport {
+            queued_count: self.entries.len(),
+            applied_count: results.len(),
             results,
             total_duration,
         })
     }
 
-    /// Apply a single limit (simulated).
-    fn apply_single(kind: &ResourceKind, value: u64) -> Result<()> {
+    /// Apply a single limit, simulated when no cgroup directory is set.
+    fn apply_single(&self, kind: &ResourceKind, value: u64) -> Result<()> {
         debug!(resource = %kind, value, "Writing cgroup control file");
-        // Real implementation writes to /sys/fs/cgroup/…
-        Ok(())
+        match &self.cgroup_dir {
+            Some(dir) => Ok(std::fs::write(dir.join(kind.to_string()), control_value(value))?),
+            None => Ok(()),
+        }
     }
 
     /// Return the number of limit changes currently queued.
@@ -149,12 +202,37 @@ impl ResourceLimitBatch {
     }
 }
 
+/// Format a limit as written to a control file, mapping [`UNLIMITED`] to
+/// `max`.
+fn control_value(value: u64) -> String {
+    if value == UNLIMITED {
+        "max".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
 impl Default for ResourceLimitBatch {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Translate an [`ExecutionContext`](crate::executor::ExecutionContext)'s
+/// limits into cgroup writes: `cpu_cores` becomes a `cpu.max` quota per
+/// [`CPU_MAX_PERIOD_MICROS`], `memory_bytes` becomes `memory.max` and
+/// `pid_limit` becomes `pids.max`.
+impl From<&ResourceLimits> for ResourceLimitBatch {
+    fn from(limits: &ResourceLimits) -> Self {
+        let quota = (limits.cpu_cores * CPU_MAX_PERIOD_MICROS as f64).round() as u64;
+        let mut batch = Self::new();
+        batch.add_limit(ResourceKind::CpuMaxMicros, quota);
+        batch.add_limit(ResourceKind::MemoryMax, limits.memory_bytes);
+        batch.add_limit(ResourceKind::PidsMax, u64::from(limits.pid_limit));
+        batch
+    }
+}
+
 /// Result of applying a single resource limit.
 #[derive(Debug, Clone)]
 pub struct LimitApplyResult {
@@ -166,11 +244,28 @@ pub struct LimitApplyResult {
     pub duration: Duration,
 }
 
+/// One limit that differs between two [`OptimizedResourceLimits`].
+///
+/// `None` means the limit is not set on that side.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimitChange {
+    /// Which resource changed.
+    pub kind: ResourceKind,
+    /// Effective value before the change.
+    pub old: Option<u64>,
+    /// Effective value after the change.
+    pub new: Option<u64>,
+}
+
 /// Aggregated results from [`ResourceLimitBatch::apply_batch`].
 #[derive(Debug)]
 pub struct BatchApplyReport {
     /// Per-limit results.
     pub results: Vec<LimitApplyResult>,
+    /// Entries queued before deduplication.
+    pub queued_count: usize,
+    /// Control files written after deduplication.
+    pub applied_count: usize,
     /// Total wall-clock time for the entire batch.
     pub total_duration: Duration,
 }
@@ -178,8 +273,11 @@ pub struct BatchApplyReport {
 /// Preset resource profiles for common workload shapes.
 ///
 /// Using a profile avoids manually specifying individual limits and
-/// ensures consistent configuration across containers.
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// ensures consistent configuration across containers.  Profiles serialize
+/// as lowercase names (`"standard"`), with custom limits keyed by cgroup
+/// control file (`{ custom = { "memory.max" = 1073741824 } }`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ResourceProfile {
     /// Low-resource profile for sidecar / init containers.
     Minimal,
@@ -191,6 +289,69 @@ pub enum ResourceProfile {
     Custom(HashMap<String, u64>),
 }
 
+impl ResourceProfile {
+    /// Return the limits this profile sets, keyed by resource.
+    ///
+    /// Unrecognized keys in a [`ResourceProfile::Custom`] map are ignored.
+    pub fn defaults(&self) -> HashMap<ResourceKind, u64> {
+        match self {
+            ResourceProfile::Minimal => HashMap::from([
+                (ResourceKind::MemoryMax, 128 * 1024 * 1024),   // 128 MiB
+                (ResourceKind::MemoryHigh, 96 * 1024 * 1024),   // 96 MiB
+                (ResourceKind::MemorySwapMax, 0),               // no swap
+                (ResourceKind::CpuWeight, 50),
+                (ResourceKind::CpuMaxMicros, 50_000),           // 50 ms / period
+                (ResourceKind::IoWeight, 50),
+                (ResourceKind::PidsMax, 64),
+            ]),
+            ResourceProfile::Standard => HashMap::from([
+                (ResourceKind::MemoryMax, 512 * 1024 * 1024),   // 512 MiB
+                (ResourceKind::MemoryHigh, 384 * 1024 * 1024),  // 384 MiB
+                (ResourceKind::MemorySwapMax, 256 * 1024 * 1024), // 256 MiB
+                (ResourceKind::CpuWeight, 100),
+                (ResourceKind::CpuMaxMicros, 100_000),          // 100 ms / period
+                (ResourceKind::IoWeight, 100),
+                (ResourceKind::PidsMax, 512),
+            ]),
+            ResourceProfile::Performance => HashMap::from([
+                (ResourceKind::MemoryMax, 4 * 1024 * 1024 * 1024),  // 4 GiB
+                (ResourceKind::MemoryHigh, 3 * 1024 * 1024 * 1024), // 3 GiB
+                (ResourceKind::MemorySwapMax, 2 * 1024 * 1024 * 1024), // 2 GiB
+                (ResourceKind::CpuWeight, 1000),
+                (ResourceKind::CpuMaxMicros, 1_000_000),            // 1 s / period
+                (ResourceKind::IoWeight, 500),
+                (ResourceKind::PidsMax, 4096),
+            ]),
+            ResourceProfile::Custom(map) => {
+                // Convert string keys back to ResourceKind where recognized.
+                let mut defaults = HashMap::new();
+                for (key, value) in map {
+                    if let Some(kind) = OptimizedResourceLimits::parse_kind(key) {
+                        defaults.insert(kind, *value);
+                    }
+                }
+                defaults
+            }
+        }
+    }
+
+    /// Layer `overlay` on top of `base`.
+    ///
+    /// Starts from the base profile's defaults and replaces every limit the
+    /// overlay sets, so a sparse `Custom` overlay only changes its own keys
+    /// while a preset overlay replaces all of them.
+    pub fn merge(base: &ResourceProfile, overlay: &ResourceProfile) -> ResourceProfile {
+        let mut limits = base.defaults();
+        limits.extend(overlay.defaults());
+        ResourceProfile::Custom(
+            limits
+                .into_iter()
+                .map(|(kind, value)| (kind.to_string(), value))
+                .collect(),
+        )
+    }
+}
+
 /// Pre-configured resource limits for container workloads.
 ///
 /// `OptimizedResourceLimits` combines a [`ResourceProfile`] with the
@@ -205,6 +366,9 @@ pub enum ResourceProfile {
 pub struct OptimizedResourceLimits {
     profile: ResourceProfile,
     overrides: Vec<LimitEntry>,
+    cgroup_dir: Option<PathBuf>,
+    /// Values last written by [`apply_delta`](Self::apply_delta)
+    applied: HashMap<ResourceKind, u64>,
 }
 
 impl OptimizedResourceLimits {
@@ -214,23 +378,107 @@ impl OptimizedResourceLimits {
         Self {
             profile,
             overrides: Vec::new(),
+            cgroup_dir: None,
+            applied: HashMap::new(),
         }
     }
 
+    /// Write limits to the control files in `dir` when applied (see
+    /// [`ResourceLimitBatch::with_cgroup_dir`]).
+    pub fn with_cgroup_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cgroup_dir = Some(dir.into());
+        self
+    }
+
+    /// Write limits to the control files of `cgroup` when applied.
+    pub fn with_cgroup(self, cgroup: &Cgroup) -> Self {
+        self.with_cgroup_dir(cgroup.path())
+    }
+
     /// Override a single limit after selecting a profile.
     pub fn set_override(&mut self, kind: ResourceKind, value: u64) {
         debug!(resource = %kind, value, "Adding limit override");
         self.overrides.push(LimitEntry { kind, value });
     }
 
+    /// Apply `other`'s effective limits (profile + overrides) on top of
+    /// these as overrides.
+    pub fn extend_from(&mut self, other: &OptimizedResourceLimits) {
+        debug!(profile = ?other.profile, "Extending limits");
+        self.overrides.extend(
+            other
+                .get_current_limits()
+                .into_iter()
+                .map(|(kind, value)| LimitEntry { kind, value }),
+        );
+    }
+
     /// Build a [`ResourceLimitBatch`] from the profile defaults plus
     /// any overrides, then apply it.
     pub fn apply(&self) -> Result<BatchApplyReport> {
-        let mut batch = self.build_batch();
-        for entry in &self.overrides {
-            batch.add_limit(entry.kind.clone(), entry.value);
+        self.apply_with(self.new_batch())
+    }
+
+    /// Apply the limits to the cgroup of the running process `pid`.
+    ///
+    /// The cgroup is looked up in `/proc/<pid>/cgroup` and the limits are
+    /// written to its control files, ignoring any directory set with
+    /// [`with_cgroup_dir`](Self::with_cgroup_dir). A process in the root
+    /// cgroup, which has no limit files, is first moved into a dedicated
+    /// `enviro/pid-<pid>` cgroup that outlives this call.
+    pub fn apply_to_pid(&self, pid: u32) -> Result<BatchApplyReport> {
+        self.apply_to_pid_in(Path::new("/proc"), Path::new(CGROUP2_MOUNT), pid)
+    }
+
+    fn apply_to_pid_in(
+        &self,
+        proc_root: &Path,
+        cgroup_root: &Path,
+        pid: u32,
+    ) -> Result<BatchApplyReport> {
+        let relative = cgroup::pid_cgroup_in(proc_root, pid)?;
+        let target = if relative.as_os_str().is_empty() {
+            let dedicated = Cgroup::create_in(cgroup_root, &format!("enviro/pid-{}", pid))?;
+            dedicated.add_process(nix::unistd::Pid::from_raw(pid as i32))?;
+            dedicated.into_path()
+        } else {
+            cgroup_root.join(relative)
+        };
+
+        info!(pid, cgroup = %target.display(), "Applying limits to process cgroup");
+        self.apply_with(ResourceLimitBatch::new().with_cgroup_dir(target))
+    }
+
+    /// Write only the given limit changes, skipping any that match the
+    /// value currently in the control file.
+    ///
+    /// Use this for live updates: rescaling one limit touches one control
+    /// file instead of rewriting all of them.  As with a batch, the last
+    /// change for a kind wins.  Each change is compared with the value the
+    /// previous `apply_delta` wrote for its kind, or with the effective
+    /// limit (as written by [`apply`](Self::apply)) if there was none.
+    pub fn apply_delta(&mut self, changes: &[(ResourceKind, u64)]) -> Result<BatchApplyReport> {
+        let mut deduped: HashMap<&ResourceKind, u64> = HashMap::new();
+        for (kind, value) in changes {
+            deduped.insert(kind, *value);
         }
-        batch.apply_batch()
+
+        let mut written = self.get_current_limits();
+        written.extend(self.applied.iter().map(|(kind, value)| (kind.clone(), *value)));
+        let mut batch = self.new_batch();
+        for (kind, value) in deduped {
+            if written.get(kind) == Some(&value) {
+                debug!(resource = %kind, value, "Skipping unchanged limit");
+                continue;
+            }
+            batch.add_limit(kind.clone(), value);
+        }
+
+        let report = batch.apply_batch()?;
+        for result in &report.results {
+            self.applied.insert(result.kind.clone(), result.value);
+        }
+        Ok(report)
     }
 
     /// Return a snapshot of the current effective limits (profile + overrides).
@@ -242,6 +490,29 @@ impl OptimizedResourceLimits {
         limits
     }
 
+    /// List the effective limits that differ between `self` (old) and
+    /// `other` (new), ordered by [`ResourceKind`].
+    pub fn diff(&self, other: &OptimizedResourceLimits) -> Vec<LimitChange> {
+        let old = self.get_current_limits();
+        let new = other.get_current_limits();
+
+        let mut kinds: Vec<&ResourceKind> = old.keys().chain(new.keys()).collect();
+        kinds.sort();
+        kinds.dedup();
+
+        kinds
+            .into_iter()
+            .filter_map(|kind| {
+                let (old, new) = (old.get(kind).copied(), new.get(kind).copied());
+                (old != new).then(|| LimitChange {
+                    kind: kind.clone(),
+                    old,
+                    new,
+                })
+            })
+            .collect()
+    }
+
     /// Return the active profile.
     pub fn profile(&self) -> &ResourceProfile {
         &self.profile
@@ -249,58 +520,33 @@ impl OptimizedResourceLimits {
 
     // ── private helpers ───────────────────────────────────────────────
 
-    fn build_batch(&self) -> ResourceLimitBatch {
-        let defaults = self.profile_defaults();
-        let mut batch = ResourceLimitBatch::new();
-        for (kind, value) in defaults {
+    fn new_batch(&self) -> ResourceLimitBatch {
+        match &self.cgroup_dir {
+            Some(dir) => ResourceLimitBatch::new().with_cgroup_dir(dir),
+            None => ResourceLimitBatch::new(),
+        }
+    }
+
+    /// Queue the profile defaults plus overrides on `batch` and apply it.
+    fn apply_with(&self, mut batch: ResourceLimitBatch) -> Result<BatchApplyReport> {
+        for (kind, value) in self.profile_defaults() {
             batch.add_limit(kind, value);
         }
-        batch
+        for entry in &self.overrides {
+            batch.add_limit(entry.kind.clone(), entry.value);
+        }
+        batch.apply_batch()
     }
 
     fn profile_defaults(&self) -> HashMap<ResourceKind, u64> {
-        match &self.profile {
-            ResourceProfile::Minimal => HashMap::from([
-                (ResourceKind::MemoryMax, 128 * 1024 * 1024),   // 128 MiB
-                (ResourceKind::MemoryHigh, 96 * 1024 * 1024),   // 96 MiB
-                (ResourceKind::CpuWeight, 50),
-                (ResourceKind::CpuMaxMicros, 50_000),           // 50 ms / period
-                (ResourceKind::IoWeight, 50),
-                (ResourceKind::PidsMax, 64),
-            ]),
-            ResourceProfile::Standard => HashMap::from([
-                (ResourceKind::MemoryMax, 512 * 1024 * 1024),   // 512 MiB
-                (ResourceKind::MemoryHigh, 384 * 1024 * 1024),  // 384 MiB
-                (ResourceKind::CpuWeight, 100),
-                (ResourceKind::CpuMaxMicros, 100_000),          // 100 ms / period
-                (ResourceKind::IoWeight, 100),
-                (ResourceKind::PidsMax, 512),
-            ]),
-            ResourceProfile::Performance => HashMap::from([
-                (ResourceKind::MemoryMax, 4 * 1024 * 1024 * 1024),  // 4 GiB
-                (ResourceKind::MemoryHigh, 3 * 1024 * 1024 * 1024), // 3 GiB
-                (ResourceKind::CpuWeight, 1000),
-                (ResourceKind::CpuMaxMicros, 1_000_000),            // 1 s / period
-                (ResourceKind::IoWeight, 500),
-                (ResourceKind::PidsMax, 4096),
-            ]),
-            ResourceProfile::Custom(map) => {
-                // Convert string keys back to ResourceKind where recognized.
-                let mut defaults = HashMap::new();
-                for (key, value) in map {
-                    if let Some(kind) = Self::parse_kind(key) {
-                        defaults.insert(kind, *value);
-                    }
-                }
-                defaults
-            }
-        }
+        self.profile.defaults()
     }
 
     fn parse_kind(key: &str) -> Option<ResourceKind> {
         match key {
             "memory.max" => Some(ResourceKind::MemoryMax),
             "memory.high" => Some(ResourceKind::MemoryHigh),
+            "memory.swap.max" => Some(ResourceKind::MemorySwapMax),
             "cpu.weight" => Some(ResourceKind::CpuWeight),
             "cpu.max" => Some(ResourceKind::CpuMaxMicros),
             "io.weight" => Some(ResourceKind::IoWeight),
@@ -358,6 +604,69 @@ mod tests {
         assert!(report.total_duration < Duration::from_secs(1));
     }
 
+    #[test]
+    fn test_batch_counts_duplicates() {
+        let mut batch = ResourceLimitBatch::new().with_warn_threshold(8);
+        for i in 0..100 {
+            batch.add_limit(ResourceKind::PidsMax, i);
+            batch.add_limit(ResourceKind::CpuWeight, 100);
+        }
+        let report = batch.apply_batch().unwrap();
+        assert_eq!(report.queued_count, 200);
+        assert_eq!(report.applied_count, 2);
+        assert_eq!(report.results.len(), report.applied_count);
+    }
+
+    #[test]
+    fn test_batch_counts_without_duplicates() {
+        let mut batch = ResourceLimitBatch::new();
+        batch.add_limit(ResourceKind::MemoryMax, 1024);
+        batch.add_limit(ResourceKind::PidsMax, 10);
+        let report = batch.apply_batch().unwrap();
+        assert_eq!(report.queued_count, 2);
+        assert_eq!(report.applied_count, 2);
+    }
+
+    #[test]
+    fn test_batch_from_execution_limits() {
+        let cgroup = tempfile::tempdir().unwrap();
+        let limits = ResourceLimits {
+            cpu_cores: 0.5,
+            memory_bytes: 256 * 1024 * 1024,
+            pid_limit: 32,
+        };
+        let batch = ResourceLimitBatch::from(&limits).with_cgroup_dir(cgroup.path());
+        assert_eq!(batch.len(), 3);
+        batch.apply_batch().unwrap();
+
+        let files = control_files(cgroup.path());
+        assert_eq!(
+            files,
+            vec![
+                ("cpu.max".to_string(), "50000".to_string()),
+                ("memory.max".to_string(), "268435456".to_string()),
+                ("pids.max".to_string(), "32".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_batch_from_fractional_cores_rounds() {
+        let cgroup = tempfile::tempdir().unwrap();
+        let limits = ResourceLimits {
+            cpu_cores: 1.0 / 3.0,
+            memory_bytes: 1,
+            pid_limit: 1,
+        };
+        ResourceLimitBatch::from(&limits)
+            .with_cgroup_dir(cgroup.path())
+            .apply_batch()
+            .unwrap();
+
+        let cpu_max = std::fs::read_to_string(cgroup.path().join("cpu.max")).unwrap();
+        assert_eq!(cpu_max, "33333");
+    }
+
     #[test]
     fn test_batch_default() {
         let batch = ResourceLimitBatch::default();
@@ -434,9 +743,275 @@ mod tests {
         assert_eq!(pids_result.value, 256);
     }
 
+    #[test]
+    fn test_merge_standard_with_custom_overlay() {
+        let overlay = ResourceProfile::Custom(HashMap::from([
+            ("memory.max".to_string(), 1024 * 1024 * 1024),
+            ("pids.max".to_string(), 2048),
+        ]));
+        let merged = ResourceProfile::merge(&ResourceProfile::Standard, &overlay);
+        assert!(matches!(merged, ResourceProfile::Custom(_)));
+
+        let standard = ResourceProfile::Standard.defaults();
+        let limits = merged.defaults();
+        assert_eq!(limits.len(), standard.len());
+        for (kind, value) in &limits {
+            match kind {
+                ResourceKind::MemoryMax => assert_eq!(*value, 1024 * 1024 * 1024),
+                ResourceKind::PidsMax => assert_eq!(*value, 2048),
+                other => assert_eq!(*value, standard[other], "{} changed", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_merge_preset_overlay_replaces_everything() {
+        let base = ResourceProfile::Custom(HashMap::from([("cpu.weight".to_string(), 7)]));
+        let merged = ResourceProfile::merge(&base, &ResourceProfile::Minimal);
+        assert_eq!(merged.defaults(), ResourceProfile::Minimal.defaults());
+
+        let empty = ResourceProfile::Custom(HashMap::new());
+        let merged = ResourceProfile::merge(&ResourceProfile::Performance, &empty);
+        assert_eq!(merged.defaults(), ResourceProfile::Performance.defaults());
+    }
+
+    #[test]
+    fn test_extend_from() {
+        let mut limits = OptimizedResourceLimits::from_profile(ResourceProfile::Standard);
+        limits.set_override(ResourceKind::CpuWeight, 300);
+        limits.set_override(ResourceKind::IoWeight, 200);
+
+        let mut team = OptimizedResourceLimits::from_profile(ResourceProfile::Custom(
+            HashMap::from([("memory.max".to_string(), 2048)]),
+        ));
+        team.set_override(ResourceKind::CpuWeight, 400);
+        limits.extend_from(&team);
+
+        let current = limits.get_current_limits();
+        assert_eq!(current[&ResourceKind::MemoryMax], 2048);
+        assert_eq!(current[&ResourceKind::CpuWeight], 400);
+        // Untouched by `team`: earlier override and profile default survive.
+        assert_eq!(current[&ResourceKind::IoWeight], 200);
+        assert_eq!(current[&ResourceKind::PidsMax], 512);
+        assert_eq!(*limits.profile(), ResourceProfile::Standard);
+    }
+
+    #[test]
+    fn test_diff_between_profiles() {
+        let standard = OptimizedResourceLimits::from_profile(ResourceProfile::Standard);
+        let mut tuned = OptimizedResourceLimits::from_profile(ResourceProfile::Standard);
+        tuned.set_override(ResourceKind::MemoryMax, 1024 * 1024 * 1024);
+        tuned.set_override(ResourceKind::PidsMax, 1024);
+        // Same as the profile default, so not a change.
+        tuned.set_override(ResourceKind::CpuWeight, 100);
+
+        assert_eq!(
+            standard.diff(&tuned),
+            vec![
+                LimitChange {
+                    kind: ResourceKind::MemoryMax,
+                    old: Some(512 * 1024 * 1024),
+                    new: Some(1024 * 1024 * 1024),
+                },
+                LimitChange {
+                    kind: ResourceKind::PidsMax,
+                    old: Some(512),
+                    new: Some(1024),
+                },
+            ]
+        );
+        assert!(standard.diff(&standard).is_empty());
+    }
+
+    #[test]
+    fn test_diff_with_unset_limits() {
+        let sparse = OptimizedResourceLimits::from_profile(ResourceProfile::Custom(
+            HashMap::from([("pids.max".to_string(), 64)]),
+        ));
+        let minimal = OptimizedResourceLimits::from_profile(ResourceProfile::Minimal);
+
+        let changes = sparse.diff(&minimal);
+        let kinds: Vec<_> = changes.iter().map(|c| c.kind.clone()).collect();
+        // pids.max is 64 in both, everything else is newly set.
+        assert_eq!(
+            kinds,
+            vec![
+                ResourceKind::MemoryMax,
+                ResourceKind::MemoryHigh,
+                ResourceKind::MemorySwapMax,
+                ResourceKind::CpuWeight,
+                ResourceKind::CpuMaxMicros,
+                ResourceKind::IoWeight,
+            ]
+        );
+        assert!(changes.iter().all(|c| c.old.is_none() && c.new.is_some()));
+
+        let reverse = minimal.diff(&sparse);
+        assert_eq!(reverse.len(), 6);
+        assert!(reverse.iter().all(|c| c.old.is_some() && c.new.is_none()));
+    }
+
+    fn control_files(dir: &std::path::Path) -> Vec<(String, String)> {
+        let mut files: Vec<_> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| {
+                let path = entry.unwrap().path();
+                (
+                    path.file_name().unwrap().to_string_lossy().into_owned(),
+                    std::fs::read_to_string(&path).unwrap(),
+                )
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn test_apply_writes_control_files() {
+        let cgroup = tempfile::tempdir().unwrap();
+        let limits = OptimizedResourceLimits::from_profile(ResourceProfile::Minimal)
+            .with_cgroup_dir(cgroup.path());
+        limits.apply().unwrap();
+
+        let files = control_files(cgroup.path());
+        assert_eq!(files.len(), 7);
+        assert!(files.contains(&("pids.max".to_string(), "64".to_string())));
+        assert!(files.contains(&("cpu.weight".to_string(), "50".to_string())));
+    }
+
+    #[test]
+    fn test_apply_targets_created_cgroup() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join("cgroup.controllers"), "cpu io memory pids").unwrap();
+        let cgroup = Cgroup::create_in(root.path(), "web-1").unwrap();
+
+        OptimizedResourceLimits::from_profile(ResourceProfile::Minimal)
+            .with_cgroup(&cgroup)
+            .apply()
+            .unwrap();
+
+        let files = control_files(cgroup.path());
+        assert_eq!(files.len(), 7);
+        assert!(files.contains(&("memory.max".to_string(), "134217728".to_string())));
+    }
+
+    #[test]
+    fn test_apply_delta_writes_only_changes() {
+        let cgroup = tempfile::tempdir().unwrap();
+        let mut limits = OptimizedResourceLimits::from_profile(ResourceProfile::Standard)
+            .with_cgroup_dir(cgroup.path());
+
+        let report = limits
+            .apply_delta(&[
+                (ResourceKind::MemoryMax, 1024 * 1024 * 1024),
+                // Unchanged from the Standard profile.
+                (ResourceKind::PidsMax, 512),
+                // Last change for a kind wins.
+                (ResourceKind::CpuWeight, 100),
+                (ResourceKind::CpuWeight, 250),
+            ])
+            .unwrap();
+
+        let mut applied: Vec<_> = report.results.iter().map(|r| (r.kind.clone(), r.value)).collect();
+        applied.sort();
+        assert_eq!(
+            applied,
+            vec![
+                (ResourceKind::MemoryMax, 1024 * 1024 * 1024),
+                (ResourceKind::CpuWeight, 250),
+            ]
+        );
+        assert_eq!(
+            control_files(cgroup.path()),
+            vec![
+                ("cpu.weight".to_string(), "250".to_string()),
+                ("memory.max".to_string(), "1073741824".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_delta_all_unchanged() {
+        let cgroup = tempfile::tempdir().unwrap();
+        let mut limits = OptimizedResourceLimits::from_profile(ResourceProfile::Standard)
+            .with_cgroup_dir(cgroup.path());
+        limits.set_override(ResourceKind::IoWeight, 300);
+
+        let report = limits
+            .apply_delta(&[(ResourceKind::IoWeight, 300), (ResourceKind::CpuWeight, 100)])
+            .unwrap();
+        assert!(report.results.is_empty());
+        assert!(control_files(cgroup.path()).is_empty());
+    }
+
+    #[test]
+    fn test_apply_delta_reports_write_failure() {
+        let cgroup = tempfile::tempdir().unwrap();
+        let mut limits = OptimizedResourceLimits::from_profile(ResourceProfile::Standard)
+            .with_cgroup_dir(cgroup.path().join("missing"));
+
+        let err = limits.apply_delta(&[(ResourceKind::PidsMax, 1)]).unwrap_err();
+        assert_eq!(err.to_string(), "Failed to apply pids.max");
+    }
+
+    #[test]
+    fn test_apply_delta_diffs_against_last_write() {
+        const MB: u64 = 1024 * 1024;
+        let cgroup = tempfile::tempdir().unwrap();
+        let mut limits = OptimizedResourceLimits::from_profile(ResourceProfile::Custom(
+            HashMap::from([("memory.max".to_string(), 512 * MB)]),
+        ))
+        .with_cgroup_dir(cgroup.path());
+        limits.apply().unwrap();
+        let memory_max = || std::fs::read_to_string(cgroup.path().join("memory.max")).unwrap();
+
+        let report = limits.apply_delta(&[(ResourceKind::MemoryMax, 1024 * MB)]).unwrap();
+        assert_eq!(report.applied_count, 1);
+        assert_eq!(memory_max(), (1024 * MB).to_string());
+
+        // Back to the profile value: the control file still says 1G
+        let report = limits.apply_delta(&[(ResourceKind::MemoryMax, 512 * MB)]).unwrap();
+        assert_eq!(report.applied_count, 1);
+        assert_eq!(memory_max(), (512 * MB).to_string());
+
+        let report = limits.apply_delta(&[(ResourceKind::MemoryMax, 512 * MB)]).unwrap();
+        assert_eq!(report.applied_count, 0);
+    }
+
+    #[test]
+    fn test_swap_limit_formatting() {
+        let cgroup = tempfile::tempdir().unwrap();
+        let swap = cgroup.path().join("memory.swap.max");
+        let batch = |value| {
+            let mut batch = ResourceLimitBatch::new().with_cgroup_dir(cgroup.path());
+            batch.add_limit(ResourceKind::MemorySwapMax, value);
+            batch.apply_batch().unwrap();
+            std::fs::read_to_string(&swap).unwrap()
+        };
+
+        assert_eq!(batch(0), "0");
+        assert_eq!(batch(UNLIMITED), "max");
+        assert_eq!(batch(64 * 1024 * 1024), "67108864");
+    }
+
+    #[test]
+    fn test_swap_profile_defaults() {
+        assert_eq!(ResourceProfile::Minimal.defaults()[&ResourceKind::MemorySwapMax], 0);
+        let standard = ResourceProfile::Standard.defaults()[&ResourceKind::MemorySwapMax];
+        let performance = ResourceProfile::Performance.defaults()[&ResourceKind::MemorySwapMax];
+        assert!(standard > 0 && performance > standard);
+
+        let custom = ResourceProfile::Custom(HashMap::from([(
+            "memory.swap.max".to_string(),
+            UNLIMITED,
+        )]));
+        assert_eq!(custom.defaults()[&ResourceKind::MemorySwapMax], UNLIMITED);
+    }
+
     #[test]
     fn test_resource_kind_display() {
         assert_eq!(ResourceKind::MemoryMax.to_string(), "memory.max");
+        assert_eq!(ResourceKind::MemorySwapMax.to_string(), "memory.swap.max");
         assert_eq!(ResourceKind::CpuWeight.to_string(), "cpu.weight");
         assert_eq!(ResourceKind::PidsMax.to_string(), "pids.max");
     }
@@ -446,4 +1021,70 @@ mod tests {
         let limits = OptimizedResourceLimits::from_profile(ResourceProfile::Performance);
         assert_eq!(*limits.profile(), ResourceProfile::Performance);
     }
+
+    /// A proc tree with `/proc/<pid>/cgroup` reading `cgroup`, plus a
+    /// cgroup2 root offering every controller.
+    fn mock_proc_and_cgroup(pid: u32, cgroup: &str) -> (tempfile::TempDir, tempfile::TempDir) {
+        let proc_root = tempfile::tempdir().unwrap();
+        let pid_dir = proc_root.path().join(pid.to_string());
+        std::fs::create_dir(&pid_dir).unwrap();
+        std::fs::write(pid_dir.join("cgroup"), cgroup).unwrap();
+
+        let cgroup_root = tempfile::tempdir().unwrap();
+        std::fs::write(cgroup_root.path().join("cgroup.controllers"), "cpu io memory pids")
+            .unwrap();
+        (proc_root, cgroup_root)
+    }
+
+    #[test]
+    fn test_apply_to_pid_targets_process_cgroup() {
+        let (proc_root, cgroup_root) =
+            mock_proc_and_cgroup(4242, "1:name=systemd:/\n0::/system.slice/web.service\n");
+        let target = cgroup_root.path().join("system.slice/web.service");
+        std::fs::create_dir_all(&target).unwrap();
+
+        let limits = OptimizedResourceLimits::from_profile(ResourceProfile::Minimal)
+            .with_cgroup_dir(cgroup_root.path().join("ignored"));
+        let report = limits
+            .apply_to_pid_in(proc_root.path(), cgroup_root.path(), 4242)
+            .unwrap();
+
+        assert_eq!(report.results.len(), 7);
+        let files = control_files(&target);
+        assert_eq!(files.len(), 7);
+        assert!(files.contains(&("pids.max".to_string(), "64".to_string())));
+        assert!(!cgroup_root.path().join("ignored").exists());
+        assert!(!cgroup_root.path().join("enviro").exists());
+    }
+
+    #[test]
+    fn test_apply_to_pid_in_root_cgroup_creates_dedicated_cgroup() {
+        let (proc_root, cgroup_root) = mock_proc_and_cgroup(4242, "0::/\n");
+        // cgroupfs populates this in every cgroup, including `enviro`
+        let parent = cgroup_root.path().join("enviro");
+        std::fs::create_dir(&parent).unwrap();
+        std::fs::write(parent.join("cgroup.controllers"), "cpu io memory pids").unwrap();
+
+        let limits = OptimizedResourceLimits::from_profile(ResourceProfile::Minimal);
+        limits
+            .apply_to_pid_in(proc_root.path(), cgroup_root.path(), 4242)
+            .unwrap();
+
+        let dedicated = cgroup_root.path().join("enviro/pid-4242");
+        let files = control_files(&dedicated);
+        assert!(files.contains(&("cgroup.procs".to_string(), "4242".to_string())));
+        assert!(files.contains(&("memory.max".to_string(), "134217728".to_string())));
+        // Limit files are never written to the root cgroup itself.
+        assert!(!cgroup_root.path().join("memory.max").exists());
+    }
+
+    #[test]
+    fn test_apply_to_unknown_pid_fails() {
+        let (proc_root, cgroup_root) = mock_proc_and_cgroup(4242, "0::/\n");
+        let limits = OptimizedResourceLimits::from_profile(ResourceProfile::Minimal);
+        let err = limits
+            .apply_to_pid_in(proc_root.path(), cgroup_root.path(), 7)
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("7/cgroup"));
+    }
 }
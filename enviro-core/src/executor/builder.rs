@@ -0,0 +1,338 @@
+//! Execution Context Builder - Validated ExecutionContext Construction
+//!
+//! [`ExecutionContext`] has many fields, most of which callers leave at the
+//! runtime's defaults. [`ExecutionContextBuilder`] starts from those
+//! defaults (cleared host environment, [`default_rlimits`],
+//! [`DEFAULT_UMASK`], 1 core / 512MB / 100 PIDs, isolated network), lets
+//! callers override only what they need, and rejects contexts no executor
+//! could run.
+//!
+//! # Performance Pattern: Fail Before Spawning
+//! `build` checks the limits once, up front, instead of letting a zero
+//! memory limit or PID limit surface as a confusing cgroup or `fork` error
+//! after namespaces have already been set up.
+
+use nix::sys::resource::Resource;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use super::{
+    default_rlimits, EnvPolicy, ExecutionContext, NetworkConfig, ResourceLimits, UndefinedEnv,
+    DEFAULT_UMASK,
+};
+
+/// A context rejected by [`ExecutionContextBuilder::build`]
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ContextError {
+    /// `container_id` is empty
+    #[error("container_id must not be empty")]
+    EmptyContainerId,
+    /// `cpu_cores` is zero, negative or not a number
+    #[error("cpu_cores must be greater than 0, got {0}")]
+    InvalidCpuCores(f64),
+    /// `memory_bytes` is zero
+    #[error("memory_bytes must be greater than 0")]
+    ZeroMemory,
+    /// `pid_limit` is zero
+    #[error("pid_limit must be greater than 0")]
+    ZeroPidLimit,
+}
+
+/// Fluent builder for [`ExecutionContext`]
+///
+/// ```
+/// use enviro_core::executor::ExecutionContext;
+///
+/// let ctx = ExecutionContext::builder("web-1")
+///     .env("PORT", "8080")
+///     .workdir("/srv")
+///     .memory_bytes(256 * 1024 * 1024)
+///     .build()
+///     .unwrap();
+/// assert_eq!(ctx.limits.memory_bytes, 256 * 1024 * 1024);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ExecutionContextBuilder {
+    ctx: ExecutionContext,
+}
+
+impl ExecutionContext {
+    /// Start building a context for `container_id` from the runtime defaults
+    pub fn builder(container_id: impl Into<String>) -> ExecutionContextBuilder {
+        ExecutionContextBuilder::new(container_id)
+    }
+}
+
+impl ExecutionContextBuilder {
+    /// Builder for `container_id` with the runtime defaults
+    pub fn new(container_id: impl Into<String>) -> Self {
+        Self {
+            ctx: ExecutionContext {
+                container_id: container_id.into(),
+                env: HashMap::new(),
+                expand_env: false,
+                undefined_env: UndefinedEnv::Empty,
+                env_policy: EnvPolicy::Clear,
+                redact_env: HashSet::new(),
+                workdir: "/".to_string(),
+                create_workdir: false,
+                merge_stderr: false,
+                rlimits: default_rlimits(),
+                umask: Some(DEFAULT_UMASK),
+                run_as: None,
+                cgroup: None,
+                limits: ResourceLimits {
+                    cpu_cores: 1.0,
+                    memory_bytes: 512 * 1024 * 1024,
+                    pid_limit: 100,
+                },
+                network: NetworkConfig {
+                    isolated: true,
+                    ip_address: None,
+                    dns_servers: vec![],
+                    port_mappings: vec![],
+                },
+            },
+        }
+    }
+
+    /// Set one environment variable
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.ctx.env.insert(key.into(), value.into());
+        self
+    }
+
+    /// Set several environment variables
+    pub fn envs<K, V>(mut self, vars: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.ctx.env.extend(
+            vars.into_iter()
+                .map(|(key, value)| (key.into(), value.into())),
+        );
+        self
+    }
+
+    /// Set an environment variable whose value is masked in `Debug` output
+    pub fn secret_env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        let key = key.into();
+        self.ctx.redact_env.insert(key.clone());
+        self.ctx.env.insert(key, value.into());
+        self
+    }
+
+    /// Expand `$VAR` references in `env` values
+    pub fn expand_env(mut self, expand: bool) -> Self {
+        self.ctx.expand_env = expand;
+        self
+    }
+
+    /// How references to unset variables are treated when expanding
+    pub fn undefined_env(mut self, undefined_env: UndefinedEnv) -> Self {
+        self.ctx.undefined_env = undefined_env;
+        self
+    }
+
+    /// Which host environment variables the workload inherits
+    pub fn env_policy(mut self, policy: EnvPolicy) -> Self {
+        self.ctx.env_policy = policy;
+        self
+    }
+
+    /// Working directory of the workload
+    pub fn workdir(mut self, workdir: impl Into<String>) -> Self {
+        self.ctx.workdir = workdir.into();
+        self
+    }
+
+    /// Create the working directory during `prepare` if it is missing
+    pub fn create_workdir(mut self, create: bool) -> Self {
+        self.ctx.create_workdir = create;
+        self
+    }
+
+    /// Capture stderr interleaved into stdout
+    pub fn merge_stderr(mut self, merge: bool) -> Self {
+        self.ctx.merge_stderr = merge;
+        self
+    }
+
+    /// Add or replace the limit for `resource`
+    pub fn rlimit(mut self, resource: Resource, soft: u64, hard: u64) -> Self {
+        self.ctx.rlimits.retain(|&(known, _, _)| known != resource);
+        self.ctx.rlimits.push((resource, soft, hard));
+        self
+    }
+
+    /// Replace all rlimits, including the defaults
+    pub fn rlimits(mut self, rlimits: Vec<(Resource, u64, u64)>) -> Self {
+        self.ctx.rlimits = rlimits;
+        self
+    }
+
+    /// File mode creation mask; `None` inherits the runtime's
+    pub fn umask(mut self, umask: Option<u32>) -> Self {
+        self.ctx.umask = umask;
+        self
+    }
+
+    /// Run the workload as `uid` with `gid` as its only group
+    pub fn run_as(mut self, uid: u32, gid: u32) -> Self {
+        self.ctx.run_as = Some((uid, gid));
+        self
+    }
+
+    /// cgroup v2 directory the workload joins before exec
+    pub fn cgroup(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.ctx.cgroup = Some(dir.into());
+        self
+    }
+
+    /// CPU cores available to the workload
+    pub fn cpu_cores(mut self, cores: f64) -> Self {
+        self.ctx.limits.cpu_cores = cores;
+        self
+    }
+
+    /// Memory limit in bytes
+    pub fn memory_bytes(mut self, bytes: u64) -> Self {
+        self.ctx.limits.memory_bytes = bytes;
+        self
+    }
+
+    /// Maximum number of processes
+    pub fn pid_limit(mut self, limit: u32) -> Self {
+        self.ctx.limits.pid_limit = limit;
+        self
+    }
+
+    /// Replace all resource limits
+    pub fn limits(mut self, limits: ResourceLimits) -> Self {
+        self.ctx.limits = limits;
+        self
+    }
+
+    /// Replace the network configuration
+    pub fn network(mut self, network: NetworkConfig) -> Self {
+        self.ctx.network = network;
+        self
+    }
+
+    /// Return the context without validating it
+    ///
+    /// For the runtime's own contexts, whose limits come from profiles it
+    /// has already accepted and whose ID may be filled in later.
+    pub(crate) fn build_unchecked(self) -> ExecutionContext {
+        self.ctx
+    }
+
+    /// Validate and return the context
+    pub fn build(self) -> Result<ExecutionContext, ContextError> {
+        let ctx = self.ctx;
+        if ctx.container_id.is_empty() {
+            return Err(ContextError::EmptyContainerId);
+        }
+        if ctx.limits.cpu_cores.is_nan() || ctx.limits.cpu_cores <= 0.0 {
+            return Err(ContextError::InvalidCpuCores(ctx.limits.cpu_cores));
+        }
+        if ctx.limits.memory_bytes == 0 {
+            return Err(ContextError::ZeroMemory);
+        }
+        if ctx.limits.pid_limit == 0 {
+            return Err(ContextError::ZeroPidLimit);
+        }
+        Ok(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minimal_build_uses_defaults() {
+        let ctx = ExecutionContext::builder("minimal").build().unwrap();
+
+        assert_eq!(ctx.container_id, "minimal");
+        assert!(ctx.env.is_empty());
+        assert_eq!(ctx.env_policy, EnvPolicy::Clear);
+        assert_eq!(ctx.workdir, "/");
+        assert_eq!(ctx.rlimits, default_rlimits());
+        assert_eq!(ctx.umask, Some(DEFAULT_UMASK));
+        assert_eq!(ctx.limits.cpu_cores, 1.0);
+        assert_eq!(ctx.limits.memory_bytes, 512 * 1024 * 1024);
+        assert_eq!(ctx.limits.pid_limit, 100);
+        assert!(ctx.network.isolated);
+    }
+
+    #[test]
+    fn test_setters() {
+        let ctx = ExecutionContext::builder("custom")
+            .envs([("A", "1"), ("B", "2")])
+            .secret_env("TOKEN", "hunter2")
+            .workdir("/srv")
+            .rlimit(Resource::RLIMIT_NOFILE, 64, 64)
+            .run_as(65534, 65534)
+            .cpu_cores(0.5)
+            .pid_limit(10)
+            .build()
+            .unwrap();
+
+        assert_eq!(ctx.env.len(), 3);
+        assert!(ctx.redact_env.contains("TOKEN"));
+        assert!(!format!("{:?}", ctx).contains("hunter2"));
+        assert_eq!(ctx.workdir, "/srv");
+        assert_eq!(
+            ctx.rlimits,
+            vec![
+                (Resource::RLIMIT_CORE, 0, 0),
+                (Resource::RLIMIT_NOFILE, 64, 64)
+            ]
+        );
+        assert_eq!(ctx.run_as, Some((65534, 65534)));
+        assert_eq!(ctx.limits.cpu_cores, 0.5);
+        assert_eq!(ctx.limits.pid_limit, 10);
+    }
+
+    #[test]
+    fn test_empty_container_id_rejected() {
+        let err = ExecutionContext::builder("").build().unwrap_err();
+        assert_eq!(err, ContextError::EmptyContainerId);
+    }
+
+    #[test]
+    fn test_invalid_cpu_cores_rejected() {
+        for cores in [0.0, -1.0] {
+            let err = ExecutionContext::builder("cpu")
+                .cpu_cores(cores)
+                .build()
+                .unwrap_err();
+            assert_eq!(err, ContextError::InvalidCpuCores(cores));
+        }
+        let err = ExecutionContext::builder("cpu")
+            .cpu_cores(f64::NAN)
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, ContextError::InvalidCpuCores(cores) if cores.is_nan()));
+    }
+
+    #[test]
+    fn test_zero_memory_rejected() {
+        let err = ExecutionContext::builder("mem")
+            .memory_bytes(0)
+            .build()
+            .unwrap_err();
+        assert_eq!(err, ContextError::ZeroMemory);
+    }
+
+    #[test]
+    fn test_zero_pid_limit_rejected() {
+        let err = ExecutionContext::builder("pids")
+            .pid_limit(0)
+            .build()
+            .unwrap_err();
+        assert_eq!(err, ContextError::ZeroPidLimit);
+    }
+}
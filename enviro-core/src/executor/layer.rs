@@ -0,0 +1,655 @@
+//! Executor Layers - Composable Cross-Cutting Behavior
+//!
+//! An [`ExecutorLayer`] wraps an executor in another executor, tower-style,
+//! so logging, metrics or retries can be added to any implementation without
+//! touching it. [`LayeredExecutor`] applies a stack of layers in order:
+//!
+//! ```rust,no_run
+//! # use std::sync::Arc;
+//! # use enviro_core::executor::{LayeredExecutor, MetricsLayer, NativeExecutor, TracingLayer};
+//! # use enviro_core::perf::PerfMetrics;
+//! let executor = LayeredExecutor::new(
+//!     Arc::new(NativeExecutor::new()),
+//!     vec![Arc::new(TracingLayer), Arc::new(MetricsLayer::new(PerfMetrics::new()))],
+//! );
+//! ```
+//!
+//! The first layer is outermost: it sees each call first and its result
+//! last.
+//!
+//! # Performance Pattern: Static Stack
+//! Layers run once, when the stack is built. Each call then goes through a
+//! fixed chain of `Arc<dyn Executor>` hops with no per-call allocation or
+//! lookups.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tracing::{debug, info_span, Instrument};
+
+use super::{ExecutionContext, ExecutionResult, Executor, ExecutorKind};
+use crate::perf::{PerfMetrics, ScopedTimer, TimerType};
+
+/// Wraps an executor with additional behavior
+pub trait ExecutorLayer: Send + Sync {
+    /// Return an executor that adds this layer's behavior around `inner`
+    fn wrap(&self, inner: Arc<dyn Executor>) -> Arc<dyn Executor>;
+}
+
+/// Borrow a wrapped executor mutably for `prepare`, `cleanup` and `restore`
+///
+/// This only succeeds while the wrapper holds the sole reference, which is
+/// always the case for stacks built by [`LayeredExecutor`].
+fn exclusive(inner: &mut Arc<dyn Executor>) -> Result<&mut (dyn Executor + 'static)> {
+    Arc::get_mut(inner).context("Wrapped executor is shared and cannot be mutated through a layer")
+}
+
+/// An executor wrapped in a stack of [`ExecutorLayer`]s
+pub struct LayeredExecutor {
+    stack: Arc<dyn Executor>,
+}
+
+impl LayeredExecutor {
+    /// Wrap `inner` in `layers`, the first of which ends up outermost
+    pub fn new(inner: Arc<dyn Executor>, layers: Vec<Arc<dyn ExecutorLayer>>) -> Self {
+        let stack = layers
+            .iter()
+            .rev()
+            .fold(inner, |executor, layer| layer.wrap(executor));
+        Self { stack }
+    }
+}
+
+#[async_trait]
+impl Executor for LayeredExecutor {
+    async fn prepare(&mut self, ctx: &ExecutionContext) -> Result<()> {
+        exclusive(&mut self.stack)?.prepare(ctx).await
+    }
+
+    async fn execute(
+        &self,
+        ctx: &ExecutionContext,
+        command: &str,
+        args: &[String],
+    ) -> Result<ExecutionResult> {
+        self.stack.execute(ctx, command, args).await
+    }
+
+    async fn cleanup(&mut self, ctx: &ExecutionContext) -> Result<()> {
+        exclusive(&mut self.stack)?.cleanup(ctx).await
+    }
+
+    fn executor_type(&self) -> &str {
+        self.stack.executor_type()
+    }
+
+    fn kind(&self) -> ExecutorKind {
+        self.stack.kind()
+    }
+
+    fn supports_checkpoint(&self) -> bool {
+        self.stack.supports_checkpoint()
+    }
+
+    async fn checkpoint(&self, ctx: &ExecutionContext, path: &str) -> Result<()> {
+        self.stack.checkpoint(ctx, path).await
+    }
+
+    async fn restore(&mut self, ctx: &ExecutionContext, path: &str) -> Result<()> {
+        exclusive(&mut self.stack)?.restore(ctx, path).await
+    }
+}
+
+/// Records every `execute` call into [`PerfMetrics`] via [`MeteredExecutor`]
+pub struct MetricsLayer {
+    metrics: Arc<PerfMetrics>,
+}
+
+impl MetricsLayer {
+    /// Record into `metrics`
+    pub fn new(metrics: Arc<PerfMetrics>) -> Self {
+        Self { metrics }
+    }
+}
+
+impl ExecutorLayer for MetricsLayer {
+    fn wrap(&self, inner: Arc<dyn Executor>) -> Arc<dyn Executor> {
+        Arc::new(MeteredExecutor::new(inner, self.metrics.clone()))
+    }
+}
+
+/// An executor that records every `execute` call into [`PerfMetrics`]
+///
+/// Each call runs under a [`ScopedTimer`]: successful calls count towards
+/// the execution average and latency histogram, and calls returning an
+/// error count as execution failures. The wrapped executor needs no
+/// knowledge of metrics.
+pub struct MeteredExecutor {
+    inner: Arc<dyn Executor>,
+    metrics: Arc<PerfMetrics>,
+}
+
+impl MeteredExecutor {
+    /// Record executions of `inner` into `metrics`
+    pub fn new(inner: Arc<dyn Executor>, metrics: Arc<PerfMetrics>) -> Self {
+        Self { inner, metrics }
+    }
+}
+
+#[async_trait]
+impl Executor for MeteredExecutor {
+    async fn prepare(&mut self, ctx: &ExecutionContext) -> Result<()> {
+        exclusive(&mut self.inner)?.prepare(ctx).await
+    }
+
+    async fn execute(
+        &self,
+        ctx: &ExecutionContext,
+        command: &str,
+        args: &[String],
+    ) -> Result<ExecutionResult> {
+        let mut timer = ScopedTimer::new(&self.metrics, TimerType::Execution);
+        let result = self.inner.execute(ctx, command, args).await;
+        if result.is_err() {
+            timer.mark_failed();
+        }
+        result
+    }
+
+    async fn cleanup(&mut self, ctx: &ExecutionContext) -> Result<()> {
+        exclusive(&mut self.inner)?.cleanup(ctx).await
+    }
+
+    fn executor_type(&self) -> &str {
+        self.inner.executor_type()
+    }
+
+    fn kind(&self) -> ExecutorKind {
+        self.inner.kind()
+    }
+
+    fn supports_checkpoint(&self) -> bool {
+        self.inner.supports_checkpoint()
+    }
+
+    async fn checkpoint(&self, ctx: &ExecutionContext, path: &str) -> Result<()> {
+        self.inner.checkpoint(ctx, path).await
+    }
+
+    async fn restore(&mut self, ctx: &ExecutionContext, path: &str) -> Result<()> {
+        exclusive(&mut self.inner)?.restore(ctx, path).await
+    }
+}
+
+/// An executor that caps how many `execute` calls run at once
+///
+/// Each call waits for a semaphore permit before reaching the inner
+/// executor, so heavy runtimes (a WASM JIT, an interpreter) can be shared
+/// without unbounded parallelism. Waiting calls are served in FIFO order.
+pub struct BoundedExecutor {
+    inner: Arc<dyn Executor>,
+    permits: Arc<Semaphore>,
+    max_concurrent: usize,
+}
+
+impl BoundedExecutor {
+    /// Allow at most `max_concurrent` concurrent executions of `inner`
+    ///
+    /// # Panics
+    /// Panics if `max_concurrent` is zero, since no call could ever run.
+    pub fn new(inner: Arc<dyn Executor>, max_concurrent: usize) -> Self {
+        assert!(max_concurrent > 0, "BoundedExecutor needs at least one permit");
+        Self {
+            inner,
+            permits: Arc::new(Semaphore::new(max_concurrent)),
+            max_concurrent,
+        }
+    }
+
+    /// Maximum number of concurrent executions
+    pub fn max_concurrent(&self) -> usize {
+        self.max_concurrent
+    }
+
+    /// Number of executions currently holding a permit
+    pub fn in_flight(&self) -> usize {
+        self.max_concurrent - self.permits.available_permits()
+    }
+}
+
+#[async_trait]
+impl Executor for BoundedExecutor {
+    async fn prepare(&mut self, ctx: &ExecutionContext) -> Result<()> {
+        exclusive(&mut self.inner)?.prepare(ctx).await
+    }
+
+    async fn execute(
+        &self,
+        ctx: &ExecutionContext,
+        command: &str,
+        args: &[String],
+    ) -> Result<ExecutionResult> {
+        let _permit = self
+            .permits
+            .acquire()
+            .await
+            .context("Executor concurrency limiter closed")?;
+        self.inner.execute(ctx, command, args).await
+    }
+
+    async fn cleanup(&mut self, ctx: &ExecutionContext) -> Result<()> {
+        exclusive(&mut self.inner)?.cleanup(ctx).await
+    }
+
+    fn executor_type(&self) -> &str {
+        self.inner.executor_type()
+    }
+
+    fn kind(&self) -> ExecutorKind {
+        self.inner.kind()
+    }
+
+    fn supports_checkpoint(&self) -> bool {
+        self.inner.supports_checkpoint()
+    }
+
+    async fn checkpoint(&self, ctx: &ExecutionContext, path: &str) -> Result<()> {
+        self.inner.checkpoint(ctx, path).await
+    }
+
+    async fn restore(&mut self, ctx: &ExecutionContext, path: &str) -> Result<()> {
+        exclusive(&mut self.inner)?.restore(ctx, path).await
+    }
+}
+
+/// Runs every `execute` call inside an `execute` tracing span
+///
+/// The span carries the container ID, executor type and command, so events
+/// logged by inner layers and the executor itself are attributed to the
+/// workload that produced them.
+pub struct TracingLayer;
+
+impl ExecutorLayer for TracingLayer {
+    fn wrap(&self, inner: Arc<dyn Executor>) -> Arc<dyn Executor> {
+        Arc::new(Traced { inner })
+    }
+}
+
+struct Traced {
+    inner: Arc<dyn Executor>,
+}
+
+#[async_trait]
+impl Executor for Traced {
+    async fn prepare(&mut self, ctx: &ExecutionContext) -> Result<()> {
+        exclusive(&mut self.inner)?.prepare(ctx).await
+    }
+
+    async fn execute(
+        &self,
+        ctx: &ExecutionContext,
+        command: &str,
+        args: &[String],
+    ) -> Result<ExecutionResult> {
+        let span = info_span!(
+            "execute",
+            container_id = %ctx.container_id,
+            executor = self.inner.executor_type(),
+            command,
+        );
+        async {
+            let result = self.inner.execute(ctx, command, args).await;
+            match &result {
+                Ok(result) => debug!(
+                    exit_code = result.exit_code,
+                    duration_ms = result.duration_ms,
+                    "Execution finished"
+                ),
+                Err(e) => debug!(error = %e, "Execution failed"),
+            }
+            result
+        }
+        .instrument(span)
+        .await
+    }
+
+    async fn cleanup(&mut self, ctx: &ExecutionContext) -> Result<()> {
+        exclusive(&mut self.inner)?.cleanup(ctx).await
+    }
+
+    fn executor_type(&self) -> &str {
+        self.inner.executor_type()
+    }
+
+    fn kind(&self) -> ExecutorKind {
+        self.inner.kind()
+    }
+
+    fn supports_checkpoint(&self) -> bool {
+        self.inner.supports_checkpoint()
+    }
+
+    async fn checkpoint(&self, ctx: &ExecutionContext, path: &str) -> Result<()> {
+        self.inner.checkpoint(ctx, path).await
+    }
+
+    async fn restore(&mut self, ctx: &ExecutionContext, path: &str) -> Result<()> {
+        exclusive(&mut self.inner)?.restore(ctx, path).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::{NativeExecutor, NetworkConfig, Termination};
+    use std::sync::Mutex;
+
+    type Log = Arc<Mutex<Vec<String>>>;
+
+    fn context() -> ExecutionContext {
+        ExecutionContext::builder("layered")
+            .memory_bytes(64 * 1024 * 1024)
+            .pid_limit(16)
+            .network(NetworkConfig {
+                isolated: false,
+                ip_address: None,
+                dns_servers: vec![],
+                port_mappings: vec![],
+            })
+            .build()
+            .unwrap()
+    }
+
+    /// Innermost executor: logs each call and fails on `command == "fail"`
+    struct Recorder {
+        log: Log,
+    }
+
+    #[async_trait]
+    impl Executor for Recorder {
+        async fn prepare(&mut self, _ctx: &ExecutionContext) -> Result<()> {
+            self.log.lock().unwrap().push("prepare".to_string());
+            Ok(())
+        }
+
+        async fn execute(
+            &self,
+            _ctx: &ExecutionContext,
+            command: &str,
+            _args: &[String],
+        ) -> Result<ExecutionResult> {
+            self.log.lock().unwrap().push("execute".to_string());
+            if command == "fail" {
+                anyhow::bail!("refusing to run '{}'", command);
+            }
+            Ok(ExecutionResult {
+                exit_code: 0,
+                stdout: String::new(),
+                stderr: String::new(),
+                duration_ms: 0,
+                termination: Termination::Exited(0),
+            })
+        }
+
+        async fn cleanup(&mut self, _ctx: &ExecutionContext) -> Result<()> {
+            self.log.lock().unwrap().push("cleanup".to_string());
+            Ok(())
+        }
+
+        fn executor_type(&self) -> &str {
+            "recorder"
+        }
+    }
+
+    /// Logs `<name>:before` / `<name>:after` around `execute`
+    struct NamedLayer {
+        name: &'static str,
+        log: Log,
+    }
+
+    impl ExecutorLayer for NamedLayer {
+        fn wrap(&self, inner: Arc<dyn Executor>) -> Arc<dyn Executor> {
+            Arc::new(Named {
+                name: self.name,
+                log: self.log.clone(),
+                inner,
+            })
+        }
+    }
+
+    struct Named {
+        name: &'static str,
+        log: Log,
+        inner: Arc<dyn Executor>,
+    }
+
+    #[async_trait]
+    impl Executor for Named {
+        async fn prepare(&mut self, ctx: &ExecutionContext) -> Result<()> {
+            exclusive(&mut self.inner)?.prepare(ctx).await
+        }
+
+        async fn execute(
+            &self,
+            ctx: &ExecutionContext,
+            command: &str,
+            args: &[String],
+        ) -> Result<ExecutionResult> {
+            self.log.lock().unwrap().push(format!("{}:before", self.name));
+            let result = self.inner.execute(ctx, command, args).await;
+            self.log.lock().unwrap().push(format!("{}:after", self.name));
+            result
+        }
+
+        async fn cleanup(&mut self, ctx: &ExecutionContext) -> Result<()> {
+            exclusive(&mut self.inner)?.cleanup(ctx).await
+        }
+
+        fn executor_type(&self) -> &str {
+            self.inner.executor_type()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_layers_wrap_in_order() {
+        let log = Log::default();
+        let layer = |name| -> Arc<dyn ExecutorLayer> {
+            Arc::new(NamedLayer {
+                name,
+                log: log.clone(),
+            })
+        };
+        let executor = LayeredExecutor::new(
+            Arc::new(Recorder { log: log.clone() }),
+            vec![layer("outer"), layer("middle"), layer("inner")],
+        );
+
+        executor.execute(&context(), "true", &[]).await.unwrap();
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                "outer:before",
+                "middle:before",
+                "inner:before",
+                "execute",
+                "inner:after",
+                "middle:after",
+                "outer:after",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_layered_executor_forwards_lifecycle() {
+        let log = Log::default();
+        let mut executor = LayeredExecutor::new(
+            Arc::new(Recorder { log: log.clone() }),
+            vec![Arc::new(TracingLayer), Arc::new(MetricsLayer::new(PerfMetrics::new()))],
+        );
+        assert_eq!(executor.executor_type(), "recorder");
+        assert!(!executor.supports_checkpoint());
+
+        let ctx = context();
+        executor.prepare(&ctx).await.unwrap();
+        executor.execute(&ctx, "true", &[]).await.unwrap();
+        executor.cleanup(&ctx).await.unwrap();
+        assert_eq!(*log.lock().unwrap(), vec!["prepare", "execute", "cleanup"]);
+    }
+
+    #[tokio::test]
+    async fn test_layered_executor_without_layers() {
+        let log = Log::default();
+        let executor = LayeredExecutor::new(Arc::new(Recorder { log: log.clone() }), vec![]);
+        executor.execute(&context(), "true", &[]).await.unwrap();
+        assert_eq!(*log.lock().unwrap(), vec!["execute"]);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_layer_records_executions_and_failures() {
+        let metrics = PerfMetrics::new();
+        let executor = LayeredExecutor::new(
+            Arc::new(Recorder { log: Log::default() }),
+            vec![Arc::new(MetricsLayer::new(metrics.clone()))],
+        );
+        let ctx = context();
+
+        executor.execute(&ctx, "true", &[]).await.unwrap();
+        executor.execute(&ctx, "true", &[]).await.unwrap();
+        assert!(executor.execute(&ctx, "fail", &[]).await.is_err());
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.executions, 2);
+        assert_eq!(snapshot.execution_failures, 1);
+    }
+
+    #[tokio::test]
+    async fn test_metered_native_executor() {
+        let metrics = PerfMetrics::new();
+        let mut executor = MeteredExecutor::new(Arc::new(NativeExecutor::new()), metrics.clone());
+        assert_eq!(executor.executor_type(), "native-rust");
+        assert_eq!(executor.kind(), ExecutorKind::Native);
+
+        let ctx = context();
+        executor.prepare(&ctx).await.unwrap();
+        let result = executor
+            .execute(&ctx, "echo", &["metered".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(result.stdout, "metered\n");
+
+        assert_eq!(metrics.snapshot().executions, 1);
+        assert_eq!(metrics.snapshot().execution_failures, 0);
+        assert!(metrics.execution_time_ns.load(std::sync::atomic::Ordering::Relaxed) > 0);
+    }
+
+    /// Counts concurrent `execute` calls and blocks each until `gate` opens
+    struct Gated {
+        gate: Arc<Semaphore>,
+        current: Arc<std::sync::atomic::AtomicUsize>,
+        peak: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Executor for Gated {
+        async fn prepare(&mut self, _ctx: &ExecutionContext) -> Result<()> {
+            Ok(())
+        }
+
+        async fn execute(
+            &self,
+            _ctx: &ExecutionContext,
+            _command: &str,
+            _args: &[String],
+        ) -> Result<ExecutionResult> {
+            use std::sync::atomic::Ordering;
+
+            let now = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            drop(self.gate.acquire().await?);
+            self.current.fetch_sub(1, Ordering::SeqCst);
+
+            Ok(ExecutionResult {
+                exit_code: 0,
+                stdout: String::new(),
+                stderr: String::new(),
+                duration_ms: 0,
+                termination: Termination::Exited(0),
+            })
+        }
+
+        async fn cleanup(&mut self, _ctx: &ExecutionContext) -> Result<()> {
+            Ok(())
+        }
+
+        fn executor_type(&self) -> &str {
+            "gated"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bounded_executor_caps_concurrency() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let gate = Arc::new(Semaphore::new(0));
+        let current = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let executor = Arc::new(BoundedExecutor::new(
+            Arc::new(Gated {
+                gate: gate.clone(),
+                current: current.clone(),
+                peak: peak.clone(),
+            }),
+            3,
+        ));
+        assert_eq!(executor.max_concurrent(), 3);
+        assert_eq!(executor.executor_type(), "gated");
+        assert_eq!(executor.kind(), ExecutorKind::Custom("gated".to_string()));
+
+        let tasks: Vec<_> = (0..10)
+            .map(|_| {
+                let executor = executor.clone();
+                tokio::spawn(async move { executor.execute(&context(), "true", &[]).await })
+            })
+            .collect();
+
+        // Wait for the permitted calls to reach the inner executor
+        while current.load(Ordering::SeqCst) < 3 {
+            tokio::task::yield_now().await;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        assert_eq!(current.load(Ordering::SeqCst), 3);
+        assert_eq!(executor.in_flight(), 3);
+
+        gate.add_permits(10);
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+        assert_eq!(peak.load(Ordering::SeqCst), 3);
+        assert_eq!(executor.in_flight(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one permit")]
+    fn test_bounded_executor_rejects_zero() {
+        BoundedExecutor::new(Arc::new(Recorder { log: Log::default() }), 0);
+    }
+
+    #[tokio::test]
+    async fn test_tracing_layer_passes_errors_through() {
+        let executor = TracingLayer.wrap(Arc::new(Recorder { log: Log::default() }));
+        assert_eq!(executor.executor_type(), "recorder");
+
+        let err = executor.execute(&context(), "fail", &[]).await.unwrap_err();
+        assert_eq!(err.to_string(), "refusing to run 'fail'");
+    }
+
+    #[tokio::test]
+    async fn test_shared_inner_cannot_be_prepared() {
+        let inner: Arc<dyn Executor> = Arc::new(Recorder { log: Log::default() });
+        let mut executor = LayeredExecutor::new(inner.clone(), vec![Arc::new(TracingLayer)]);
+
+        let err = executor.prepare(&context()).await.unwrap_err();
+        assert!(err.to_string().contains("shared"), "{:#}", err);
+    }
+}
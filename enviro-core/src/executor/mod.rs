@@ -9,27 +9,336 @@
 //! - **Async-First**: All operations return futures for tokio integration
 //! - **Hot-Swappable**: Executors can be dynamically loaded via libloading
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use nix::sys::resource::Resource;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 
+pub mod builder;
+pub mod layer;
+pub mod network;
+pub mod wasm;
+pub use builder::{ContextError, ExecutionContextBuilder};
+pub use layer::{
+    BoundedExecutor, ExecutorLayer, LayeredExecutor, MeteredExecutor, MetricsLayer, TracingLayer,
+};
+pub use network::{render_resolv_conf, NetworkConfigError};
+pub use wasm::WasmExecutor;
+
 /// Container execution context passed to executors
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// The `Debug` output, which is what tracing spans and pool logs print,
+/// replaces the values of the `env` keys listed in `redact_env` with `***`.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ExecutionContext {
     /// Unique container ID
     pub container_id: String,
     /// Environment variables
     pub env: HashMap<String, String>,
+    /// Expand `$VAR` / `${VAR}` references in `env` values before spawning
+    #[serde(default)]
+    pub expand_env: bool,
+    /// How references to unset variables are treated when `expand_env` is set
+    #[serde(default)]
+    pub undefined_env: UndefinedEnv,
+    /// Which host environment variables the workload inherits
+    #[serde(default)]
+    pub env_policy: EnvPolicy,
+    /// Names of `env` variables holding secrets, whose values are shown as
+    /// `***` in `Debug` output
+    #[serde(default)]
+    pub redact_env: HashSet<String>,
     /// Working directory
     pub workdir: String,
+    /// Create `workdir` (with parents) during `prepare` instead of failing
+    /// when it does not exist
+    #[serde(default)]
+    pub create_workdir: bool,
+    /// Send the command's stderr to its stdout, like shell `2>&1`, so both
+    /// are captured interleaved in write order in `ExecutionResult::stdout`
+    /// and `stderr` is left empty
+    #[serde(default)]
+    pub merge_stderr: bool,
+    /// `(resource, soft, hard)` limits set on the workload process before
+    /// it starts
+    #[serde(default, with = "rlimit_names")]
+    pub rlimits: Vec<(Resource, u64, u64)>,
+    /// File mode creation mask for the workload; inherited when `None`
+    #[serde(default)]
+    pub umask: Option<u32>,
+    /// `(uid, gid)` the workload runs as, with `gid` as its only group;
+    /// inherited when `None`
+    #[serde(default)]
+    pub run_as: Option<(u32, u32)>,
+    /// cgroup v2 directory the workload joins before exec; it stays in the
+    /// engine's cgroup when `None`
+    #[serde(default)]
+    pub cgroup: Option<PathBuf>,
     /// Resource limits (CPU, memory, etc.)
     pub limits: ResourceLimits,
     /// Network configuration
     pub network: NetworkConfig,
 }
 
+impl fmt::Debug for ExecutionContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExecutionContext")
+            .field("container_id", &self.container_id)
+            .field("env", &RedactedEnv(self))
+            .field("expand_env", &self.expand_env)
+            .field("undefined_env", &self.undefined_env)
+            .field("env_policy", &self.env_policy)
+            .field("redact_env", &self.redact_env)
+            .field("workdir", &self.workdir)
+            .field("create_workdir", &self.create_workdir)
+            .field("merge_stderr", &self.merge_stderr)
+            .field("rlimits", &self.rlimits)
+            .field("umask", &self.umask)
+            .field("run_as", &self.run_as)
+            .field("cgroup", &self.cgroup)
+            .field("limits", &self.limits)
+            .field("network", &self.network)
+            .finish()
+    }
+}
+
+/// `ExecutionContext::env` with the values of `redact_env` keys masked
+struct RedactedEnv<'a>(&'a ExecutionContext);
+
+impl fmt::Debug for RedactedEnv<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ctx = self.0;
+        f.debug_map()
+            .entries(ctx.env.iter().map(|(key, value)| {
+                let value = if ctx.redact_env.contains(key) { "***" } else { value.as_str() };
+                (key, value)
+            }))
+            .finish()
+    }
+}
+
+/// Handling of `$VAR` references to variables that are not set anywhere
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UndefinedEnv {
+    /// Substitute an empty string, like a POSIX shell
+    #[default]
+    Empty,
+    /// Fail the execution
+    Error,
+}
+
+/// umask the runtime gives containers: no write for group, nothing for
+/// others
+pub const DEFAULT_UMASK: u32 = 0o027;
+
+/// rlimits the runtime gives containers: no core dumps, and at most 1024
+/// open files (4096 if the workload raises its own soft limit)
+pub fn default_rlimits() -> Vec<(Resource, u64, u64)> {
+    vec![(Resource::RLIMIT_CORE, 0, 0), (Resource::RLIMIT_NOFILE, 1024, 4096)]
+}
+
+/// (De)serialize `ExecutionContext::rlimits` as `[name, soft, hard]`, with
+/// names like `"nofile"`; nix's `Resource` has no serde support
+mod rlimit_names {
+    use nix::sys::resource::Resource;
+    use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+    const NAMES: [(Resource, &str); 15] = [
+        (Resource::RLIMIT_AS, "as"),
+        (Resource::RLIMIT_CORE, "core"),
+        (Resource::RLIMIT_CPU, "cpu"),
+        (Resource::RLIMIT_DATA, "data"),
+        (Resource::RLIMIT_FSIZE, "fsize"),
+        (Resource::RLIMIT_LOCKS, "locks"),
+        (Resource::RLIMIT_MEMLOCK, "memlock"),
+        (Resource::RLIMIT_MSGQUEUE, "msgqueue"),
+        (Resource::RLIMIT_NICE, "nice"),
+        (Resource::RLIMIT_NOFILE, "nofile"),
+        (Resource::RLIMIT_NPROC, "nproc"),
+        (Resource::RLIMIT_RSS, "rss"),
+        (Resource::RLIMIT_RTPRIO, "rtprio"),
+        (Resource::RLIMIT_SIGPENDING, "sigpending"),
+        (Resource::RLIMIT_STACK, "stack"),
+    ];
+
+    pub fn serialize<S: Serializer>(
+        rlimits: &[(Resource, u64, u64)],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        rlimits
+            .iter()
+            .map(|&(resource, soft, hard)| {
+                let (_, name) = NAMES
+                    .iter()
+                    .find(|(known, _)| *known == resource)
+                    .ok_or_else(|| {
+                        ser::Error::custom(format!("unsupported rlimit {:?}", resource))
+                    })?;
+                Ok((*name, soft, hard))
+            })
+            .collect::<Result<Vec<_>, S::Error>>()?
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<(Resource, u64, u64)>, D::Error> {
+        Vec::<(String, u64, u64)>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(name, soft, hard)| {
+                let (resource, _) = NAMES
+                    .iter()
+                    .find(|(_, known)| *known == name)
+                    .ok_or_else(|| de::Error::custom(format!("unknown rlimit '{}'", name)))?;
+                Ok((*resource, soft, hard))
+            })
+            .collect()
+    }
+}
+
+/// `PATH` given to a workload that neither sets nor inherits one
+pub const DEFAULT_PATH: &str = "/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin";
+
+/// Host environment passed through to a workload
+///
+/// The container's own `env` is always set on top and wins over inherited
+/// values.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvPolicy {
+    /// Inherit the whole host environment
+    Inherit,
+    /// Inherit nothing; `PATH` is [`DEFAULT_PATH`] unless the container
+    /// sets one
+    #[default]
+    Clear,
+    /// Inherit only the named host variables
+    AllowList(Vec<String>),
+}
+
+impl EnvPolicy {
+    /// Whether the host's value of `name` is passed through
+    pub fn inherits(&self, name: &str) -> bool {
+        match self {
+            Self::Inherit => true,
+            Self::Clear => false,
+            Self::AllowList(names) => names.iter().any(|allowed| allowed == name),
+        }
+    }
+
+    /// Replace `cmd`'s inherited environment with the one this policy allows
+    fn apply(&self, cmd: &mut tokio::process::Command) {
+        self.apply_from(cmd, std::env::vars_os());
+    }
+
+    /// Like [`apply`](Self::apply), inheriting from `host` instead of the
+    /// process environment
+    fn apply_from<I, K, V>(&self, cmd: &mut tokio::process::Command, host: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<std::ffi::OsStr>,
+        V: AsRef<std::ffi::OsStr>,
+    {
+        cmd.env_clear();
+        for (name, value) in host {
+            let inherited = match name.as_ref().to_str() {
+                Some(name) => self.inherits(name),
+                None => *self == Self::Inherit,
+            };
+            if inherited {
+                cmd.env(name, value);
+            }
+        }
+    }
+}
+
+impl ExecutionContext {
+    /// Environment to hand to the workload
+    ///
+    /// With `expand_env` set, `$VAR` and `${VAR}` in each value are replaced
+    /// by the container's own (unexpanded) value for `VAR`, falling back to
+    /// the host environment. A variable referring to itself, as in
+    /// `PATH=$PATH:/opt/bin`, always resolves against the host. `$$` yields a
+    /// literal `$`.
+    pub fn resolved_env(&self) -> Result<HashMap<String, String>> {
+        if !self.expand_env {
+            return Ok(self.env.clone());
+        }
+        self.env
+            .iter()
+            .map(|(key, value)| Ok((key.clone(), self.expand_value(key, value)?)))
+            .collect()
+    }
+
+    fn expand_value(&self, key: &str, value: &str) -> Result<String> {
+        let mut out = String::with_capacity(value.len());
+        let mut chars = value.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if c != '$' {
+                out.push(c);
+                continue;
+            }
+            match chars.peek() {
+                Some('$') => {
+                    chars.next();
+                    out.push('$');
+                }
+                Some('{') => {
+                    chars.next();
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => anyhow::bail!("Unterminated '${{' in environment variable {}", key),
+                        }
+                    }
+                    out.push_str(&self.lookup_env(key, &name)?);
+                }
+                Some(&c) if c == '_' || c.is_ascii_alphabetic() => {
+                    let mut name = String::new();
+                    while let Some(&c) = chars.peek() {
+                        if c != '_' && !c.is_ascii_alphanumeric() {
+                            break;
+                        }
+                        name.push(c);
+                        chars.next();
+                    }
+                    out.push_str(&self.lookup_env(key, &name)?);
+                }
+                // A lone `$` is kept as-is
+                _ => out.push('$'),
+            }
+        }
+        Ok(out)
+    }
+
+    fn lookup_env(&self, key: &str, name: &str) -> Result<String> {
+        let value = self
+            .env
+            .get(name)
+            .filter(|_| name != key)
+            .cloned()
+            .or_else(|| std::env::var(name).ok());
+
+        match (value, self.undefined_env) {
+            (Some(value), _) => Ok(value),
+            (None, UndefinedEnv::Empty) => Ok(String::new()),
+            (None, UndefinedEnv::Error) => {
+                anyhow::bail!("Undefined variable ${} in environment variable {}", name, key)
+            }
+        }
+    }
+}
+
 /// Resource limits for container execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResourceLimits {
@@ -44,16 +353,52 @@ pub struct ResourceLimits {
 /// Network configuration for container
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkConfig {
-    /// Enable network isolation
+    /// Run in a fresh network namespace with no external connectivity
     pub isolated: bool,
     /// IP address (if not isolated)
     pub ip_address: Option<String>,
-    /// DNS servers
+    /// DNS servers written to the container's `/etc/resolv.conf`
+    /// (host networking only)
     pub dns_servers: Vec<String>,
+    /// Host ports forwarded to `ip_address`
+    #[serde(default)]
+    pub port_mappings: Vec<PortMap>,
+}
+
+/// A host port forwarded to a port inside the container
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PortMap {
+    /// Port on the host
+    pub host_port: u16,
+    /// Port the container listens on
+    pub container_port: u16,
+    /// Transport protocol
+    #[serde(default)]
+    pub protocol: Protocol,
+}
+
+/// Transport protocol of a forwarded port
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    #[default]
+    Tcp,
+    Udp,
+}
+
+impl std::fmt::Display for Protocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Protocol::Tcp => "tcp",
+            Protocol::Udp => "udp",
+        })
+    }
 }
 
 /// Execution result returned by executors
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "StoredExecutionResult")]
 pub struct ExecutionResult {
     /// Exit code
     pub exit_code: i32,
@@ -63,6 +408,162 @@ pub struct ExecutionResult {
     pub stderr: String,
     /// Execution time in milliseconds
     pub duration_ms: u64,
+    /// How the workload ended
+    pub termination: Termination,
+}
+
+/// Serialized form of [`ExecutionResult`]
+///
+/// Results stored before `termination` existed lack the field; they are
+/// read as a normal exit with their `exit_code`.
+#[derive(Deserialize)]
+struct StoredExecutionResult {
+    exit_code: i32,
+    stdout: String,
+    stderr: String,
+    duration_ms: u64,
+    termination: Option<Termination>,
+}
+
+impl From<StoredExecutionResult> for ExecutionResult {
+    fn from(stored: StoredExecutionResult) -> Self {
+        Self {
+            termination: stored
+                .termination
+                .unwrap_or(Termination::Exited(stored.exit_code)),
+            exit_code: stored.exit_code,
+            stdout: stored.stdout,
+            stderr: stored.stderr,
+            duration_ms: stored.duration_ms,
+        }
+    }
+}
+
+/// How a workload process ended
+///
+/// A process killed by a signal has no exit code of its own (its
+/// `exit_code` is reported as `-1`); this tells, say, an OOM kill
+/// (`Signaled(9)`) apart from a normal exit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Termination {
+    /// Exited normally with this code
+    Exited(i32),
+    /// Killed by this signal number
+    Signaled(i32),
+}
+
+impl From<std::process::ExitStatus> for Termination {
+    fn from(status: std::process::ExitStatus) -> Self {
+        use std::os::unix::process::ExitStatusExt;
+
+        match (status.code(), status.signal()) {
+            (Some(code), _) => Self::Exited(code),
+            (None, Some(signal)) => Self::Signaled(signal),
+            // Stopped/continued statuses are never returned for a reaped child
+            (None, None) => Self::Exited(-1),
+        }
+    }
+}
+
+impl ExecutionResult {
+    /// Whether the workload exited with code 0
+    pub fn success(&self) -> bool {
+        self.exit_code == 0
+    }
+
+    /// Turn a non-zero exit into an [`ExecutionError`]
+    ///
+    /// Allows `executor.execute(...).await?.into_result()?`.
+    pub fn into_result(self) -> Result<ExecutionResult, ExecutionError> {
+        if self.success() {
+            Ok(self)
+        } else {
+            Err(ExecutionError {
+                exit_code: self.exit_code,
+                stderr: self.stderr,
+            })
+        }
+    }
+}
+
+/// A workload that exited with a non-zero code
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("workload exited with code {exit_code}{}", stderr_suffix(.stderr))]
+pub struct ExecutionError {
+    /// Exit code of the workload
+    pub exit_code: i32,
+    /// Everything the workload wrote to stderr
+    pub stderr: String,
+}
+
+/// An executor's `prepare` ran past the deadline given to
+/// [`Executor::prepare_with_deadline`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("executor {executor} did not finish preparing {container_id} before its deadline")]
+pub struct PrepareTimeout {
+    /// `executor_type` of the executor that timed out
+    pub executor: String,
+    /// Container the executor was being prepared for
+    pub container_id: String,
+}
+
+/// `: <last stderr line>` for error messages, or nothing when stderr is empty
+fn stderr_suffix(stderr: &str) -> String {
+    match stderr.trim_end().lines().last() {
+        Some(line) => format!(": {}", line),
+        None => String::new(),
+    }
+}
+
+/// Which kind of runtime an [`Executor`] is, for dispatching on executor
+/// capabilities without comparing [`Executor::executor_type`] strings
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ExecutorKind {
+    /// [`NativeExecutor`], running commands directly on the host
+    Native,
+    /// [`WasmExecutor`], running WebAssembly modules
+    Wasm,
+    /// Zig executor loaded through the C FFI
+    Zig,
+    /// Go executor loaded through cgo
+    Go,
+    /// Python executor embedding an interpreter
+    Python,
+    /// Any other executor, by its `executor_type`
+    Custom(String),
+}
+
+impl ExecutorKind {
+    /// Map an [`Executor::executor_type`] identifier to its kind
+    pub fn from_type(executor_type: &str) -> Self {
+        match executor_type {
+            "native-rust" => ExecutorKind::Native,
+            "wasm" => ExecutorKind::Wasm,
+            "zig" => ExecutorKind::Zig,
+            "go" => ExecutorKind::Go,
+            "python" => ExecutorKind::Python,
+            other => ExecutorKind::Custom(other.to_string()),
+        }
+    }
+
+    /// The `executor_type` identifier of this kind
+    pub fn as_str(&self) -> &str {
+        match self {
+            ExecutorKind::Native => "native-rust",
+            ExecutorKind::Wasm => "wasm",
+            ExecutorKind::Zig => "zig",
+            ExecutorKind::Go => "go",
+            ExecutorKind::Python => "python",
+            ExecutorKind::Custom(name) => name,
+        }
+    }
+}
+
+impl fmt::Display for ExecutorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 /// The core Executor trait that all runtime implementations must satisfy
@@ -86,6 +587,28 @@ pub trait Executor: Send + Sync {
     /// any expensive initialization (loading libraries, JIT compilation, etc.)
     async fn prepare(&mut self, ctx: &ExecutionContext) -> Result<()>;
 
+    /// Prepare the executor, giving up at `deadline`
+    ///
+    /// Bounds initialization that can hang, such as JIT compilation or
+    /// loading a library. The default runs [`prepare`](Self::prepare) under
+    /// [`tokio::time::timeout_at`], dropping it when the deadline passes and
+    /// failing with a [`PrepareTimeout`]; the executor then needs `prepare`
+    /// again before use.
+    async fn prepare_with_deadline(
+        &mut self,
+        ctx: &ExecutionContext,
+        deadline: tokio::time::Instant,
+    ) -> Result<()> {
+        match tokio::time::timeout_at(deadline, self.prepare(ctx)).await {
+            Ok(result) => result,
+            Err(_) => Err(PrepareTimeout {
+                executor: self.executor_type().to_string(),
+                container_id: ctx.container_id.clone(),
+            }
+            .into()),
+        }
+    }
+
     /// Execute the workload in the prepared environment
     ///
     /// # Performance Pattern: Zero-Copy Execution
@@ -107,6 +630,14 @@ pub trait Executor: Send + Sync {
     /// Return the executor type identifier
     fn executor_type(&self) -> &str;
 
+    /// Return the kind of executor, derived from
+    /// [`executor_type`](Self::executor_type) unless overridden
+    ///
+    /// Wrappers forward this to the executor they wrap.
+    fn kind(&self) -> ExecutorKind {
+        ExecutorKind::from_type(self.executor_type())
+    }
+
     /// Check if this executor supports CRIU checkpointing
     ///
     /// # Process Snapshotting:
@@ -120,7 +651,8 @@ pub trait Executor: Send + Sync {
     ///
     /// # CRIU Integration:
     /// This uses Checkpoint/Restore in Userspace to serialize process state,
-    /// including memory, file descriptors, and thread state.
+    /// including memory, file descriptors, and thread state. Images are
+    /// written in the format defined by [`crate::engine::checkpoint`].
     async fn checkpoint(&self, _ctx: &ExecutionContext, _path: &str) -> Result<()> {
         anyhow::bail!("Checkpointing not supported by this executor")
     }
@@ -134,20 +666,41 @@ pub trait Executor: Send + Sync {
 /// Example: Native Rust Executor
 ///
 /// This executor runs commands directly using tokio::process, providing
-/// the highest performance for Rust-native workloads.
+/// the highest performance for Rust-native workloads. The context's
+/// [`NetworkConfig`] is enforced with namespaces (see [`network`]).
 pub struct NativeExecutor {
     initialized: bool,
+    /// PID of the command being executed, 0 while idle
+    pid: Arc<AtomicU32>,
 }
 
 impl NativeExecutor {
     pub fn new() -> Self {
-        Self { initialized: false }
+        Self {
+            initialized: false,
+            pid: Arc::default(),
+        }
+    }
+
+    /// Shared cell holding the PID of the running command (0 when none)
+    ///
+    /// Lets a caller that has handed the executor to a task still signal
+    /// the command, e.g. to stop it.
+    pub fn pid_handle(&self) -> Arc<AtomicU32> {
+        self.pid.clone()
+    }
+}
+
+impl Default for NativeExecutor {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 #[async_trait]
 impl Executor for NativeExecutor {
-    async fn prepare(&mut self, _ctx: &ExecutionContext) -> Result<()> {
+    async fn prepare(&mut self, ctx: &ExecutionContext) -> Result<()> {
+        ensure_workdir(ctx).await?;
         self.initialized = true;
         Ok(())
     }
@@ -158,17 +711,98 @@ impl Executor for NativeExecutor {
         command: &str,
         args: &[String],
     ) -> Result<ExecutionResult> {
+        use std::os::fd::OwnedFd;
+        use tokio::io::AsyncReadExt;
         use tokio::process::Command;
         use tokio::time::Instant;
 
         let start = Instant::now();
 
-        let output = Command::new(command)
-            .args(args)
-            .current_dir(&ctx.workdir)
-            .envs(&ctx.env)
-            .output()
-            .await?;
+        let mut env = ctx.resolved_env().context("Failed to expand container environment")?;
+        if !env.contains_key("PATH") && !ctx.env_policy.inherits("PATH") {
+            env.insert("PATH".to_string(), DEFAULT_PATH.to_string());
+        }
+        // Kept alive until the child has exited; dropping it removes the
+        // generated resolv.conf.
+        let network = network::NetworkSetup::prepare(&ctx.container_id, &ctx.network)
+            .context("Failed to prepare container network")?
+            .map(Arc::new);
+
+        let program = resolve_command(command, &env)?;
+        let mut cmd = Command::new(program);
+        ctx.env_policy.apply(&mut cmd);
+        cmd.args(args).current_dir(&ctx.workdir).envs(&env);
+        if let Some(dir) = &ctx.cgroup {
+            let procs = open_cgroup_procs(dir)?;
+            // Registered first so the limits cover everything the hooks
+            // below set up.
+            // SAFETY: the hook only writes to a descriptor opened before the
+            // fork.
+            unsafe {
+                cmd.pre_exec(move || join_cgroup(&procs));
+            }
+        }
+        if let Some(network) = network.clone() {
+            // SAFETY: the hook only performs raw syscalls on buffers rendered
+            // before the fork.
+            unsafe {
+                cmd.pre_exec(move || network.apply());
+            }
+        }
+        if !ctx.rlimits.is_empty() || ctx.umask.is_some() {
+            let (rlimits, umask) = (ctx.rlimits.clone(), ctx.umask);
+            // SAFETY: setrlimit and umask are plain syscalls, and the limits
+            // were copied before the fork, so nothing allocates or locks.
+            unsafe {
+                cmd.pre_exec(move || apply_process_limits(&rlimits, umask));
+            }
+        }
+        if let Some((uid, gid)) = ctx.run_as {
+            check_run_as(uid, gid)?;
+            // Not `Command::uid`/`gid`: std switches ids before running any
+            // pre_exec hook, which would leave no privilege to set the
+            // supplementary groups or raise rlimits. Registered last, so
+            // it runs after the hooks above.
+            // SAFETY: setgroups, setgid and setuid are plain syscalls.
+            unsafe {
+                cmd.pre_exec(move || switch_user(uid, gid));
+            }
+        }
+        // Both streams share one pipe when merged, so the kernel keeps the
+        // order the command wrote in
+        let merged = if ctx.merge_stderr {
+            let (reader, writer) = std::io::pipe().context("Failed to create output pipe")?;
+            cmd.stdout(writer.try_clone()?).stderr(writer);
+            Some(reader)
+        } else {
+            cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+            None
+        };
+        let mut child = cmd
+            .stdin(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Failed to run '{}'", command))?;
+        // Close our copies of the pipe's write end so the read sees EOF
+        drop(cmd);
+        self.pid.store(child.id().unwrap_or(0), Ordering::Release);
+        let output = match merged {
+            Some(reader) => {
+                let mut reader =
+                    tokio::fs::File::from_std(std::fs::File::from(OwnedFd::from(reader)));
+                let mut stdout = Vec::new();
+                let (status, read) = tokio::join!(child.wait(), reader.read_to_end(&mut stdout));
+                status.and_then(|status| {
+                    read.map(|_| std::process::Output {
+                        status,
+                        stdout,
+                        stderr: Vec::new(),
+                    })
+                })
+            }
+            None => child.wait_with_output().await,
+        };
+        self.pid.store(0, Ordering::Release);
+        let output = output.with_context(|| format!("Failed to run '{}'", command))?;
 
         let duration_ms = start.elapsed().as_millis() as u64;
 
@@ -177,6 +811,7 @@ impl Executor for NativeExecutor {
             stdout: String::from_utf8_lossy(&output.stdout).to_string(),
             stderr: String::from_utf8_lossy(&output.stderr).to_string(),
             duration_ms,
+            termination: output.status.into(),
         })
     }
 
@@ -190,6 +825,136 @@ impl Executor for NativeExecutor {
     }
 }
 
+/// Set rlimits and the umask in a forked child before exec
+///
+/// Runs between fork and exec, so it must stay async-signal-safe: only
+/// syscalls, no allocation.
+fn apply_process_limits(
+    rlimits: &[(Resource, u64, u64)],
+    umask: Option<u32>,
+) -> std::io::Result<()> {
+    for &(resource, soft, hard) in rlimits {
+        nix::sys::resource::setrlimit(resource, soft, hard)?;
+    }
+    if let Some(mask) = umask {
+        // SAFETY: umask cannot fail and touches no memory
+        unsafe {
+            libc::umask(mask as libc::mode_t);
+        }
+    }
+    Ok(())
+}
+
+/// Open `dir`'s `cgroup.procs` for [`join_cgroup`]
+///
+/// Opened in the parent so a missing or unwritable cgroup is reported with
+/// its path rather than as a spawn failure. The descriptor is close-on-exec,
+/// so the workload does not inherit it.
+fn open_cgroup_procs(dir: &std::path::Path) -> Result<std::fs::File> {
+    let path = dir.join("cgroup.procs");
+    std::fs::OpenOptions::new()
+        .write(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))
+}
+
+/// Move the calling process into the cgroup owning `procs`, in a forked
+/// child before exec
+///
+/// Writing `0` to `cgroup.procs` moves the writer itself.
+fn join_cgroup(procs: &std::fs::File) -> std::io::Result<()> {
+    use std::io::Write;
+
+    (&*procs).write_all(b"0")
+}
+
+/// Fail early, with a clear error, when `run_as` needs privileges this
+/// process does not have
+fn check_run_as(uid: u32, gid: u32) -> Result<()> {
+    use caps::{CapSet, Capability};
+
+    let has = |cap| caps::has_cap(None, CapSet::Effective, cap).unwrap_or(false);
+    if has(Capability::CAP_SETUID) && has(Capability::CAP_SETGID) {
+        return Ok(());
+    }
+    anyhow::bail!(
+        "Cannot run as uid {} gid {}: switching user needs root or CAP_SETUID and CAP_SETGID \
+         (running as uid {})",
+        uid,
+        gid,
+        nix::unistd::geteuid()
+    )
+}
+
+/// Make `gid` the only group, then switch to `gid` and `uid`, in a forked
+/// child before exec
+///
+/// The uid goes last since dropping it gives up the right to change groups.
+fn switch_user(uid: u32, gid: u32) -> std::io::Result<()> {
+    let (uid, gid) = (nix::unistd::Uid::from_raw(uid), nix::unistd::Gid::from_raw(gid));
+    nix::unistd::setgroups(&[gid])?;
+    nix::unistd::setgid(gid)?;
+    nix::unistd::setuid(uid)?;
+    Ok(())
+}
+
+/// Check that the context's working directory is a directory, creating it
+/// first when `create_workdir` is set
+///
+/// Without this a missing directory only surfaces when the command is
+/// spawned, as an OS error that does not name the path.
+async fn ensure_workdir(ctx: &ExecutionContext) -> Result<()> {
+    let workdir = &ctx.workdir;
+    if ctx.create_workdir {
+        tokio::fs::create_dir_all(workdir)
+            .await
+            .with_context(|| format!("Failed to create working directory '{}'", workdir))?;
+    }
+
+    match tokio::fs::metadata(workdir).await {
+        Ok(metadata) if metadata.is_dir() => Ok(()),
+        Ok(_) => anyhow::bail!("Working directory '{}' is not a directory", workdir),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            anyhow::bail!("Working directory '{}' does not exist", workdir)
+        }
+        Err(e) => Err(e).with_context(|| format!("Failed to access working directory '{}'", workdir)),
+    }
+}
+
+/// Resolve a bare command name against the container's `PATH`
+///
+/// The `PATH` in `env` is searched, falling back to the host's when the
+/// container sets none. Names containing a `/` (including absolute paths)
+/// are used as-is.
+fn resolve_command(command: &str, env: &HashMap<String, String>) -> Result<std::path::PathBuf> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if command.contains('/') {
+        return Ok(command.into());
+    }
+
+    let path = match env.get("PATH") {
+        Some(path) => path.clone(),
+        None => std::env::var("PATH").unwrap_or_default(),
+    };
+    let dirs: Vec<&str> = path.split(':').filter(|dir| !dir.is_empty()).collect();
+    for dir in &dirs {
+        let candidate = std::path::Path::new(dir).join(command);
+        let executable = std::fs::metadata(&candidate)
+            .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+            .unwrap_or(false);
+        if executable {
+            return Ok(candidate);
+        }
+    }
+
+    anyhow::bail!(
+        "Command '{}' not found (searched: {})",
+        command,
+        if dirs.is_empty() { "PATH is empty".to_string() } else { dirs.join(", ") }
+    )
+}
+
 /// Executor registry for managing multiple executor implementations
 ///
 /// # Performance Pattern: Arc for Zero-Cost Cloning
@@ -220,6 +985,26 @@ impl ExecutorRegistry {
     pub fn list_types(&self) -> Vec<String> {
         self.executors.keys().cloned().collect()
     }
+
+    /// Remove an executor by name, returning it if it existed
+    pub fn remove(&mut self, name: &str) -> Option<Arc<dyn Executor>> {
+        self.executors.remove(name)
+    }
+
+    /// Number of registered executors
+    pub fn len(&self) -> usize {
+        self.executors.len()
+    }
+
+    /// Returns `true` when no executors are registered
+    pub fn is_empty(&self) -> bool {
+        self.executors.is_empty()
+    }
+
+    /// Whether an executor is registered under `name`
+    pub fn contains(&self, name: &str) -> bool {
+        self.executors.contains_key(name)
+    }
 }
 
 impl Default for ExecutorRegistry {
@@ -284,6 +1069,30 @@ impl ConcurrentExecutorRegistry {
             .expect("registry lock poisoned during remove")
             .remove(name)
     }
+
+    /// Number of registered executors (thread-safe).
+    pub fn len(&self) -> usize {
+        self.executors
+            .read()
+            .expect("registry lock poisoned during len")
+            .len()
+    }
+
+    /// Returns `true` when no executors are registered (thread-safe).
+    pub fn is_empty(&self) -> bool {
+        self.executors
+            .read()
+            .expect("registry lock poisoned during is_empty")
+            .is_empty()
+    }
+
+    /// Whether an executor is registered under `name` (thread-safe).
+    pub fn contains(&self, name: &str) -> bool {
+        self.executors
+            .read()
+            .expect("registry lock poisoned during contains")
+            .contains_key(name)
+    }
 }
 
 impl Default for ConcurrentExecutorRegistry {
@@ -308,21 +1117,11 @@ mod tests {
     async fn test_native_executor() {
         let mut executor = NativeExecutor::new();
         
-        let ctx = ExecutionContext {
-            container_id: "test-001".to_string(),
-            env: HashMap::new(),
-            workdir: "/tmp".to_string(),
-            limits: ResourceLimits {
-                cpu_cores: 1.0,
-                memory_bytes: 1024 * 1024 * 100, // 100MB
-                pid_limit: 100,
-            },
-            network: NetworkConfig {
-                isolated: true,
-                ip_address: None,
-                dns_servers: vec![],
-            },
-        };
+        let ctx = ExecutionContext::builder("test-001")
+            .workdir("/tmp")
+            .memory_bytes(100 * 1024 * 1024)
+            .build()
+            .unwrap();
 
         assert!(executor.prepare(&ctx).await.is_ok());
         
@@ -336,6 +1135,547 @@ mod tests {
         assert!(executor.cleanup(&ctx).await.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_native_executor_missing_workdir() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut executor = NativeExecutor::new();
+
+        let mut ctx = env_context(&[], UndefinedEnv::Empty);
+        ctx.workdir = dir.path().join("missing").display().to_string();
+        let err = executor.prepare(&ctx).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("Working directory '{}' does not exist", ctx.workdir)
+        );
+
+        let file = dir.path().join("file");
+        std::fs::write(&file, "").unwrap();
+        ctx.workdir = file.display().to_string();
+        let err = executor.prepare(&ctx).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("Working directory '{}' is not a directory", ctx.workdir)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_native_executor_create_workdir() {
+        let dir = tempfile::tempdir().unwrap();
+        let workdir = dir.path().join("a/b/c");
+        let mut executor = NativeExecutor::new();
+
+        let mut ctx = env_context(&[], UndefinedEnv::Empty);
+        ctx.workdir = workdir.display().to_string();
+        ctx.create_workdir = true;
+        executor.prepare(&ctx).await.unwrap();
+        assert!(workdir.is_dir());
+
+        // Already existing is fine
+        executor.prepare(&ctx).await.unwrap();
+
+        let result = executor.execute(&ctx, "pwd", &[]).await.unwrap();
+        assert_eq!(result.stdout.trim_end(), ctx.workdir);
+    }
+
+    fn write_script(dir: &std::path::Path, name: &str, mode: u32) -> std::path::PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.join(name);
+        std::fs::write(&path, "#!/bin/sh\necho \"$0\"\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn test_native_executor_resolves_container_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let (first, second) = (dir.path().join("first"), dir.path().join("second"));
+        std::fs::create_dir(&first).unwrap();
+        std::fs::create_dir(&second).unwrap();
+        // Not executable, so skipped in favour of the later entry
+        write_script(&first, "enviro-hello", 0o644);
+        let expected = write_script(&second, "enviro-hello", 0o755);
+
+        let path = format!("{}::{}", first.display(), second.display());
+        let ctx = env_context(&[("PATH", &path)], UndefinedEnv::Empty);
+        let result = NativeExecutor::new().execute(&ctx, "enviro-hello", &[]).await.unwrap();
+        assert_eq!(result.stdout.trim_end(), expected.display().to_string());
+
+        // Absolute paths bypass the search entirely
+        let ctx = env_context(&[("PATH", "/nonexistent")], UndefinedEnv::Empty);
+        let result = NativeExecutor::new()
+            .execute(&ctx, &expected.display().to_string(), &[])
+            .await
+            .unwrap();
+        assert_eq!(result.stdout.trim_end(), expected.display().to_string());
+    }
+
+    #[test]
+    fn test_resolve_command_falls_back_to_host_path() {
+        let resolved = resolve_command("sh", &HashMap::new()).unwrap();
+        assert!(resolved.is_absolute());
+        assert_eq!(resolved.file_name().unwrap(), "sh");
+    }
+
+    #[tokio::test]
+    async fn test_native_executor_command_not_found() {
+        let ctx = env_context(&[("PATH", "/nonexistent/a:/nonexistent/b")], UndefinedEnv::Empty);
+        let err = NativeExecutor::new().execute(&ctx, "sh", &[]).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Command 'sh' not found (searched: /nonexistent/a, /nonexistent/b)"
+        );
+
+        let ctx = env_context(&[("PATH", "")], UndefinedEnv::Empty);
+        let err = NativeExecutor::new().execute(&ctx, "sh", &[]).await.unwrap_err();
+        assert_eq!(err.to_string(), "Command 'sh' not found (searched: PATH is empty)");
+    }
+
+    fn network_context(isolated: bool, dns_servers: &[&str]) -> ExecutionContext {
+        let mut ctx = env_context(&[], UndefinedEnv::Empty);
+        ctx.container_id = "net-test".to_string();
+        ctx.network = NetworkConfig {
+            isolated,
+            ip_address: None,
+            dns_servers: dns_servers.iter().map(|s| s.to_string()).collect(),
+            port_mappings: vec![],
+        };
+        ctx
+    }
+
+    #[tokio::test]
+    async fn test_native_executor_dns_servers() {
+        let executor = NativeExecutor::new();
+        let ctx = network_context(false, &["192.0.2.53"]);
+
+        let result = executor
+            .execute(&ctx, "cat", &["/etc/resolv.conf".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(result.exit_code, 0, "stderr: {}", result.stderr);
+        assert_eq!(result.stdout, "# Generated by enviro\nnameserver 192.0.2.53\n");
+
+        // The host's resolver configuration is untouched
+        let host = std::fs::read_to_string("/etc/resolv.conf").unwrap_or_default();
+        assert!(!host.contains("192.0.2.53"));
+    }
+
+    #[tokio::test]
+    async fn test_native_executor_isolated_network() {
+        let executor = NativeExecutor::new();
+        let ctx = network_context(true, &[]);
+
+        let result = executor
+            .execute(&ctx, "cat", &["/proc/net/dev".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(result.exit_code, 0, "stderr: {}", result.stderr);
+
+        let interfaces: Vec<&str> = result
+            .stdout
+            .lines()
+            .skip(2)
+            .filter_map(|line| line.split(':').next())
+            .map(str::trim)
+            .collect();
+        assert_eq!(interfaces, ["lo"]);
+    }
+
+    fn env_context(vars: &[(&str, &str)], undefined_env: UndefinedEnv) -> ExecutionContext {
+        ExecutionContext::builder("env-test")
+            .envs(vars.iter().copied())
+            .expand_env(true)
+            .undefined_env(undefined_env)
+            .workdir("/tmp")
+            .memory_bytes(100 * 1024 * 1024)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_env_expansion() {
+        let host_path = std::env::var("PATH").unwrap();
+        let ctx = env_context(
+            &[
+                ("APP_HOME", "/opt/app"),
+                ("PATH", "$PATH:/opt/bin"),
+                ("BIN", "${APP_HOME}/bin"),
+                ("SUFFIXED", "$APP_HOME-v2"),
+            ],
+            UndefinedEnv::Empty,
+        );
+
+        let env = ctx.resolved_env().unwrap();
+        assert_eq!(env["PATH"], format!("{}:/opt/bin", host_path));
+        assert_eq!(env["BIN"], "/opt/app/bin");
+        assert_eq!(env["SUFFIXED"], "/opt/app-v2");
+        assert_eq!(env["APP_HOME"], "/opt/app");
+    }
+
+    #[test]
+    fn test_env_expansion_disabled() {
+        let mut ctx = env_context(&[("PATH", "$PATH:/opt/bin")], UndefinedEnv::Empty);
+        ctx.expand_env = false;
+        assert_eq!(ctx.resolved_env().unwrap()["PATH"], "$PATH:/opt/bin");
+    }
+
+    #[test]
+    fn test_env_expansion_escaping() {
+        let ctx = env_context(
+            &[("PRICE", "$$5"), ("LONE", "a $ b"), ("TRAILING", "cost$")],
+            UndefinedEnv::Error,
+        );
+
+        let env = ctx.resolved_env().unwrap();
+        assert_eq!(env["PRICE"], "$5");
+        assert_eq!(env["LONE"], "a $ b");
+        assert_eq!(env["TRAILING"], "cost$");
+    }
+
+    #[test]
+    fn test_env_expansion_undefined() {
+        let vars = [("GREETING", "hello ${ENVIRO_TEST_UNDEFINED_VAR}!")];
+
+        let ctx = env_context(&vars, UndefinedEnv::Empty);
+        assert_eq!(ctx.resolved_env().unwrap()["GREETING"], "hello !");
+
+        let ctx = env_context(&vars, UndefinedEnv::Error);
+        let err = ctx.resolved_env().unwrap_err();
+        assert!(err.to_string().contains("$ENVIRO_TEST_UNDEFINED_VAR"));
+    }
+
+    #[test]
+    fn test_env_expansion_unterminated_brace() {
+        let ctx = env_context(&[("BROKEN", "${HOME")], UndefinedEnv::Empty);
+        assert!(ctx.resolved_env().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_native_executor_expands_env() {
+        let executor = NativeExecutor::new();
+        let ctx = env_context(&[("NAME", "enviro"), ("GREETING", "hi $NAME")], UndefinedEnv::Empty);
+
+        let result = executor
+            .execute(&ctx, "sh", &["-c".to_string(), "echo $GREETING".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(result.stdout, "hi enviro\n");
+    }
+
+    #[tokio::test]
+    async fn test_execution_result_into_result() {
+        let executor = NativeExecutor::new();
+        let ctx = env_context(&[], UndefinedEnv::Empty);
+
+        let result = executor.execute(&ctx, "echo", &["ok".to_string()]).await.unwrap();
+        assert!(result.success());
+        assert_eq!(result.into_result().unwrap().stdout, "ok\n");
+
+        let script = "echo starting >&2; echo 'disk full' >&2; exit 7".to_string();
+        let result = executor.execute(&ctx, "sh", &["-c".to_string(), script]).await.unwrap();
+        assert!(!result.success());
+        let err = result.into_result().unwrap_err();
+        assert_eq!(err.exit_code, 7);
+        assert_eq!(err.stderr, "starting\ndisk full\n");
+        assert_eq!(err.to_string(), "workload exited with code 7: disk full");
+
+        // Converts into anyhow for `?` in callers
+        let err: anyhow::Error = ExecutionResult {
+            exit_code: 1,
+            stdout: String::new(),
+            stderr: String::new(),
+            duration_ms: 0,
+            termination: Termination::Exited(1),
+        }
+        .into_result()
+        .unwrap_err()
+        .into();
+        assert_eq!(err.to_string(), "workload exited with code 1");
+    }
+
+    #[test]
+    fn test_execution_result_without_termination() {
+        let old = serde_json::json!({
+            "exit_code": 3,
+            "stdout": "out",
+            "stderr": "",
+            "duration_ms": 12,
+        });
+        let result: ExecutionResult = serde_json::from_value(old).unwrap();
+        assert_eq!(result.termination, Termination::Exited(3));
+        assert_eq!(result.stdout, "out");
+
+        let killed = ExecutionResult {
+            termination: Termination::Signaled(9),
+            exit_code: -1,
+            ..result
+        };
+        let parsed: ExecutionResult =
+            serde_json::from_value(serde_json::to_value(&killed).unwrap()).unwrap();
+        assert_eq!(parsed.termination, Termination::Signaled(9));
+    }
+
+    #[tokio::test]
+    async fn test_native_executor_merge_stderr() {
+        let executor = NativeExecutor::new();
+        let mut ctx = env_context(&[], UndefinedEnv::Empty);
+        let script = "echo one; echo two >&2; echo three; echo four >&2; exit 3".to_string();
+        let args = ["-c".to_string(), script];
+
+        let separate = executor.execute(&ctx, "sh", &args).await.unwrap();
+        assert_eq!(separate.stdout, "one\nthree\n");
+        assert_eq!(separate.stderr, "two\nfour\n");
+
+        ctx.merge_stderr = true;
+        let merged = executor.execute(&ctx, "sh", &args).await.unwrap();
+        assert_eq!(merged.stdout, "one\ntwo\nthree\nfour\n");
+        assert_eq!(merged.stderr, "");
+        assert_eq!(merged.exit_code, 3);
+    }
+
+    /// Executor whose `prepare` takes `delay`
+    struct SlowPrepare {
+        delay: std::time::Duration,
+        prepared: bool,
+    }
+
+    #[async_trait]
+    impl Executor for SlowPrepare {
+        async fn prepare(&mut self, _ctx: &ExecutionContext) -> Result<()> {
+            tokio::time::sleep(self.delay).await;
+            self.prepared = true;
+            Ok(())
+        }
+
+        async fn execute(
+            &self,
+            _ctx: &ExecutionContext,
+            _command: &str,
+            _args: &[String],
+        ) -> Result<ExecutionResult> {
+            anyhow::bail!("not used")
+        }
+
+        async fn cleanup(&mut self, _ctx: &ExecutionContext) -> Result<()> {
+            Ok(())
+        }
+
+        fn executor_type(&self) -> &str {
+            "slow-prepare"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prepare_with_deadline_times_out() {
+        use std::time::Duration;
+        use tokio::time::Instant;
+
+        let ctx = env_context(&[], UndefinedEnv::Empty);
+        let mut executor = SlowPrepare {
+            delay: Duration::from_secs(30),
+            prepared: false,
+        };
+        let start = Instant::now();
+        let err = executor
+            .prepare_with_deadline(&ctx, start + Duration::from_millis(50))
+            .await
+            .unwrap_err();
+
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(!executor.prepared);
+        let timeout = err.downcast_ref::<PrepareTimeout>().expect("a PrepareTimeout");
+        assert_eq!(timeout.executor, "slow-prepare");
+        assert_eq!(timeout.container_id, "env-test");
+
+        // Within the deadline the result of `prepare` is passed through
+        executor.delay = Duration::from_millis(1);
+        executor
+            .prepare_with_deadline(&ctx, Instant::now() + Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert!(executor.prepared);
+    }
+
+    #[test]
+    fn test_native_executor_kind() {
+        let executor = NativeExecutor::new();
+        assert_eq!(executor.kind(), ExecutorKind::Native);
+        assert_eq!(executor.kind().as_str(), executor.executor_type());
+    }
+
+    #[test]
+    fn test_executor_kind_from_type() {
+        for kind in [
+            ExecutorKind::Native,
+            ExecutorKind::Wasm,
+            ExecutorKind::Zig,
+            ExecutorKind::Go,
+            ExecutorKind::Python,
+            ExecutorKind::Custom("sample".to_string()),
+        ] {
+            assert_eq!(ExecutorKind::from_type(kind.as_str()), kind);
+        }
+        assert_eq!(
+            ExecutorKind::from_type("native"),
+            ExecutorKind::Custom("native".to_string())
+        );
+        assert_eq!(ExecutorKind::Wasm.to_string(), "wasm");
+    }
+
+    #[test]
+    fn test_debug_redacts_secret_env() {
+        let mut ctx = env_context(
+            &[("DB_PASSWORD", "hunter2-secret"), ("LOG_LEVEL", "debug")],
+            UndefinedEnv::Empty,
+        );
+        ctx.redact_env.insert("DB_PASSWORD".to_string());
+
+        let debug = format!("{:?}", ctx);
+        assert!(!debug.contains("hunter2-secret"), "secret leaked: {}", debug);
+        assert!(debug.contains(r#""DB_PASSWORD": "***""#));
+        assert!(debug.contains(r#""LOG_LEVEL": "debug""#));
+        assert!(!format!("{:#?}", ctx).contains("hunter2-secret"));
+
+        // The workload still gets the real value
+        assert_eq!(ctx.resolved_env().unwrap()["DB_PASSWORD"], "hunter2-secret");
+    }
+
+    #[tokio::test]
+    async fn test_env_policy_apply_from_host() {
+        let host = [
+            ("ENVIRO_TEST_SECRET", "hunter2"),
+            ("ENVIRO_TEST_ALLOWED", "visible"),
+            ("PATH", DEFAULT_PATH),
+        ];
+        let output = |policy: EnvPolicy| async move {
+            let mut cmd = tokio::process::Command::new("/bin/sh");
+            policy.apply_from(&mut cmd, host);
+            let script = "echo \"${ENVIRO_TEST_SECRET-unset} ${ENVIRO_TEST_ALLOWED-unset}\"";
+            let out = cmd.args(["-c", script]).output().await.unwrap();
+            String::from_utf8(out.stdout).unwrap()
+        };
+
+        assert_eq!(output(EnvPolicy::Clear).await, "unset unset\n");
+        let allow = EnvPolicy::AllowList(vec!["ENVIRO_TEST_ALLOWED".to_string()]);
+        assert_eq!(output(allow).await, "unset visible\n");
+        assert_eq!(output(EnvPolicy::Inherit).await, "hunter2 visible\n");
+    }
+
+    #[tokio::test]
+    async fn test_native_executor_env_policy() {
+        let executor = NativeExecutor::new();
+        let script = "echo \"${ENVIRO_TEST_OVERRIDE-unset} $PATH\"";
+        let args = ["-c".to_string(), script.to_string()];
+        let mut ctx = env_context(&[], UndefinedEnv::Empty);
+
+        // Clear by default: a standard PATH rather than the host's
+        assert_eq!(ctx.env_policy, EnvPolicy::Clear);
+        let result = executor.execute(&ctx, "sh", &args).await.unwrap();
+        assert_eq!(result.stdout, format!("unset {}\n", DEFAULT_PATH));
+
+        ctx.env_policy = EnvPolicy::Inherit;
+        let result = executor.execute(&ctx, "sh", &args).await.unwrap();
+        let host_path = std::env::var("PATH").unwrap();
+        assert_eq!(result.stdout, format!("unset {}\n", host_path));
+
+        // The container's own env wins over inherited values
+        ctx.env.insert("ENVIRO_TEST_OVERRIDE".to_string(), "overridden".to_string());
+        ctx.env.insert("PATH".to_string(), DEFAULT_PATH.to_string());
+        let result = executor.execute(&ctx, "sh", &args).await.unwrap();
+        assert_eq!(result.stdout, format!("overridden {}\n", DEFAULT_PATH));
+    }
+
+    #[tokio::test]
+    async fn test_native_executor_rlimits_and_umask() {
+        use nix::sys::resource::getrlimit;
+
+        let (_, hard) = getrlimit(Resource::RLIMIT_NOFILE).unwrap();
+        let mut ctx = env_context(&[], UndefinedEnv::Empty);
+        ctx.rlimits = vec![(Resource::RLIMIT_NOFILE, 64, hard), (Resource::RLIMIT_CORE, 0, 0)];
+        ctx.umask = Some(0o077);
+
+        let script = "ulimit -n; ulimit -c; umask".to_string();
+        let result = NativeExecutor::new()
+            .execute(&ctx, "sh", &["-c".to_string(), script])
+            .await
+            .unwrap();
+        assert_eq!(result.stdout, "64\n0\n0077\n");
+
+        // A hard limit above the current one is refused before exec
+        ctx.rlimits = vec![(Resource::RLIMIT_NOFILE, 64, u64::MAX)];
+        if hard != u64::MAX && !nix::unistd::geteuid().is_root() {
+            let result = NativeExecutor::new().execute(&ctx, "true", &[]).await;
+            assert!(result.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_native_executor_run_as() {
+        let mut ctx = env_context(&[], UndefinedEnv::Empty);
+        ctx.run_as = Some((65534, 65534));
+        let script = "id -u; id -g; id -G".to_string();
+        let result = NativeExecutor::new()
+            .execute(&ctx, "sh", &["-c".to_string(), script])
+            .await;
+
+        if check_run_as(65534, 65534).is_err() {
+            let err = format!("{:#}", result.unwrap_err());
+            assert!(err.contains("CAP_SETUID"), "unexpected error: {}", err);
+            return;
+        }
+        assert_eq!(result.unwrap().stdout, "65534\n65534\n65534\n");
+    }
+
+    #[tokio::test]
+    async fn test_native_executor_joins_cgroup() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut ctx = env_context(&[], UndefinedEnv::Empty);
+        ctx.cgroup = Some(dir.path().to_path_buf());
+
+        // Without the control file the context is rejected up front
+        let err = NativeExecutor::new().execute(&ctx, "true", &[]).await.unwrap_err();
+        assert!(format!("{:#}", err).contains("cgroup.procs"), "unexpected error: {:#}", err);
+
+        // A plain file stands in for cgroupfs, recording what the child wrote
+        std::fs::write(dir.path().join("cgroup.procs"), "").unwrap();
+        let result = NativeExecutor::new().execute(&ctx, "true", &[]).await.unwrap();
+        assert!(result.success());
+        assert_eq!(std::fs::read_to_string(dir.path().join("cgroup.procs")).unwrap(), "0");
+    }
+
+    #[test]
+    fn test_rlimits_serde() {
+        let mut ctx = env_context(&[], UndefinedEnv::Empty);
+        ctx.rlimits = default_rlimits();
+
+        let json = serde_json::to_value(&ctx).unwrap();
+        assert_eq!(json["rlimits"], serde_json::json!([["core", 0, 0], ["nofile", 1024, 4096]]));
+        let parsed: ExecutionContext = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.rlimits, ctx.rlimits);
+
+        let mut json = serde_json::to_value(&ctx).unwrap();
+        json["rlimits"] = serde_json::json!([["bogus", 1, 1]]);
+        let err = serde_json::from_value::<ExecutionContext>(json).unwrap_err();
+        assert!(err.to_string().contains("unknown rlimit 'bogus'"));
+    }
+
+    #[tokio::test]
+    async fn test_native_executor_termination() {
+        let executor = NativeExecutor::new();
+        let ctx = env_context(&[], UndefinedEnv::Empty);
+        let sh = |script: &str| vec!["-c".to_string(), script.to_string()];
+
+        let result = executor.execute(&ctx, "sh", &sh("exit 4")).await.unwrap();
+        assert_eq!(result.termination, Termination::Exited(4));
+        assert_eq!(result.exit_code, 4);
+
+        let result = executor.execute(&ctx, "sh", &sh("kill -TERM $$")).await.unwrap();
+        assert_eq!(result.termination, Termination::Signaled(libc::SIGTERM));
+        assert_eq!(result.termination, Termination::Signaled(15));
+        assert_eq!(result.exit_code, -1);
+        assert!(!result.success());
+    }
+
     #[test]
     fn test_executor_registry() {
         let mut registry = ExecutorRegistry::new();
@@ -350,6 +1690,29 @@ mod tests {
         assert!(types.contains(&"native".to_string()));
     }
 
+    #[test]
+    fn test_executor_registry_len_and_contains() {
+        let mut registry = ExecutorRegistry::new();
+        assert!(registry.is_empty());
+        assert_eq!(registry.len(), 0);
+        assert!(!registry.contains("native"));
+
+        registry.register("native".to_string(), Arc::new(NativeExecutor::new()));
+        registry.register("other".to_string(), Arc::new(NativeExecutor::new()));
+        assert!(!registry.is_empty());
+        assert_eq!(registry.len(), 2);
+        assert!(registry.contains("native"));
+        assert!(!registry.contains("nonexistent"));
+
+        assert!(registry.remove("native").is_some());
+        assert_eq!(registry.len(), 1);
+        assert!(!registry.contains("native"));
+        assert!(registry.contains("other"));
+
+        assert!(registry.remove("other").is_some());
+        assert!(registry.is_empty());
+    }
+
     #[test]
     fn test_concurrent_registry_basic() {
         let registry = ConcurrentExecutorRegistry::new();
@@ -376,6 +1739,31 @@ mod tests {
         assert!(registry.remove("native").is_none());
     }
 
+    #[test]
+    fn test_concurrent_registry_len_and_contains() {
+        let registry = ConcurrentExecutorRegistry::new();
+        assert!(registry.is_empty());
+        assert_eq!(registry.len(), 0);
+        assert!(!registry.contains("native"));
+
+        registry.register("native".to_string(), Arc::new(NativeExecutor::new()));
+        registry.register("other".to_string(), Arc::new(NativeExecutor::new()));
+        assert!(!registry.is_empty());
+        assert_eq!(registry.len(), 2);
+        assert!(registry.contains("native"));
+        assert!(!registry.contains("nonexistent"));
+
+        // Clones share the same map
+        let clone = registry.clone();
+        registry.remove("native");
+        assert_eq!(clone.len(), 1);
+        assert!(!clone.contains("native"));
+        assert!(clone.contains("other"));
+
+        clone.remove("other");
+        assert!(registry.is_empty());
+    }
+
     #[test]
     fn test_concurrent_registry_threaded_access() {
         let registry = ConcurrentExecutorRegistry::new();
@@ -0,0 +1,396 @@
+//! Network Enforcement - Applying NetworkConfig to Native Workloads
+//!
+//! Turns a container's [`NetworkConfig`] into namespace operations performed
+//! in the child between `fork` and `exec`:
+//! - `isolated: true` unshares the network namespace, leaving the workload
+//!   with nothing but a loopback device that is down.
+//! - `isolated: false` with `dns_servers` set keeps the host network but
+//!   bind-mounts a per-container `resolv.conf` over `/etc/resolv.conf` in a
+//!   private mount namespace, so only the workload sees the resolvers.
+//!
+//! When the engine is not running as root, a user namespace mapping the
+//! caller's own uid/gid is created first so the unshare is permitted.
+//!
+//! # Performance Pattern: Prepare in the Parent
+//! Everything the child needs (paths, map contents) is rendered before
+//! `fork`. The `pre_exec` hook only issues raw syscalls, which keeps it
+//! async-signal-safe inside a multi-threaded tokio process.
+
+use anyhow::{Context, Result};
+use std::ffi::CString;
+use std::io;
+use std::net::IpAddr;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::NetworkConfig;
+
+/// Location of the resolver configuration inside the workload's view
+const RESOLV_CONF: &str = "/etc/resolv.conf";
+
+/// Counter making per-execution directories unique within the process
+static NEXT_DIR: AtomicU64 = AtomicU64::new(0);
+
+/// Render the `resolv.conf` contents for `network`
+///
+/// Returns `None` when no resolvers are configured. Each entry must be an IP
+/// address; anything else (including embedded newlines) is rejected rather
+/// than written into the file.
+pub fn render_resolv_conf(network: &NetworkConfig) -> Result<Option<String>> {
+    if network.dns_servers.is_empty() {
+        return Ok(None);
+    }
+
+    let mut conf = String::from("# Generated by enviro\n");
+    for server in &network.dns_servers {
+        let addr: IpAddr = server
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid DNS server '{}': expected an IP address", server))?;
+        conf.push_str(&format!("nameserver {}\n", addr));
+    }
+    Ok(Some(conf))
+}
+
+/// A [`NetworkConfig`] rejected by [`NetworkConfig::validate`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum NetworkConfigError {
+    /// `ip_address` is not an IP address, optionally with a `/len` prefix
+    #[error("Invalid IP address '{0}'")]
+    InvalidIpAddress(String),
+    /// A `dns_servers` entry is not an IP address
+    #[error("Invalid DNS server '{0}': expected an IP address")]
+    InvalidDnsServer(String),
+    /// `ip_address` is set on an isolated network, whose namespace only has
+    /// a loopback device
+    #[error("ip_address '{0}' cannot be assigned in an isolated network, which only has loopback")]
+    AddressWhileIsolated(String),
+}
+
+impl NetworkConfig {
+    /// Check the addresses before they reach namespace or firewall setup
+    ///
+    /// `ip_address` may carry a `/len` prefix. An isolated network has no
+    /// interface besides `lo`, so the only address it can be given is a
+    /// loopback one.
+    pub fn validate(&self) -> Result<(), NetworkConfigError> {
+        if let Some(ip_address) = &self.ip_address {
+            let addr = parse_ip_address(ip_address)
+                .ok_or_else(|| NetworkConfigError::InvalidIpAddress(ip_address.clone()))?;
+            if self.isolated && !addr.is_loopback() {
+                return Err(NetworkConfigError::AddressWhileIsolated(ip_address.clone()));
+            }
+        }
+        for server in &self.dns_servers {
+            if server.trim().parse::<IpAddr>().is_err() {
+                return Err(NetworkConfigError::InvalidDnsServer(server.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The address in `value`, an IP address with an optional `/len` suffix
+/// that fits the address family
+fn parse_ip_address(value: &str) -> Option<IpAddr> {
+    let (addr, len) = match value.trim().split_once('/') {
+        Some((addr, len)) => (addr, Some(len)),
+        None => (value.trim(), None),
+    };
+    let addr: IpAddr = addr.parse().ok()?;
+    let max = if addr.is_ipv4() { 32 } else { 128 };
+    match len {
+        Some(len) => len.parse::<u8>().ok().filter(|len| *len <= max).map(|_| addr),
+        None => Some(addr),
+    }
+}
+
+/// Namespace setup for one execution, created before the child is spawned
+///
+/// Dropping it removes the per-container `resolv.conf`, so it must outlive
+/// the child process.
+pub(crate) struct NetworkSetup {
+    unshare_flags: libc::c_int,
+    id_maps: Option<IdMaps>,
+    resolv: Option<ResolvMount>,
+    dir: Option<PathBuf>,
+}
+
+/// Identity mappings written when a user namespace is needed
+struct IdMaps {
+    uid_map: Vec<u8>,
+    gid_map: Vec<u8>,
+}
+
+/// Bind mount of the generated file over `/etc/resolv.conf`
+struct ResolvMount {
+    source: CString,
+    target: CString,
+}
+
+impl NetworkSetup {
+    /// Plan the namespace operations for `network`
+    ///
+    /// Returns `None` when the workload should simply share the host network
+    /// and resolvers.
+    pub(crate) fn prepare(container_id: &str, network: &NetworkConfig) -> Result<Option<Self>> {
+        let mut unshare_flags = 0;
+        let mut resolv = None;
+        let mut dir = None;
+
+        if network.isolated {
+            unshare_flags |= libc::CLONE_NEWNET;
+        } else if let Some(conf) = render_resolv_conf(network)? {
+            let path = write_resolv_conf(container_id, &conf)?;
+            unshare_flags |= libc::CLONE_NEWNS;
+            resolv = Some(ResolvMount {
+                source: path_cstring(&path)?,
+                target: CString::new(RESOLV_CONF).expect("constant path has no NUL"),
+            });
+            dir = path.parent().map(Path::to_path_buf);
+        }
+
+        if unshare_flags == 0 {
+            return Ok(None);
+        }
+
+        // SAFETY: these calls have no preconditions and cannot fail.
+        let (uid, gid) = unsafe { (libc::geteuid(), libc::getegid()) };
+        let id_maps = (uid != 0).then(|| {
+            unshare_flags |= libc::CLONE_NEWUSER;
+            IdMaps {
+                uid_map: format!("{} {} 1", uid, uid).into_bytes(),
+                gid_map: format!("{} {} 1", gid, gid).into_bytes(),
+            }
+        });
+
+        Ok(Some(Self {
+            unshare_flags,
+            id_maps,
+            resolv,
+            dir,
+        }))
+    }
+
+    /// Path of the generated `resolv.conf`, if one is bind-mounted
+    #[cfg(test)]
+    fn resolv_conf_path(&self) -> Option<&Path> {
+        self.resolv
+            .as_ref()
+            .map(|mount| Path::new(std::ffi::OsStr::from_bytes(mount.source.as_bytes())))
+    }
+
+    /// Enter the namespaces; called in the child between `fork` and `exec`
+    ///
+    /// Only raw syscalls on pre-rendered buffers are used here.
+    pub(crate) fn apply(&self) -> io::Result<()> {
+        // SAFETY: unshare only affects the calling (child) process.
+        if unsafe { libc::unshare(self.unshare_flags) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        if let Some(maps) = &self.id_maps {
+            write_proc(c"/proc/self/setgroups", b"deny")?;
+            write_proc(c"/proc/self/uid_map", &maps.uid_map)?;
+            write_proc(c"/proc/self/gid_map", &maps.gid_map)?;
+        }
+
+        if let Some(mount) = &self.resolv {
+            // Keep the bind mount from propagating back to the host.
+            // SAFETY: all pointers are NUL-terminated strings or null.
+            let ret = unsafe {
+                libc::mount(
+                    std::ptr::null(),
+                    c"/".as_ptr(),
+                    std::ptr::null(),
+                    libc::MS_REC | libc::MS_PRIVATE,
+                    std::ptr::null(),
+                )
+            };
+            if ret != 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            // SAFETY: as above.
+            let ret = unsafe {
+                libc::mount(
+                    mount.source.as_ptr(),
+                    mount.target.as_ptr(),
+                    std::ptr::null(),
+                    libc::MS_BIND,
+                    std::ptr::null(),
+                )
+            };
+            if ret != 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for NetworkSetup {
+    fn drop(&mut self) {
+        if let Some(dir) = &self.dir {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+}
+
+/// Write `conf` to a fresh per-container directory and return the file path
+fn write_resolv_conf(container_id: &str, conf: &str) -> Result<PathBuf> {
+    let name: String = container_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    let dir = std::env::temp_dir().join(format!(
+        "enviro-net-{}-{}-{}",
+        name,
+        std::process::id(),
+        NEXT_DIR.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    std::fs::create_dir(&dir)
+        .with_context(|| format!("Failed to create network directory {:?}", dir))?;
+    let path = dir.join("resolv.conf");
+    std::fs::write(&path, conf).with_context(|| format!("Failed to write {:?}", path))?;
+    Ok(path)
+}
+
+fn path_cstring(path: &Path) -> Result<CString> {
+    CString::new(path.as_os_str().as_bytes())
+        .with_context(|| format!("Path {:?} contains a NUL byte", path))
+}
+
+/// Write `data` to a `/proc` file using only async-signal-safe calls
+fn write_proc(path: &std::ffi::CStr, data: &[u8]) -> io::Result<()> {
+    // SAFETY: `path` is NUL-terminated and `data` is a valid buffer.
+    unsafe {
+        let fd = libc::open(path.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let written = libc::write(fd, data.as_ptr().cast(), data.len());
+        let err = io::Error::last_os_error();
+        libc::close(fd);
+        if written != data.len() as isize {
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn network(isolated: bool, dns_servers: &[&str]) -> NetworkConfig {
+        NetworkConfig {
+            isolated,
+            ip_address: None,
+            dns_servers: dns_servers.iter().map(|s| s.to_string()).collect(),
+            port_mappings: vec![],
+        }
+    }
+
+    #[test]
+    fn test_validate_accepts_valid_config() {
+        let mut config = network(false, &["1.1.1.1", " 2001:4860:4860::8888 "]);
+        config.ip_address = Some("10.88.0.2/16".to_string());
+        assert_eq!(config.validate(), Ok(()));
+
+        config.ip_address = Some("fd00::2".to_string());
+        assert_eq!(config.validate(), Ok(()));
+        assert_eq!(network(true, &[]).validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_addresses() {
+        let mut config = network(false, &[]);
+        for bad in ["10.88.0", "10.88.0.2/33", "fd00::2/129", "localhost"] {
+            config.ip_address = Some(bad.to_string());
+            assert_eq!(
+                config.validate(),
+                Err(NetworkConfigError::InvalidIpAddress(bad.to_string()))
+            );
+        }
+
+        let config = network(false, &["1.1.1.1", "1.1.1.1\nnameserver 6.6.6.6"]);
+        assert_eq!(
+            config.validate(),
+            Err(NetworkConfigError::InvalidDnsServer("1.1.1.1\nnameserver 6.6.6.6".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_address_on_isolated_network() {
+        let mut config = network(true, &[]);
+        config.ip_address = Some("10.88.0.2".to_string());
+        assert_eq!(
+            config.validate(),
+            Err(NetworkConfigError::AddressWhileIsolated("10.88.0.2".to_string()))
+        );
+
+        // The namespace's own loopback is fine
+        config.ip_address = Some("127.0.0.1/8".to_string());
+        assert_eq!(config.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_render_resolv_conf() {
+        let conf = render_resolv_conf(&network(false, &["1.1.1.1", " 2001:4860:4860::8888 "]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            conf,
+            "# Generated by enviro\nnameserver 1.1.1.1\nnameserver 2001:4860:4860::8888\n"
+        );
+    }
+
+    #[test]
+    fn test_render_resolv_conf_empty() {
+        assert!(render_resolv_conf(&network(false, &[])).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_render_resolv_conf_rejects_invalid() {
+        let err = render_resolv_conf(&network(false, &["8.8.8.8\noptions ndots:15"])).unwrap_err();
+        assert!(err.to_string().contains("Invalid DNS server"));
+        assert!(render_resolv_conf(&network(false, &["dns.example.com"])).is_err());
+    }
+
+    #[test]
+    fn test_prepare_writes_per_container_file() {
+        let setup = NetworkSetup::prepare("web/1", &network(false, &["9.9.9.9"]))
+            .unwrap()
+            .unwrap();
+        let path = setup.resolv_conf_path().unwrap().to_path_buf();
+
+        assert!(path.parent().unwrap().file_name().unwrap().to_string_lossy().starts_with("enviro-net-web_1-"));
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "# Generated by enviro\nnameserver 9.9.9.9\n"
+        );
+        assert_ne!(setup.unshare_flags & libc::CLONE_NEWNS, 0);
+        assert_eq!(setup.unshare_flags & libc::CLONE_NEWNET, 0);
+
+        drop(setup);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_prepare_isolated_unshares_network() {
+        let setup = NetworkSetup::prepare("iso", &network(true, &["8.8.8.8"]))
+            .unwrap()
+            .unwrap();
+        assert_ne!(setup.unshare_flags & libc::CLONE_NEWNET, 0);
+        assert!(setup.resolv_conf_path().is_none());
+    }
+
+    #[test]
+    fn test_prepare_host_network_without_dns() {
+        assert!(NetworkSetup::prepare("host", &network(false, &[])).unwrap().is_none());
+    }
+}
@@ -0,0 +1,145 @@
+//! WASM Executor - Sandboxed WebAssembly Workloads
+//!
+//! Runs WebAssembly modules through an external WASI runtime (`wasmtime` by
+//! default) instead of dlopening native code into the engine. Modules found by
+//! plugin discovery are registered as `WasmExecutor`s.
+//!
+//! # Performance Pattern: Validate Once
+//! The module header is checked when the executor is prepared, so a corrupt
+//! module fails fast instead of on every execution.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use super::{ExecutionContext, ExecutionResult, Executor};
+
+/// Magic number and version 1 header every binary WASM module starts with
+const WASM_HEADER: [u8; 8] = *b"\0asm\x01\0\0\0";
+
+/// Default WASI runtime binary used to run modules
+pub const DEFAULT_WASM_RUNTIME: &str = "wasmtime";
+
+/// Check that `path` is a binary WebAssembly module (magic + version 1)
+pub fn validate_module(path: &Path) -> Result<()> {
+    let mut header = [0u8; WASM_HEADER.len()];
+    std::fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .with_context(|| format!("Failed to read WASM module {:?}", path))?;
+
+    if header != WASM_HEADER {
+        anyhow::bail!("{:?} is not a WebAssembly module", path);
+    }
+    Ok(())
+}
+
+/// Executor running a WebAssembly module under a WASI runtime
+///
+/// The module sees `command` followed by `args` as its argument list, the
+/// context's environment, and the context's working directory preopened.
+pub struct WasmExecutor {
+    module: PathBuf,
+    runtime: PathBuf,
+}
+
+impl WasmExecutor {
+    /// Create an executor for the module at `module` using `wasmtime`
+    pub fn new(module: PathBuf) -> Self {
+        Self {
+            module,
+            runtime: PathBuf::from(DEFAULT_WASM_RUNTIME),
+        }
+    }
+
+    /// Use a different WASI runtime binary
+    pub fn with_runtime(mut self, runtime: PathBuf) -> Self {
+        self.runtime = runtime;
+        self
+    }
+
+    /// Path of the module this executor runs
+    pub fn module(&self) -> &Path {
+        &self.module
+    }
+}
+
+#[async_trait]
+impl Executor for WasmExecutor {
+    async fn prepare(&mut self, _ctx: &ExecutionContext) -> Result<()> {
+        validate_module(&self.module)
+    }
+
+    async fn execute(
+        &self,
+        ctx: &ExecutionContext,
+        command: &str,
+        args: &[String],
+    ) -> Result<ExecutionResult> {
+        use tokio::process::Command;
+        use tokio::time::Instant;
+
+        let start = Instant::now();
+
+        let mut cmd = Command::new(&self.runtime);
+        cmd.arg("run").arg("--dir").arg(&ctx.workdir);
+        let env = ctx.resolved_env().context("Failed to expand container environment")?;
+        for (key, value) in &env {
+            cmd.arg("--env").arg(format!("{}={}", key, value));
+        }
+
+        let output = cmd
+            .arg(&self.module)
+            .arg(command)
+            .args(args)
+            .current_dir(&ctx.workdir)
+            .output()
+            .await
+            .with_context(|| format!("Failed to run WASM runtime {:?}", self.runtime))?;
+
+        let duration_ms = start.elapsed().as_millis() as u64;
+
+        Ok(ExecutionResult {
+            exit_code: output.status.code().unwrap_or(-1),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            duration_ms,
+            termination: output.status.into(),
+        })
+    }
+
+    async fn cleanup(&mut self, _ctx: &ExecutionContext) -> Result<()> {
+        Ok(())
+    }
+
+    fn executor_type(&self) -> &str {
+        "wasm"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_empty_module() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), WASM_HEADER).unwrap();
+        assert!(validate_module(file.path()).is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_non_wasm() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), b"\x7fELF\x02\x01\x01\0").unwrap();
+        let err = validate_module(file.path()).unwrap_err();
+        assert!(err.to_string().contains("not a WebAssembly module"));
+    }
+
+    #[test]
+    fn test_validate_rejects_truncated_file() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), b"\0asm").unwrap();
+        assert!(validate_module(file.path()).is_err());
+    }
+}
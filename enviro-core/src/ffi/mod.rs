@@ -9,7 +9,10 @@
 //! - Minimal marshaling overhead (C structs, no JSON)
 //! - Direct memory access where safe
 
+use std::fs;
+use std::ops::RangeInclusive;
 use std::os::raw::{c_int, c_uint};
+use std::path::{Path, PathBuf};
 
 #[cfg(go_available)]
 use std::ffi::CString;
@@ -22,6 +25,70 @@ pub type FfiResult = c_int;
 pub const FFI_SUCCESS: FfiResult = 0;
 pub const FFI_ERROR: FfiResult = -1;
 
+/// Errors returned by the safe FFI wrappers
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FfiError {
+    /// The Zig library was not built into this binary
+    #[error("Zig FFI not available on this platform or build configuration")]
+    ZigUnavailable,
+    /// The Go library was not built into this binary
+    #[error("Go FFI not available on this platform or build configuration")]
+    GoUnavailable,
+    /// `op` failed with the given return code, or errno for the pure-Rust
+    /// paths
+    #[error("{op}{}", describe_code(*code))]
+    SyscallFailed { op: FfiOp, code: i32 },
+    /// An argument was rejected before crossing the FFI boundary
+    #[error("{0}")]
+    InvalidInput(String),
+    /// The blocking task running the FFI call panicked or was cancelled
+    #[error("FFI task failed: {0}")]
+    TaskFailed(String),
+}
+
+impl FfiError {
+    /// Map an I/O error from the pure-Rust paths onto `SyscallFailed`
+    fn from_io(op: FfiOp, err: &std::io::Error) -> Self {
+        FfiError::SyscallFailed {
+            op,
+            code: err.raw_os_error().unwrap_or(FFI_ERROR),
+        }
+    }
+}
+
+/// The operation behind an [`FfiError::SyscallFailed`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FfiOp {
+    /// Setting `oom_score_adj` for `pid`
+    #[error("Failed to tune OOM killer for PID {pid}")]
+    TuneOomKiller { pid: u32 },
+    /// Writing the cgroup's `memory.oom.group` at `path`
+    #[error("Failed to write {}", path.display())]
+    WriteOomGroup { path: PathBuf },
+    /// Reading the Zig allocator's counters
+    #[error("Failed to get allocator stats")]
+    AllocatorStats,
+    /// Starting the Go control plane
+    #[error("Failed to initialize Go control plane")]
+    InitControlPlane,
+    /// Stopping the Go control plane
+    #[error("Failed to shutdown Go control plane")]
+    ShutdownControlPlane,
+}
+
+/// Suffix for a failure code: the errno text for positive codes, nothing
+/// for the generic [`FFI_ERROR`]
+fn describe_code(code: i32) -> String {
+    match code {
+        FFI_ERROR => String::new(),
+        errno if errno > 0 => format!(": {}", std::io::Error::from_raw_os_error(errno)),
+        other => format!(" (code {})", other),
+    }
+}
+
+/// Range accepted by the kernel for `/proc/<pid>/oom_score_adj`
+pub const OOM_SCORE_ADJ_RANGE: RangeInclusive<i32> = -1000..=1000;
+
 /// OOM (Out-Of-Memory) killer configuration
 ///
 /// This struct maps directly to the C ABI layout used by Zig.
@@ -95,7 +162,9 @@ extern "C" {
 /// This wrapper adds < 1ns overhead (inlined function call) while providing
 /// Rust's safety guarantees for the configuration.
 #[cfg(zig_available)]
-pub fn tune_oom_killer(pid: u32, oom_score_adj: i32, enable: bool) -> Result<(), String> {
+pub fn tune_oom_killer(pid: u32, oom_score_adj: i32, enable: bool) -> Result<(), FfiError> {
+    validate_oom_score_adj(oom_score_adj)?;
+
     let config = OomConfig {
         pid: pid as c_uint,
         oom_score_adj: oom_score_adj as c_int,
@@ -107,19 +176,81 @@ pub fn tune_oom_killer(pid: u32, oom_score_adj: i32, enable: bool) -> Result<(),
     if result == FFI_SUCCESS {
         Ok(())
     } else {
-        Err(format!("Failed to tune OOM killer for PID {}", pid))
+        Err(FfiError::SyscallFailed {
+            op: FfiOp::TuneOomKiller { pid },
+            code: result,
+        })
     }
 }
 
-/// Fallback implementation when Zig is not available
+/// Pure-Rust fallback when Zig is not available
+///
+/// Performs the same writes as the Zig implementation against the real
+/// `/proc` and `/sys/fs/cgroup` hierarchies.
 #[cfg(not(zig_available))]
-pub fn tune_oom_killer(_pid: u32, _oom_score_adj: i32, _enable: bool) -> Result<(), String> {
-    Err("Zig FFI not available on this platform or build configuration".to_string())
+pub fn tune_oom_killer(pid: u32, oom_score_adj: i32, enable: bool) -> Result<(), FfiError> {
+    tune_oom_killer_at(Path::new("/proc"), Path::new("/sys/fs/cgroup"), pid, oom_score_adj, enable)
+}
+
+/// Tune the OOM killer using explicit procfs and cgroup v2 mount points
+///
+/// 1. Writes `oom_score_adj` to `<proc_root>/<pid>/oom_score_adj`
+/// 2. Resolves the process's cgroup v2 path from `<proc_root>/<pid>/cgroup`
+///    and writes `memory.oom.group` (1 when `enable` is set, 0 otherwise) so
+///    the whole container is killed together rather than a single process
+///
+/// The cgroup step is skipped when the process is not in a cgroup v2
+/// hierarchy or the cgroup has no memory controller.
+pub fn tune_oom_killer_at(
+    proc_root: &Path,
+    cgroup_root: &Path,
+    pid: u32,
+    oom_score_adj: i32,
+    enable: bool,
+) -> Result<(), FfiError> {
+    validate_oom_score_adj(oom_score_adj)?;
+
+    let adj_path = proc_root.join(pid.to_string()).join("oom_score_adj");
+    fs::write(&adj_path, oom_score_adj.to_string())
+        .map_err(|e| FfiError::from_io(FfiOp::TuneOomKiller { pid }, &e))?;
+
+    if let Some(cgroup_dir) = cgroup_v2_dir(proc_root, cgroup_root, pid) {
+        let group_path = cgroup_dir.join("memory.oom.group");
+        if group_path.exists() {
+            fs::write(&group_path, if enable { "1" } else { "0" }).map_err(|e| {
+                FfiError::from_io(FfiOp::WriteOomGroup { path: group_path.clone() }, &e)
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolve a process's cgroup v2 directory from its `/proc/<pid>/cgroup` entry
+fn cgroup_v2_dir(proc_root: &Path, cgroup_root: &Path, pid: u32) -> Option<PathBuf> {
+    let contents = fs::read_to_string(proc_root.join(pid.to_string()).join("cgroup")).ok()?;
+    // The unified hierarchy is the "0::<path>" line
+    let path = contents.lines().find_map(|line| line.strip_prefix("0::"))?;
+    Some(cgroup_root.join(path.trim().trim_start_matches('/')))
+}
+
+/// Reject `oom_score_adj` values the kernel would refuse
+fn validate_oom_score_adj(oom_score_adj: i32) -> Result<(), FfiError> {
+    if OOM_SCORE_ADJ_RANGE.contains(&oom_score_adj) {
+        Ok(())
+    } else {
+        Err(FfiError::InvalidInput(format!(
+            "oom_score_adj {} out of range ({}..={})",
+            oom_score_adj,
+            OOM_SCORE_ADJ_RANGE.start(),
+            OOM_SCORE_ADJ_RANGE.end()
+        )))
+    }
 }
 
 /// Get allocator statistics from Zig's custom allocator
 #[cfg(zig_available)]
-pub fn get_allocator_stats() -> Result<(u64, u64), String> {
+pub fn get_allocator_stats() -> Result<(u64, u64), FfiError> {
     let mut total_allocs: u64 = 0;
     let mut total_frees: u64 = 0;
 
@@ -130,14 +261,17 @@ pub fn get_allocator_stats() -> Result<(u64, u64), String> {
     if result == FFI_SUCCESS {
         Ok((total_allocs, total_frees))
     } else {
-        Err("Failed to get allocator stats".to_string())
+        Err(FfiError::SyscallFailed {
+            op: FfiOp::AllocatorStats,
+            code: result,
+        })
     }
 }
 
 /// Fallback implementation when Zig is not available
 #[cfg(not(zig_available))]
-pub fn get_allocator_stats() -> Result<(u64, u64), String> {
-    Err("Zig FFI not available on this platform or build configuration".to_string())
+pub fn get_allocator_stats() -> Result<(u64, u64), FfiError> {
+    Err(FfiError::ZigUnavailable)
 }
 
 // External Go functions for control plane
@@ -164,40 +298,132 @@ extern "C" {
 
 /// Safe Rust wrapper for Go control plane initialization
 #[cfg(go_available)]
-pub fn init_control_plane(addr: &str) -> Result<(), String> {
-    let c_addr = CString::new(addr).map_err(|e| format!("Invalid address: {}", e))?;
+pub fn init_control_plane(addr: &str) -> Result<(), FfiError> {
+    let c_addr = CString::new(addr)
+        .map_err(|e| FfiError::InvalidInput(format!("Invalid address: {}", e)))?;
 
     let result = unsafe { go_init_control_plane(c_addr.as_ptr()) };
 
     if result == FFI_SUCCESS {
         Ok(())
     } else {
-        Err("Failed to initialize Go control plane".to_string())
+        Err(FfiError::SyscallFailed {
+            op: FfiOp::InitControlPlane,
+            code: result,
+        })
     }
 }
 
 /// Fallback implementation when Go is not available
 #[cfg(not(go_available))]
-pub fn init_control_plane(_addr: &str) -> Result<(), String> {
-    Err("Go FFI not available on this platform or build configuration".to_string())
+pub fn init_control_plane(_addr: &str) -> Result<(), FfiError> {
+    Err(FfiError::GoUnavailable)
 }
 
 /// Safe Rust wrapper for Go control plane shutdown
 #[cfg(go_available)]
-pub fn shutdown_control_plane() -> Result<(), String> {
+pub fn shutdown_control_plane() -> Result<(), FfiError> {
     let result = unsafe { go_shutdown_control_plane() };
 
     if result == FFI_SUCCESS {
         Ok(())
     } else {
-        Err("Failed to shutdown Go control plane".to_string())
+        Err(FfiError::SyscallFailed {
+            op: FfiOp::ShutdownControlPlane,
+            code: result,
+        })
     }
 }
 
 /// Fallback implementation when Go is not available
 #[cfg(not(go_available))]
-pub fn shutdown_control_plane() -> Result<(), String> {
-    Err("Go FFI not available on this platform or build configuration".to_string())
+pub fn shutdown_control_plane() -> Result<(), FfiError> {
+    Err(FfiError::GoUnavailable)
+}
+
+/// Owned handle to a running Go control plane
+///
+/// Starting and stopping the control plane are blocking FFI calls, so they
+/// run on tokio's blocking pool rather than stalling the async workers.
+/// Call [`shutdown`](Self::shutdown) to stop it and see whether that
+/// worked; dropping a handle that was not shut down is only a best-effort
+/// fallback, so the Go threads never outlive the owner.
+///
+/// # Usage
+/// ```rust,no_run
+/// # async fn run() -> anyhow::Result<()> {
+/// use enviro_core::ffi::ControlPlane;
+///
+/// let plane = ControlPlane::start("0.0.0.0:50051").await?;
+/// // ... serve requests ...
+/// plane.shutdown().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ControlPlane {
+    addr: String,
+    running: bool,
+}
+
+impl ControlPlane {
+    /// Start the control plane listening on `addr`
+    pub async fn start(addr: &str) -> Result<ControlPlane, FfiError> {
+        let addr = addr.to_string();
+        let bind_addr = addr.clone();
+        run_blocking(move || init_control_plane(&bind_addr)).await?;
+
+        tracing::info!(addr = %addr, "Go control plane started");
+        Ok(ControlPlane {
+            addr,
+            running: true,
+        })
+    }
+
+    /// Address the control plane was bound to
+    pub fn addr(&self) -> &str {
+        &self.addr
+    }
+
+    /// Shut the control plane down without blocking the async runtime
+    pub async fn shutdown(mut self) -> Result<(), FfiError> {
+        self.running = false;
+        run_blocking(shutdown_control_plane).await?;
+        tracing::info!(addr = %self.addr, "Go control plane stopped");
+        Ok(())
+    }
+}
+
+/// Run a blocking FFI call on tokio's blocking pool
+async fn run_blocking<T: Send + 'static>(
+    call: impl FnOnce() -> Result<T, FfiError> + Send + 'static,
+) -> Result<T, FfiError> {
+    tokio::task::spawn_blocking(call)
+        .await
+        .map_err(|e| FfiError::TaskFailed(e.to_string()))?
+}
+
+impl Drop for ControlPlane {
+    /// Best-effort fallback for a handle that was not
+    /// [`shutdown`](ControlPlane::shutdown): inside a tokio runtime the
+    /// call is handed to the blocking pool without waiting for it.
+    fn drop(&mut self) {
+        if !self.running {
+            return;
+        }
+        let addr = std::mem::take(&mut self.addr);
+        let stop = move || {
+            if let Err(e) = shutdown_control_plane() {
+                tracing::warn!(addr = %addr, "Failed to shut down control plane: {}", e);
+            }
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn_blocking(stop);
+            }
+            Err(_) => stop(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -219,6 +445,141 @@ mod tests {
         assert_eq!(FFI_ERROR, -1);
     }
 
+    /// Build a fake /proc/<pid> and cgroup v2 tree under a tempdir
+    fn mock_proc(pid: u32) -> (tempfile::TempDir, PathBuf, PathBuf) {
+        let root = tempfile::tempdir().unwrap();
+        let proc_root = root.path().join("proc");
+        let cgroup_root = root.path().join("cgroup");
+
+        let pid_dir = proc_root.join(pid.to_string());
+        fs::create_dir_all(&pid_dir).unwrap();
+        fs::write(pid_dir.join("oom_score_adj"), "0").unwrap();
+        fs::write(pid_dir.join("cgroup"), "0::/enviro/ctr-1\n").unwrap();
+
+        let cg = cgroup_root.join("enviro/ctr-1");
+        fs::create_dir_all(&cg).unwrap();
+        fs::write(cg.join("memory.oom.group"), "0").unwrap();
+
+        (root, proc_root, cgroup_root)
+    }
+
+    #[test]
+    fn test_tune_oom_writes_proc_and_cgroup() {
+        let (_root, proc_root, cgroup_root) = mock_proc(42);
+
+        tune_oom_killer_at(&proc_root, &cgroup_root, 42, -500, true).unwrap();
+
+        let adj = fs::read_to_string(proc_root.join("42/oom_score_adj")).unwrap();
+        assert_eq!(adj, "-500");
+        let group = fs::read_to_string(cgroup_root.join("enviro/ctr-1/memory.oom.group")).unwrap();
+        assert_eq!(group, "1");
+    }
+
+    #[test]
+    fn test_tune_oom_without_cgroup_v2() {
+        let (_root, proc_root, cgroup_root) = mock_proc(7);
+        fs::write(proc_root.join("7/cgroup"), "1:memory:/legacy\n").unwrap();
+
+        tune_oom_killer_at(&proc_root, &cgroup_root, 7, 100, false).unwrap();
+        let adj = fs::read_to_string(proc_root.join("7/oom_score_adj")).unwrap();
+        assert_eq!(adj, "100");
+    }
+
+    #[test]
+    fn test_tune_oom_rejects_out_of_range() {
+        let (_root, proc_root, cgroup_root) = mock_proc(1);
+        assert!(tune_oom_killer_at(&proc_root, &cgroup_root, 1, 1001, true).is_err());
+        assert!(tune_oom_killer_at(&proc_root, &cgroup_root, 1, -1001, true).is_err());
+        // Nothing written on a validation failure
+        let adj = fs::read_to_string(proc_root.join("1/oom_score_adj")).unwrap();
+        assert_eq!(adj, "0");
+    }
+
+    #[test]
+    fn test_tune_oom_missing_pid() {
+        let (_root, proc_root, cgroup_root) = mock_proc(1);
+        let err = tune_oom_killer_at(&proc_root, &cgroup_root, 99, 0, true).unwrap_err();
+        assert_eq!(
+            err,
+            FfiError::SyscallFailed {
+                op: FfiOp::TuneOomKiller { pid: 99 },
+                code: libc::ENOENT,
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Failed to tune OOM killer for PID 99: No such file or directory (os error 2)"
+        );
+    }
+
+    #[test]
+    fn test_out_of_range_is_invalid_input() {
+        let (_root, proc_root, cgroup_root) = mock_proc(1);
+        let err = tune_oom_killer_at(&proc_root, &cgroup_root, 1, 5000, true).unwrap_err();
+        assert!(matches!(err, FfiError::InvalidInput(_)), "unexpected error: {:?}", err);
+        assert_eq!(err.to_string(), "oom_score_adj 5000 out of range (-1000..=1000)");
+    }
+
+    #[cfg(not(zig_available))]
+    #[test]
+    fn test_allocator_stats_zig_unavailable() {
+        assert_eq!(get_allocator_stats(), Err(FfiError::ZigUnavailable));
+    }
+
+    #[cfg(not(go_available))]
+    #[test]
+    fn test_control_plane_go_unavailable() {
+        assert_eq!(init_control_plane("127.0.0.1:50051"), Err(FfiError::GoUnavailable));
+        assert_eq!(shutdown_control_plane(), Err(FfiError::GoUnavailable));
+    }
+
+    #[test]
+    fn test_ffi_error_display_preserves_messages() {
+        assert_eq!(
+            FfiError::ZigUnavailable.to_string(),
+            "Zig FFI not available on this platform or build configuration"
+        );
+        assert_eq!(
+            FfiError::GoUnavailable.to_string(),
+            "Go FFI not available on this platform or build configuration"
+        );
+        let failed = |op| FfiError::SyscallFailed { op, code: FFI_ERROR }.to_string();
+        assert_eq!(
+            failed(FfiOp::TuneOomKiller { pid: 42 }),
+            "Failed to tune OOM killer for PID 42"
+        );
+        assert_eq!(failed(FfiOp::AllocatorStats), "Failed to get allocator stats");
+        assert_eq!(failed(FfiOp::InitControlPlane), "Failed to initialize Go control plane");
+        assert_eq!(failed(FfiOp::ShutdownControlPlane), "Failed to shutdown Go control plane");
+        assert_eq!(
+            FfiError::SyscallFailed {
+                op: FfiOp::AllocatorStats,
+                code: -7,
+            }
+            .to_string(),
+            "Failed to get allocator stats (code -7)"
+        );
+        assert_eq!(
+            FfiError::InvalidInput("Invalid address: nul byte found".to_string()).to_string(),
+            "Invalid address: nul byte found"
+        );
+    }
+
+    #[cfg(not(go_available))]
+    #[tokio::test]
+    async fn test_control_plane_start_without_go() {
+        let err = ControlPlane::start("127.0.0.1:50051").await.unwrap_err();
+        assert_eq!(err, FfiError::GoUnavailable);
+    }
+
+    #[cfg(go_available)]
+    #[tokio::test]
+    async fn test_control_plane_start_and_drop() {
+        let plane = ControlPlane::start("127.0.0.1:0").await.unwrap();
+        assert_eq!(plane.addr(), "127.0.0.1:0");
+        drop(plane);
+    }
+
     // Note: Actual FFI tests require the Zig/Go libraries to be built
     // In CI/CD, these should run after the build process completes
 }
@@ -10,6 +10,7 @@
 //! - io_uring for async I/O (Linux 5.1+)
 //! - Thread-per-core architecture with work stealing
 
+pub mod cli;
 pub mod engine;
 pub mod executor;
 pub mod ffi;
@@ -17,32 +18,102 @@ pub mod memory;
 pub mod perf;
 pub mod plugin;
 pub mod runtime;
+pub mod server;
 
 pub use engine::buffer::{BufferPool, ZeroCopyBuffer};
-pub use engine::cow_resources::{CowResource, SharedResourceManager};
+pub use engine::cow_resources::{ConcurrentResourceManager, CowResource, SharedResourceManager};
+pub use engine::id_generator::{CounterGenerator, IdGenerator, UuidGenerator};
 pub use engine::isolation::Isolation;
 pub use engine::io_uring::IoUringManager;
 pub use engine::lazy_init::{LazyResource, LazyResourcePool};
-pub use engine::memory_pool::{ContextPool, PoolStats};
+pub use engine::memory_pool::{ContextPool, PoolStats, PooledContext, SyncContextPool};
 pub use engine::namespace_cache::{NamespaceCache, NamespaceTemplate};
 pub use engine::parallel_setup::{ParallelNamespaceSetup, ParallelSetupReport, SetupResult};
 pub use engine::resource_limits::{OptimizedResourceLimits, ResourceLimitBatch, ResourceProfile};
-pub use executor::{ConcurrentExecutorRegistry, Executor};
+pub use executor::{
+    ConcurrentExecutorRegistry, ExecutionContextBuilder, Executor, ExecutorKind, PrepareTimeout,
+};
 // Note: memory::BufferPool is the original pool used by runtime module
 // For new code, use engine::buffer::BufferPool which is the optimized zero-copy implementation
+pub use memory::{collect_allocator_stats, AllocatorStats};
 pub use perf::PerfMetrics;
-pub use runtime::{FastRuntime, FastStartConfig};
+pub use runtime::{
+    BatchStartReport, CapacityExceeded, ContainerEvent, ContainerInfo, ContainerSpec,
+    ContainerState, EventKind, FastRuntime, FastStartConfig, HostCapacity, RestartPolicy,
+    RuntimeError,
+};
 
 use anyhow::Result;
-use tracing::info;
+use tracing::{info, Level};
+use tracing_subscriber::filter::{EnvFilter, LevelFilter};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::util::SubscriberInitExt;
 
-/// Initialize the Enviro runtime with zero-trust defaults
+/// Logging configuration applied by [`init_with_config`]
+#[derive(Debug, Clone)]
+pub struct LogConfig {
+    /// Most verbose level that is logged
+    pub level: Level,
+    /// Honor `RUST_LOG` directives, falling back to `level` when unset
+    pub use_env_filter: bool,
+    /// Emit newline-delimited JSON for log aggregation instead of text
+    pub json: bool,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            level: Level::INFO,
+            use_env_filter: false,
+            json: false,
+        }
+    }
+}
+
+/// Build the tracing subscriber described by `config`, writing to `writer`
+fn log_subscriber<W>(config: &LogConfig, writer: W) -> Box<dyn tracing::Subscriber + Send + Sync>
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let level = LevelFilter::from_level(config.level);
+    let filter = if config.use_env_filter {
+        EnvFilter::builder()
+            .with_default_directive(level.into())
+            .from_env_lossy()
+    } else {
+        EnvFilter::default().add_directive(level.into())
+    };
+
+    let builder = tracing_subscriber::fmt()
+        .with_writer(writer)
+        .with_env_filter(filter);
+    if config.json {
+        Box::new(builder.json().finish())
+    } else {
+        Box::new(builder.finish())
+    }
+}
+
+/// Initialize the Enviro runtime with zero-trust defaults, logging at INFO
+///
+/// Installs a tracing subscriber unless one is already set (for example by
+/// an application embedding Enviro), so calling this more than once is safe.
 pub async fn init() -> Result<()> {
-    // Initialize tracing subscriber
-    tracing_subscriber::fmt()
-        .with_max_level(tracing::Level::INFO)
-        .init();
-    
+    init_with_config(LogConfig::default()).await
+}
+
+/// Initialize the Enviro runtime with the given logging configuration
+///
+/// Like [`init`], an already-installed global subscriber is left in place.
+pub async fn init_with_config(config: LogConfig) -> Result<()> {
+    // Initialize tracing subscriber; an existing global subscriber wins.
+    // Logs go to stderr so they never mix with output on stdout, such as
+    // that of a container run in the foreground
+    let _ = log_subscriber(&config, std::io::stderr).try_init();
+
+    // Create the process-global metrics up front
+    perf::global();
+
     info!("Initializing Enviro Runtime v{}", env!("CARGO_PKG_VERSION"));
     info!("Rust Core: async orchestration with tokio");
     info!("Zig Bridge: high-speed syscall wrapping");
@@ -60,4 +131,87 @@ mod tests {
     async fn test_init() {
         assert!(init().await.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_init_twice() {
+        assert!(init().await.is_ok());
+        assert!(init().await.is_ok());
+    }
+
+    /// Run `f` under the subscriber for `config` and return what it logged
+    fn capture_logs(config: LogConfig, f: impl FnOnce()) -> String {
+        use std::sync::{Arc, Mutex};
+
+        struct Capture(Arc<Mutex<Vec<u8>>>);
+
+        impl std::io::Write for Capture {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let writer = {
+            let output = output.clone();
+            move || Capture(output.clone())
+        };
+        tracing::subscriber::with_default(log_subscriber(&config, writer), f);
+
+        let bytes = output.lock().unwrap().clone();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn test_default_log_config() {
+        let config = LogConfig::default();
+        assert_eq!(config.level, Level::INFO);
+        assert!(!config.use_env_filter);
+        assert!(!config.json);
+    }
+
+    #[test]
+    fn test_log_level_applied() {
+        let config = LogConfig {
+            level: Level::WARN,
+            ..LogConfig::default()
+        };
+        let logs = capture_logs(config, || {
+            tracing::info!("quiet info");
+            tracing::warn!("loud warning");
+        });
+        assert!(logs.contains("loud warning"));
+        assert!(!logs.contains("quiet info"));
+
+        let config = LogConfig {
+            level: Level::DEBUG,
+            ..LogConfig::default()
+        };
+        let logs = capture_logs(config, || tracing::debug!("debug detail"));
+        assert!(logs.contains("debug detail"));
+    }
+
+    #[test]
+    fn test_json_logs() {
+        let config = LogConfig {
+            json: true,
+            ..LogConfig::default()
+        };
+        let logs = capture_logs(config, || {
+            tracing::info!("structured");
+            tracing::debug!("filtered");
+        });
+
+        let lines: Vec<serde_json::Value> = logs
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0]["level"], "INFO");
+        assert_eq!(lines[0]["fields"]["message"], "structured");
+    }
 }
@@ -7,19 +7,53 @@
 //! - Python for developer SDK
 
 use anyhow::Result;
-use enviro_core::{init, Isolation};
-use tracing::info;
+use enviro_core::cli::{
+    format_containers, parse_args, self_benchmark, Cli, OutputFormat, ServerEndpoint, StatsReport,
+    STATS_BENCHMARK_STARTS,
+};
+use enviro_core::engine::{Envirofile, PortForwarder};
+use enviro_core::executor::Termination;
+use enviro_core::server::{Server, DEFAULT_ADDR};
+use enviro_core::{init, init_with_config, ContainerSpec, FastRuntime, Isolation, LogConfig};
+use std::io::Write;
+use tokio::signal::unix::{signal, Signal, SignalKind};
+use tracing::{info, warn, Level};
 
 fn print_help() {
     println!("enviro - Next-Generation Container Runtime v{}", env!("CARGO_PKG_VERSION"));
     println!();
     println!("USAGE:");
     println!("  enviro [OPTIONS]");
+    println!("  enviro run [RUN OPTIONS] <image> -- <command> [args...]");
+    println!("  enviro run -f <Envirofile>");
+    println!("  enviro ps [PS OPTIONS] [SERVER OPTIONS]");
+    println!("  enviro stop <id> [SERVER OPTIONS]");
+    println!("  enviro serve [SERVER OPTIONS]");
+    println!("  enviro stats [--format <format>]");
     println!();
     println!("OPTIONS:");
     println!("  -h, --help       Print this help message");
     println!("  -v, --version    Print version information");
     println!();
+    println!("RUN OPTIONS:");
+    println!("  -e, --env KEY=VALUE      Set an environment variable (repeatable)");
+    println!("  -w, --workdir <dir>      Working directory inside the container");
+    println!("  -f, --file <path>        Run the container described by a TOML/YAML Envirofile");
+    println!();
+    println!("PS OPTIONS:");
+    println!("  -a, --all                Include stopped containers");
+    println!("      --format <format>    Output format: table (default) or json");
+    println!();
+    println!("SERVER OPTIONS:");
+    println!("  ps and stop act on the containers of a running 'enviro serve'.");
+    println!("      --addr <host:port>   Address of the JSON-RPC server (default: {})", DEFAULT_ADDR);
+    println!("                           Serving on a non-loopback address requires --token-file");
+    println!("      --token-file <path>  Token clients must 'auth' with");
+    println!("      --socket <path>      Unix socket only this user can connect to (instead of --addr)");
+    println!();
+    println!("STATS OPTIONS:");
+    println!("      --format <format>    Output format: table (default) or json");
+    println!();
     println!("DESCRIPTION:");
     println!("  Enviro is a zero-trust, high-concurrency container runtime built with");
     println!("  Rust, Zig, Go, and Python for maximum performance and security.");
@@ -27,28 +61,133 @@ fn print_help() {
     println!("For more information, see https://github.com/Deployed-Labs/Envyro");
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    let args: Vec<String> = std::env::args().collect();
+/// Print the containers of the `enviro serve` at `endpoint`
+///
+/// Containers live in the serving process, so there is nothing to list
+/// without one.
+async fn list_containers(all: bool, format: OutputFormat, endpoint: ServerEndpoint) -> Result<()> {
+    let containers = endpoint.connect().await?.list().await?;
+    print!("{}", format_containers(&containers, all, format)?);
+    Ok(())
+}
 
-    for arg in &args[1..] {
-        match arg.as_str() {
-            "-h" | "--help" => {
-                print_help();
-                return Ok(());
-            }
-            "-v" | "--version" => {
-                println!("enviro {}", env!("CARGO_PKG_VERSION"));
-                return Ok(());
-            }
-            _ => {
-                eprintln!("error: unrecognized argument '{}'", arg);
-                eprintln!("Run 'enviro --help' for usage information.");
-                std::process::exit(1);
+/// Print performance metrics from a short self-benchmark
+async fn print_stats(format: OutputFormat) -> Result<()> {
+    let runtime = FastRuntime::new();
+    let snapshot = self_benchmark(&runtime, STATS_BENCHMARK_STARTS).await?;
+    match format {
+        OutputFormat::Table => {
+            snapshot.print_report();
+            println!("{}", snapshot.docker_comparison());
+        }
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&StatsReport::new(snapshot))?);
+        }
+    }
+    Ok(())
+}
+
+/// Exit code after the container was stopped by SIGINT/SIGTERM
+const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// SIGINT and SIGTERM, listened for together
+struct ShutdownSignals {
+    interrupt: Signal,
+    terminate: Signal,
+}
+
+impl ShutdownSignals {
+    fn install() -> Result<Self> {
+        Ok(Self {
+            interrupt: signal(SignalKind::interrupt())?,
+            terminate: signal(SignalKind::terminate())?,
+        })
+    }
+
+    /// Wait for the next SIGINT or SIGTERM
+    async fn recv(&mut self) {
+        tokio::select! {
+            _ = self.interrupt.recv() => {}
+            _ = self.terminate.recv() => {}
+        }
+    }
+}
+
+/// Run a container to completion and exit with its exit code
+///
+/// The command is spawned for real through
+/// [`FastRuntime::start_container_spec`]. A command killed by signal `n`
+/// exits with `128 + n`, as in a shell. SIGINT or SIGTERM stops the
+/// container with the runtime's grace period and exits with 130; a second
+/// signal kills it immediately.
+async fn run_container(spec: ContainerSpec) -> Result<()> {
+    // Only warnings, so the container's own stderr stays readable;
+    // RUST_LOG turns more on
+    init_with_config(LogConfig {
+        level: Level::WARN,
+        use_env_filter: true,
+        ..LogConfig::default()
+    })
+    .await?;
+
+    // Installed first so an early signal does not kill us outright
+    let mut signals = ShutdownSignals::install()?;
+    let runtime = FastRuntime::new();
+    let handle = runtime.start_container_spec(spec).await?;
+    let result = tokio::select! {
+        result = handle.wait() => result?,
+        _ = signals.recv() => {
+            eprintln!("Stopping container {}...", handle.id());
+            tokio::select! {
+                stopped = handle.stop() => stopped?,
+                _ = signals.recv() => {
+                    eprintln!("Killing container {}", handle.id());
+                    handle.kill().await?;
+                }
             }
+            std::process::exit(INTERRUPTED_EXIT_CODE);
+        }
+    };
+
+    print!("{}", result.stdout);
+    eprint!("{}", result.stderr);
+    std::io::stdout().flush()?;
+    std::process::exit(match result.termination {
+        Termination::Exited(code) => code,
+        Termination::Signaled(signal) => 128 + signal,
+    });
+}
+
+/// Serve the runtime over JSON-RPC until SIGINT/SIGTERM
+///
+/// Containers still running when the server exits are stopped.
+async fn serve(endpoint: ServerEndpoint) -> Result<()> {
+    init().await?;
+
+    let mut signals = ShutdownSignals::install()?;
+    let runtime = FastRuntime::new();
+    let server = match (&endpoint.socket, endpoint.token()?) {
+        (Some(socket), _) => Server::bind_unix(socket, runtime.clone()).await?,
+        (None, Some(token)) => {
+            Server::bind_with_token(endpoint.addr(), runtime.clone(), token).await?
         }
+        (None, None) => Server::bind(endpoint.addr(), runtime.clone()).await?,
+    };
+    match server.socket_path() {
+        Some(path) => println!("Listening on {}", path.display()),
+        None => println!("Listening on {}", server.local_addr()?),
+    }
+    std::io::stdout().flush()?;
+
+    tokio::select! {
+        result = server.run() => result?,
+        _ = signals.recv() => info!("Shutting down RPC server"),
     }
+    Ok(runtime.shutdown().await?)
+}
 
+/// Start the engine when no subcommand is given
+async fn run_engine() -> Result<()> {
     // Initialize the runtime
     init().await?;
 
@@ -60,8 +199,51 @@ async fn main() -> Result<()> {
     info!("Isolation manager initialized with zero-trust defaults");
     info!("Configuration: {:?}", isolation.config());
 
+    // Remove port forwards left behind by an engine that crashed
+    match PortForwarder::default().cleanup_stale().await {
+        Ok(0) => {}
+        Ok(removed) => info!("Removed {} stale port forward table(s)", removed),
+        Err(e) => warn!("Failed to clean up stale port forwards: {:#}", e),
+    }
+
     // In production, this would start the control plane and listen for requests
     info!("Run 'enviro --help' for usage information");
 
     Ok(())
 }
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let cli = match parse_args(&args) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            eprintln!("Run 'enviro --help' for usage information.");
+            std::process::exit(1);
+        }
+    };
+
+    match cli {
+        Cli::Engine => run_engine().await,
+        Cli::Run(spec) => run_container(*spec).await,
+        Cli::RunFile { path } => run_container(Envirofile::from_path(&path)?).await,
+        Cli::Ps {
+            all,
+            format,
+            endpoint,
+        } => list_containers(all, format, endpoint).await,
+        Cli::Stop { id, endpoint } => endpoint.connect().await?.stop(id).await,
+        Cli::Serve { endpoint } => serve(endpoint).await,
+        Cli::Stats { format } => print_stats(format).await,
+        Cli::Version => {
+            println!("enviro {}", env!("CARGO_PKG_VERSION"));
+            Ok(())
+        }
+        Cli::Help => {
+            print_help();
+            Ok(())
+        }
+    }
+}
@@ -11,6 +11,10 @@
 
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use tracing::debug;
+
+use crate::ffi;
+use crate::perf::PerfMetrics;
 
 /// Size categories for buffer pools (powers of 2 for efficient allocation)
 const POOL_SIZES: [usize; 6] = [
@@ -76,23 +80,26 @@ impl Drop for PooledBuffer {
 /// High-performance buffer pool with multiple size classes
 pub struct BufferPool {
     pools: Vec<Mutex<Vec<Vec<u8>>>>,
+    /// Optional metrics sink for allocation vs reuse counts
+    metrics: Option<Arc<PerfMetrics>>,
 }
 
 impl BufferPool {
     /// Create a new buffer pool with pre-allocated buffers
     pub fn new() -> Arc<Self> {
-        let mut pools = Vec::with_capacity(POOL_SIZES.len());
-        
-        for &size in &POOL_SIZES {
-            let mut pool = Vec::with_capacity(POOL_COUNT);
-            // Pre-allocate buffers
-            for _ in 0..POOL_COUNT {
-                pool.push(Vec::with_capacity(size));
-            }
-            pools.push(Mutex::new(pool));
-        }
-        
-        Arc::new(Self { pools })
+        Arc::new(Self::default())
+    }
+
+    /// Create a new buffer pool that records every `get_buffer` in `metrics`
+    ///
+    /// Buffers served from the pool (including the pre-allocated ones) count
+    /// as reuses; buffers allocated because a size class ran dry count as
+    /// allocations.
+    pub fn with_metrics(metrics: Arc<PerfMetrics>) -> Arc<Self> {
+        Arc::new(Self {
+            metrics: Some(metrics),
+            ..Self::default()
+        })
     }
 
     /// Get a buffer of at least the requested size
@@ -116,10 +123,17 @@ impl BufferPool {
         let actual_size = POOL_SIZES[size_class];
         
         // Try to get from pool
-        let data = {
-            let mut pool = self.pools[size_class].lock().await;
-            pool.pop().unwrap_or_else(|| Vec::with_capacity(actual_size))
-        };
+        let pooled = self.pools[size_class].lock().await.pop();
+        let reused = pooled.is_some();
+        let data = pooled.unwrap_or_else(|| Vec::with_capacity(actual_size));
+
+        if let Some(metrics) = &self.metrics {
+            if reused {
+                metrics.record_buffer_reuse();
+            } else {
+                metrics.record_buffer_allocation();
+            }
+        }
         
         PooledBuffer {
             data,
@@ -172,7 +186,10 @@ impl Default for BufferPool {
             pools.push(Mutex::new(pool));
         }
         
-        Self { pools }
+        Self {
+            pools,
+            metrics: None,
+        }
     }
 }
 
@@ -183,6 +200,40 @@ pub struct PoolStats {
     pub buffers_by_size: Vec<(usize, usize)>,
 }
 
+/// Allocation totals from the Zig allocator and the Rust buffer pool
+///
+/// The Zig fields are `None` when the Zig components were not built or the
+/// allocator could not be queried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize)]
+pub struct AllocatorStats {
+    /// Allocations made by the Zig allocator
+    pub zig_allocs: Option<u64>,
+    /// Frees made by the Zig allocator
+    pub zig_frees: Option<u64>,
+    /// Zig allocations not yet freed
+    pub zig_live: Option<u64>,
+    /// Buffers currently idle in the Rust [`BufferPool`]
+    pub rust_pool_buffers: usize,
+}
+
+/// Collect [`AllocatorStats`] from the Zig allocator and `pool`
+pub async fn collect_allocator_stats(pool: &BufferPool) -> AllocatorStats {
+    let (zig_allocs, zig_frees) = match ffi::get_allocator_stats() {
+        Ok((allocs, frees)) => (Some(allocs), Some(frees)),
+        Err(e) => {
+            debug!("Zig allocator stats unavailable: {}", e);
+            (None, None)
+        }
+    };
+
+    AllocatorStats {
+        zig_allocs,
+        zig_frees,
+        zig_live: zig_allocs.zip(zig_frees).map(|(allocs, frees)| allocs.saturating_sub(frees)),
+        rust_pool_buffers: pool.stats().await.total_buffers,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -231,6 +282,26 @@ mod tests {
         assert_eq!(buffer_oversized.capacity(), 16 * 1024 * 1024);
     }
 
+    #[tokio::test]
+    async fn test_metrics_wired_pool() {
+        let metrics = PerfMetrics::new();
+        let pool = BufferPool::with_metrics(metrics.clone());
+
+        // Drain the pre-allocated 4KB class: every one is a reuse
+        let mut held = Vec::new();
+        for _ in 0..POOL_COUNT {
+            held.push(pool.get_buffer(4096).await);
+        }
+        // The class is now empty, so the next two are fresh allocations
+        held.push(pool.get_buffer(4096).await);
+        held.push(pool.get_buffer(4096).await);
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.buffer_reuses, POOL_COUNT as u64);
+        assert_eq!(snapshot.buffer_allocations, 2);
+        assert!(snapshot.buffer_reuse_rate > 90.0);
+    }
+
     #[tokio::test]
     async fn test_buffer_reuse() {
         let pool = BufferPool::new();
@@ -255,4 +326,21 @@ mod tests {
         let stats_after = pool.stats().await;
         assert!(stats_after.total_buffers >= stats_before.total_buffers - 1);
     }
+
+    #[tokio::test]
+    async fn test_collect_allocator_stats() {
+        let pool = BufferPool::new();
+        let _held = pool.get_buffer(1024).await;
+
+        let stats = collect_allocator_stats(&pool).await;
+        assert_eq!(stats.rust_pool_buffers, POOL_COUNT * POOL_SIZES.len() - 1);
+        if cfg!(zig_available) {
+            assert_eq!(
+                stats.zig_live,
+                Some(stats.zig_allocs.unwrap() - stats.zig_frees.unwrap())
+            );
+        } else {
+            assert_eq!((stats.zig_allocs, stats.zig_frees, stats.zig_live), (None, None, None));
+        }
+    }
 }
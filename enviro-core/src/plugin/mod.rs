@@ -2,19 +2,67 @@
 //!
 //! This module implements hot-swappable plugin loading using libloading,
 //! allowing Zig, Go, or other language modules to be loaded at runtime
-//! without restarting the Enviro engine.
+//! without restarting the Enviro engine. WebAssembly modules (`.wasm`) are
+//! discovered alongside native libraries and run by a [`WasmExecutor`].
 //!
 //! # Performance Pattern: Lazy Loading
 //! Plugins are loaded on-demand and cached, minimizing memory footprint
 //! and startup time.
 
 use anyhow::{Context, Result};
+use async_trait::async_trait;
 use libloading::{Library, Symbol};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use rayon::prelude::*;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::os::fd::{AsRawFd, FromRawFd};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tokio::sync::Notify;
 use tracing::{debug, info, warn};
 
-use crate::executor::Executor;
+use crate::executor::wasm::validate_module;
+use crate::executor::{ExecutionContext, ExecutionResult, Executor, ExecutorKind, WasmExecutor};
+
+/// Version of the plugin ABI understood by this build of enviro-core
+///
+/// Plugins export it through `plugin_abi_version`; `load_plugin` refuses any
+/// library reporting a different value. Bump it whenever the `Executor`
+/// trait, `PluginInfo`, or the exported function signatures change.
+pub const PLUGIN_ABI_VERSION: u32 = 4;
+
+/// Suggested time to wait on a [`PluginDrain`] for outstanding executors
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Chunk size used when copying a plugin file for verification
+const COPY_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Semver of the enviro-core crate, recorded by plugins in `PluginInfo`
+pub const CORE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Longest `PluginInfo::name` accepted from a plugin, in bytes
+pub const MAX_PLUGIN_NAME_LEN: usize = 128;
+
+/// Longest `PluginInfo::version` and `core_version` accepted, in bytes
+pub const MAX_PLUGIN_VERSION_LEN: usize = 64;
+
+/// Longest `PluginInfo::author` accepted, in bytes
+pub const MAX_PLUGIN_AUTHOR_LEN: usize = 256;
+
+/// Longest `PluginInfo::description` accepted, in bytes
+pub const MAX_PLUGIN_DESCRIPTION_LEN: usize = 4096;
+
+/// How a plugin's executors are run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PluginKind {
+    /// Shared library loaded with dlopen
+    Native,
+    /// WebAssembly module run by a [`WasmExecutor`]
+    Wasm,
+}
 
 /// Plugin metadata loaded from the shared library
 #[derive(Debug, Clone)]
@@ -27,28 +75,46 @@ pub struct PluginInfo {
     pub author: String,
     /// Plugin description
     pub description: String,
+    /// enviro-core version the plugin was built against ([`CORE_VERSION`])
+    pub core_version: String,
+    /// Whether the plugin is native code or a WASM module
+    pub kind: PluginKind,
+    /// Hex SHA-256 of the plugin file, set by the registry when it was
+    /// loaded with [`PluginRegistry::load_plugin_verified`]
+    pub sha256: Option<String>,
 }
 
+/// Function signature for the plugin ABI version
+///
+/// Plain C types only, so it can be called before trusting anything else
+/// the library exports.
+type PluginAbiVersionFn = unsafe extern "C" fn() -> u32;
+
 /// Function signature for plugin initialization
 ///
 /// Each plugin must export an `init_plugin` function that returns
 /// a pointer to an Executor implementation.
+#[allow(improper_ctypes_definitions)]
 type InitPluginFn = unsafe extern "C" fn() -> *mut dyn Executor;
 
 /// Function signature for plugin metadata
+#[allow(improper_ctypes_definitions)]
 type GetPluginInfoFn = unsafe extern "C" fn() -> PluginInfo;
 
 /// Plugin manager for dynamic loading and hot-swapping
 ///
 /// # Safety Model:
+/// - Plugins must report [`PLUGIN_ABI_VERSION`] (checked on load)
 /// - Plugins must be compiled with the same Rust version
 /// - Plugins must implement the Executor trait correctly
 /// - Plugin loading is synchronized to prevent race conditions
 pub struct PluginRegistry {
-    /// Loaded libraries (kept alive to prevent symbol unloading)
-    libraries: HashMap<String, Library>,
+    /// Loaded plugins
+    plugins: HashMap<String, LoadedPlugin>,
     /// Plugin metadata
     info: HashMap<String, PluginInfo>,
+    /// Canonical file path of each loaded plugin, mapped to its name
+    loaded_paths: HashMap<PathBuf, String>,
     /// Plugin search paths
     search_paths: Vec<PathBuf>,
 }
@@ -57,22 +123,32 @@ impl PluginRegistry {
     /// Create a new plugin registry
     pub fn new() -> Self {
         Self {
-            libraries: HashMap::new(),
+            plugins: HashMap::new(),
             info: HashMap::new(),
+            loaded_paths: HashMap::new(),
             search_paths: vec![
-                PathBuf::from("./plugins"),
-                PathBuf::from("/usr/lib/enviro/plugins"),
-                PathBuf::from("/usr/local/lib/enviro/plugins"),
+                canonical_path(Path::new("./plugins")),
+                canonical_path(Path::new("/usr/lib/enviro/plugins")),
+                canonical_path(Path::new("/usr/local/lib/enviro/plugins")),
             ],
         }
     }
 
     /// Add a search path for plugins
+    ///
+    /// The path is canonicalized (resolving symlinks and relative
+    /// components) and ignored if it is already searched, so no directory
+    /// is scanned twice.
     pub fn add_search_path(&mut self, path: PathBuf) {
+        let path = canonical_path(&path);
+        if self.search_paths.contains(&path) {
+            debug!("Search path {:?} already registered, skipping", path);
+            return;
+        }
         self.search_paths.push(path);
     }
 
-    /// Load a plugin from a shared library
+    /// Load a plugin from a shared library or WASM module
     ///
     /// # Performance: Dynamic Linking Overhead
     /// - Initial load: ~5-10ms per plugin (dlopen + symbol resolution)
@@ -81,69 +157,212 @@ impl PluginRegistry {
     ///
     /// # Arguments:
     /// - `name`: Plugin identifier (e.g., "zig-executor")
-    /// - `path`: Path to the .so/.dylib/.dll file
+    /// - `path`: Path to the .so/.dylib/.dll or .wasm file
     ///
     /// # Returns:
     /// - `Ok(())` if plugin loaded successfully
     /// - `Err(_)` if loading failed
     pub fn load_plugin(&mut self, name: String, path: PathBuf) -> Result<()> {
+        self.load_plugin_with_digest(name, path, None)
+    }
+
+    /// Load a plugin only if its file has the given SHA-256
+    ///
+    /// The file is read once into a sealed in-memory copy while it is
+    /// hashed, and a native library is dlopened from that copy, so the
+    /// verified bytes are the ones loaded even if `path` is swapped
+    /// afterwards. Nothing is loaded on a mismatch. WASM modules are
+    /// validated from the copy but run by path, so for them the digest
+    /// covers the file as it was at load time. The verified digest is
+    /// recorded in the plugin's [`PluginInfo::sha256`]. `expected_sha256`
+    /// is 64 hex digits, optionally prefixed with `sha256:`.
+    ///
+    /// Unlike [`load_plugin`](Self::load_plugin), a plugin already loaded
+    /// under `name` is an error, since it was not checked against the
+    /// digest.
+    pub fn load_plugin_verified(
+        &mut self,
+        name: String,
+        path: PathBuf,
+        expected_sha256: &str,
+    ) -> Result<()> {
+        if self.plugins.contains_key(&name) {
+            anyhow::bail!("Plugin '{}' already loaded", name);
+        }
+
+        let expected = parse_sha256(expected_sha256)?;
+        let (copy, actual) = sealed_copy(&path)?;
+        if actual != expected {
+            anyhow::bail!(
+                "Plugin '{}' at {:?} has SHA-256 {}, expected {}",
+                name,
+                path,
+                actual,
+                expected
+            );
+        }
+        self.load_plugin_with_digest(name, path, Some((copy, actual)))
+    }
+
+    /// Load `name` from `path`, or from `verified`'s sealed copy of it
+    fn load_plugin_with_digest(
+        &mut self,
+        name: String,
+        path: PathBuf,
+        verified: Option<(File, String)>,
+    ) -> Result<()> {
         info!("Loading plugin '{}' from {:?}", name, path);
 
         // Check if already loaded
-        if self.libraries.contains_key(&name) {
+        if self.plugins.contains_key(&name) {
             warn!("Plugin '{}' already loaded, skipping", name);
             return Ok(());
         }
 
-        // Load the library
-        let lib = unsafe {
-            Library::new(&path)
-                .with_context(|| format!("Failed to load library from {:?}", path))?
-        };
+        let (copy, digest) = verified.unzip();
+        let (plugin, mut info) = open_plugin(&name, &path, copy)?;
+        info.sha256 = digest;
 
-        // Get plugin info
-        let get_info: Symbol<GetPluginInfoFn> = unsafe {
-            lib.get(b"get_plugin_info")
-                .context("Plugin missing 'get_plugin_info' export")?
-        };
+        // Store the plugin and info
+        self.loaded_paths.insert(canonical_path(&path), name.clone());
+        self.plugins.insert(name.clone(), plugin);
+        self.info.insert(name.clone(), info);
 
-        let info = unsafe { get_info() };
-        debug!("Loaded plugin: {} v{} by {}", info.name, info.version, info.author);
+        info!("Plugin '{}' loaded successfully", name);
+        Ok(())
+    }
 
-        // Verify the plugin exports init_plugin
-        let _init: Symbol<InitPluginFn> = unsafe {
-            lib.get(b"init_plugin")
-                .context("Plugin missing 'init_plugin' export")?
+    /// Instantiate an executor from a loaded plugin
+    ///
+    /// For native plugins, resolves the `init_plugin` export, calls it, and
+    /// takes ownership of the returned executor. WASM plugins get a fresh
+    /// [`WasmExecutor`] for their module.
+    ///
+    /// # Safety Invariants:
+    /// - `init_plugin` must return a pointer obtained from `Box::into_raw`
+    ///   on a `Box<dyn Executor>`, built with the same compiler and
+    ///   enviro-core version as the host (the trait object layout is not a
+    ///   stable ABI). Ownership passes to the caller.
+    /// - The returned executor holds a reference to the plugin's `Library`,
+    ///   so its code and vtable stay mapped until the last clone of the
+    ///   `Arc` is dropped, even if the plugin is unloaded in the meantime.
+    /// - The executor is dropped before the library reference it holds.
+    pub fn instantiate(&self, name: &str) -> Result<Arc<dyn Executor>> {
+        let native = match self.plugins.get(name) {
+            Some(LoadedPlugin::Native(native)) => native,
+            Some(LoadedPlugin::Wasm(module)) => {
+                return Ok(Arc::new(WasmExecutor::new(module.clone())));
+            }
+            None => anyhow::bail!("Plugin '{}' not loaded", name),
         };
 
-        // Store the library and info
-        self.libraries.insert(name.clone(), lib);
-        self.info.insert(name.clone(), info);
+        let raw = unsafe {
+            let init: Symbol<InitPluginFn> = native
+                .library
+                .get(b"init_plugin")
+                .context("Plugin missing 'init_plugin' export")?;
+            init()
+        };
 
-        info!("Plugin '{}' loaded successfully", name);
-        Ok(())
+        if raw.is_null() {
+            anyhow::bail!("Plugin '{}' returned a null executor", name);
+        }
+
+        // SAFETY: per the invariants above, `raw` came from `Box::into_raw`
+        // and we are its sole owner.
+        let executor = unsafe { Box::from_raw(raw) };
+        debug!("Instantiated '{}' executor from plugin '{}'", executor.executor_type(), name);
+
+        Ok(Arc::new(PluginExecutor {
+            executor,
+            _library: LibraryRef(Some(Arc::clone(native))),
+        }))
     }
 
     /// Unload a plugin
     ///
     /// # Safety:
-    /// This is safe because we drop the Library, which calls dlclose().
-    /// However, if any executor instances from this plugin are still alive,
-    /// this will cause undefined behavior. The caller must ensure all
-    /// executors are dropped before unloading.
+    /// This drops the registry's reference to the Library. Executors created
+    /// by [`instantiate`](Self::instantiate) hold their own reference, so
+    /// dlclose() only runs once the last of them is dropped.
     pub fn unload_plugin(&mut self, name: &str) -> Result<()> {
         info!("Unloading plugin '{}'", name);
 
-        self.libraries
+        self.plugins
             .remove(name)
             .context("Plugin not loaded")?;
 
         self.info.remove(name);
+        self.loaded_paths.retain(|_, loaded| loaded != name);
 
         info!("Plugin '{}' unloaded successfully", name);
         Ok(())
     }
 
+    /// Hot-swap a plugin with the library at `path`
+    ///
+    /// The replacement is loaded and validated first and then swapped in,
+    /// so from then on [`instantiate`](Self::instantiate) uses it. Executors
+    /// instantiated before the swap keep running the old library, which is
+    /// unloaded once the last of them drops; the returned [`PluginDrain`]
+    /// reports and awaits that. A native replacement is loaded from a
+    /// sealed in-memory copy, which gives new code even when `path` is the
+    /// file already loaded.
+    ///
+    /// # Errors:
+    /// - The plugin is not loaded
+    /// - The new library fails to load or validate; the old plugin stays
+    ///   loaded and usable
+    pub fn reload_plugin(&mut self, name: String, path: PathBuf) -> Result<PluginDrain> {
+        info!("Reloading plugin '{}' from {:?}", name, path);
+
+        if !self.plugins.contains_key(&name) {
+            anyhow::bail!("Plugin '{}' not loaded", name);
+        }
+
+        let copy = match is_wasm_module(&path) {
+            true => None,
+            false => Some(sealed_copy(&path)?.0),
+        };
+        let (plugin, info) = open_plugin(&name, &path, copy)?;
+
+        let old = self.plugins.insert(name.clone(), plugin);
+        self.info.insert(name.clone(), info);
+        self.loaded_paths.retain(|_, loaded| *loaded != name);
+        self.loaded_paths.insert(canonical_path(&path), name.clone());
+
+        let drain = match old {
+            Some(LoadedPlugin::Native(library)) => PluginDrain {
+                library: Arc::downgrade(&library),
+                released: Arc::clone(&library.released),
+                name,
+            },
+            // WASM executors only hold the module path; nothing to drain
+            _ => PluginDrain {
+                library: Weak::new(),
+                released: Arc::new(Notify::new()),
+                name,
+            },
+        };
+        info!(
+            "Plugin '{}' reloaded, {} executor(s) still on the old library",
+            drain.name,
+            drain.outstanding()
+        );
+        Ok(drain)
+    }
+
+    /// Number of live executors instantiated from a loaded native plugin
+    ///
+    /// Returns `None` for unknown plugins and for WASM plugins, whose
+    /// executors are not tracked.
+    pub fn executor_count(&self, name: &str) -> Option<usize> {
+        match self.plugins.get(name)? {
+            LoadedPlugin::Native(library) => Some(Arc::strong_count(library) - 1),
+            LoadedPlugin::Wasm(_) => None,
+        }
+    }
+
     /// Get information about a loaded plugin
     pub fn get_plugin_info(&self, name: &str) -> Option<&PluginInfo> {
         self.info.get(name)
@@ -151,53 +370,477 @@ impl PluginRegistry {
 
     /// List all loaded plugins
     pub fn list_plugins(&self) -> Vec<String> {
-        self.libraries.keys().cloned().collect()
+        self.plugins.keys().cloned().collect()
     }
 
     /// Auto-discover and load plugins from search paths
     ///
     /// # Performance: Parallel Discovery
-    /// Uses rayon (if available) to scan directories in parallel
+    /// Uses rayon to scan the search paths and open the candidate
+    /// libraries and WASM modules in parallel. Each worker returns its own results and the
+    /// registry is only updated after the join, so no locking is needed.
+    /// When two search paths provide the same plugin name, the earlier
+    /// search path wins and the shadowed file is never opened (so its
+    /// library constructors never run). A file already loaded under another
+    /// name (e.g. via a symlink) is skipped.
     pub fn discover_plugins(&mut self) -> Result<Vec<String>> {
-        let mut discovered = Vec::new();
+        let scanned = self
+            .search_paths
+            .par_iter()
+            .map(|search_path| scan_search_path(search_path))
+            .collect::<Result<Vec<_>>>()?;
 
-        // Clone search paths to avoid borrow issues
-        let search_paths = self.search_paths.clone();
-        
-        for search_path in &search_paths {
-            if !search_path.exists() {
-                debug!("Search path {:?} does not exist, skipping", search_path);
-                continue;
-            }
+        let candidates = self.select_candidates(scanned);
+
+        let opened: Vec<_> = candidates
+            .into_par_iter()
+            .map(|(name, path)| {
+                let result = open_plugin(&name, &path, None);
+                (name, path, result)
+            })
+            .collect();
 
-            debug!("Scanning {:?} for plugins", search_path);
-
-            let entries = std::fs::read_dir(search_path)
-                .with_context(|| format!("Failed to read directory {:?}", search_path))?;
-
-            for entry in entries {
-                let entry = entry?;
-                let path = entry.path();
-
-                // Check for shared library extensions
-                if let Some(ext) = path.extension() {
-                    if ext == "so" || ext == "dylib" || ext == "dll" {
-                        if let Some(stem) = path.file_stem() {
-                            let name = stem.to_string_lossy().to_string();
-                            
-                            match self.load_plugin(name.clone(), path) {
-                                Ok(_) => discovered.push(name),
-                                Err(e) => warn!("Failed to load plugin: {}", e),
-                            }
-                        }
-                    }
+        let mut discovered = Vec::new();
+        for (name, path, result) in opened {
+            match result {
+                Ok((plugin, info)) => {
+                    self.loaded_paths.insert(path, name.clone());
+                    self.plugins.insert(name.clone(), plugin);
+                    self.info.insert(name.clone(), info);
+                    discovered.push(name);
                 }
+                Err(e) => warn!("Failed to load plugin: {:#}", e),
             }
         }
 
         info!("Discovered {} plugins", discovered.len());
         Ok(discovered)
     }
+
+    /// Pick the files discovery should open from per-search-path scans, in
+    /// search path order
+    ///
+    /// Keeps the first file for each plugin name not already loaded, and
+    /// drops files already loaded under any name.
+    fn select_candidates(&self, scanned: Vec<Vec<(String, PathBuf)>>) -> Vec<(String, PathBuf)> {
+        let mut seen_paths: HashSet<PathBuf> = self.loaded_paths.keys().cloned().collect();
+        let mut seen_names = HashSet::new();
+        scanned
+            .into_iter()
+            .flatten()
+            .filter(|(name, path)| {
+                if self.plugins.contains_key(name) {
+                    return false;
+                }
+                if seen_names.contains(name) {
+                    warn!("Plugin '{}' at {:?} shadowed by an earlier search path", name, path);
+                    return false;
+                }
+                if !seen_paths.insert(path.clone()) {
+                    debug!("Plugin '{}' at {:?} already loaded under another name", name, path);
+                    return false;
+                }
+                seen_names.insert(name.clone());
+                true
+            })
+            .collect()
+    }
+}
+
+/// A plugin held by the registry
+enum LoadedPlugin {
+    /// dlopened library
+    ///
+    /// Shared with every executor instantiated from the plugin, so the
+    /// library outlives its executors even after `unload_plugin`.
+    Native(Arc<NativeLibrary>),
+    /// Path of a validated WASM module
+    Wasm(PathBuf),
+}
+
+/// A dlopened plugin library
+///
+/// Fields drop in declaration order, so a library loaded from a verified
+/// copy is closed before the copy's descriptor is.
+struct NativeLibrary {
+    library: Library,
+    /// Notified each time an executor releases its reference
+    released: Arc<Notify>,
+    /// Sealed copy the library was loaded from, kept open so its
+    /// `/proc/self/fd` path is not reused while the library is loaded
+    _copy: Option<File>,
+}
+
+/// Canonicalize `path`, falling back to the path as given when it cannot be
+/// resolved (e.g. a search path that does not exist yet)
+fn canonical_path(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Whether `path` names a WASM module rather than a shared library
+fn is_wasm_module(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "wasm")
+}
+
+/// Open a plugin of either kind
+///
+/// `path` is the plugin's file, which decides its kind and is what WASM
+/// executors run. Its bytes are read from `copy`, a verified sealed copy
+/// of it, when one is given.
+///
+/// Touches no registry state, so discovery can call it from many threads at
+/// once and merge the results afterwards.
+fn open_plugin(
+    name: &str,
+    path: &Path,
+    copy: Option<File>,
+) -> Result<(LoadedPlugin, PluginInfo)> {
+    let (copy, source) = match copy {
+        Some(copy) => {
+            let (copy, source) = unused_fd_path(copy)?;
+            (Some(copy), source)
+        }
+        None => (None, path.to_path_buf()),
+    };
+
+    if is_wasm_module(path) {
+        validate_module(&source)?;
+        return open_wasm_plugin(name, path);
+    }
+
+    let (library, info) = open_native_plugin(name, &source)?;
+    let native = NativeLibrary {
+        library,
+        released: Arc::new(Notify::new()),
+        _copy: copy,
+    };
+    Ok((LoadedPlugin::Native(Arc::new(native)), info))
+}
+
+/// `/proc/self/fd` path of `copy` that no loaded library is known by
+///
+/// The dynamic loader identifies libraries by the path they were opened
+/// with, so a library that stayed resident after its copy was closed (e.g.
+/// one marked NODELETE) would be returned in place of a new copy that got
+/// the same descriptor number. Such numbers are skipped by duplicating the
+/// descriptor until a free path turns up.
+fn unused_fd_path(copy: File) -> Result<(File, PathBuf)> {
+    let mut copy = copy;
+    let mut skipped = Vec::new();
+    loop {
+        let path = PathBuf::from(format!("/proc/self/fd/{}", copy.as_raw_fd()));
+        let flags = libc::RTLD_LAZY | libc::RTLD_NOLOAD;
+        // SAFETY: RTLD_NOLOAD only looks up an already loaded library and
+        // runs no initializers.
+        let resident = unsafe { libloading::os::unix::Library::open(Some(&path), flags) };
+        if resident.is_err() {
+            return Ok((copy, path));
+        }
+        let duplicate = copy.try_clone().context("Failed to duplicate plugin memfd")?;
+        skipped.push(std::mem::replace(&mut copy, duplicate));
+    }
+}
+
+/// Describe a validated WASM module as a plugin
+///
+/// Modules carry no metadata export, so the info is derived from the file.
+fn open_wasm_plugin(name: &str, path: &Path) -> Result<(LoadedPlugin, PluginInfo)> {
+    debug!("Loaded WASM plugin '{}' from {:?}", name, path);
+
+    let info = PluginInfo {
+        name: name.to_string(),
+        version: "unknown".to_string(),
+        author: "unknown".to_string(),
+        description: format!("WebAssembly module {}", path.display()),
+        core_version: CORE_VERSION.to_string(),
+        kind: PluginKind::Wasm,
+        sha256: None,
+    };
+    Ok((LoadedPlugin::Wasm(path.to_path_buf()), info))
+}
+
+/// dlopen a native plugin and validate its exports
+fn open_native_plugin(name: &str, path: &Path) -> Result<(Library, PluginInfo)> {
+    // Load the library
+    let lib = unsafe {
+        Library::new(path).with_context(|| format!("Failed to load library from {:?}", path))?
+    };
+
+    // Check the ABI version before calling anything that passes Rust types
+    let abi_version: Symbol<PluginAbiVersionFn> = unsafe {
+        lib.get(b"plugin_abi_version")
+            .context("Plugin missing 'plugin_abi_version' export")?
+    };
+
+    let abi_version = unsafe { abi_version() };
+    if abi_version != PLUGIN_ABI_VERSION {
+        anyhow::bail!(
+            "Plugin '{}' has ABI version {}, expected {}",
+            name,
+            abi_version,
+            PLUGIN_ABI_VERSION
+        );
+    }
+
+    // Get plugin info
+    let get_info: Symbol<GetPluginInfoFn> = unsafe {
+        lib.get(b"get_plugin_info")
+            .context("Plugin missing 'get_plugin_info' export")?
+    };
+
+    let info = read_plugin_info(name, *get_info)?;
+    debug!("Loaded plugin: {} v{} by {}", info.name, info.version, info.author);
+    if info.core_version != CORE_VERSION {
+        warn!(
+            "Plugin '{}' was built against enviro-core {}, running {}",
+            name, info.core_version, CORE_VERSION
+        );
+    }
+
+    // Verify the plugin exports init_plugin
+    unsafe {
+        lib.get::<InitPluginFn>(b"init_plugin")
+            .context("Plugin missing 'init_plugin' export")?;
+    }
+
+    Ok((lib, info))
+}
+
+/// Call a plugin's `get_plugin_info` and check the result before trusting it
+///
+/// A broken plugin can hand back strings that are huge, so each field's
+/// length is bounded before its contents are looked at, and control
+/// characters (which could forge log lines) are rejected. The fields
+/// arrive as `String`s, whose UTF-8 the plugin (built against the same
+/// ABI version) already guarantees, so they are not re-checked.
+fn read_plugin_info(name: &str, get_info: GetPluginInfoFn) -> Result<PluginInfo> {
+    let mut info = unsafe { get_info() };
+
+    let fields = [
+        ("name", &info.name, MAX_PLUGIN_NAME_LEN),
+        ("version", &info.version, MAX_PLUGIN_VERSION_LEN),
+        ("author", &info.author, MAX_PLUGIN_AUTHOR_LEN),
+        ("description", &info.description, MAX_PLUGIN_DESCRIPTION_LEN),
+        ("core_version", &info.core_version, MAX_PLUGIN_VERSION_LEN),
+    ];
+    for (field, value, max_len) in fields {
+        if value.len() > max_len {
+            anyhow::bail!(
+                "Plugin '{}' reported a {} of {} bytes, longer than {}",
+                name,
+                field,
+                value.len(),
+                max_len
+            );
+        }
+        if value.chars().any(char::is_control) {
+            anyhow::bail!("Plugin '{}' reported a {} with non-printable characters", name, field);
+        }
+    }
+
+    // The registry, not the plugin, decides how it was loaded and verified
+    info.kind = PluginKind::Native;
+    info.sha256 = None;
+    Ok(info)
+}
+
+/// Normalize an expected plugin digest to lowercase hex
+fn parse_sha256(digest: &str) -> Result<String> {
+    let hex = digest.strip_prefix("sha256:").unwrap_or(digest);
+    if hex.len() != 64 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        anyhow::bail!("Invalid SHA-256 digest '{}': expected 64 hex digits", digest);
+    }
+    Ok(hex.to_ascii_lowercase())
+}
+
+/// Copy the file at `path` into a sealed memfd, returning the copy and the
+/// hex SHA-256 of the bytes copied
+///
+/// The file is opened once and every byte hashed is a byte written to the
+/// copy, which can no longer be modified once sealed.
+fn sealed_copy(path: &Path) -> Result<(File, String)> {
+    let mut file =
+        File::open(path).with_context(|| format!("Failed to open plugin {:?} for hashing", path))?;
+
+    // SAFETY: the name is NUL-terminated and the flags are valid.
+    let fd = unsafe {
+        libc::memfd_create(c"enviro-plugin".as_ptr(), libc::MFD_CLOEXEC | libc::MFD_ALLOW_SEALING)
+    };
+    if fd < 0 {
+        return Err(std::io::Error::last_os_error()).context("Failed to create plugin memfd");
+    }
+    // SAFETY: memfd_create just returned this descriptor and nothing else owns it.
+    let mut copy = unsafe { File::from_raw_fd(fd) };
+
+    let mut hasher = Sha256::new();
+    let mut chunk = vec![0u8; COPY_CHUNK_SIZE];
+    loop {
+        let n = file
+            .read(&mut chunk)
+            .with_context(|| format!("Failed to read plugin {:?}", path))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&chunk[..n]);
+        copy.write_all(&chunk[..n]).context("Failed to copy plugin into memfd")?;
+    }
+
+    let seals = libc::F_SEAL_SEAL | libc::F_SEAL_SHRINK | libc::F_SEAL_GROW | libc::F_SEAL_WRITE;
+    // SAFETY: F_ADD_SEALS takes an integer argument and no pointers.
+    if unsafe { libc::fcntl(copy.as_raw_fd(), libc::F_ADD_SEALS, seals) } != 0 {
+        return Err(std::io::Error::last_os_error()).context("Failed to seal plugin memfd");
+    }
+
+    let digest = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+    Ok((copy, digest))
+}
+
+/// The library replaced by [`PluginRegistry::reload_plugin`]
+///
+/// Executors instantiated before the reload keep the old library mapped
+/// and unload it when the last of them is dropped, whether or not anyone
+/// waits here.
+pub struct PluginDrain {
+    name: String,
+    library: Weak<NativeLibrary>,
+    released: Arc<Notify>,
+}
+
+impl PluginDrain {
+    /// Executors still running the replaced library
+    pub fn outstanding(&self) -> usize {
+        self.library.strong_count()
+    }
+
+    /// Wait until every executor of the replaced library has been dropped
+    ///
+    /// Woken by each executor as it is dropped rather than by polling.
+    /// Timing out leaves the executors untouched.
+    pub async fn wait(&self, timeout: Duration) -> Result<()> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let released = self.released.notified();
+            tokio::pin!(released);
+            // Register before checking, so a drop in between is not missed
+            released.as_mut().enable();
+
+            let outstanding = self.outstanding();
+            if outstanding == 0 {
+                return Ok(());
+            }
+            debug!("Waiting for {} executor(s) of plugin '{}'", outstanding, self.name);
+            if tokio::time::timeout_at(deadline, released).await.is_err() {
+                anyhow::bail!(
+                    "Timed out after {:?} waiting for {} executor(s) of plugin '{}' to drop",
+                    timeout,
+                    self.outstanding(),
+                    self.name
+                );
+            }
+        }
+    }
+}
+
+/// List the shared libraries and WASM modules in one search path as
+/// `(name, path)` pairs
+///
+/// Paths are returned canonicalized. A missing directory yields no
+/// candidates rather than an error.
+fn scan_search_path(search_path: &Path) -> Result<Vec<(String, PathBuf)>> {
+    if !search_path.exists() {
+        debug!("Search path {:?} does not exist, skipping", search_path);
+        return Ok(Vec::new());
+    }
+
+    debug!("Scanning {:?} for plugins", search_path);
+
+    let entries = std::fs::read_dir(search_path)
+        .with_context(|| format!("Failed to read directory {:?}", search_path))?;
+
+    let mut candidates = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+
+        // Check for shared library and WASM module extensions
+        let is_library = path
+            .extension()
+            .is_some_and(|ext| ext == "so" || ext == "dylib" || ext == "dll" || ext == "wasm");
+        if !is_library {
+            continue;
+        }
+
+        if let Some(stem) = path.file_stem() {
+            candidates.push((stem.to_string_lossy().to_string(), canonical_path(&path)));
+        }
+    }
+
+    Ok(candidates)
+}
+
+/// Executor instantiated from a plugin
+///
+/// Fields drop in declaration order, so the executor (whose drop glue and
+/// vtable live inside the library) is always dropped before the library
+/// reference that keeps it mapped.
+struct PluginExecutor {
+    executor: Box<dyn Executor>,
+    _library: LibraryRef,
+}
+
+/// One executor's reference to its plugin library
+///
+/// Releasing it wakes any [`PluginDrain`] waiting for the library, after
+/// the reference count has already dropped.
+struct LibraryRef(Option<Arc<NativeLibrary>>);
+
+impl Drop for LibraryRef {
+    fn drop(&mut self) {
+        if let Some(library) = self.0.take() {
+            let released = Arc::clone(&library.released);
+            drop(library);
+            released.notify_waiters();
+        }
+    }
+}
+
+#[async_trait]
+impl Executor for PluginExecutor {
+    async fn prepare(&mut self, ctx: &ExecutionContext) -> Result<()> {
+        self.executor.prepare(ctx).await
+    }
+
+    async fn execute(
+        &self,
+        ctx: &ExecutionContext,
+        command: &str,
+        args: &[String],
+    ) -> Result<ExecutionResult> {
+        self.executor.execute(ctx, command, args).await
+    }
+
+    async fn cleanup(&mut self, ctx: &ExecutionContext) -> Result<()> {
+        self.executor.cleanup(ctx).await
+    }
+
+    fn executor_type(&self) -> &str {
+        self.executor.executor_type()
+    }
+
+    fn kind(&self) -> ExecutorKind {
+        self.executor.kind()
+    }
+
+    fn supports_checkpoint(&self) -> bool {
+        self.executor.supports_checkpoint()
+    }
+
+    async fn checkpoint(&self, ctx: &ExecutionContext, path: &str) -> Result<()> {
+        self.executor.checkpoint(ctx, path).await
+    }
+
+    async fn restore(&mut self, ctx: &ExecutionContext, path: &str) -> Result<()> {
+        self.executor.restore(ctx, path).await
+    }
 }
 
 impl Default for PluginRegistry {
@@ -223,6 +866,362 @@ mod tests {
         assert!(registry.search_paths.contains(&PathBuf::from("/custom/path")));
     }
 
-    // Note: Actual plugin loading tests require compiled plugins
-    // These should be integration tests that run after the build
+    #[test]
+    fn test_search_path_deduplicated() {
+        let dir = tempfile::tempdir().unwrap();
+        let plugins = dir.path().join("plugins");
+        std::fs::create_dir(&plugins).unwrap();
+        std::fs::write(plugins.join("hello.wasm"), EMPTY_WASM_MODULE).unwrap();
+        std::os::unix::fs::symlink(&plugins, dir.path().join("link")).unwrap();
+
+        let mut registry = PluginRegistry::new();
+        let before = registry.search_paths.len();
+        registry.add_search_path(plugins.clone());
+        registry.add_search_path(dir.path().join("plugins/../plugins"));
+        registry.add_search_path(dir.path().join("link"));
+
+        assert_eq!(registry.search_paths.len(), before + 1);
+        assert_eq!(registry.discover_plugins().unwrap(), vec!["hello".to_string()]);
+    }
+
+    #[test]
+    fn test_discover_skips_aliased_plugin() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("hello.wasm"), EMPTY_WASM_MODULE).unwrap();
+        std::os::unix::fs::symlink(dir.path().join("hello.wasm"), dir.path().join("alias.wasm"))
+            .unwrap();
+
+        let mut registry = PluginRegistry::new();
+        registry.add_search_path(dir.path().to_path_buf());
+
+        // Only one of the two names is loaded, depending on directory order
+        assert_eq!(registry.discover_plugins().unwrap().len(), 1);
+        assert_eq!(registry.list_plugins().len(), 1);
+    }
+
+    #[test]
+    fn test_discover_skips_path_loaded_by_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let module = dir.path().join("hello.wasm");
+        std::fs::write(&module, EMPTY_WASM_MODULE).unwrap();
+
+        let mut registry = PluginRegistry::new();
+        registry.load_plugin("greeter".to_string(), module).unwrap();
+        registry.add_search_path(dir.path().to_path_buf());
+
+        assert!(registry.discover_plugins().unwrap().is_empty());
+        assert_eq!(registry.list_plugins(), vec!["greeter".to_string()]);
+
+        // Unloading frees the path for discovery again
+        registry.unload_plugin("greeter").unwrap();
+        assert_eq!(registry.discover_plugins().unwrap(), vec!["hello".to_string()]);
+    }
+
+    #[test]
+    fn test_discover_opens_only_first_plugin_per_name() {
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        std::fs::write(first.path().join("hello.wasm"), EMPTY_WASM_MODULE).unwrap();
+        std::fs::write(second.path().join("hello.so"), b"not an elf").unwrap();
+        std::fs::write(second.path().join("other.wasm"), EMPTY_WASM_MODULE).unwrap();
+
+        let mut registry = PluginRegistry::new();
+        registry.add_search_path(first.path().to_path_buf());
+        registry.add_search_path(second.path().to_path_buf());
+        let scanned = [first.path(), second.path()]
+            .map(|dir| scan_search_path(&canonical_path(dir)).unwrap())
+            .to_vec();
+
+        let names: Vec<_> = registry
+            .select_candidates(scanned)
+            .into_iter()
+            .map(|(name, path)| (name, path.extension().unwrap().to_os_string()))
+            .collect();
+        assert_eq!(
+            names,
+            [("hello".to_string(), "wasm".into()), ("other".to_string(), "wasm".into())]
+        );
+
+        let mut discovered = registry.discover_plugins().unwrap();
+        discovered.sort();
+        assert_eq!(discovered, ["hello", "other"]);
+        assert_eq!(registry.get_plugin_info("hello").unwrap().kind, PluginKind::Wasm);
+    }
+
+    #[test]
+    fn test_instantiate_unknown_plugin() {
+        let registry = PluginRegistry::new();
+        let err = registry.instantiate("missing").err().unwrap();
+        assert!(err.to_string().contains("not loaded"));
+    }
+
+    #[test]
+    fn test_discover_skips_invalid_libraries() {
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..16 {
+            std::fs::write(dir.path().join(format!("dummy{}.so", i)), b"not an elf").unwrap();
+        }
+        std::fs::write(dir.path().join("README.txt"), b"ignored").unwrap();
+
+        let mut registry = PluginRegistry::new();
+        registry.add_search_path(dir.path().to_path_buf());
+
+        assert!(registry.discover_plugins().unwrap().is_empty());
+        assert!(registry.list_plugins().is_empty());
+    }
+
+    /// Register an arbitrary shared library under `name`, bypassing the
+    /// plugin export checks; enough to exercise the reference counting.
+    fn register_raw_library(registry: &mut PluginRegistry, name: &str) -> LibraryRef {
+        let library = Arc::new(NativeLibrary {
+            library: unsafe { Library::new("libc.so.6").unwrap() },
+            released: Arc::new(Notify::new()),
+            _copy: None,
+        });
+        registry
+            .plugins
+            .insert(name.to_string(), LoadedPlugin::Native(Arc::clone(&library)));
+        LibraryRef(Some(library))
+    }
+
+    #[test]
+    fn test_reload_unknown_plugin() {
+        let mut registry = PluginRegistry::new();
+        let err = registry
+            .reload_plugin("missing".to_string(), PathBuf::from("/nonexistent.so"))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("not loaded"));
+    }
+
+    #[test]
+    fn test_failed_reload_keeps_old_plugin() {
+        let mut registry = PluginRegistry::new();
+        let _outstanding = register_raw_library(&mut registry, "busy");
+
+        let err = registry
+            .reload_plugin("busy".to_string(), PathBuf::from("/nonexistent.so"))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("Failed to open plugin"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let broken = dir.path().join("broken.wasm");
+        std::fs::write(&broken, b"garbage").unwrap();
+        assert!(registry.reload_plugin("busy".to_string(), broken).is_err());
+
+        // The old plugin is left in place, outstanding executor and all
+        assert_eq!(registry.list_plugins(), vec!["busy".to_string()]);
+        assert_eq!(registry.executor_count("busy"), Some(1));
+    }
+
+    /// Registry with a raw library under "busy" that has one outstanding
+    /// executor, reloaded with a WASM module
+    fn reload_busy_plugin(dir: &Path) -> (PluginRegistry, LibraryRef, PluginDrain) {
+        let mut registry = PluginRegistry::new();
+        let outstanding = register_raw_library(&mut registry, "busy");
+        let module = dir.join("busy.wasm");
+        std::fs::write(&module, EMPTY_WASM_MODULE).unwrap();
+
+        let drain = registry.reload_plugin("busy".to_string(), module).unwrap();
+        (registry, outstanding, drain)
+    }
+
+    #[tokio::test]
+    async fn test_reload_swaps_before_draining() {
+        let dir = tempfile::tempdir().unwrap();
+        let (registry, outstanding, drain) = reload_busy_plugin(dir.path());
+
+        // Swapped in immediately, with the old executor still running
+        assert_eq!(registry.get_plugin_info("busy").unwrap().kind, PluginKind::Wasm);
+        assert_eq!(registry.instantiate("busy").unwrap().executor_type(), "wasm");
+        assert_eq!(drain.outstanding(), 1);
+
+        let release = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            drop(outstanding);
+        });
+        let start = tokio::time::Instant::now();
+        drain.wait(Duration::from_secs(5)).await.unwrap();
+        release.await.unwrap();
+
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        assert_eq!(drain.outstanding(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_drain_times_out_with_outstanding_executor() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_registry, outstanding, drain) = reload_busy_plugin(dir.path());
+
+        let err = drain.wait(Duration::from_millis(50)).await.unwrap_err();
+        assert!(err.to_string().contains("Timed out"));
+        assert_eq!(drain.outstanding(), 1);
+
+        drop(outstanding);
+        drain.wait(Duration::from_millis(50)).await.unwrap();
+    }
+
+    const EMPTY_WASM_MODULE: &[u8] = b"\0asm\x01\0\0\0";
+
+    #[test]
+    fn test_discover_wasm_module() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("hello.wasm"), EMPTY_WASM_MODULE).unwrap();
+        std::fs::write(dir.path().join("broken.wasm"), b"garbage").unwrap();
+
+        let mut registry = PluginRegistry::new();
+        registry.add_search_path(dir.path().to_path_buf());
+
+        assert_eq!(registry.discover_plugins().unwrap(), vec!["hello".to_string()]);
+        let info = registry.get_plugin_info("hello").unwrap();
+        assert_eq!(info.kind, PluginKind::Wasm);
+        assert_eq!(registry.executor_count("hello"), None);
+
+        let executor = registry.instantiate("hello").unwrap();
+        assert_eq!(executor.executor_type(), "wasm");
+        assert_eq!(executor.kind(), ExecutorKind::Wasm);
+    }
+
+    fn sample_info() -> PluginInfo {
+        PluginInfo {
+            name: "mock".to_string(),
+            version: "1.0.0".to_string(),
+            author: "Enviro Contributors".to_string(),
+            description: "Mock plugin".to_string(),
+            core_version: CORE_VERSION.to_string(),
+            kind: PluginKind::Wasm,
+            sha256: Some("0".repeat(64)),
+        }
+    }
+
+    #[allow(improper_ctypes_definitions)]
+    extern "C" fn mock_plugin_info() -> PluginInfo {
+        sample_info()
+    }
+
+    #[allow(improper_ctypes_definitions)]
+    extern "C" fn mock_long_name_info() -> PluginInfo {
+        PluginInfo {
+            name: "x".repeat(MAX_PLUGIN_NAME_LEN + 1),
+            ..sample_info()
+        }
+    }
+
+    #[allow(improper_ctypes_definitions)]
+    extern "C" fn mock_control_char_info() -> PluginInfo {
+        PluginInfo {
+            description: "fine\n[ERROR] forged log line".to_string(),
+            ..sample_info()
+        }
+    }
+
+    #[test]
+    fn test_read_plugin_info_accepts_valid_metadata() {
+        let info = read_plugin_info("mock", mock_plugin_info).unwrap();
+        assert_eq!(info.name, "mock");
+        assert_eq!(info.kind, PluginKind::Native);
+        assert_eq!(info.sha256, None);
+    }
+
+    #[test]
+    fn test_read_plugin_info_rejects_bad_metadata() {
+        let err = read_plugin_info("mock", mock_long_name_info).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Plugin 'mock' reported a name of {} bytes, longer than {}",
+                MAX_PLUGIN_NAME_LEN + 1,
+                MAX_PLUGIN_NAME_LEN
+            )
+        );
+
+        let err = read_plugin_info("mock", mock_control_char_info).unwrap_err();
+        assert!(err.to_string().contains("description with non-printable characters"));
+    }
+
+    /// SHA-256 of [`EMPTY_WASM_MODULE`]
+    const EMPTY_WASM_MODULE_SHA256: &str =
+        "93a44bbb96c751218e4c00d479e4c14358122a389acca16205b1e4d0dc5f9476";
+
+    #[test]
+    fn test_load_plugin_verified() {
+        let dir = tempfile::tempdir().unwrap();
+        let module = dir.path().join("hello.wasm");
+        std::fs::write(&module, EMPTY_WASM_MODULE).unwrap();
+
+        let mut registry = PluginRegistry::new();
+        let digest = format!("sha256:{}", EMPTY_WASM_MODULE_SHA256.to_uppercase());
+        registry.load_plugin_verified("hello".to_string(), module.clone(), &digest).unwrap();
+
+        let info = registry.get_plugin_info("hello").unwrap();
+        assert_eq!(info.sha256.as_deref(), Some(EMPTY_WASM_MODULE_SHA256));
+
+        // Already loaded, and so not verified against this call's digest
+        let err = registry
+            .load_plugin_verified("hello".to_string(), module, EMPTY_WASM_MODULE_SHA256)
+            .unwrap_err();
+        assert!(err.to_string().contains("already loaded"));
+    }
+
+    #[test]
+    fn test_load_plugin_verified_rejects_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let module = dir.path().join("hello.wasm");
+        std::fs::write(&module, EMPTY_WASM_MODULE).unwrap();
+
+        let mut registry = PluginRegistry::new();
+        let err = registry
+            .load_plugin_verified("hello".to_string(), module.clone(), &"0".repeat(64))
+            .unwrap_err();
+        assert!(err.to_string().contains(&format!("has SHA-256 {}", EMPTY_WASM_MODULE_SHA256)));
+        assert!(registry.list_plugins().is_empty());
+
+        let err = registry
+            .load_plugin_verified("hello".to_string(), module.clone(), "abc")
+            .unwrap_err();
+        assert!(err.to_string().contains("expected 64 hex digits"));
+
+        // Plain loads record no digest
+        registry.load_plugin("hello".to_string(), module).unwrap();
+        assert_eq!(registry.get_plugin_info("hello").unwrap().sha256, None);
+    }
+
+    #[test]
+    fn test_sealed_copy_is_independent_of_original() {
+        use std::io::Seek;
+
+        let dir = tempfile::tempdir().unwrap();
+        let module = dir.path().join("hello.wasm");
+        std::fs::write(&module, EMPTY_WASM_MODULE).unwrap();
+
+        let (mut copy, digest) = sealed_copy(&module).unwrap();
+        assert_eq!(digest, EMPTY_WASM_MODULE_SHA256);
+
+        // Replacing the original after hashing does not change the copy
+        std::fs::write(&module, b"swapped").unwrap();
+        let mut bytes = Vec::new();
+        copy.seek(std::io::SeekFrom::Start(0)).unwrap();
+        copy.read_to_end(&mut bytes).unwrap();
+        assert_eq!(bytes, EMPTY_WASM_MODULE);
+
+        // and the copy itself can no longer be written
+        assert!(copy.write_all(b"more").is_err());
+        let source = PathBuf::from(format!("/proc/self/fd/{}", copy.as_raw_fd()));
+        assert!(validate_module(&source).is_ok());
+    }
+
+    #[test]
+    fn test_unused_fd_path_uses_copy_descriptor() {
+        let dir = tempfile::tempdir().unwrap();
+        let module = dir.path().join("hello.wasm");
+        std::fs::write(&module, EMPTY_WASM_MODULE).unwrap();
+
+        let (copy, _) = sealed_copy(&module).unwrap();
+        let (copy, path) = unused_fd_path(copy).unwrap();
+        assert_eq!(path, PathBuf::from(format!("/proc/self/fd/{}", copy.as_raw_fd())));
+    }
+
+    // Note: Actual plugin loading tests require compiled plugins; see
+    // tests/plugin_loading.rs (run with `--features sample-plugin`)
 }
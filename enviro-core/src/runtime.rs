@@ -10,24 +10,63 @@
 //! - Zero-copy image mounting
 //! - Pre-warmed executor pools
 
-use crate::engine::Isolation;
-use crate::executor::{ExecutionContext, ResourceLimits, NetworkConfig};
+use crate::engine::cgroup::{Cgroup, CgroupUsage};
+use crate::engine::id_generator::{IdGenerator, UuidGenerator};
+use crate::engine::io_uring::blocking_read_at;
+use crate::engine::parallel_setup::NamespaceSetupConfig;
+use crate::engine::resource_limits::{ResourceKind, ResourceLimitBatch, CPU_MAX_PERIOD_MICROS};
+use crate::engine::{
+    IoUringConfig, IoUringManager, Isolation, ParallelNamespaceSetup, ParallelSetupReport,
+    PortForwarder, ResourceProfile,
+};
+use crate::executor::{
+    ExecutionContext, ExecutionResult, Executor, NativeExecutor, NetworkConfig, ResourceLimits,
+    Termination,
+};
 use crate::memory::BufferPool;
-use crate::perf::{PerfMetrics, ScopedTimer, TimerType};
+use crate::perf::{PerfMetrics, ScopedTimer, StopFailureKind, TimerType};
 use anyhow::{Context, Result};
+use nix::errno::Errno;
+use nix::sys::signal::Signal;
+use nix::unistd::Pid;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{broadcast, Mutex, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
 
 /// Configuration for fast container startup
 #[derive(Debug, Clone)]
 pub struct FastStartConfig {
     /// Enable parallel namespace creation
     pub parallel_namespaces: bool,
+    /// Which namespaces each new container namespace sets up
+    pub namespaces: NamespaceSetupConfig,
     /// Use cached namespace templates
     pub use_namespace_cache: bool,
     /// Pre-warm executor pool
     pub prewarm_executors: bool,
+    /// Executors kept prepared when `prewarm_executors` is set
+    pub prewarm_count: usize,
+    /// Delay before the first restart under a [`RestartPolicy`]; doubles
+    /// with each further restart up to [`MAX_RESTART_BACKOFF`]
+    pub restart_backoff: Duration,
+    /// Host resources shared by running containers; unlimited when `None`
+    pub host_capacity: Option<HostCapacity>,
+    /// Time a stopped container's command gets to exit after SIGTERM
+    /// before it is killed
+    pub stop_grace_period: Duration,
+    /// Directory each container's output is appended to as `<id>.log`;
+    /// output is kept in memory when `None`
+    pub log_dir: Option<PathBuf>,
+    /// cgroup v2 hierarchy each container gets an `enviro/<id>` cgroup in,
+    /// holding its [`ResourceLimits`]; limits are not enforced when `None`
+    pub cgroup_root: Option<PathBuf>,
     /// Maximum cached namespaces
     pub max_cached_namespaces: usize,
 }
@@ -36,13 +75,310 @@ impl Default for FastStartConfig {
     fn default() -> Self {
         Self {
             parallel_namespaces: true,
+            namespaces: NamespaceSetupConfig::default(),
             use_namespace_cache: true,
             prewarm_executors: true,
+            prewarm_count: 4,
+            restart_backoff: Duration::from_millis(100),
+            host_capacity: None,
+            stop_grace_period: Duration::from_secs(10),
+            log_dir: None,
+            cgroup_root: None,
             max_cached_namespaces: 10,
         }
     }
 }
 
+/// Total resources containers may reserve on this host
+///
+/// Each container reserves its CPU and memory limits from start until it is
+/// stopped or waited on.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HostCapacity {
+    /// CPU cores shared by all running containers
+    pub total_cpu_cores: f64,
+    /// Memory shared by all running containers
+    pub total_memory_bytes: u64,
+}
+
+/// A start rejected because it would oversubscribe [`HostCapacity`]
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error(
+    "Host capacity exceeded starting {container_id}: requested {requested_cpu_cores} CPU cores \
+     and {requested_memory_bytes} bytes of memory, but only {available_cpu_cores} cores and \
+     {available_memory_bytes} bytes are free"
+)]
+pub struct CapacityExceeded {
+    /// Container that was rejected
+    pub container_id: String,
+    /// CPU cores the container asked for
+    pub requested_cpu_cores: f64,
+    /// Memory the container asked for
+    pub requested_memory_bytes: u64,
+    /// CPU cores not reserved by running containers
+    pub available_cpu_cores: f64,
+    /// Memory not reserved by running containers
+    pub available_memory_bytes: u64,
+}
+
+/// Errors returned by the public [`FastRuntime`] API
+///
+/// Internal steps still use `anyhow`; their errors are kept as the source
+/// of the variant for the step that failed.
+#[derive(Debug, thiserror::Error)]
+pub enum RuntimeError {
+    /// A container with this ID is already running
+    #[error("Container {0} already exists")]
+    DuplicateContainer(String),
+
+    /// No container with this ID is tracked
+    #[error("No such container: {0}")]
+    NotFound(String),
+
+    /// The container has already exited or been stopped
+    #[error("Container {id} is not running ({state})")]
+    NotRunning { id: String, state: ContainerState },
+
+    /// Starting the container would oversubscribe the host
+    #[error(transparent)]
+    CapacityExceeded(#[from] CapacityExceeded),
+
+    /// Namespace setup failed
+    #[error(transparent)]
+    Namespace(anyhow::Error),
+
+    /// The executor could not be prepared for the container
+    #[error("Failed to prepare executor: {0:#}")]
+    Executor(anyhow::Error),
+
+    /// The container's network (port forwards) could not be set up
+    #[error("Failed to set up container network: {0:#}")]
+    Network(anyhow::Error),
+
+    /// The stop signal could not be delivered to the container's command
+    #[error(transparent)]
+    Signal(anyhow::Error),
+
+    /// The container's log file under [`FastStartConfig::log_dir`] could not
+    /// be created
+    #[error("Failed to create container log: {0:#}")]
+    Log(anyhow::Error),
+
+    /// The container's cgroup under [`FastStartConfig::cgroup_root`] could
+    /// not be created or limited
+    #[error("Failed to apply resource limits: {0:#}")]
+    Limits(anyhow::Error),
+}
+
+/// CPU and memory held by a running container
+#[derive(Debug, Clone, Copy)]
+struct Reservation {
+    cpu_cores: f64,
+    memory_bytes: u64,
+}
+
+/// Description of a container workload to launch
+///
+/// Image unpacking is not wired up yet: `image` is recorded for the
+/// container, and the command runs against the host filesystem.
+#[derive(Debug, Clone)]
+pub struct ContainerSpec {
+    /// Unique container ID; when empty the runtime mints one with its
+    /// [`IdGenerator`]
+    pub id: String,
+    /// Image the container is started from
+    pub image: String,
+    /// Command to run
+    pub command: String,
+    /// Command arguments
+    pub args: Vec<String>,
+    /// Environment variables
+    pub env: HashMap<String, String>,
+    /// Working directory
+    pub workdir: String,
+    /// Resource profile; the runtime's default limits apply when `None`
+    pub profile: Option<ResourceProfile>,
+    /// Network settings; the runtime's default network applies when `None`
+    pub network: Option<NetworkConfig>,
+    /// Whether the command is run again after it exits
+    pub restart: RestartPolicy,
+}
+
+impl ContainerSpec {
+    /// Create a spec running `command` from `image`
+    ///
+    /// The ID is left empty, so the runtime that starts the spec assigns one
+    /// with its [`IdGenerator`].
+    pub fn new(image: impl Into<String>, command: impl Into<String>, args: Vec<String>) -> Self {
+        Self {
+            id: String::new(),
+            image: image.into(),
+            command: command.into(),
+            args,
+            env: HashMap::new(),
+            workdir: "/".to_string(),
+            profile: None,
+            network: None,
+            restart: RestartPolicy::No,
+        }
+    }
+}
+
+/// Upper bound on the delay between restarts
+pub const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(30);
+
+/// When the runtime re-launches a container whose command has exited
+///
+/// Restarts reuse the container's namespace, executor and log, and each
+/// one waits out an exponential backoff starting at
+/// [`FastStartConfig::restart_backoff`]. A stopped container is never
+/// restarted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "policy")]
+pub enum RestartPolicy {
+    /// Never restart
+    #[default]
+    No,
+    /// Restart after a non-zero exit, at most `max_retries` times
+    OnFailure { max_retries: u32 },
+    /// Restart after every exit until the container is stopped
+    Always,
+}
+
+impl RestartPolicy {
+    /// Whether to restart a command that exited with `exit_code` after
+    /// `restarts` earlier restarts
+    ///
+    /// A command that could not be run at all counts as a failure.
+    pub fn should_restart(&self, exit_code: i32, restarts: u32) -> bool {
+        match *self {
+            Self::No => false,
+            Self::OnFailure { max_retries } => exit_code != 0 && restarts < max_retries,
+            Self::Always => true,
+        }
+    }
+}
+
+/// Delay before restart number `restarts + 1`
+fn restart_backoff(base: Duration, restarts: u32) -> Duration {
+    base.saturating_mul(1 << restarts.min(16))
+        .min(MAX_RESTART_BACKOFF)
+}
+
+/// Lifecycle state of a container tracked by the runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase", tag = "status")]
+pub enum ContainerState {
+    /// Started and not yet exited or stopped
+    Running,
+    /// The container's command exited with `code`
+    Exited { code: i32 },
+    /// Stopped through [`ContainerHandle::stop`]
+    Stopped,
+}
+
+impl ContainerState {
+    /// Whether the container is still running
+    pub fn is_running(&self) -> bool {
+        matches!(self, Self::Running)
+    }
+}
+
+impl fmt::Display for ContainerState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Running => write!(f, "running"),
+            Self::Exited { code } => write!(f, "exited ({})", code),
+            Self::Stopped => write!(f, "stopped"),
+        }
+    }
+}
+
+/// Snapshot of a container returned by [`FastRuntime::list_containers`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerInfo {
+    /// Container ID
+    pub id: String,
+    /// Image the container was started from
+    pub image: String,
+    /// Current lifecycle state
+    pub state: ContainerState,
+    /// Namespace the container runs in
+    pub namespace_id: u64,
+    /// Seconds the container has been (or was) running
+    pub uptime_secs: u64,
+}
+
+/// A container lifecycle change, delivered by [`FastRuntime::subscribe`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContainerEvent {
+    /// Container the event is about
+    pub id: String,
+    /// What happened
+    pub kind: EventKind,
+    /// When it happened
+    pub timestamp: SystemTime,
+}
+
+/// What a [`ContainerEvent`] reports
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "event")]
+pub enum EventKind {
+    /// The container was registered and its command launched
+    Started,
+    /// The container failed to start; it is not tracked
+    Failed { reason: String },
+    /// The command exited and is being run again under its
+    /// [`RestartPolicy`]
+    Restarted { restarts: u32 },
+    /// The command exited on its own with `code`
+    Exited { code: i32 },
+    /// The command was killed by a SIGKILL the runtime did not send, which
+    /// for a memory-limited container is the OOM killer; also sent when
+    /// [`FastRuntime::check_oom_kills`] sees the cgroup's `oom_kill` count
+    /// go up
+    OomKilled,
+    /// The container was stopped or killed through the runtime
+    Stopped,
+    /// A live usage sample read from the container's cgroup (see
+    /// [`FastRuntime::sample_usage`])
+    Usage(CgroupUsage),
+}
+
+impl EventKind {
+    /// The event and final state for a command that exited on its own
+    fn from_exit(result: &Result<ExecutionResult>) -> (Self, ContainerState) {
+        match result {
+            Ok(result) if result.termination == Termination::Signaled(Signal::SIGKILL as i32) => {
+                (Self::OomKilled, ContainerState::Exited { code: result.exit_code })
+            }
+            Ok(result) => (
+                Self::Exited { code: result.exit_code },
+                ContainerState::Exited { code: result.exit_code },
+            ),
+            Err(_) => (Self::Exited { code: -1 }, ContainerState::Exited { code: -1 }),
+        }
+    }
+}
+
+/// Events buffered per subscriber; a subscriber that falls further behind
+/// skips the oldest (see [`broadcast::error::RecvError::Lagged`])
+pub const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// How often a stop checks whether the command has exited
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Registry entry for a container started by the runtime
+struct ContainerRecord {
+    /// PID of the container's command, 0 when none is running
+    pid: Arc<AtomicU32>,
+    image: String,
+    state: ContainerState,
+    namespace_id: u64,
+    started_at: Instant,
+    finished_at: Option<Instant>,
+}
+
 /// Fast container runtime optimized for startup speed
 pub struct FastRuntime {
     config: FastStartConfig,
@@ -50,6 +386,18 @@ pub struct FastRuntime {
     buffer_pool: Arc<BufferPool>,
     metrics: Arc<PerfMetrics>,
     namespace_cache: Arc<RwLock<Vec<CachedNamespace>>>,
+    namespace_setup: Arc<ParallelNamespaceSetup>,
+    /// Mints IDs for namespaces and for containers started without one
+    id_generator: Arc<dyn IdGenerator>,
+    executor_pool: Arc<Mutex<Vec<NativeExecutor>>>,
+    containers: Arc<RwLock<HashMap<String, ContainerRecord>>>,
+    /// Capacity held by each running container, checked against
+    /// [`FastStartConfig::host_capacity`]
+    reservations: Arc<Mutex<HashMap<String, Reservation>>>,
+    port_forwarder: Arc<PortForwarder>,
+    events: broadcast::Sender<ContainerEvent>,
+    /// Reads file-backed logs; std I/O is used when `None`
+    io_uring: Option<Arc<IoUringManager>>,
 }
 
 /// A cached namespace template ready for reuse
@@ -67,18 +415,85 @@ impl FastRuntime {
     }
 
     /// Create a new fast runtime with custom configuration
+    ///
+    /// With `prewarm_executors` set, `prewarm_count` executors are prepared
+    /// in a background task; this needs a Tokio runtime, and without one the
+    /// pool starts empty and fills as containers finish.
     pub fn with_config(config: FastStartConfig) -> Arc<Self> {
-        Arc::new(Self {
+        let io_uring = Self::log_io_uring(&config);
+        Self::with_io_uring(config, io_uring)
+    }
+
+    /// Create a runtime that reads file-backed logs through `io_uring`
+    ///
+    /// [`with_config`](Self::with_config) creates a manager itself when the
+    /// `io_uring` feature is enabled and `log_dir` is set.
+    pub fn with_io_uring(
+        config: FastStartConfig,
+        io_uring: Option<Arc<IoUringManager>>,
+    ) -> Arc<Self> {
+        Self::build(config, io_uring, Arc::new(UuidGenerator::new()))
+    }
+
+    /// Create a runtime that mints container and namespace IDs with `ids`
+    ///
+    /// The other constructors use a [`UuidGenerator`], whose IDs do not
+    /// collide across hosts.
+    pub fn with_id_generator(config: FastStartConfig, ids: Arc<dyn IdGenerator>) -> Arc<Self> {
+        let io_uring = Self::log_io_uring(&config);
+        Self::build(config, io_uring, ids)
+    }
+
+    fn build(
+        config: FastStartConfig,
+        io_uring: Option<Arc<IoUringManager>>,
+        id_generator: Arc<dyn IdGenerator>,
+    ) -> Arc<Self> {
+        let runtime = Arc::new(Self {
+            namespace_setup: Arc::new(ParallelNamespaceSetup::new(config.namespaces.clone())),
             config,
-            isolation: Arc::new(Isolation::with_defaults()),
-            buffer_pool: BufferPool::new(),
-            metrics: PerfMetrics::new(),
-            namespace_cache: Arc::new(RwLock::new(Vec::new())),
-        })
+            io_uring,
+            id_generator,
+            ..Self::default()
+        });
+        if runtime.config.prewarm_executors && runtime.config.prewarm_count > 0 {
+            match tokio::runtime::Handle::try_current() {
+                Ok(handle) => {
+                    handle.spawn(Self::prewarm(
+                        runtime.executor_pool.clone(),
+                        runtime.config.prewarm_count,
+                    ));
+                }
+                Err(_) => debug!("No Tokio runtime; skipping executor pre-warming"),
+            }
+        }
+        runtime
+    }
+
+    /// An io_uring manager for file-backed logs, if the feature is enabled
+    /// and the kernel supports it
+    fn log_io_uring(config: &FastStartConfig) -> Option<Arc<IoUringManager>> {
+        if !cfg!(feature = "io_uring") || config.log_dir.is_none() {
+            return None;
+        }
+        match IoUringManager::new(IoUringConfig::default()) {
+            Ok(manager) if manager.is_active() => Some(Arc::new(manager)),
+            Ok(_) => None,
+            Err(e) => {
+                warn!("io_uring unavailable, reading logs with std I/O: {:#}", e);
+                None
+            }
+        }
     }
 
     /// Start a container with optimized fast path
     ///
+    /// This only reserves capacity, sets up the namespace and registers the
+    /// container as running: `command` and `args` are not executed and the
+    /// handle's PID stays 0. Use
+    /// [`start_container_spec`](Self::start_container_spec) to launch the
+    /// command, as `enviro run` does.
+    ///
     /// # Performance Target: < 100ms
     ///
     /// Breakdown:
@@ -89,301 +504,2309 @@ impl FastRuntime {
     pub async fn start_container(
         &self,
         container_id: &str,
-        _image: &str,
+        image: &str,
+        command: &str,
+        args: Vec<String>,
+    ) -> Result<ContainerHandle, RuntimeError> {
+        let minted;
+        let container_id = if container_id.is_empty() {
+            minted = self.id_generator.container_id();
+            &minted
+        } else {
+            container_id
+        };
+        let result = self.launch_container(container_id, image, command, args).await;
+        self.emit_start_failure(container_id, &result);
+        result
+    }
+
+    async fn launch_container(
+        &self,
+        container_id: &str,
+        image: &str,
         _command: &str,
         _args: Vec<String>,
-    ) -> Result<ContainerHandle> {
-        let _timer = ScopedTimer::new(&self.metrics, TimerType::ContainerStart);
+    ) -> Result<ContainerHandle, RuntimeError> {
+        let mut timer = ScopedTimer::new(&self.metrics, TimerType::ContainerStart);
 
-        // Step 1: Get or create namespace (optimized path)
-        let namespace_id = if self.config.use_namespace_cache {
-            self.get_cached_namespace().await?
-        } else {
-            self.create_namespace_fast().await?
-        };
+        // Step 1: Setup execution context (zero-copy)
+        let ctx = Self::execution_context(container_id, HashMap::new(), "/".to_string(), None, None);
 
-        // Step 2: Setup execution context (zero-copy)
-        let _ctx = ExecutionContext {
-            container_id: container_id.to_string(),
-            env: HashMap::new(),
-            workdir: "/".to_string(),
-            limits: ResourceLimits {
-                cpu_cores: 1.0,
-                memory_bytes: 512 * 1024 * 1024, // 512MB default
-                pid_limit: 100,
-            },
-            network: NetworkConfig {
-                isolated: true,
-                ip_address: None,
-                dns_servers: vec!["8.8.8.8".to_string()],
-            },
+        // Step 2: Reserve host capacity before doing any work
+        if let Err(e) = self.reserve(container_id, &ctx.limits).await {
+            timer.mark_failed();
+            return Err(e);
+        }
+
+        // Step 3: Get or create namespace (optimized path)
+        let (namespace_id, setup_report) = match self.acquire_namespace(&mut timer).await {
+            Ok(namespace) => namespace,
+            Err(e) => {
+                self.release_reservation(container_id).await;
+                return Err(RuntimeError::Namespace(e));
+            }
         };
 
-        // Step 3: Create container handle
+        // Step 4: Create container handle
+        let pid: Arc<AtomicU32> = Arc::default();
+        self.register_container(container_id, image, namespace_id, pid.clone())
+            .await;
         Ok(ContainerHandle {
             id: container_id.to_string(),
             namespace_id,
+            setup_report,
             runtime: self.clone(),
+            workload: Mutex::new(None),
+            log: LogStore::Memory(Arc::default()),
+            log_cursor: Mutex::default(),
+            restarts: Arc::default(),
+            pid,
         })
     }
 
-    /// Get a cached namespace or create a new one
-    async fn get_cached_namespace(&self) -> Result<u64> {
-        let mut cache = self.namespace_cache.write().await;
-        
-        // Try to reuse from cache
-        if let Some(cached) = cache.pop() {
-            // Check if namespace is still valid (< 60 seconds old)
-            if cached.created_at.elapsed().as_secs() < 60 {
-                self.metrics.record_buffer_reuse(); // Track cache hit
-                return Ok(cached.id);
+    /// Start a container from a spec and launch its command
+    ///
+    /// The command runs in a background task; use [`ContainerHandle::wait`]
+    /// to collect its exit code and output.
+    pub async fn start_container_spec(
+        &self,
+        mut spec: ContainerSpec,
+    ) -> Result<ContainerHandle, RuntimeError> {
+        if spec.id.is_empty() {
+            spec.id = self.id_generator.container_id();
+        }
+        let id = spec.id.clone();
+        let result = self.launch_spec(spec).await;
+        self.emit_start_failure(&id, &result);
+        result
+    }
+
+    /// Start many containers concurrently, collecting every outcome
+    ///
+    /// A failed start does not stop the others; its spec is returned in
+    /// [`BatchStartReport::failed`] together with the reason, with the ID it
+    /// was given. Successful handles keep the order of `specs`.
+    pub async fn start_containers(&self, specs: Vec<ContainerSpec>) -> BatchStartReport {
+        let starts: Vec<_> = specs
+            .into_iter()
+            .map(|mut spec| {
+                if spec.id.is_empty() {
+                    spec.id = self.id_generator.container_id();
+                }
+                let runtime = self.clone();
+                let start = tokio::spawn({
+                    let spec = spec.clone();
+                    async move { runtime.start_container_spec(spec).await }
+                });
+                (spec, start)
+            })
+            .collect();
+
+        let mut report = BatchStartReport {
+            succeeded: Vec::new(),
+            failed: Vec::new(),
+        };
+        for (spec, start) in starts {
+            // The tasks are never aborted, so a join error is a panic
+            match start.await.unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic())) {
+                Ok(handle) => report.succeeded.push(handle),
+                Err(e) => report.failed.push((spec, e)),
             }
         }
-        
-        // Cache miss - create new namespace
-        drop(cache); // Release lock before expensive operation
-        self.create_namespace_fast().await
+        info!(
+            succeeded = report.succeeded.len(),
+            failed = report.failed.len(),
+            "Batch start complete"
+        );
+        report
     }
 
-    /// Create a namespace using the fast parallel path
-    async fn create_namespace_fast(&self) -> Result<u64> {
-        let _timer = ScopedTimer::new(&self.metrics, TimerType::NamespaceCreate);
-        
-        if self.config.parallel_namespaces {
-            // Parallel namespace setup (all namespaces in parallel)
-            let tasks = vec![
-                tokio::spawn(async { Self::setup_user_namespace() }),
-                tokio::spawn(async { Self::setup_network_namespace() }),
-                tokio::spawn(async { Self::setup_mount_namespace() }),
-                tokio::spawn(async { Self::setup_pid_namespace() }),
-            ];
-            
-            // Wait for all to complete
-            for task in tasks {
-                task.await.context("Namespace setup failed")??;
+    async fn launch_spec(&self, spec: ContainerSpec) -> Result<ContainerHandle, RuntimeError> {
+        let mut timer = ScopedTimer::new(&self.metrics, TimerType::ContainerStart);
+
+        if let Err(e) = self.reserve(&spec.id, &Self::resource_limits(spec.profile.as_ref())).await {
+            timer.mark_failed();
+            return Err(e);
+        }
+        let (namespace_id, setup_report) = match self.acquire_namespace(&mut timer).await {
+            Ok(namespace) => namespace,
+            Err(e) => {
+                self.release_reservation(&spec.id).await;
+                return Err(RuntimeError::Namespace(e));
             }
-        } else {
-            // Sequential fallback
-            Self::setup_user_namespace()?;
-            Self::setup_network_namespace()?;
-            Self::setup_mount_namespace()?;
-            Self::setup_pid_namespace()?;
+        };
+        let mut ctx = Self::execution_context(
+            &spec.id,
+            spec.env,
+            spec.workdir,
+            spec.profile.as_ref(),
+            spec.network,
+        );
+        if let Err(e) = ctx.network.validate() {
+            timer.mark_failed();
+            self.release_reservation(&spec.id).await;
+            return Err(RuntimeError::Network(e.into()));
         }
-        
-        // Generate namespace ID
-        let namespace_id = Self::generate_namespace_id();
-        
-        // Add to cache for future reuse
-        if self.config.use_namespace_cache {
-            let mut cache = self.namespace_cache.write().await;
-            if cache.len() < self.config.max_cached_namespaces {
-                cache.push(CachedNamespace {
-                    id: namespace_id,
-                    created_at: std::time::Instant::now(),
-                });
+        let cgroup = match self.create_cgroup(&spec.id, &ctx.limits) {
+            Ok(cgroup) => cgroup,
+            Err(e) => {
+                timer.mark_failed();
+                self.release_reservation(&spec.id).await;
+                return Err(RuntimeError::Limits(e));
             }
+        };
+        ctx.cgroup = cgroup.as_ref().map(|cgroup| cgroup.path().to_path_buf());
+
+        // A pre-warmed executor still gets the container's own context
+        let mut executor = self.take_executor().await;
+        if let Err(e) = executor.prepare(&ctx).await {
+            timer.mark_failed();
+            self.release_reservation(&spec.id).await;
+            return Err(RuntimeError::Executor(e));
         }
-        
-        Ok(namespace_id)
+        let log = match self.create_log(&spec.id).await {
+            Ok(log) => log,
+            Err(e) => {
+                timer.mark_failed();
+                self.release_reservation(&spec.id).await;
+                return Err(RuntimeError::Log(e));
+            }
+        };
+        // Last fallible step, so nothing after it has to remove the forwards
+        if let Err(e) = self.port_forwarder.install(&spec.id, &ctx.network).await {
+            timer.mark_failed();
+            self.release_reservation(&spec.id).await;
+            return Err(RuntimeError::Network(e));
+        }
+
+        // Registered first so the supervisor sees the container as running
+        let pid = executor.pid_handle();
+        self.register_container(&spec.id, &spec.image, namespace_id, pid.clone())
+            .await;
+
+        let (command, args, policy) = (spec.command, spec.args, spec.restart);
+        let restarts: Arc<AtomicU32> = Arc::default();
+        let workload_log = log.clone();
+        let workload_restarts = restarts.clone();
+        let runtime = self.clone();
+        let workload = tokio::spawn(async move {
+            let result = loop {
+                let result = executor.execute(&ctx, &command, &args).await;
+                if let Ok(result) = &result {
+                    workload_log.append(result).await;
+                }
+                let exit_code = result.as_ref().map_or(-1, |result| result.exit_code);
+                if !runtime
+                    .await_restart(&ctx.container_id, policy, &workload_restarts, exit_code)
+                    .await
+                {
+                    break result;
+                }
+            };
+            // Removed now that nothing is left running in it
+            drop(cgroup);
+            let (event, state) = EventKind::from_exit(&result);
+            runtime.finish_container(&ctx.container_id, state, event).await;
+            Self::recycle_executor(&runtime.executor_pool, runtime.prewarm_size(), executor, &ctx)
+                .await;
+            result
+        });
+
+        Ok(ContainerHandle {
+            id: spec.id,
+            namespace_id,
+            setup_report,
+            runtime: self.clone(),
+            workload: Mutex::new(Some(workload)),
+            log,
+            log_cursor: Mutex::default(),
+            restarts,
+            pid,
+        })
     }
 
-    /// Setup user namespace (placeholder - would call isolation manager)
-    fn setup_user_namespace() -> Result<()> {
-        // In real implementation, this would:
-        // 1. Call unshare(CLONE_NEWUSER)
-        // 2. Write UID/GID mappings to /proc/self/uid_map
-        // 3. Set up capability sets
-        Ok(())
+    /// Create container `id`'s cgroup under [`FastStartConfig::cgroup_root`]
+    /// and write `limits` to it; `None` when no root is configured
+    fn create_cgroup(&self, id: &str, limits: &ResourceLimits) -> Result<Option<Cgroup>> {
+        let Some(root) = &self.config.cgroup_root else {
+            return Ok(None);
+        };
+        let cgroup = Cgroup::create_in(root, &format!("enviro/{}", id))?;
+        ResourceLimitBatch::from(limits)
+            .with_cgroup_dir(cgroup.path())
+            .apply_batch()?;
+        Ok(Some(cgroup))
     }
 
-    /// Setup network namespace (placeholder)
-    fn setup_network_namespace() -> Result<()> {
-        // In real implementation, this would:
-        // 1. Call unshare(CLONE_NEWNET)
-        // 2. Create veth pair
-        // 3. Configure routes and iptables
-        Ok(())
+    /// Create the store for a container's output: an empty `<id>.log` under
+    /// [`FastStartConfig::log_dir`], or memory when that is unset
+    async fn create_log(&self, id: &str) -> Result<LogStore> {
+        let Some(dir) = &self.config.log_dir else {
+            return Ok(LogStore::Memory(Arc::default()));
+        };
+        anyhow::ensure!(
+            Path::new(id).file_name().is_some_and(|name| name == id),
+            "Container ID {:?} is not a valid log file name",
+            id
+        );
+        let path = dir.join(format!("{}.log", id));
+        tokio::fs::create_dir_all(dir)
+            .await
+            .with_context(|| format!("Failed to create log directory {}", dir.display()))?;
+        // Truncates output left by an earlier container with the same ID
+        tokio::fs::File::create(&path)
+            .await
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        Ok(LogStore::File(path))
     }
 
-    /// Setup mount namespace (placeholder)
-    fn setup_mount_namespace() -> Result<()> {
-        // In real implementation, this would:
-        // 1. Call unshare(CLONE_NEWNS)
-        // 2. Mount private /proc and /sys
-        // 3. Setup bind mounts for container rootfs
-        Ok(())
+    /// Read up to `len` bytes of the file-backed container log at `path`
+    /// from `offset`, through io_uring when available
+    async fn read_log_at(
+        &self,
+        path: &Path,
+        file: Arc<std::fs::File>,
+        offset: usize,
+        len: usize,
+    ) -> Result<Vec<u8>> {
+        let data = match &self.io_uring {
+            Some(io_uring) => io_uring.read_at_async(file, offset as u64, len).await,
+            None => blocking_read_at(file, offset as u64, len).await,
+        };
+        data.with_context(|| format!("Failed to read container log {}", path.display()))
     }
 
-    /// Setup PID namespace (placeholder)
-    fn setup_pid_namespace() -> Result<()> {
-        // In real implementation, this would:
-        // 1. Call unshare(CLONE_NEWPID)
-        // 2. Fork to become PID 1 in new namespace
-        Ok(())
+    /// Read the live usage of container `id` from its `cgroup`
+    ///
+    /// The sample is recorded in [`metrics`](Self::metrics) and sent to
+    /// subscribers as an [`EventKind::Usage`] event. Call this periodically
+    /// to monitor a container.
+    pub fn sample_usage(&self, id: &str, cgroup: &Cgroup) -> Result<CgroupUsage> {
+        let usage = cgroup
+            .usage()
+            .with_context(|| format!("Failed to read usage of container {}", id))?;
+        self.metrics.record_cgroup_usage(&usage);
+        self.emit(id, EventKind::Usage(usage));
+        Ok(usage)
     }
 
-    /// Generate a unique namespace ID
-    fn generate_namespace_id() -> u64 {
-        use std::sync::atomic::{AtomicU64, Ordering};
-        static COUNTER: AtomicU64 = AtomicU64::new(1);
-        COUNTER.fetch_add(1, Ordering::Relaxed)
+    /// Send an [`EventKind::OomKilled`] event for container `id` if its
+    /// `cgroup` recorded OOM kills since the previous check
+    ///
+    /// Unlike the event inferred from a SIGKILL exit, this also reports a
+    /// kill of any process in the cgroup that the command survived. Returns
+    /// the number of new kills.
+    pub fn check_oom_kills(&self, id: &str, cgroup: &Cgroup) -> Result<u64> {
+        let kills = cgroup
+            .new_oom_kills()
+            .with_context(|| format!("Failed to read OOM kills of container {}", id))?;
+        if kills > 0 {
+            warn!("Container {} had {} process(es) killed by the OOM killer", id, kills);
+            self.emit(id, EventKind::OomKilled);
+        }
+        Ok(kills)
     }
 
-    /// Get performance metrics
-    pub fn metrics(&self) -> Arc<PerfMetrics> {
-        self.metrics.clone()
+    /// Subscribe to lifecycle events of this runtime's containers
+    ///
+    /// Only events sent after the call are received. A subscriber more than
+    /// [`EVENT_CHANNEL_CAPACITY`] events behind gets
+    /// [`RecvError::Lagged`](broadcast::error::RecvError::Lagged) and
+    /// continues from the oldest event still buffered.
+    pub fn subscribe(&self) -> broadcast::Receiver<ContainerEvent> {
+        self.events.subscribe()
     }
 
-    /// Get buffer pool
-    pub fn buffer_pool(&self) -> Arc<BufferPool> {
-        self.buffer_pool.clone()
+    /// Send an event to current subscribers, if there are any
+    fn emit(&self, id: &str, kind: EventKind) {
+        let _ = self.events.send(ContainerEvent {
+            id: id.to_string(),
+            kind,
+            timestamp: SystemTime::now(),
+        });
     }
-}
 
-impl Clone for FastRuntime {
-    fn clone(&self) -> Self {
-        Self {
-            config: self.config.clone(),
-            isolation: self.isolation.clone(),
-            buffer_pool: self.buffer_pool.clone(),
-            metrics: self.metrics.clone(),
-            namespace_cache: self.namespace_cache.clone(),
+    fn emit_start_failure(&self, id: &str, result: &Result<ContainerHandle, RuntimeError>) {
+        if let Err(e) = result {
+            self.emit(id, EventKind::Failed { reason: e.to_string() });
         }
     }
-}
 
-impl Default for FastRuntime {
-    fn default() -> Self {
-        Self {
-            config: FastStartConfig::default(),
-            isolation: Arc::new(Isolation::with_defaults()),
-            buffer_pool: BufferPool::new(),
-            metrics: PerfMetrics::new(),
-            namespace_cache: Arc::new(RwLock::new(Vec::new())),
-        }
+    /// List the containers started by this runtime, oldest first
+    pub async fn list_containers(&self) -> Vec<ContainerInfo> {
+        let containers = self.containers.read().await;
+        let mut records: Vec<_> = containers.iter().collect();
+        records.sort_by_key(|(_, record)| record.started_at);
+
+        records
+            .into_iter()
+            .map(|(id, record)| ContainerInfo {
+                id: id.clone(),
+                image: record.image.clone(),
+                state: record.state,
+                namespace_id: record.namespace_id,
+                uptime_secs: record
+                    .finished_at
+                    .unwrap_or_else(Instant::now)
+                    .duration_since(record.started_at)
+                    .as_secs(),
+            })
+            .collect()
     }
-}
 
-/// Handle to a running container
-pub struct ContainerHandle {
-    id: String,
-    namespace_id: u64,
-    runtime: FastRuntime,
-}
+    /// Stop a running container by ID
+    ///
+    /// The command gets [`FastStartConfig::stop_grace_period`] to exit after
+    /// SIGTERM before it is killed.
+    pub async fn stop_container(&self, id: &str) -> Result<(), RuntimeError> {
+        let (state, pid) = self
+            .containers
+            .read()
+            .await
+            .get(id)
+            .map(|record| (record.state, record.pid.clone()))
+            .ok_or_else(|| RuntimeError::NotFound(id.to_string()))?;
+        if !state.is_running() {
+            return Err(RuntimeError::NotRunning {
+                id: id.to_string(),
+                state,
+            });
+        }
 
-impl ContainerHandle {
-    /// Get the container ID
-    pub fn id(&self) -> &str {
-        &self.id
+        self.terminate(id, &pid, self.config.stop_grace_period).await
     }
 
-    /// Get the namespace ID
-    pub fn namespace_id(&self) -> u64 {
-        self.namespace_id
+    /// Shut the runtime down: stop every tracked container and release
+    /// cached namespaces
+    ///
+    /// `Drop` cannot be async, so there is no implicit cleanup: a daemon must
+    /// call this before exiting or any namespaces it created are leaked.
+    /// Containers are stopped concurrently, so this takes about one grace
+    /// period however many are running; one that exits on its own meanwhile
+    /// is not an error. Afterwards the container registry and namespace
+    /// cache are empty and the final metrics have been logged, even if a
+    /// stop failed; the first such failure is returned.
+    pub async fn shutdown(&self) -> Result<(), RuntimeError> {
+        let running: Vec<String> = self
+            .containers
+            .read()
+            .await
+            .iter()
+            .filter(|(_, record)| record.state.is_running())
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        let mut stops = tokio::task::JoinSet::new();
+        let mut tasks = HashMap::new();
+        for id in running {
+            let runtime = self.clone();
+            let task = stops.spawn({
+                let id = id.clone();
+                async move { runtime.stop_container(&id).await }
+            });
+            tasks.insert(task.id(), id);
+        }
+        let mut stopped = 0;
+        let mut first_error = None;
+        while let Some(joined) = stops.join_next_with_id().await {
+            // A panicking stop must not abort the rest of the cleanup
+            let (id, result) = match joined {
+                Ok((task, result)) => (&tasks[&task], result),
+                Err(e) => (
+                    &tasks[&e.id()],
+                    Err(RuntimeError::Signal(anyhow::anyhow!("Stop task failed: {}", e))),
+                ),
+            };
+            match result {
+                Ok(()) => stopped += 1,
+                // Exited (or was stopped) since the registry was read
+                Err(RuntimeError::NotRunning { .. } | RuntimeError::NotFound(_)) => {}
+                Err(e) => {
+                    warn!("Failed to stop container {} during shutdown: {}", id, e);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        self.containers.write().await.clear();
+        self.reservations.lock().await.clear();
+
+        // In real implementation, each cached namespace would be torn down
+        // here (close the namespace fds, remove veths and mounts).
+        let released = {
+            let mut cache = self.namespace_cache.write().await;
+            let released = cache.len();
+            cache.clear();
+            released
+        };
+        self.executor_pool.lock().await.clear();
+
+        info!(
+            stopped,
+            released_namespaces = released,
+            metrics = %self.metrics.snapshot().to_json(),
+            "Runtime shut down"
+        );
+        first_error.map_or(Ok(()), Err)
     }
 
-    /// Stop the container
-    pub async fn stop(&self) -> Result<()> {
-        let _timer = ScopedTimer::new(&self.runtime.metrics, TimerType::ContainerStop);
-        
-        // In real implementation:
-        // 1. Send SIGTERM to container init
-        // 2. Wait for graceful shutdown (timeout)
-        // 3. Send SIGKILL if still running
-        // 4. Clean up namespaces and mounts
-        // 5. Return namespace to cache if enabled
-        
-        Ok(())
+    /// Number of pre-warmed executors ready for the next start
+    pub async fn prewarmed_executors(&self) -> usize {
+        self.executor_pool.lock().await.len()
     }
 
-    /// Get logs from the container
-    pub async fn logs(&self) -> Result<String> {
-        // Use buffer pool for zero-copy log reading
-        let _buffer = self.runtime.buffer_pool.get_buffer(64 * 1024).await;
-        
-        // In real implementation:
-        // Read from container stdout/stderr pipes
-        
-        Ok(String::from("Container logs would appear here"))
+    /// Target pool size; zero when pre-warming is disabled
+    fn prewarm_size(&self) -> usize {
+        if self.config.prewarm_executors {
+            self.config.prewarm_count
+        } else {
+            0
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Context used to prepare executors before a container claims them
+    fn prewarm_context() -> ExecutionContext {
+        Self::execution_context("prewarm", HashMap::new(), "/".to_string(), None, None)
+    }
 
-    #[tokio::test]
-    async fn test_fast_runtime_creation() {
-        let runtime = FastRuntime::new();
-        assert!(runtime.config.parallel_namespaces);
-        assert!(runtime.config.use_namespace_cache);
+    /// Fill the executor pool up to `count` prepared executors
+    async fn prewarm(pool: Arc<Mutex<Vec<NativeExecutor>>>, count: usize) {
+        let ctx = Self::prewarm_context();
+        for _ in 0..count {
+            let mut executor = NativeExecutor::new();
+            if let Err(e) = executor.prepare(&ctx).await {
+                warn!("Failed to pre-warm executor: {:#}", e);
+                return;
+            }
+            let mut pool = pool.lock().await;
+            if pool.len() >= count {
+                return;
+            }
+            pool.push(executor);
+        }
+        debug!(count, "Executor pool pre-warmed");
     }
 
-    #[tokio::test]
-    async fn test_namespace_cache() {
-        let runtime = FastRuntime::new();
-        
-        // Create first namespace
-        let ns1 = runtime.get_cached_namespace().await.unwrap();
-        assert!(ns1 > 0);
-        
-        // Return it to cache by creating a new one
-        let ns2 = runtime.create_namespace_fast().await.unwrap();
-        assert!(ns2 > 0);
-        
-        // Check cache has entries
-        let cache = runtime.namespace_cache.read().await;
-        assert!(cache.len() > 0);
+    /// Take a pre-warmed executor, or a fresh one when the pool is empty
+    async fn take_executor(&self) -> NativeExecutor {
+        if self.prewarm_size() == 0 {
+            return NativeExecutor::new();
+        }
+        match self.executor_pool.lock().await.pop() {
+            Some(executor) => {
+                self.metrics.record_prewarm_hit();
+                executor
+            }
+            None => {
+                self.metrics.record_prewarm_miss();
+                NativeExecutor::new()
+            }
+        }
     }
 
-    #[tokio::test]
-    async fn test_container_start() {
+    /// Clean up a finished container's executor and return it to the pool
+    /// prepared for the next start, if the pool has room
+    async fn recycle_executor(
+        pool: &Mutex<Vec<NativeExecutor>>,
+        pool_size: usize,
+        mut executor: NativeExecutor,
+        ctx: &ExecutionContext,
+    ) {
+        if pool_size == 0 || pool.lock().await.len() >= pool_size {
+            return;
+        }
+        if let Err(e) = executor.cleanup(ctx).await {
+            warn!("Failed to clean up executor for {}: {:#}", ctx.container_id, e);
+            return;
+        }
+        if let Err(e) = executor.prepare(&Self::prewarm_context()).await {
+            warn!("Failed to re-warm executor: {:#}", e);
+            return;
+        }
+        let mut pool = pool.lock().await;
+        if pool.len() < pool_size {
+            pool.push(executor);
+        }
+    }
+
+    /// Decide whether an exited command runs again, waiting out the backoff
+    /// first
+    ///
+    /// Returns `false` once `policy` is exhausted or the container is no
+    /// longer running (stopped, or removed by [`shutdown`](Self::shutdown)).
+    async fn await_restart(
+        &self,
+        id: &str,
+        policy: RestartPolicy,
+        restarts: &AtomicU32,
+        exit_code: i32,
+    ) -> bool {
+        let count = restarts.load(Ordering::Relaxed);
+        if !policy.should_restart(exit_code, count) || !self.is_running(id).await {
+            return false;
+        }
+        tokio::time::sleep(restart_backoff(self.config.restart_backoff, count)).await;
+        if !self.is_running(id).await {
+            return false;
+        }
+        restarts.fetch_add(1, Ordering::Relaxed);
+        info!(container = id, exit_code, restarts = count + 1, "Restarting container");
+        self.emit(id, EventKind::Restarted { restarts: count + 1 });
+        true
+    }
+
+    /// Whether a tracked container is still running
+    async fn is_running(&self, id: &str) -> bool {
+        self.containers
+            .read()
+            .await
+            .get(id)
+            .is_some_and(|record| record.state.is_running())
+    }
+
+    /// Record a newly started container as running
+    async fn register_container(
+        &self,
+        id: &str,
+        image: &str,
+        namespace_id: u64,
+        pid: Arc<AtomicU32>,
+    ) {
+        self.containers.write().await.insert(
+            id.to_string(),
+            ContainerRecord {
+                pid,
+                image: image.to_string(),
+                state: ContainerState::Running,
+                namespace_id,
+                started_at: Instant::now(),
+                finished_at: None,
+            },
+        );
+        self.emit(id, EventKind::Started);
+    }
+
+    /// Move a running container to a final state, remove its port forwards
+    /// and report `event`
+    ///
+    /// Does nothing if the container has already finished, so whichever of
+    /// a stop and the command exiting comes first decides the state.
+    async fn finish_container(&self, id: &str, state: ContainerState, event: EventKind) {
+        let finished = match self.containers.write().await.get_mut(id) {
+            Some(record) if record.state.is_running() => {
+                record.state = state;
+                record.finished_at = Some(Instant::now());
+                true
+            }
+            _ => false,
+        };
+        if finished {
+            self.emit(id, event);
+            self.release_reservation(id).await;
+            if let Err(e) = self.port_forwarder.remove(id).await {
+                warn!("Failed to remove port forwards for {}: {:#}", id, e);
+            }
+        }
+    }
+
+    /// Stop a container: send its command SIGTERM, then SIGKILL once
+    /// `grace` has passed
+    ///
+    /// The container is marked stopped first so its restart policy does not
+    /// launch the command again. A kill after the grace period still counts
+    /// as stopped, and is recorded as a timeout.
+    async fn terminate(
+        &self,
+        id: &str,
+        pid: &AtomicU32,
+        grace: Duration,
+    ) -> Result<(), RuntimeError> {
+        let start = Instant::now();
+        self.finish_container(id, ContainerState::Stopped, EventKind::Stopped)
+            .await;
+
+        let exited_in_time = async {
+            if !signal_command(pid, Signal::SIGTERM)? {
+                return Ok(true);
+            }
+            let deadline = start + grace;
+            while pid.load(Ordering::Acquire) != 0 {
+                if Instant::now() >= deadline {
+                    signal_command(pid, Signal::SIGKILL)?;
+                    return Ok(false);
+                }
+                tokio::time::sleep(STOP_POLL_INTERVAL).await;
+            }
+            Ok(true)
+        }
+        .await;
+
+        match exited_in_time {
+            Ok(true) => self.metrics.record_container_stop(start.elapsed()),
+            Ok(false) => {
+                warn!("Container {} did not exit within {:?}; killed", id, grace);
+                self.metrics.record_container_stop_failure(StopFailureKind::Timeout);
+            }
+            Err(_) => self.metrics.record_container_stop_failure(StopFailureKind::SignalFailed),
+        }
+        exited_in_time.map(|_| ()).map_err(RuntimeError::Signal)
+    }
+
+    /// Reserve `limits` for a starting container, failing if the ID is
+    /// already in use or the host would be oversubscribed
+    async fn reserve(&self, id: &str, limits: &ResourceLimits) -> Result<(), RuntimeError> {
+        let requested = Reservation {
+            cpu_cores: limits.cpu_cores,
+            memory_bytes: limits.memory_bytes,
+        };
+        let mut reservations = self.reservations.lock().await;
+        if reservations.contains_key(id) {
+            return Err(RuntimeError::DuplicateContainer(id.to_string()));
+        }
+        if let Some(capacity) = self.config.host_capacity {
+            let (used_cpu, used_memory) = reservations
+                .values()
+                .fold((0.0, 0u64), |(cpu, memory), r| {
+                    (cpu + r.cpu_cores, memory.saturating_add(r.memory_bytes))
+                });
+            let available_cpu_cores = (capacity.total_cpu_cores - used_cpu).max(0.0);
+            let available_memory_bytes = capacity.total_memory_bytes.saturating_sub(used_memory);
+            // Tolerate rounding in the summed fractional cores
+            if requested.cpu_cores > available_cpu_cores + 1e-9
+                || requested.memory_bytes > available_memory_bytes
+            {
+                return Err(CapacityExceeded {
+                    container_id: id.to_string(),
+                    requested_cpu_cores: requested.cpu_cores,
+                    requested_memory_bytes: requested.memory_bytes,
+                    available_cpu_cores,
+                    available_memory_bytes,
+                }
+                .into());
+            }
+        }
+        reservations.insert(id.to_string(), requested);
+        Ok(())
+    }
+
+    /// Return a container's reserved capacity to the host
+    async fn release_reservation(&self, id: &str) {
+        self.reservations.lock().await.remove(id);
+    }
+
+    /// Get or create the namespace for a starting container
+    async fn acquire_namespace(
+        &self,
+        timer: &mut ScopedTimer<'_>,
+    ) -> Result<(u64, Option<ParallelSetupReport>)> {
+        let namespace = if self.config.use_namespace_cache {
+            self.get_cached_namespace().await
+        } else {
+            self.create_namespace_fast()
+                .await
+                .map(|(id, report)| (id, Some(report)))
+        };
+        if namespace.is_err() {
+            // Keep failed starts out of the start-time average
+            timer.mark_failed();
+        }
+        namespace
+    }
+
+    /// Build the execution context for a container
+    ///
+    /// Limits come from `profile` when given, and `network` replaces the
+    /// default isolated network; otherwise the runtime defaults apply.
+    fn execution_context(
+        container_id: &str,
+        env: HashMap<String, String>,
+        workdir: String,
+        profile: Option<&ResourceProfile>,
+        network: Option<NetworkConfig>,
+    ) -> ExecutionContext {
+        ExecutionContext::builder(container_id)
+            .envs(env)
+            .workdir(workdir)
+            .limits(Self::resource_limits(profile))
+            .network(network.unwrap_or_else(|| NetworkConfig {
+                isolated: true,
+                ip_address: None,
+                dns_servers: vec!["8.8.8.8".to_string()],
+                port_mappings: vec![],
+            }))
+            .build_unchecked()
+    }
+
+    /// Limits for a container with `profile`, or the runtime defaults
+    fn resource_limits(profile: Option<&ResourceProfile>) -> ResourceLimits {
+        let mut limits = ResourceLimits {
+            cpu_cores: 1.0,
+            memory_bytes: 512 * 1024 * 1024, // 512MB default
+            pid_limit: 100,
+        };
+        if let Some(profile) = profile {
+            let defaults = profile.defaults();
+            if let Some(&micros) = defaults.get(&ResourceKind::CpuMaxMicros) {
+                limits.cpu_cores = micros as f64 / CPU_MAX_PERIOD_MICROS as f64;
+            }
+            if let Some(&bytes) = defaults.get(&ResourceKind::MemoryMax) {
+                limits.memory_bytes = bytes;
+            }
+            if let Some(&pids) = defaults.get(&ResourceKind::PidsMax) {
+                limits.pid_limit = u32::try_from(pids).unwrap_or(u32::MAX);
+            }
+        }
+        limits
+    }
+
+    /// Get a cached namespace or create a new one
+    ///
+    /// The setup report is `None` when a cached namespace was reused.
+    async fn get_cached_namespace(&self) -> Result<(u64, Option<ParallelSetupReport>)> {
+        let mut cache = self.namespace_cache.write().await;
+        
+        // Try to reuse from cache
+        if let Some(cached) = cache.pop() {
+            // Check if namespace is still valid (< 60 seconds old)
+            if cached.created_at.elapsed().as_secs() < 60 {
+                return Ok((cached.id, None));
+            }
+        }
+        
+        // Cache miss - create new namespace
+        drop(cache); // Release lock before expensive operation
+        let (id, report) = self.create_namespace_fast().await?;
+        Ok((id, Some(report)))
+    }
+
+    /// Create a namespace using the fast parallel path
+    ///
+    /// Sets up the namespaces enabled in [`FastStartConfig::namespaces`],
+    /// concurrently unless `parallel_namespaces` is off. Fails with every
+    /// failed namespace when any setup step fails; the namespace_create
+    /// metric records the report's total duration.
+    async fn create_namespace_fast(&self) -> Result<(u64, ParallelSetupReport)> {
+        let report = if self.config.parallel_namespaces {
+            self.namespace_setup.run().await
+        } else {
+            self.namespace_setup.run_sequential().await
+        };
+        let report = match report.and_then(|report| report.ensure_succeeded().map(|_| report)) {
+            Ok(report) => report,
+            Err(e) => {
+                self.metrics.record_failure(&TimerType::NamespaceCreate);
+                return Err(e);
+            }
+        };
+        self.metrics.record_namespace_create(report.total_duration);
+        for result in &report.results {
+            debug!(
+                namespace = %result.kind,
+                duration_us = result.duration.as_micros(),
+                "Namespace set up"
+            );
+        }
+        
+        // Generate namespace ID
+        let namespace_id = self.generate_namespace_id();
+        
+        // Add to cache for future reuse
+        if self.config.use_namespace_cache {
+            let mut cache = self.namespace_cache.write().await;
+            if cache.len() < self.config.max_cached_namespaces {
+                cache.push(CachedNamespace {
+                    id: namespace_id,
+                    created_at: std::time::Instant::now(),
+                });
+            }
+        }
+        
+        Ok((namespace_id, report))
+    }
+
+    /// Generate a namespace ID unique within this runtime and its clones
+    fn generate_namespace_id(&self) -> u64 {
+        self.id_generator.namespace_id()
+    }
+
+    /// Get performance metrics
+    pub fn metrics(&self) -> Arc<PerfMetrics> {
+        self.metrics.clone()
+    }
+
+    /// Get buffer pool
+    pub fn buffer_pool(&self) -> Arc<BufferPool> {
+        self.buffer_pool.clone()
+    }
+}
+
+impl Clone for FastRuntime {
+    fn clone(&self) -> Self {
+        Self {
+            config: self.config.clone(),
+            isolation: self.isolation.clone(),
+            buffer_pool: self.buffer_pool.clone(),
+            metrics: self.metrics.clone(),
+            namespace_cache: self.namespace_cache.clone(),
+            namespace_setup: self.namespace_setup.clone(),
+            id_generator: self.id_generator.clone(),
+            executor_pool: self.executor_pool.clone(),
+            containers: self.containers.clone(),
+            reservations: self.reservations.clone(),
+            port_forwarder: self.port_forwarder.clone(),
+            events: self.events.clone(),
+            io_uring: self.io_uring.clone(),
+        }
+    }
+}
+
+impl Default for FastRuntime {
+    fn default() -> Self {
+        let metrics = PerfMetrics::new();
+        Self {
+            config: FastStartConfig::default(),
+            isolation: Arc::new(Isolation::with_defaults()),
+            buffer_pool: BufferPool::with_metrics(metrics.clone()),
+            metrics,
+            namespace_cache: Arc::new(RwLock::new(Vec::new())),
+            namespace_setup: Arc::new(ParallelNamespaceSetup::new(NamespaceSetupConfig::default())),
+            id_generator: Arc::new(UuidGenerator::new()),
+            executor_pool: Arc::new(Mutex::new(Vec::new())),
+            containers: Arc::new(RwLock::new(HashMap::new())),
+            reservations: Arc::new(Mutex::new(HashMap::new())),
+            port_forwarder: Arc::new(PortForwarder::default()),
+            events: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+            io_uring: None,
+        }
+    }
+}
+
+/// Options for [`ContainerHandle::logs_with`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LogReadOptions {
+    /// Maximum bytes to return per call; everything available when `None`
+    ///
+    /// Reads are also capped at the largest pool buffer (16MB) and never
+    /// split a UTF-8 character, so a call may return slightly less.
+    pub chunk_size: Option<usize>,
+}
+
+/// Where a container's captured output is kept
+#[derive(Clone)]
+enum LogStore {
+    /// In memory
+    Memory(Arc<std::sync::Mutex<Vec<u8>>>),
+    /// Appended to a file under [`FastStartConfig::log_dir`]
+    File(PathBuf),
+}
+
+impl LogStore {
+    /// Append a run's stdout followed by its stderr
+    async fn append(&self, result: &ExecutionResult) {
+        match self {
+            Self::Memory(log) => {
+                let mut log = log.lock().expect("container log lock poisoned");
+                log.extend_from_slice(result.stdout.as_bytes());
+                log.extend_from_slice(result.stderr.as_bytes());
+            }
+            Self::File(path) => {
+                if let Err(e) = Self::append_file(path, result).await {
+                    warn!("Failed to append to container log {}: {:#}", path.display(), e);
+                }
+            }
+        }
+    }
+
+    async fn append_file(path: &Path, result: &ExecutionResult) -> Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        let mut file = tokio::fs::OpenOptions::new().append(true).open(path).await?;
+        file.write_all(result.stdout.as_bytes()).await?;
+        file.write_all(result.stderr.as_bytes()).await?;
+        // tokio finishes the last write in the background unless flushed,
+        // so a read right after `wait` could miss it
+        file.flush().await?;
+        Ok(())
+    }
+}
+
+/// Where [`ContainerHandle::logs_with`] resumes reading a [`LogStore`]
+#[derive(Default)]
+struct LogCursor {
+    /// Bytes already returned
+    offset: usize,
+    /// A file-backed log, opened by the first read and kept so each chunk
+    /// is read from `offset` rather than the whole file re-read
+    file: Option<Arc<std::fs::File>>,
+}
+
+impl LogCursor {
+    /// The log file at `path`, opened on first use
+    async fn file(&mut self, path: &Path) -> Result<Arc<std::fs::File>> {
+        if let Some(file) = &self.file {
+            return Ok(file.clone());
+        }
+        let file = tokio::fs::File::open(path)
+            .await
+            .with_context(|| format!("Failed to open container log {}", path.display()))?;
+        let file = Arc::new(file.into_std().await);
+        self.file = Some(file.clone());
+        Ok(file)
+    }
+}
+
+/// Outcome of [`FastRuntime::start_containers`]
+pub struct BatchStartReport {
+    /// Containers that started, in the order their specs were given
+    pub succeeded: Vec<ContainerHandle>,
+    /// Specs that failed to start, with the reason for each
+    pub failed: Vec<(ContainerSpec, RuntimeError)>,
+}
+
+impl BatchStartReport {
+    /// Returns `true` when every container started
+    pub fn all_succeeded(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Handle to a running container
+pub struct ContainerHandle {
+    id: String,
+    namespace_id: u64,
+    /// How the namespace was set up; `None` when a cached one was reused
+    setup_report: Option<ParallelSetupReport>,
+    runtime: FastRuntime,
+    /// The container's command, if it was started with one
+    workload: Mutex<Option<JoinHandle<Result<ExecutionResult>>>>,
+    /// Captured stdout followed by stderr, appended each time the command
+    /// exits
+    log: LogStore,
+    /// How far [`logs`](Self::logs) has read into `log`
+    log_cursor: Mutex<LogCursor>,
+    /// Restarts performed under the spec's [`RestartPolicy`]
+    restarts: Arc<AtomicU32>,
+    /// PID of the container's command, 0 when none is running
+    pid: Arc<AtomicU32>,
+}
+
+impl ContainerHandle {
+    /// Get the container ID
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Get the namespace ID
+    pub fn namespace_id(&self) -> u64 {
+        self.namespace_id
+    }
+
+    /// Per-namespace setup timings for this container's namespace
+    ///
+    /// `None` when the namespace came from the cache.
+    pub fn setup_report(&self) -> Option<&ParallelSetupReport> {
+        self.setup_report.as_ref()
+    }
+
+    /// Wait for the container's command to exit and return its result
+    ///
+    /// With a [`RestartPolicy`] this is the result of the last run, once no
+    /// further restart follows; under `Always` that means after
+    /// [`stop`](Self::stop). Fails if the container was started without a
+    /// command or has already been waited on.
+    pub async fn wait(&self) -> Result<ExecutionResult> {
+        let workload = self
+            .workload
+            .lock()
+            .await
+            .take()
+            .context("Container has no running command")?;
+        match workload.await {
+            // The supervisor task has already recorded the exit
+            Ok(result) => result,
+            Err(e) => {
+                self.runtime
+                    .finish_container(
+                        &self.id,
+                        ContainerState::Exited { code: -1 },
+                        EventKind::Exited { code: -1 },
+                    )
+                    .await;
+                Err(e).context("Container command task failed")
+            }
+        }
+    }
+
+    /// Number of times the container's command has been restarted
+    pub fn restart_count(&self) -> u32 {
+        self.restarts.load(Ordering::Relaxed)
+    }
+
+    /// Stop the container
+    ///
+    /// The command gets [`FastStartConfig::stop_grace_period`] to exit after
+    /// SIGTERM before it is killed.
+    pub async fn stop(&self) -> Result<()> {
+        self.stop_with_grace(self.runtime.config.stop_grace_period)
+            .await
+    }
+
+    /// Stop the container, killing its command if it has not exited
+    /// `grace` after SIGTERM
+    pub async fn stop_with_grace(&self, grace: Duration) -> Result<()> {
+        // In real implementation, namespaces and mounts would also be
+        // cleaned up and the namespace returned to the cache if enabled.
+        self.runtime.terminate(&self.id, &self.pid, grace).await?;
+        Ok(())
+    }
+
+    /// Stop the container immediately with SIGKILL
+    pub async fn kill(&self) -> Result<()> {
+        self.runtime
+            .finish_container(&self.id, ContainerState::Stopped, EventKind::Stopped)
+            .await;
+        signal_command(&self.pid, Signal::SIGKILL)?;
+        Ok(())
+    }
+
+    /// Get the container's logs written since the previous call
+    ///
+    /// Output is captured when the command exits, stdout first, so this is
+    /// empty while it is still running.
+    /// With [`FastStartConfig::log_dir`] set the log file is read back,
+    /// through io_uring when the `io_uring` feature is enabled.
+    pub async fn logs(&self) -> Result<String> {
+        self.logs_with(LogReadOptions::default()).await
+    }
+
+    /// Get the next chunk of the container's logs
+    ///
+    /// Each call resumes where the previous one (on this handle) left off
+    /// and returns an empty string once everything has been read.
+    pub async fn logs_with(&self, options: LogReadOptions) -> Result<String> {
+        let mut cursor = self.log_cursor.lock().await;
+        let log_len = match &self.log {
+            LogStore::Memory(log) => log.lock().expect("container log lock poisoned").len(),
+            LogStore::File(path) => {
+                let file = cursor.file(path).await?;
+                let metadata = file
+                    .metadata()
+                    .with_context(|| format!("Failed to stat container log {}", path.display()))?;
+                metadata.len() as usize
+            }
+        };
+        let available = log_len.saturating_sub(cursor.offset);
+        if available == 0 {
+            return Ok(String::new());
+        }
+
+        // Size the pooled buffer to what will actually be returned
+        let wanted = options.chunk_size.map_or(available, |size| size.clamp(1, available));
+        let mut buffer = self.runtime.buffer_pool.get_buffer(wanted).await;
+        let wanted = wanted.min(buffer.capacity());
+
+        let mut copy_chunk = |pending: &[u8]| {
+            let len = utf8_chunk_len(pending, wanted);
+            buffer.resize(len, 0);
+            buffer.as_mut_slice().copy_from_slice(&pending[..len]);
+            len
+        };
+        let len = match &self.log {
+            LogStore::Memory(log) => {
+                copy_chunk(&log.lock().expect("container log lock poisoned")[cursor.offset..])
+            }
+            LogStore::File(path) => {
+                // The bytes past `wanted` show whether it splits a character
+                let len = wanted.saturating_add(UTF8_MAX_CONTINUATION).min(available);
+                let file = cursor.file(path).await?;
+                let pending = self.runtime.read_log_at(path, file, cursor.offset, len).await?;
+                copy_chunk(&pending)
+            }
+        };
+        cursor.offset += len;
+
+        Ok(String::from_utf8_lossy(buffer.as_slice()).into_owned())
+    }
+}
+
+/// Send `signal` to the command whose PID is in `pid`
+///
+/// Returns `false` when no command is running (or it exited just now).
+fn signal_command(pid: &AtomicU32, signal: Signal) -> Result<bool> {
+    let raw = pid.load(Ordering::Acquire);
+    if raw == 0 {
+        return Ok(false);
+    }
+    match nix::sys::signal::kill(Pid::from_raw(raw as i32), signal) {
+        Ok(()) => Ok(true),
+        Err(Errno::ESRCH) => Ok(false),
+        Err(e) => Err(e).with_context(|| format!("Failed to send {} to PID {}", signal, raw)),
+    }
+}
+
+/// Most continuation bytes that can follow the first byte of a UTF-8
+/// character
+const UTF8_MAX_CONTINUATION: usize = 3;
+
+/// Length of the longest prefix of `data` of at most `max` bytes that does
+/// not end inside a UTF-8 character (at least one character when `max` is
+/// smaller than the first)
+fn utf8_chunk_len(data: &[u8], max: usize) -> usize {
+    let is_continuation = |i: usize| data.get(i).is_some_and(|b| b & 0xC0 == 0x80);
+    if max >= data.len() {
+        return data.len();
+    }
+
+    let mut end = max;
+    while end > 0 && is_continuation(end) {
+        end -= 1;
+    }
+    if end == 0 {
+        end = max.max(1);
+        while is_continuation(end) {
+            end += 1;
+        }
+    }
+    end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::id_generator::CounterGenerator;
+
+    #[tokio::test]
+    async fn test_fast_runtime_creation() {
+        let runtime = FastRuntime::new();
+        assert!(runtime.config.parallel_namespaces);
+        assert!(runtime.config.use_namespace_cache);
+    }
+
+    #[tokio::test]
+    async fn test_namespace_cache() {
+        let runtime = FastRuntime::new();
+        
+        // Create first namespace
+        let (ns1, _) = runtime.get_cached_namespace().await.unwrap();
+        assert!(ns1 > 0);
+        
+        // Return it to cache by creating a new one
+        let (ns2, _) = runtime.create_namespace_fast().await.unwrap();
+        assert!(ns2 > 0);
+        
+        // Check cache has entries
+        let cache = runtime.namespace_cache.read().await;
+        assert!(!cache.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_container_start() {
+        let runtime = FastRuntime::new();
+        
+        let handle = runtime
+            .start_container("test-container", "alpine:latest", "/bin/sh", vec![])
+            .await
+            .unwrap();
+        
+        assert_eq!(handle.id(), "test-container");
+        assert!(handle.namespace_id() > 0);
+    }
+
+    #[tokio::test]
+    async fn test_start_container_spec_runs_command() {
+        let runtime = FastRuntime::new();
+
+        let mut spec = ContainerSpec::new("alpine:latest", "sh", vec![
+            "-c".to_string(),
+            "echo $GREETING; exit 3".to_string(),
+        ]);
+        spec.env.insert("GREETING".to_string(), "hello".to_string());
+        let handle = runtime.start_container_spec(spec).await.unwrap();
+
+        let result = handle.wait().await.unwrap();
+        assert_eq!(result.stdout, "hello\n");
+        assert_eq!(result.exit_code, 3);
+
+        // The result can only be collected once
+        assert!(handle.wait().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_start_containers_reports_partial_failure() {
+        let runtime = FastRuntime::new();
+        let spec = |id: &str, workdir: &str| {
+            let mut spec = ContainerSpec::new("alpine", "true", vec![]);
+            spec.id = id.to_string();
+            spec.workdir = workdir.to_string();
+            spec
+        };
+
+        let report = runtime
+            .start_containers(vec![
+                spec("batch-ok-1", "/"),
+                spec("batch-bad-1", "/nonexistent/enviro-workdir"),
+                spec("batch-ok-2", "/tmp"),
+                spec("batch-bad-2", "/nonexistent/enviro-workdir"),
+            ])
+            .await;
+
+        assert!(!report.all_succeeded());
+        let started: Vec<&str> = report.succeeded.iter().map(|h| h.id()).collect();
+        assert_eq!(started, ["batch-ok-1", "batch-ok-2"]);
+        let failed: Vec<&str> = report.failed.iter().map(|(spec, _)| spec.id.as_str()).collect();
+        assert_eq!(failed, ["batch-bad-1", "batch-bad-2"]);
+        assert!(report
+            .failed
+            .iter()
+            .all(|(_, e)| matches!(e, RuntimeError::Executor(_))));
+
+        for handle in &report.succeeded {
+            assert_eq!(handle.wait().await.unwrap().exit_code, 0);
+        }
+
+        let report = runtime.start_containers(vec![spec("batch-ok-3", "/")]).await;
+        assert!(report.all_succeeded());
+    }
+
+    #[tokio::test]
+    async fn test_wait_without_command() {
+        let runtime = FastRuntime::new();
+        let handle = runtime
+            .start_container("test-container", "alpine:latest", "/bin/sh", vec![])
+            .await
+            .unwrap();
+        assert!(handle.wait().await.is_err());
+    }
+
+    #[test]
+    fn test_execution_context_uses_spec_profile_and_network() {
+        let defaults = FastRuntime::execution_context("a", HashMap::new(), "/".to_string(), None, None);
+        assert_eq!(defaults.limits.pid_limit, 100);
+        assert!(defaults.network.isolated);
+
+        let network = NetworkConfig {
+            isolated: false,
+            ip_address: Some("10.88.0.2".to_string()),
+            dns_servers: vec![],
+            port_mappings: vec![],
+        };
+        let ctx = FastRuntime::execution_context(
+            "b",
+            HashMap::new(),
+            "/".to_string(),
+            Some(&ResourceProfile::Minimal),
+            Some(network),
+        );
+        assert_eq!(ctx.limits.memory_bytes, 128 * 1024 * 1024);
+        assert_eq!(ctx.limits.pid_limit, 64);
+        assert_eq!(ctx.limits.cpu_cores, 0.5);
+        assert!(!ctx.network.isolated);
+        assert_eq!(ctx.network.ip_address.as_deref(), Some("10.88.0.2"));
+    }
+
+    #[tokio::test]
+    async fn test_container_spec_ids_are_unique() {
         let runtime = FastRuntime::new();
+        let spec = ContainerSpec::new("alpine", "true", vec![]);
+        assert_eq!(spec.workdir, "/");
+
+        let a = runtime.start_container_spec(spec.clone()).await.unwrap();
+        let b = runtime.start_container_spec(spec).await.unwrap();
+        assert!(!a.id().is_empty());
+        assert_ne!(a.id(), b.id());
+    }
+
+    #[tokio::test]
+    async fn test_list_containers_tracks_state() {
+        let runtime = FastRuntime::new();
+
+        let running = runtime
+            .start_container("running", "alpine", "/bin/sh", vec![])
+            .await
+            .unwrap();
+        let stopped = runtime
+            .start_container("stopped", "alpine", "/bin/sh", vec![])
+            .await
+            .unwrap();
+        stopped.stop().await.unwrap();
+        let mut spec = ContainerSpec::new("busybox", "sh", vec!["-c".to_string(), "exit 2".to_string()]);
+        spec.id = "exited".to_string();
+        runtime.start_container_spec(spec).await.unwrap().wait().await.unwrap();
+
+        let containers = runtime.list_containers().await;
+        let ids: Vec<&str> = containers.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["running", "stopped", "exited"]);
+        assert_eq!(containers[0].state, ContainerState::Running);
+        assert_eq!(containers[0].namespace_id, running.namespace_id());
+        assert_eq!(containers[1].state, ContainerState::Stopped);
+        assert_eq!(containers[2].state, ContainerState::Exited { code: 2 });
+        assert_eq!(containers[2].image, "busybox");
+    }
+
+    #[tokio::test]
+    async fn test_stop_container_by_id() {
+        let runtime = FastRuntime::new();
+        runtime
+            .start_container("web", "alpine", "/bin/sh", vec![])
+            .await
+            .unwrap();
+
+        runtime.stop_container("web").await.unwrap();
+        assert_eq!(runtime.list_containers().await[0].state, ContainerState::Stopped);
+
+        let err = runtime.stop_container("web").await.unwrap_err();
+        assert!(err.to_string().contains("not running"));
+        let err = runtime.stop_container("missing").await.unwrap_err();
+        assert!(err.to_string().contains("No such container"));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_clears_registry() {
+        let runtime = FastRuntime::new();
+        for i in 0..3 {
+            runtime
+                .start_container(&format!("container-{}", i), "alpine", "/bin/sh", vec![])
+                .await
+                .unwrap();
+        }
+        runtime.stop_container("container-0").await.unwrap();
+
+        runtime.shutdown().await.unwrap();
+
+        assert!(runtime.list_containers().await.is_empty());
+        assert!(runtime.namespace_cache.read().await.is_empty());
+        // The two containers still running were stopped on the way out
+        assert_eq!(runtime.metrics().snapshot().container_stops, 3);
+
+        // Shutting down an idle runtime is a no-op
+        runtime.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_with_exited_container() {
+        let runtime = FastRuntime::new();
+        let exited = runtime
+            .start_container_spec(ContainerSpec::new("alpine", "true", vec![]))
+            .await
+            .unwrap();
+        exited.wait().await.unwrap();
+        let _running = start_script(&runtime, "exec sleep 30").await;
+
+        // The exited container would fail stop_container with NotRunning
+        assert!(matches!(
+            runtime.stop_container(exited.id()).await,
+            Err(RuntimeError::NotRunning { .. })
+        ));
+        runtime.shutdown().await.unwrap();
+
+        assert!(runtime.list_containers().await.is_empty());
+        assert_eq!(runtime.metrics().snapshot().container_stops, 1);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_stops_containers_concurrently() {
+        let grace = Duration::from_millis(300);
+        let runtime = FastRuntime::with_config(FastStartConfig {
+            stop_grace_period: grace,
+            ..Default::default()
+        });
+        for _ in 0..4 {
+            start_script(&runtime, "trap '' TERM; exec sleep 30").await;
+        }
+
+        let start = Instant::now();
+        runtime.shutdown().await.unwrap();
+        let elapsed = start.elapsed();
+        assert!(elapsed >= grace);
+        // One at a time would take 4 grace periods
+        assert!(elapsed < grace * 2, "shutdown took {:?}", elapsed);
+        assert!(runtime.list_containers().await.is_empty());
+        assert_eq!(runtime.metrics().snapshot().stop_failures.timeout, 4);
+    }
+
+    #[test]
+    fn test_container_state_display() {
+        assert_eq!(ContainerState::Running.to_string(), "running");
+        assert_eq!(ContainerState::Exited { code: 1 }.to_string(), "exited (1)");
+        assert_eq!(ContainerState::Stopped.to_string(), "stopped");
+    }
+
+    #[tokio::test]
+    async fn test_container_stop() {
+        let runtime = FastRuntime::new();
+        
+        let handle = runtime
+            .start_container("test-container", "alpine:latest", "/bin/sh", vec![])
+            .await
+            .unwrap();
+        
+        // Stop should complete without error
+        handle.stop().await.unwrap();
+        
+        // Check metrics
+        let snapshot = runtime.metrics().snapshot();
+        assert_eq!(snapshot.container_starts, 1);
+        assert_eq!(snapshot.container_stops, 1);
+    }
+
+    #[tokio::test]
+    async fn test_parallel_namespace_setup() {
+        let config = FastStartConfig {
+            parallel_namespaces: true,
+            namespaces: NamespaceSetupConfig::default(),
+            use_namespace_cache: false,
+            prewarm_executors: false,
+            prewarm_count: 0,
+            restart_backoff: Duration::from_millis(100),
+            host_capacity: None,
+            stop_grace_period: Duration::from_secs(10),
+            log_dir: None,
+            cgroup_root: None,
+            max_cached_namespaces: 10,
+        };
+        
+        let runtime = FastRuntime::with_config(config);
         
+        let start = std::time::Instant::now();
+        let _ns_id = runtime.create_namespace_fast().await.unwrap();
+        let duration = start.elapsed();
+        
+        // Parallel setup should be faster than 50ms
+        assert!(duration.as_millis() < 50);
+    }
+
+    async fn wait_for_prewarm(runtime: &FastRuntime, count: usize) {
+        for _ in 0..100 {
+            if runtime.prewarmed_executors().await == count {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!("executor pool never reached {} executors", count);
+    }
+
+    #[tokio::test]
+    async fn test_prewarmed_executors_are_reused() {
+        let runtime = FastRuntime::with_config(FastStartConfig {
+            prewarm_count: 2,
+            ..FastStartConfig::default()
+        });
+        wait_for_prewarm(&runtime, 2).await;
+
+        for _ in 0..3 {
+            let handle = run_to_exit(&runtime, "true").await;
+            assert_eq!(handle.logs().await.unwrap(), "");
+        }
+        let snapshot = runtime.metrics().snapshot();
+        assert_eq!(snapshot.prewarm_hits, 3);
+        assert_eq!(snapshot.prewarm_misses, 0);
+        assert_eq!(runtime.prewarmed_executors().await, 2);
+
+        // Concurrent starts drain the pool; the overflow gets a fresh one.
+        // They keep running so none returns its executor before the third start.
+        let mut handles = Vec::new();
+        for _ in 0..3 {
+            let spec = ContainerSpec::new("alpine", "sleep", vec!["0.5".to_string()]);
+            handles.push(runtime.start_container_spec(spec).await.unwrap());
+        }
+        let snapshot = runtime.metrics().snapshot();
+        assert_eq!(snapshot.prewarm_hits, 5);
+        assert_eq!(snapshot.prewarm_misses, 1);
+
+        for handle in handles {
+            handle.wait().await.unwrap();
+        }
+        // Refilled only up to the configured size
+        assert_eq!(runtime.prewarmed_executors().await, 2);
+
+        runtime.shutdown().await.unwrap();
+        assert_eq!(runtime.prewarmed_executors().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_prewarm_disabled() {
+        let runtime = FastRuntime::with_config(FastStartConfig {
+            prewarm_executors: false,
+            ..FastStartConfig::default()
+        });
+        run_to_exit(&runtime, "true").await;
+
+        let snapshot = runtime.metrics().snapshot();
+        assert_eq!(snapshot.prewarm_hits, 0);
+        assert_eq!(snapshot.prewarm_misses, 0);
+        assert_eq!(runtime.prewarmed_executors().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_start_reports_namespace_setup() {
+        let runtime = FastRuntime::with_config(FastStartConfig {
+            use_namespace_cache: false,
+            prewarm_executors: false,
+            ..FastStartConfig::default()
+        });
         let handle = runtime
-            .start_container("test-container", "alpine:latest", "/bin/sh", vec![])
+            .start_container("reported", "alpine", "true", vec![])
             .await
             .unwrap();
-        
-        assert_eq!(handle.id(), "test-container");
-        assert!(handle.namespace_id() > 0);
+
+        let report = handle.setup_report().expect("fresh namespace has a report");
+        assert_eq!(report.results.len(), 4);
+        assert!(report.all_succeeded());
+        let snapshot = runtime.metrics().snapshot();
+        assert_eq!(snapshot.namespace_creates, 1);
+        assert_eq!(snapshot.namespace_create_failures, 0);
     }
 
     #[tokio::test]
-    async fn test_container_stop() {
-        let runtime = FastRuntime::new();
-        
-        let handle = runtime
-            .start_container("test-container", "alpine:latest", "/bin/sh", vec![])
+    async fn test_namespace_config_from_start_config() {
+        use crate::engine::parallel_setup::NamespaceKind;
+
+        for parallel_namespaces in [true, false] {
+            let runtime = FastRuntime::with_config(FastStartConfig {
+                parallel_namespaces,
+                namespaces: NamespaceSetupConfig {
+                    user: true,
+                    network: false,
+                    mount: true,
+                    pid: false,
+                },
+                use_namespace_cache: false,
+                prewarm_executors: false,
+                ..FastStartConfig::default()
+            });
+            let (_, report) = runtime.create_namespace_fast().await.unwrap();
+
+            let kinds: Vec<_> = report.results.iter().map(|r| r.kind).collect();
+            assert_eq!(kinds, vec![NamespaceKind::User, NamespaceKind::Mount]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_namespace_ids_per_runtime() {
+        let config = FastStartConfig {
+            use_namespace_cache: false,
+            prewarm_executors: false,
+            ..FastStartConfig::default()
+        };
+        let counter = || -> Arc<dyn IdGenerator> { Arc::new(CounterGenerator::new()) };
+        let first = FastRuntime::with_id_generator(config.clone(), counter());
+        let second = FastRuntime::with_id_generator(config, counter());
+
+        let mut ids = Vec::new();
+        for runtime in [&first, &second, &first, &second] {
+            ids.push(runtime.create_namespace_fast().await.unwrap().0);
+        }
+        assert_eq!(ids, vec![1, 1, 2, 2]);
+
+        // Clones share their runtime's sequence
+        let clone = (*first).clone();
+        assert_eq!(clone.create_namespace_fast().await.unwrap().0, 3);
+        assert_eq!(first.create_namespace_fast().await.unwrap().0, 4);
+    }
+
+    #[tokio::test]
+    async fn test_failed_namespace_fails_start() {
+        use crate::engine::parallel_setup::NamespaceKind;
+
+        let runtime = FastRuntime {
+            config: FastStartConfig {
+                prewarm_executors: false,
+                ..FastStartConfig::default()
+            },
+            namespace_setup: Arc::new(
+                ParallelNamespaceSetup::new(NamespaceSetupConfig::default())
+                    .failing(NamespaceKind::Network),
+            ),
+            ..FastRuntime::default()
+        };
+
+        let Err(err) = runtime
+            .start_container_spec(ContainerSpec::new("alpine", "true", vec![]))
             .await
-            .unwrap();
-        
-        // Stop should complete without error
+        else {
+            panic!("start succeeded despite a failed namespace");
+        };
+        assert!(matches!(err, RuntimeError::Namespace(_)));
+        assert_eq!(
+            err.to_string(),
+            "Namespace setup failed (network: network namespace setup denied)"
+        );
+
+        let snapshot = runtime.metrics().snapshot();
+        assert_eq!(snapshot.namespace_creates, 0);
+        assert_eq!(snapshot.namespace_create_failures, 1);
+        assert_eq!(snapshot.container_start_failures, 1);
+        assert!(runtime.list_containers().await.is_empty());
+        // A failed namespace is never cached for reuse
+        assert!(runtime.namespace_cache.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_panicking_namespace_fails_start() {
+        use crate::engine::parallel_setup::NamespaceKind;
+
+        let runtime = FastRuntime {
+            config: FastStartConfig {
+                prewarm_executors: false,
+                ..FastStartConfig::default()
+            },
+            namespace_setup: Arc::new(
+                ParallelNamespaceSetup::new(NamespaceSetupConfig::default())
+                    .panicking(NamespaceKind::Mount),
+            ),
+            ..FastRuntime::default()
+        };
+
+        let Err(err) = runtime
+            .start_container_spec(ContainerSpec::new("alpine", "true", vec![]))
+            .await
+        else {
+            panic!("start succeeded despite a panicking namespace");
+        };
+        assert!(matches!(err, RuntimeError::Namespace(_)));
+        assert_eq!(
+            err.to_string(),
+            "Namespace setup failed (mount: setup panicked: mount namespace setup exploded)"
+        );
+        assert!(runtime.list_containers().await.is_empty());
+    }
+
+    async fn start_script(runtime: &FastRuntime, script: &str) -> ContainerHandle {
+        let spec = ContainerSpec::new("alpine", "sh", vec!["-c".to_string(), script.to_string()]);
+        let handle = runtime.start_container_spec(spec).await.unwrap();
+        while handle.pid.load(Ordering::Acquire) == 0 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        handle
+    }
+
+    #[tokio::test]
+    async fn test_stop_terminates_command() {
+        let runtime = FastRuntime::new();
+        let handle = start_script(&runtime, "exec sleep 30").await;
+
         handle.stop().await.unwrap();
-        
-        // Check metrics
+        let result = handle.wait().await.unwrap();
+        assert_eq!(result.termination, Termination::Signaled(15));
+
         let snapshot = runtime.metrics().snapshot();
-        assert_eq!(snapshot.container_starts, 1);
         assert_eq!(snapshot.container_stops, 1);
+        assert_eq!(snapshot.container_stop_failures, 0);
     }
 
     #[tokio::test]
-    async fn test_parallel_namespace_setup() {
+    async fn test_stop_kills_after_grace_period() {
+        let runtime = FastRuntime::new();
+        let handle = start_script(&runtime, "trap '' TERM; exec sleep 30").await;
+
+        let start = Instant::now();
+        handle.stop_with_grace(Duration::from_millis(100)).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        let result = handle.wait().await.unwrap();
+        assert_eq!(result.termination, Termination::Signaled(9));
+
+        let snapshot = runtime.metrics().snapshot();
+        assert_eq!(snapshot.container_stops, 0);
+        assert_eq!(snapshot.stop_failures.timeout, 1);
+    }
+
+    #[tokio::test]
+    async fn test_stop_container_by_id_signals_command() {
+        let runtime = FastRuntime::new();
+        let handle = start_script(&runtime, "exec sleep 30").await;
+
+        runtime.stop_container(handle.id()).await.unwrap();
+        let result = handle.wait().await.unwrap();
+        assert_eq!(result.termination, Termination::Signaled(15));
+        assert_eq!(runtime.list_containers().await[0].state, ContainerState::Stopped);
+    }
+
+    fn next_event(events: &mut broadcast::Receiver<ContainerEvent>) -> (String, EventKind) {
+        let event = events.try_recv().expect("expected an event");
+        (event.id, event.kind)
+    }
+
+    #[tokio::test]
+    async fn test_events_follow_lifecycle() {
+        let runtime = FastRuntime::new();
+        let mut events = runtime.subscribe();
+
+        let stopped = start_script(&runtime, "exec sleep 30").await;
+        let Err(_) = runtime.start_container(stopped.id(), "alpine", "true", vec![]).await else {
+            panic!("started a second container with the same id");
+        };
+        stopped.stop().await.unwrap();
+        let exited = run_to_exit(&runtime, "exit 3").await;
+
+        let stopped = stopped.id().to_string();
+        let exited = exited.id().to_string();
+        assert_eq!(next_event(&mut events), (stopped.clone(), EventKind::Started));
+        assert_eq!(
+            next_event(&mut events),
+            (
+                stopped.clone(),
+                EventKind::Failed { reason: format!("Container {} already exists", stopped) }
+            )
+        );
+        assert_eq!(next_event(&mut events), (stopped, EventKind::Stopped));
+        assert_eq!(next_event(&mut events), (exited.clone(), EventKind::Started));
+        assert_eq!(next_event(&mut events), (exited, EventKind::Exited { code: 3 }));
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_events_only_reach_later_subscribers() {
+        let runtime = FastRuntime::new();
+        let early = start_script(&runtime, "exec sleep 30").await;
+
+        let mut events = runtime.subscribe();
+        early.kill().await.unwrap();
+        run_to_exit(&runtime, "kill -9 $$").await;
+
+        assert_eq!(next_event(&mut events).1, EventKind::Stopped);
+        assert_eq!(next_event(&mut events).1, EventKind::Started);
+        assert_eq!(next_event(&mut events).1, EventKind::OomKilled);
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_sample_usage_records_and_emits() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join("cgroup.controllers"), "cpu memory pids").unwrap();
+        let cgroup = Cgroup::create_in(root.path(), "web-1").unwrap();
+        std::fs::write(cgroup.path().join("memory.current"), "1048576\n").unwrap();
+        std::fs::write(
+            cgroup.path().join("cpu.stat"),
+            "usage_usec 300\nuser_usec 200\nsystem_usec 100\n",
+        )
+        .unwrap();
+        std::fs::write(cgroup.path().join("pids.current"), "2\n").unwrap();
+
+        let runtime = FastRuntime::default();
+        let mut events = runtime.subscribe();
+        let usage = runtime.sample_usage("web-1", &cgroup).unwrap();
+
+        assert_eq!(usage.memory_current, 1_048_576);
+        assert_eq!(usage.cpu.user_usec, 200);
+        assert_eq!(next_event(&mut events), ("web-1".to_string(), EventKind::Usage(usage)));
+        assert_eq!(runtime.metrics().snapshot().peak_memory_bytes, 1_048_576);
+
+        std::fs::remove_file(cgroup.path().join("cpu.stat")).unwrap();
+        assert!(runtime.sample_usage("web-1", &cgroup).is_err());
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_check_oom_kills_emits_on_increment() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join("cgroup.controllers"), "memory").unwrap();
+        let cgroup = Cgroup::create_in(root.path(), "web-1").unwrap();
+        let events_file = cgroup.path().join("memory.events");
+
+        let runtime = FastRuntime::default();
+        let mut events = runtime.subscribe();
+        std::fs::write(&events_file, "oom 0\noom_kill 0\n").unwrap();
+        assert_eq!(runtime.check_oom_kills("web-1", &cgroup).unwrap(), 0);
+        assert!(events.try_recv().is_err());
+
+        std::fs::write(&events_file, "oom 1\noom_kill 1\n").unwrap();
+        assert_eq!(runtime.check_oom_kills("web-1", &cgroup).unwrap(), 1);
+        assert_eq!(next_event(&mut events), ("web-1".to_string(), EventKind::OomKilled));
+        assert_eq!(runtime.check_oom_kills("web-1", &cgroup).unwrap(), 0);
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_container_id_rejected() {
+        let runtime = FastRuntime::new();
+        let running = runtime.start_container("dup", "alpine", "true", vec![]).await.unwrap();
+
+        let Err(err) = runtime.start_container("dup", "alpine", "true", vec![]).await else {
+            panic!("started a second container with the same id");
+        };
+        assert!(matches!(&err, RuntimeError::DuplicateContainer(id) if id == "dup"));
+        assert_eq!(err.to_string(), "Container dup already exists");
+        assert_eq!(runtime.list_containers().await.len(), 1);
+
+        // The id is free again once the first container is stopped
+        running.stop().await.unwrap();
+        runtime.start_container("dup", "alpine", "true", vec![]).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stop_container_error_variants() {
+        let runtime = FastRuntime::new();
+        assert!(matches!(
+            runtime.stop_container("missing").await,
+            Err(RuntimeError::NotFound(id)) if id == "missing"
+        ));
+
+        runtime.start_container("once", "alpine", "true", vec![]).await.unwrap();
+        runtime.stop_container("once").await.unwrap();
+        let err = runtime.stop_container("once").await.unwrap_err();
+        assert!(matches!(
+            err,
+            RuntimeError::NotRunning { state: ContainerState::Stopped, .. }
+        ));
+        assert_eq!(err.to_string(), "Container once is not running (stopped)");
+    }
+
+    fn with_capacity(total_cpu_cores: f64, total_memory_bytes: u64) -> Arc<FastRuntime> {
+        FastRuntime::with_config(FastStartConfig {
+            prewarm_executors: false,
+            host_capacity: Some(HostCapacity {
+                total_cpu_cores,
+                total_memory_bytes,
+            }),
+            ..FastStartConfig::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_start_rejected_past_host_capacity() {
+        const MIB: u64 = 1024 * 1024;
+        // Default limits are 1 core and 512MB: room for two containers
+        let runtime = with_capacity(2.0, 1024 * MIB);
+        let first = runtime.start_container("a", "alpine", "true", vec![]).await.unwrap();
+        runtime.start_container("b", "alpine", "true", vec![]).await.unwrap();
+
+        let Err(err) = runtime.start_container("c", "alpine", "true", vec![]).await else {
+            panic!("third container fit on a full host");
+        };
+        let RuntimeError::CapacityExceeded(err) = err else {
+            panic!("unexpected error: {}", err);
+        };
+        assert_eq!(
+            err,
+            CapacityExceeded {
+                container_id: "c".to_string(),
+                requested_cpu_cores: 1.0,
+                requested_memory_bytes: 512 * MIB,
+                available_cpu_cores: 0.0,
+                available_memory_bytes: 0,
+            }
+        );
+        assert!(err.to_string().starts_with("Host capacity exceeded starting c"));
+        assert_eq!(runtime.metrics().snapshot().container_start_failures, 1);
+        assert_eq!(runtime.list_containers().await.len(), 2);
+
+        first.stop().await.unwrap();
+        runtime.start_container("c", "alpine", "true", vec![]).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reservation_follows_profile_and_wait() {
+        // Minimal is 0.5 cores and 128MB; memory is the binding limit here
+        let runtime = with_capacity(8.0, 200 * 1024 * 1024);
+        let mut spec = ContainerSpec::new("alpine", "true", vec![]);
+        spec.profile = Some(ResourceProfile::Minimal);
+        let handle = runtime.start_container_spec(spec.clone()).await.unwrap();
+
+        spec.id = "second".to_string();
+        let Err(err) = runtime.start_container_spec(spec.clone()).await else {
+            panic!("second container fit on a full host");
+        };
+        assert!(matches!(err, RuntimeError::CapacityExceeded(_)));
+
+        // Exiting releases the reservation once the container is waited on
+        handle.wait().await.unwrap();
+        runtime.start_container_spec(spec).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failed_start_releases_reservation() {
+        let runtime = with_capacity(1.0, 512 * 1024 * 1024);
+        let mut spec = ContainerSpec::new("alpine", "true", vec![]);
+        spec.workdir = "/nonexistent/enviro-workdir".to_string();
+        let Err(err) = runtime.start_container_spec(spec).await else {
+            panic!("started with a missing workdir");
+        };
+        assert!(matches!(err, RuntimeError::Executor(_)));
+        assert!(err.to_string().starts_with("Failed to prepare executor: Working directory"));
+
+        runtime.start_container("fits", "alpine", "true", vec![]).await.unwrap();
+    }
+
+    fn fast_restarts() -> Arc<FastRuntime> {
+        FastRuntime::with_config(FastStartConfig {
+            prewarm_executors: false,
+            restart_backoff: Duration::from_millis(1),
+            ..FastStartConfig::default()
+        })
+    }
+
+    /// Spec whose command fails `failures` times, then succeeds
+    fn flaky_spec(dir: &std::path::Path, failures: u32, restart: RestartPolicy) -> ContainerSpec {
+        let script = format!(
+            "n=$(($(cat runs 2>/dev/null || echo 0) + 1)); echo $n > runs; echo run $n; [ $n -gt {} ]",
+            failures
+        );
+        let mut spec = ContainerSpec::new("alpine", "sh", vec!["-c".to_string(), script]);
+        spec.workdir = dir.display().to_string();
+        spec.restart = restart;
+        spec
+    }
+
+    #[test]
+    fn test_restart_policy_decisions() {
+        assert!(!RestartPolicy::No.should_restart(1, 0));
+
+        let on_failure = RestartPolicy::OnFailure { max_retries: 2 };
+        assert!(on_failure.should_restart(1, 0));
+        assert!(on_failure.should_restart(-1, 1));
+        assert!(!on_failure.should_restart(1, 2));
+        assert!(!on_failure.should_restart(0, 0));
+
+        assert!(RestartPolicy::Always.should_restart(0, 100));
+        assert_eq!(RestartPolicy::default(), RestartPolicy::No);
+    }
+
+    #[test]
+    fn test_restart_backoff_doubles_up_to_cap() {
+        let base = Duration::from_millis(100);
+        assert_eq!(restart_backoff(base, 0), base);
+        assert_eq!(restart_backoff(base, 3), Duration::from_millis(800));
+        assert_eq!(restart_backoff(base, 40), MAX_RESTART_BACKOFF);
+    }
+
+    #[tokio::test]
+    async fn test_on_failure_restarts_until_success() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime = fast_restarts();
+        let spec = flaky_spec(dir.path(), 3, RestartPolicy::OnFailure { max_retries: 5 });
+
+        let handle = runtime.start_container_spec(spec).await.unwrap();
+        let result = handle.wait().await.unwrap();
+
+        assert_eq!(result.exit_code, 0);
+        assert_eq!(handle.restart_count(), 3);
+        assert_eq!(handle.logs().await.unwrap(), "run 1\nrun 2\nrun 3\nrun 4\n");
+    }
+
+    #[tokio::test]
+    async fn test_on_failure_gives_up_after_max_retries() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime = fast_restarts();
+        let spec = flaky_spec(dir.path(), 5, RestartPolicy::OnFailure { max_retries: 2 });
+
+        let handle = runtime.start_container_spec(spec).await.unwrap();
+        let result = handle.wait().await.unwrap();
+
+        assert_eq!(result.exit_code, 1);
+        assert_eq!(handle.restart_count(), 2);
+        let containers = runtime.list_containers().await;
+        assert_eq!(containers[0].state, ContainerState::Exited { code: 1 });
+    }
+
+    #[tokio::test]
+    async fn test_no_restart_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime = fast_restarts();
+        let spec = flaky_spec(dir.path(), 1, RestartPolicy::No);
+
+        let handle = runtime.start_container_spec(spec).await.unwrap();
+        assert_eq!(handle.wait().await.unwrap().exit_code, 1);
+        assert_eq!(handle.restart_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_always_restarts_until_stopped() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime = fast_restarts();
+        let spec = flaky_spec(dir.path(), 0, RestartPolicy::Always);
+
+        let handle = runtime.start_container_spec(spec).await.unwrap();
+        for _ in 0..100 {
+            if handle.restart_count() >= 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(handle.restart_count() >= 2, "successful runs are restarted too");
+
+        handle.stop().await.unwrap();
+        // The stop may land mid-run and terminate the command
+        let result = handle.wait().await.unwrap();
+        assert!(
+            result.exit_code == 0 || result.termination == Termination::Signaled(15),
+            "unexpected result: {:?}",
+            result
+        );
+        let restarts = handle.restart_count();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(handle.restart_count(), restarts);
+    }
+
+    async fn run_to_exit(runtime: &FastRuntime, script: &str) -> ContainerHandle {
+        let spec = ContainerSpec::new("alpine", "sh", vec!["-c".to_string(), script.to_string()]);
+        let handle = runtime.start_container_spec(spec).await.unwrap();
+        handle.wait().await.unwrap();
+        handle
+    }
+
+    #[tokio::test]
+    async fn test_logs_small_volume() {
+        let runtime = FastRuntime::new();
+        let handle = run_to_exit(&runtime, "echo out; echo err >&2").await;
+
+        assert_eq!(handle.logs().await.unwrap(), "out\nerr\n");
+        // Resumes after what was already read
+        assert_eq!(handle.logs().await.unwrap(), "");
+
+        let idle = runtime
+            .start_container("no-command", "alpine", "true", vec![])
+            .await
+            .unwrap();
+        assert_eq!(idle.logs().await.unwrap(), "");
+    }
+
+    #[tokio::test]
+    async fn test_logs_large_volume_in_chunks() {
+        let runtime = FastRuntime::new();
+        // 200000 bytes: 40000 lines of "line\n"
+        let handle = run_to_exit(&runtime, "yes line | head -n 40000").await;
+        let options = LogReadOptions {
+            chunk_size: Some(64 * 1024),
+        };
+
+        let mut chunks = Vec::new();
+        loop {
+            let chunk = handle.logs_with(options).await.unwrap();
+            if chunk.is_empty() {
+                break;
+            }
+            chunks.push(chunk);
+        }
+        let sizes: Vec<usize> = chunks.iter().map(String::len).collect();
+        assert_eq!(sizes, vec![65536, 65536, 65536, 3392]);
+        assert_eq!(chunks.concat(), "line\n".repeat(40000));
+    }
+
+    #[tokio::test]
+    async fn test_logs_do_not_split_characters() {
+        let runtime = FastRuntime::new();
+        let handle = run_to_exit(&runtime, "printf 'aé€'").await;
+        let chunk = |size| handle.logs_with(LogReadOptions { chunk_size: Some(size) });
+
+        assert_eq!(chunk(2).await.unwrap(), "a");
+        // Smaller than the next character: still returns all of it
+        assert_eq!(chunk(1).await.unwrap(), "é");
+        assert_eq!(chunk(0).await.unwrap(), "€");
+        assert_eq!(chunk(1).await.unwrap(), "");
+    }
+
+    #[tokio::test]
+    async fn test_logs_file_backed() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime = FastRuntime::with_config(FastStartConfig {
+            log_dir: Some(dir.path().join("logs")),
+            ..FastStartConfig::default()
+        });
+        let handle = run_to_exit(&runtime, "echo out; echo err >&2").await;
+
+        let path = dir.path().join("logs").join(format!("{}.log", handle.id()));
+        assert_eq!(std::fs::read_to_string(path).unwrap(), "out\nerr\n");
+        assert_eq!(handle.logs().await.unwrap(), "out\nerr\n");
+        assert_eq!(handle.logs().await.unwrap(), "");
+
+        let mut spec = ContainerSpec::new("alpine", "true", vec![]);
+        spec.id = "../escape".to_string();
+        let Err(err) = runtime.start_container_spec(spec).await else {
+            panic!("start succeeded with an ID outside the log directory");
+        };
+        assert!(matches!(err, RuntimeError::Log(_)), "unexpected error: {err}");
+    }
+
+    #[tokio::test]
+    async fn test_limits_written_to_container_cgroup() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("enviro/limited");
+        std::fs::create_dir_all(&dir).unwrap();
+        for parent in [root.path(), &root.path().join("enviro")] {
+            std::fs::write(parent.join("cgroup.controllers"), "cpu memory pids").unwrap();
+        }
+        // A plain file stands in for cgroupfs, recording what the child wrote
+        std::fs::write(dir.join("cgroup.procs"), "").unwrap();
+        let runtime = FastRuntime::with_config(FastStartConfig {
+            prewarm_executors: false,
+            cgroup_root: Some(root.path().to_path_buf()),
+            ..FastStartConfig::default()
+        });
+
+        let mut spec = ContainerSpec::new("alpine", "true", vec![]);
+        spec.id = "limited".to_string();
+        spec.profile = Some(ResourceProfile::Custom(HashMap::from([
+            ("cpu.max".to_string(), 50_000),
+            ("memory.max".to_string(), 64 * 1024 * 1024),
+            ("pids.max".to_string(), 16),
+        ])));
+        let handle = runtime.start_container_spec(spec).await.unwrap();
+        assert_eq!(handle.wait().await.unwrap().exit_code, 0);
+
+        let read = |file: &str| std::fs::read_to_string(dir.join(file)).unwrap();
+        assert_eq!(read("cpu.max"), "50000");
+        assert_eq!(read("memory.max"), "67108864");
+        assert_eq!(read("pids.max"), "16");
+        assert_eq!(read("cgroup.procs"), "0");
+    }
+
+    #[tokio::test]
+    async fn test_start_fails_without_cgroup_hierarchy() {
+        let root = tempfile::tempdir().unwrap();
+        let runtime = FastRuntime::with_config(FastStartConfig {
+            prewarm_executors: false,
+            cgroup_root: Some(root.path().to_path_buf()),
+            ..FastStartConfig::default()
+        });
+
+        let spec = ContainerSpec::new("alpine", "true", vec![]);
+        let Err(err) = runtime.start_container_spec(spec).await else {
+            panic!("started without a cgroup2 hierarchy");
+        };
+        assert!(matches!(err, RuntimeError::Limits(_)), "unexpected error: {err}");
+        assert!(runtime.list_containers().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ids_minted_by_injected_generator() {
         let config = FastStartConfig {
-            parallel_namespaces: true,
-            use_namespace_cache: false,
             prewarm_executors: false,
-            max_cached_namespaces: 10,
+            use_namespace_cache: false,
+            ..FastStartConfig::default()
         };
-        
-        let runtime = FastRuntime::with_config(config);
-        
-        let start = std::time::Instant::now();
-        let _ns_id = runtime.create_namespace_fast().await.unwrap();
-        let duration = start.elapsed();
-        
-        // Parallel setup should be faster than 50ms
-        assert!(duration.as_millis() < 50);
+        let runtime = FastRuntime::with_id_generator(
+            config,
+            Arc::new(CounterGenerator::with_prefix("minted")),
+        );
+
+        // Specs built with `new` leave the ID to the runtime
+        let spec = ContainerSpec::new("alpine", "true", vec![]);
+        assert!(spec.id.is_empty());
+        let first = runtime.start_container_spec(spec).await.unwrap();
+        assert_eq!(first.id(), "minted-1");
+        assert_eq!(first.namespace_id(), 1);
+
+        let second = runtime.start_container("", "alpine", "true", vec![]).await.unwrap();
+        assert_eq!(second.id(), "minted-2");
+        assert_eq!(second.namespace_id(), 2);
+
+        // Caller-supplied IDs are kept
+        let named = runtime.start_container("named", "alpine", "true", vec![]).await.unwrap();
+        assert_eq!(named.id(), "named");
+    }
+
+    #[tokio::test]
+    async fn test_start_rejects_invalid_network() {
+        let runtime = FastRuntime::new();
+        let mut spec = ContainerSpec::new("alpine", "true", vec![]);
+        spec.network = Some(NetworkConfig {
+            isolated: false,
+            ip_address: Some("10.88.0.300".to_string()),
+            dns_servers: vec![],
+            port_mappings: vec![],
+        });
+
+        let Err(err) = runtime.start_container_spec(spec).await else {
+            panic!("started with an invalid IP address");
+        };
+        assert!(matches!(err, RuntimeError::Network(_)), "unexpected error: {err}");
+        assert!(err.to_string().contains("10.88.0.300"), "unexpected error: {err}");
+    }
+
+    #[tokio::test]
+    async fn test_failed_start_leaves_no_port_forwards() {
+        use crate::executor::{PortMap, Protocol};
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let nft = dir.path().join("nft");
+        std::fs::write(&nft, "#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(&nft, std::fs::Permissions::from_mode(0o755)).unwrap();
+        // A file where the log directory should be makes the log fail
+        let log_dir = dir.path().join("logs");
+        std::fs::write(&log_dir, "").unwrap();
+        let state_dir = dir.path().join("state");
+        let runtime = FastRuntime {
+            config: FastStartConfig {
+                prewarm_executors: false,
+                log_dir: Some(log_dir),
+                ..FastStartConfig::default()
+            },
+            port_forwarder: Arc::new(PortForwarder::new(&state_dir).with_nft(nft)),
+            ..FastRuntime::default()
+        };
+        let mut spec = ContainerSpec::new("alpine", "true", vec![]);
+        spec.network = Some(NetworkConfig {
+            isolated: false,
+            ip_address: Some("10.88.0.2/16".to_string()),
+            dns_servers: vec![],
+            port_mappings: vec![PortMap {
+                host_port: 8080,
+                container_port: 80,
+                protocol: Protocol::Tcp,
+            }],
+        });
+
+        let Err(err) = runtime.start_container_spec(spec).await else {
+            panic!("started without a log");
+        };
+        assert!(matches!(err, RuntimeError::Log(_)), "unexpected error: {err}");
+        let forwards = std::fs::read_dir(&state_dir).map_or(0, |entries| entries.count());
+        assert_eq!(forwards, 0);
+    }
+
+    #[tokio::test]
+    async fn test_logs_file_backed_in_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime = FastRuntime::with_config(FastStartConfig {
+            log_dir: Some(dir.path().to_path_buf()),
+            ..FastStartConfig::default()
+        });
+        let handle = run_to_exit(&runtime, "printf 'aé€'; yes line | head -n 3").await;
+        let chunk = |size| handle.logs_with(LogReadOptions { chunk_size: Some(size) });
+
+        assert_eq!(chunk(2).await.unwrap(), "a");
+        assert_eq!(chunk(1).await.unwrap(), "é");
+        assert_eq!(chunk(4).await.unwrap(), "€l");
+        assert_eq!(chunk(6).await.unwrap(), "ine\nli");
+        assert_eq!(chunk(64).await.unwrap(), "ne\nline\n");
+        assert_eq!(chunk(64).await.unwrap(), "");
+    }
+
+    #[cfg(feature = "io_uring")]
+    #[tokio::test]
+    async fn test_logs_read_through_io_uring() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime = FastRuntime::with_config(FastStartConfig {
+            log_dir: Some(dir.path().to_path_buf()),
+            ..FastStartConfig::default()
+        });
+        let io_uring = runtime.io_uring.clone().expect("io_uring manager for file-backed logs");
+        let handle = run_to_exit(&runtime, "yes line | head -n 2000").await;
+
+        let options = LogReadOptions {
+            chunk_size: Some(4096),
+        };
+        let mut log = String::new();
+        loop {
+            let chunk = handle.logs_with(options).await.unwrap();
+            if chunk.is_empty() {
+                break;
+            }
+            log.push_str(&chunk);
+        }
+        assert_eq!(log, "line\n".repeat(2000));
+        assert!(io_uring.stats().completions > 0);
+    }
+
+    #[test]
+    fn test_utf8_chunk_len() {
+        let data = "aé€".as_bytes();
+        assert_eq!(utf8_chunk_len(data, 100), data.len());
+        assert_eq!(utf8_chunk_len(data, 1), 1);
+        assert_eq!(utf8_chunk_len(data, 2), 1);
+        assert_eq!(utf8_chunk_len(data, 3), 3);
+        assert_eq!(utf8_chunk_len(data, 5), 3);
+        assert_eq!(utf8_chunk_len(&data[1..], 1), 2);
     }
 
     #[tokio::test]
@@ -0,0 +1,988 @@
+//! Runtime Server - Line-Delimited JSON-RPC
+//!
+//! `enviro serve` exposes a [`FastRuntime`] over TCP or a Unix socket so
+//! other processes can start, stop, list and read the logs of containers.
+//! Each request is one JSON-RPC 2.0 object on its own line, and each
+//! response is written back as a single line in the order the requests
+//! arrived:
+//!
+//! ```text
+//! -> {"jsonrpc":"2.0","id":1,"method":"start","params":{"image":"alpine","command":"echo","args":["hi"]}}
+//! <- {"jsonrpc":"2.0","id":1,"result":{"id":"0b6f3c1e-8d2a-4f5b-9c7e-2a1d4e6f8b90","namespace_id":1,"restarts":0}}
+//! -> {"jsonrpc":"2.0","id":2,"method":"logs","params":{"id":"0b6f3c1e-8d2a-4f5b-9c7e-2a1d4e6f8b90"}}
+//! <- {"jsonrpc":"2.0","id":2,"result":"hi\n"}
+//! ```
+//!
+//! | Method  | Params            | Result               |
+//! |---------|-------------------|----------------------|
+//! | `start` | [`StartRequest`]  | [`HandleInfo`]       |
+//! | `stop`  | [`ContainerId`]   | `null`               |
+//! | `list`  | none              | `[`[`ContainerInfo`](crate::ContainerInfo)`]` |
+//! | `logs`  | [`ContainerId`]   | new output since the previous `logs` call |
+//! | `auth`  | [`AuthRequest`]   | `null`               |
+//!
+//! [`Client`] speaks the protocol from the other end; `enviro ps` and
+//! `enviro stop` use it to reach a running `enviro serve`.
+//!
+//! `start` runs arbitrary commands, so the server only listens where its
+//! clients are trusted: a Unix socket only the owner can connect to
+//! ([`Server::bind_unix`]), a loopback address ([`Server::bind`]), or any
+//! address once a token is configured ([`Server::bind_with_token`]). With a
+//! token, every connection must send a matching `auth` before anything else.
+//!
+//! A container's handle is kept for [`EXITED_HANDLE_RETENTION`] after its
+//! command exits, so `logs` can pick up the final output; after that its ID
+//! is unknown to `logs`.
+//!
+//! # Performance Pattern: Connection per Task
+//! Every connection is served by its own task, so a slow client never holds
+//! up another; the runtime's registry is shared between them.
+
+use crate::executor::NetworkConfig;
+use crate::runtime::{
+    ContainerHandle, ContainerInfo, ContainerSpec, FastRuntime, RestartPolicy, RuntimeError,
+};
+use crate::ResourceProfile;
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::{DirBuilder, Permissions};
+use std::net::SocketAddr;
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{
+    AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader, ReadHalf,
+    WriteHalf,
+};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs, UnixListener, UnixStream};
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+
+/// Address `enviro serve` listens on when `--addr` is not given
+pub const DEFAULT_ADDR: &str = "127.0.0.1:7000";
+
+/// Longest request line accepted, newline excluded; a client that sends a
+/// longer one gets an error and is disconnected
+pub const MAX_LINE_LEN: usize = 1024 * 1024;
+
+/// How long a container's handle outlives its command
+pub const EXITED_HANDLE_RETENTION: Duration = Duration::from_secs(60);
+
+/// Pause after a failed `accept`, which is usually fd exhaustion that only
+/// clears once a connection closes
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Error codes defined by JSON-RPC 2.0, plus the one used for runtime errors
+pub mod error_code {
+    /// The line is not valid JSON
+    pub const PARSE_ERROR: i64 = -32700;
+    /// The JSON is not a request object
+    pub const INVALID_REQUEST: i64 = -32600;
+    /// No such method
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    /// The params do not match the method
+    pub const INVALID_PARAMS: i64 = -32602;
+    /// The runtime rejected or failed the request
+    pub const RUNTIME_ERROR: i64 = -32000;
+    /// The connection has not sent a valid `auth` yet, or sent a wrong token
+    pub const UNAUTHORIZED: i64 = -32001;
+}
+
+/// Params of `start`, mirroring [`ContainerSpec`]
+///
+/// Only `image` and `command` are required; the rest default as in
+/// [`ContainerSpec::new`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartRequest {
+    /// Container ID; generated when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Image the container is started from
+    pub image: String,
+    /// Command to run
+    pub command: String,
+    /// Command arguments
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Environment variables
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Working directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workdir: Option<String>,
+    /// Resource profile
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<ResourceProfile>,
+    /// Network settings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<NetworkConfig>,
+    /// Whether the command is run again after it exits
+    #[serde(default)]
+    pub restart: RestartPolicy,
+}
+
+impl StartRequest {
+    /// Build the [`ContainerSpec`] this request describes
+    pub fn into_spec(self) -> ContainerSpec {
+        let mut spec = ContainerSpec::new(self.image, self.command, self.args);
+        if let Some(id) = self.id {
+            spec.id = id;
+        }
+        if let Some(workdir) = self.workdir {
+            spec.workdir = workdir;
+        }
+        spec.env = self.env;
+        spec.profile = self.profile;
+        spec.network = self.network;
+        spec.restart = self.restart;
+        spec
+    }
+}
+
+impl From<ContainerSpec> for StartRequest {
+    fn from(spec: ContainerSpec) -> Self {
+        Self {
+            id: (!spec.id.is_empty()).then_some(spec.id),
+            image: spec.image,
+            command: spec.command,
+            args: spec.args,
+            env: spec.env,
+            workdir: Some(spec.workdir),
+            profile: spec.profile,
+            network: spec.network,
+            restart: spec.restart,
+        }
+    }
+}
+
+/// Result of `start`, mirroring [`ContainerHandle`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HandleInfo {
+    /// Container ID
+    pub id: String,
+    /// Namespace the container runs in
+    pub namespace_id: u64,
+    /// Restarts performed under the container's restart policy
+    pub restarts: u32,
+}
+
+impl From<&ContainerHandle> for HandleInfo {
+    fn from(handle: &ContainerHandle) -> Self {
+        Self {
+            id: handle.id().to_string(),
+            namespace_id: handle.namespace_id(),
+            restarts: handle.restart_count(),
+        }
+    }
+}
+
+/// Params of `stop` and `logs`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContainerId {
+    /// Container ID
+    pub id: String,
+}
+
+/// Params of `auth`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthRequest {
+    /// The token the server was started with
+    pub token: String,
+}
+
+/// A JSON-RPC request line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Request {
+    /// Protocol version, always `"2.0"`
+    pub jsonrpc: String,
+    /// Echoed in the response; `null` when omitted
+    #[serde(default)]
+    pub id: Value,
+    /// `start`, `stop`, `list`, `logs` or `auth`
+    pub method: String,
+    /// Method params
+    #[serde(default)]
+    pub params: Value,
+}
+
+impl Request {
+    /// Build a request for `method` with the given params
+    pub fn new(
+        id: impl Into<Value>,
+        method: impl Into<String>,
+        params: impl Serialize,
+    ) -> Result<Self> {
+        Ok(Self {
+            jsonrpc: "2.0".to_string(),
+            id: id.into(),
+            method: method.into(),
+            params: serde_json::to_value(params)?,
+        })
+    }
+}
+
+/// A JSON-RPC response line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Response {
+    /// Protocol version, always `"2.0"`
+    pub jsonrpc: String,
+    /// ID of the request this answers
+    pub id: Value,
+    /// Set on success
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    /// Set on failure
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl Response {
+    fn success(id: Value, result: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    fn failure(id: Value, error: RpcError) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// Error object of a failed [`Response`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+#[error("{message} (code {code})")]
+pub struct RpcError {
+    /// One of the [`error_code`] constants
+    pub code: i64,
+    /// Human-readable description
+    pub message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<RuntimeError> for RpcError {
+    fn from(err: RuntimeError) -> Self {
+        Self::new(
+            error_code::RUNTIME_ERROR,
+            format!("{:#}", anyhow::Error::from(err)),
+        )
+    }
+}
+
+/// State shared by every connection
+struct Dispatcher {
+    runtime: Arc<FastRuntime>,
+    /// Token every connection must present through `auth`, if any
+    token: Option<String>,
+    /// Handles of containers started through this server, for `logs` and
+    /// `stop`; each is removed `retention` after its command exits
+    handles: Arc<Mutex<HashMap<String, Arc<ContainerHandle>>>>,
+    retention: Duration,
+}
+
+/// Per-connection state
+struct Session {
+    authenticated: bool,
+}
+
+impl Dispatcher {
+    fn new(runtime: Arc<FastRuntime>, token: Option<String>) -> Self {
+        Self {
+            runtime,
+            token,
+            handles: Arc::new(Mutex::new(HashMap::new())),
+            retention: EXITED_HANDLE_RETENTION,
+        }
+    }
+
+    fn session(&self) -> Session {
+        Session {
+            authenticated: self.token.is_none(),
+        }
+    }
+
+    /// Answer one request line
+    async fn handle_line(&self, line: &str, session: &mut Session) -> Response {
+        let request: Value = match serde_json::from_str(line) {
+            Ok(value) => value,
+            Err(e) => {
+                return Response::failure(
+                    Value::Null,
+                    RpcError::new(error_code::PARSE_ERROR, e.to_string()),
+                )
+            }
+        };
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let request = match serde_json::from_value::<Request>(request) {
+            Ok(request) if request.jsonrpc == "2.0" => request,
+            Ok(_) => {
+                let error = RpcError::new(error_code::INVALID_REQUEST, "'jsonrpc' must be \"2.0\"");
+                return Response::failure(id, error);
+            }
+            Err(e) => {
+                return Response::failure(
+                    id,
+                    RpcError::new(error_code::INVALID_REQUEST, e.to_string()),
+                )
+            }
+        };
+
+        debug!("RPC {} (id {})", request.method, request.id);
+        let result = if request.method == "auth" {
+            parse_params(request.params).and_then(|auth| self.auth(auth, session))
+        } else if session.authenticated {
+            self.call(&request.method, request.params).await
+        } else {
+            Err(RpcError::new(error_code::UNAUTHORIZED, "'auth' is required first"))
+        };
+        match result {
+            Ok(result) => Response::success(request.id, result),
+            Err(error) => Response::failure(request.id, error),
+        }
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "start" => to_result(self.start(parse_params(params)?).await?),
+            "stop" => to_result(self.stop(parse_params(params)?).await?),
+            "list" => to_result(self.runtime.list_containers().await),
+            "logs" => to_result(self.logs(parse_params(params)?).await?),
+            other => Err(RpcError::new(
+                error_code::METHOD_NOT_FOUND,
+                format!("unknown method '{}'", other),
+            )),
+        }
+    }
+
+    fn auth(
+        &self,
+        AuthRequest { token }: AuthRequest,
+        session: &mut Session,
+    ) -> Result<Value, RpcError> {
+        let Some(expected) = &self.token else {
+            return Ok(Value::Null);
+        };
+        session.authenticated = tokens_match(expected.as_bytes(), token.as_bytes());
+        if session.authenticated {
+            Ok(Value::Null)
+        } else {
+            Err(RpcError::new(error_code::UNAUTHORIZED, "invalid token"))
+        }
+    }
+
+    async fn start(&self, request: StartRequest) -> Result<HandleInfo, RpcError> {
+        let handle = self
+            .runtime
+            .start_container_spec(request.into_spec())
+            .await?;
+        let info = HandleInfo::from(&handle);
+        let handle = Arc::new(handle);
+        self.handles
+            .lock()
+            .await
+            .insert(info.id.clone(), Arc::clone(&handle));
+
+        // Forget the handle once the command is done for good; the ID may
+        // have been reused by then, so only this handle is removed
+        let handles = Arc::clone(&self.handles);
+        let retention = self.retention;
+        tokio::spawn(async move {
+            let _ = handle.wait().await;
+            tokio::time::sleep(retention).await;
+            let mut handles = handles.lock().await;
+            if handles.get(handle.id()).is_some_and(|h| Arc::ptr_eq(h, &handle)) {
+                handles.remove(handle.id());
+            }
+        });
+        Ok(info)
+    }
+
+    async fn stop(&self, ContainerId { id }: ContainerId) -> Result<(), RpcError> {
+        let handle = self.handles.lock().await.get(&id).cloned();
+        match handle {
+            // The handle also knows the command's PID, so use it when we have it
+            Some(handle) => handle
+                .stop()
+                .await
+                .map_err(|e| RpcError::new(error_code::RUNTIME_ERROR, format!("{:#}", e))),
+            None => Ok(self.runtime.stop_container(&id).await?),
+        }
+    }
+
+    async fn logs(&self, ContainerId { id }: ContainerId) -> Result<String, RpcError> {
+        let handle = self.handles.lock().await.get(&id).cloned();
+        let handle = handle.ok_or_else(|| RpcError::from(RuntimeError::NotFound(id)))?;
+        handle
+            .logs()
+            .await
+            .map_err(|e| RpcError::new(error_code::RUNTIME_ERROR, format!("{:#}", e)))
+    }
+}
+
+fn parse_params<T: for<'de> Deserialize<'de>>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params)
+        .map_err(|e| RpcError::new(error_code::INVALID_PARAMS, e.to_string()))
+}
+
+fn to_result(value: impl Serialize) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|e| RpcError::new(error_code::RUNTIME_ERROR, e.to_string()))
+}
+
+/// Compare tokens in time independent of where they differ
+fn tokens_match(expected: &[u8], given: &[u8]) -> bool {
+    expected.len() == given.len()
+        && expected
+            .iter()
+            .zip(given)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// JSON-RPC server in front of a [`FastRuntime`]
+pub struct Server {
+    listener: Listener,
+    dispatcher: Arc<Dispatcher>,
+}
+
+enum Listener {
+    Tcp(TcpListener),
+    Unix(UnixSocket),
+}
+
+/// A bound Unix socket, removed from the filesystem when dropped
+struct UnixSocket {
+    listener: UnixListener,
+    path: PathBuf,
+}
+
+impl Drop for UnixSocket {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+impl Server {
+    /// Bind to a loopback `addr`; port 0 picks a free port, see
+    /// [`local_addr`](Self::local_addr)
+    ///
+    /// Fails for any other address, since anyone who can connect can run
+    /// commands; use [`bind_with_token`](Self::bind_with_token) for those.
+    pub async fn bind(addr: impl ToSocketAddrs, runtime: Arc<FastRuntime>) -> Result<Self> {
+        let listener = bind_tcp(addr).await?;
+        let local = listener.local_addr()?;
+        if !local.ip().is_loopback() {
+            anyhow::bail!(
+                "Refusing to serve on non-loopback address {} without a token",
+                local
+            );
+        }
+        Ok(Self::new(Listener::Tcp(listener), runtime, None))
+    }
+
+    /// Bind to `addr`, requiring every connection to `auth` with `token`
+    pub async fn bind_with_token(
+        addr: impl ToSocketAddrs,
+        runtime: Arc<FastRuntime>,
+        token: impl Into<String>,
+    ) -> Result<Self> {
+        let token = token.into();
+        anyhow::ensure!(!token.is_empty(), "The RPC server token must not be empty");
+        let listener = bind_tcp(addr).await?;
+        Ok(Self::new(Listener::Tcp(listener), runtime, Some(token)))
+    }
+
+    /// Listen on a Unix socket at `path` that only the current user can
+    /// connect to
+    ///
+    /// A stale socket left at `path` is replaced; anything else there, or a
+    /// server still accepting on it, is an error. The socket is removed
+    /// when the server is dropped.
+    pub async fn bind_unix(path: impl AsRef<Path>, runtime: Arc<FastRuntime>) -> Result<Self> {
+        let path = path.as_ref();
+        let listener = bind_private_socket(path)
+            .with_context(|| format!("Failed to bind the RPC server to {}", path.display()))?;
+        let socket = UnixSocket {
+            listener,
+            path: path.to_path_buf(),
+        };
+        Ok(Self::new(Listener::Unix(socket), runtime, None))
+    }
+
+    fn new(listener: Listener, runtime: Arc<FastRuntime>, token: Option<String>) -> Self {
+        Self {
+            listener,
+            dispatcher: Arc::new(Dispatcher::new(runtime, token)),
+        }
+    }
+
+    /// Address the server is listening on; fails for a Unix socket
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        match &self.listener {
+            Listener::Tcp(listener) => Ok(listener.local_addr()?),
+            Listener::Unix(socket) => {
+                anyhow::bail!("RPC server is on Unix socket {}", socket.path.display())
+            }
+        }
+    }
+
+    /// Path of the Unix socket the server is listening on, if it is on one
+    pub fn socket_path(&self) -> Option<&Path> {
+        match &self.listener {
+            Listener::Tcp(_) => None,
+            Listener::Unix(socket) => Some(&socket.path),
+        }
+    }
+
+    /// Accept connections until the task is dropped
+    ///
+    /// A failed `accept` is logged and retried rather than ending the
+    /// server.
+    pub async fn run(self) -> Result<()> {
+        match &self.listener {
+            Listener::Tcp(listener) => info!("RPC server listening on {}", listener.local_addr()?),
+            Listener::Unix(socket) => info!("RPC server listening on {}", socket.path.display()),
+        }
+        loop {
+            let accepted = match &self.listener {
+                Listener::Tcp(listener) => listener
+                    .accept()
+                    .await
+                    .map(|(stream, peer)| self.spawn_connection(stream, peer.to_string())),
+                Listener::Unix(socket) => socket
+                    .listener
+                    .accept()
+                    .await
+                    .map(|(stream, _)| self.spawn_connection(stream, "Unix socket".to_string())),
+            };
+            if let Err(e) = accepted {
+                warn!("Failed to accept an RPC connection: {}", e);
+                tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+            }
+        }
+    }
+
+    fn spawn_connection<S>(&self, stream: S, peer: String)
+    where
+        S: AsyncRead + AsyncWrite + Send + 'static,
+    {
+        debug!("RPC connection from {}", peer);
+        let dispatcher = Arc::clone(&self.dispatcher);
+        tokio::spawn(async move {
+            if let Err(e) = serve_connection(stream, &dispatcher).await {
+                warn!("RPC connection from {} failed: {:#}", peer, e);
+            }
+        });
+    }
+}
+
+async fn bind_tcp(addr: impl ToSocketAddrs) -> Result<TcpListener> {
+    TcpListener::bind(addr)
+        .await
+        .context("Failed to bind the RPC server")
+}
+
+/// Bind a Unix socket at `path` with mode 0600
+///
+/// The socket is bound and chmod'ed inside a fresh 0700 directory and then
+/// renamed into place, so it is never reachable with the looser mode the
+/// umask would give it.
+fn bind_private_socket(path: &Path) -> Result<UnixListener> {
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if !meta.file_type().is_socket() => {
+            anyhow::bail!("{} exists and is not a socket", path.display())
+        }
+        Ok(_) if std::os::unix::net::UnixStream::connect(path).is_ok() => {
+            anyhow::bail!("Another server is listening on {}", path.display())
+        }
+        _ => {}
+    }
+
+    let name = path.file_name().context("Socket path has no file name")?;
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let staging = parent.join(format!(
+        ".{}.{}.tmp",
+        name.to_string_lossy(),
+        std::process::id()
+    ));
+    DirBuilder::new()
+        .mode(0o700)
+        .create(&staging)
+        .with_context(|| format!("Failed to create {}", staging.display()))?;
+
+    let staged = staging.join("socket");
+    let bound = UnixListener::bind(&staged)
+        .map_err(anyhow::Error::from)
+        .and_then(|listener| {
+            std::fs::set_permissions(&staged, Permissions::from_mode(0o600))?;
+            std::fs::rename(&staged, path)?;
+            Ok(listener)
+        });
+    let _ = std::fs::remove_file(&staged);
+    let _ = std::fs::remove_dir(&staging);
+    bound
+}
+
+/// Answer each request line on `stream` until the client disconnects
+async fn serve_connection<S>(stream: S, dispatcher: &Dispatcher) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut reader = BufReader::new(reader);
+    let mut session = dispatcher.session();
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        // One byte over the limit tells a too-long line from one that fits
+        let read = (&mut reader)
+            .take(MAX_LINE_LEN as u64 + 1)
+            .read_until(b'\n', &mut line)
+            .await?;
+        if read == 0 {
+            return Ok(());
+        }
+        if line.len() > MAX_LINE_LEN && line.last() != Some(&b'\n') {
+            let error = RpcError::new(
+                error_code::INVALID_REQUEST,
+                format!("request line exceeds {} bytes", MAX_LINE_LEN),
+            );
+            write_line(&mut writer, &Response::failure(Value::Null, error)).await?;
+            anyhow::bail!("Request line exceeds {} bytes", MAX_LINE_LEN);
+        }
+
+        let line = String::from_utf8_lossy(&line);
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = dispatcher.handle_line(&line, &mut session).await;
+        write_line(&mut writer, &response).await?;
+    }
+}
+
+async fn write_line(
+    writer: &mut (impl AsyncWrite + Unpin),
+    message: &impl Serialize,
+) -> Result<()> {
+    let mut out = serde_json::to_vec(message)?;
+    out.push(b'\n');
+    writer.write_all(&out).await?;
+    Ok(())
+}
+
+/// A connection a [`Client`] can talk over
+trait Connection: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> Connection for T {}
+
+/// Connection to a [`Server`], one request at a time
+pub struct Client {
+    reader: BufReader<ReadHalf<Box<dyn Connection>>>,
+    writer: WriteHalf<Box<dyn Connection>>,
+    next_id: u64,
+}
+
+impl Client {
+    /// Connect to a server listening on TCP
+    pub async fn connect(addr: impl ToSocketAddrs) -> Result<Self> {
+        let stream = TcpStream::connect(addr).await?;
+        Ok(Self::new(Box::new(stream)))
+    }
+
+    /// Connect to a server listening on the Unix socket at `path`
+    pub async fn connect_unix(path: impl AsRef<Path>) -> Result<Self> {
+        let stream = UnixStream::connect(path).await?;
+        Ok(Self::new(Box::new(stream)))
+    }
+
+    fn new(connection: Box<dyn Connection>) -> Self {
+        let (reader, writer) = tokio::io::split(connection);
+        Self {
+            reader: BufReader::new(reader),
+            writer,
+            next_id: 1,
+        }
+    }
+
+    /// Authenticate the connection with the server's token
+    pub async fn auth(&mut self, token: impl Into<String>) -> Result<()> {
+        let token = token.into();
+        self.call::<Value>("auth", AuthRequest { token }).await?;
+        Ok(())
+    }
+
+    /// Containers in the server's registry
+    pub async fn list(&mut self) -> Result<Vec<ContainerInfo>> {
+        self.call("list", ()).await
+    }
+
+    /// Stop the container `id`
+    pub async fn stop(&mut self, id: impl Into<String>) -> Result<()> {
+        self.call::<Value>("stop", ContainerId { id: id.into() })
+            .await?;
+        Ok(())
+    }
+
+    /// Send `method` and wait for its result; a JSON-RPC error comes back
+    /// as an [`RpcError`]
+    pub async fn call<T: DeserializeOwned>(
+        &mut self,
+        method: &str,
+        params: impl Serialize,
+    ) -> Result<T> {
+        let id = self.next_id;
+        self.next_id += 1;
+        write_line(&mut self.writer, &Request::new(id, method, params)?).await?;
+
+        let mut line = String::new();
+        if self.reader.read_line(&mut line).await? == 0 {
+            anyhow::bail!("RPC server closed the connection");
+        }
+        let response: Response =
+            serde_json::from_str(&line).context("Malformed RPC response")?;
+        if let Some(error) = response.error {
+            return Err(error.into());
+        }
+        Ok(serde_json::from_value(response.result.unwrap_or(Value::Null))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::{ContainerInfo, FastStartConfig};
+
+    fn runtime() -> Arc<FastRuntime> {
+        FastRuntime::with_config(FastStartConfig {
+            prewarm_executors: false,
+            ..Default::default()
+        })
+    }
+
+    fn dispatcher() -> Dispatcher {
+        Dispatcher::new(runtime(), None)
+    }
+
+    /// `handle_line` on a fresh connection
+    async fn handle(dispatcher: &Dispatcher, line: &str) -> Response {
+        dispatcher.handle_line(line, &mut dispatcher.session()).await
+    }
+
+    fn error_code_of(response: &Response) -> i64 {
+        assert!(
+            response.result.is_none(),
+            "unexpected result: {:?}",
+            response.result
+        );
+        response.error.as_ref().expect("error response").code
+    }
+
+    #[tokio::test]
+    async fn test_protocol_errors() {
+        let dispatcher = dispatcher();
+
+        let response = handle(&dispatcher, "{not json").await;
+        assert_eq!(error_code_of(&response), error_code::PARSE_ERROR);
+        assert_eq!(response.id, Value::Null);
+
+        let response = handle(&dispatcher, r#"{"jsonrpc":"1.0","id":1,"method":"list"}"#).await;
+        assert_eq!(error_code_of(&response), error_code::INVALID_REQUEST);
+        assert_eq!(response.id, 1);
+
+        let response = handle(&dispatcher, r#"{"jsonrpc":"2.0","id":2,"method":"pause"}"#).await;
+        assert_eq!(error_code_of(&response), error_code::METHOD_NOT_FOUND);
+
+        let response = handle(
+            &dispatcher,
+            r#"{"jsonrpc":"2.0","id":3,"method":"start","params":{"image":"alpine"}}"#,
+        )
+        .await;
+        assert_eq!(error_code_of(&response), error_code::INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_start_logs_and_stop() {
+        let dispatcher = dispatcher();
+        let spec = ContainerSpec::new("alpine", "echo", vec!["served".to_string()]);
+
+        let start = Request::new(1, "start", StartRequest::from(spec)).unwrap();
+        let response = handle(&dispatcher, &serde_json::to_string(&start).unwrap()).await;
+        let info: HandleInfo = serde_json::from_value(response.result.unwrap()).unwrap();
+        let id = info.id;
+        assert!(!id.is_empty());
+
+        // Output is captured once the command exits
+        let logs = Request::new(2, "logs", ContainerId { id: id.clone() }).unwrap();
+        let logs = serde_json::to_string(&logs).unwrap();
+        let mut output = String::new();
+        for _ in 0..200 {
+            let response = handle(&dispatcher, &logs).await;
+            output.push_str(response.result.unwrap().as_str().unwrap());
+            if !output.is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(output, "served\n");
+
+        let list = handle(&dispatcher, r#"{"jsonrpc":"2.0","id":3,"method":"list"}"#).await;
+        let containers: Vec<ContainerInfo> = serde_json::from_value(list.result.unwrap()).unwrap();
+        assert!(containers.iter().any(|c| c.id == id));
+
+        let stop = Request::new(
+            4,
+            "stop",
+            ContainerId {
+                id: "missing".to_string(),
+            },
+        )
+        .unwrap();
+        let response = handle(&dispatcher, &serde_json::to_string(&stop).unwrap()).await;
+        assert_eq!(error_code_of(&response), error_code::RUNTIME_ERROR);
+        assert!(response.error.unwrap().message.contains("missing"));
+    }
+
+    #[tokio::test]
+    async fn test_token_required_before_other_methods() {
+        let dispatcher = Dispatcher::new(runtime(), Some("s3cret".to_string()));
+        let mut session = dispatcher.session();
+        let list = r#"{"jsonrpc":"2.0","id":1,"method":"list"}"#;
+
+        let response = dispatcher.handle_line(list, &mut session).await;
+        assert_eq!(error_code_of(&response), error_code::UNAUTHORIZED);
+
+        let wrong = r#"{"jsonrpc":"2.0","id":2,"method":"auth","params":{"token":"s3cre"}}"#;
+        let response = dispatcher.handle_line(wrong, &mut session).await;
+        assert_eq!(error_code_of(&response), error_code::UNAUTHORIZED);
+        let response = dispatcher.handle_line(list, &mut session).await;
+        assert_eq!(error_code_of(&response), error_code::UNAUTHORIZED);
+
+        let right = r#"{"jsonrpc":"2.0","id":3,"method":"auth","params":{"token":"s3cret"}}"#;
+        let response = dispatcher.handle_line(right, &mut session).await;
+        assert_eq!(response.result, Some(Value::Null));
+        let response = dispatcher.handle_line(list, &mut session).await;
+        assert!(response.error.is_none(), "error: {:?}", response.error);
+
+        // Authentication belongs to the connection
+        let response = handle(&dispatcher, list).await;
+        assert_eq!(error_code_of(&response), error_code::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_bind_refuses_public_address_without_token() {
+        let err = Server::bind("0.0.0.0:0", runtime()).await.err().unwrap();
+        assert!(err.to_string().contains("non-loopback"), "{:#}", err);
+
+        assert!(Server::bind("127.0.0.1:0", runtime()).await.is_ok());
+        assert!(Server::bind_with_token("0.0.0.0:0", runtime(), "s3cret")
+            .await
+            .is_ok());
+        assert!(Server::bind_with_token("127.0.0.1:0", runtime(), "")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unix_socket_is_private() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("enviro.sock");
+        let server = Server::bind_unix(&path, runtime()).await.unwrap();
+        assert_eq!(server.socket_path(), Some(path.as_path()));
+        assert!(server.local_addr().is_err());
+
+        let meta = std::fs::symlink_metadata(&path).unwrap();
+        assert!(meta.file_type().is_socket());
+        assert_eq!(meta.permissions().mode() & 0o777, 0o600);
+        // Only the socket is left behind in the directory
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        // A second server may not take over a live socket
+        assert!(Server::bind_unix(&path, runtime()).await.is_err());
+
+        let task = tokio::spawn(server.run());
+        let stream = tokio::net::UnixStream::connect(&path).await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        writer
+            .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"list\"}\n")
+            .await
+            .unwrap();
+        let mut line = String::new();
+        BufReader::new(reader).read_line(&mut line).await.unwrap();
+        let response: Response = serde_json::from_str(&line).unwrap();
+        assert_eq!(response.result, Some(serde_json::json!([])));
+
+        task.abort();
+        let _ = task.await;
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_overlong_line_closes_connection() {
+        let dispatcher = Arc::new(dispatcher());
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        let served = {
+            let dispatcher = Arc::clone(&dispatcher);
+            tokio::spawn(async move { serve_connection(server, &dispatcher).await })
+        };
+
+        let (reader, mut writer) = tokio::io::split(client);
+        let line = vec![b'x'; MAX_LINE_LEN + 10];
+        let writing = tokio::spawn(async move {
+            // The server hangs up partway, so the write may fail
+            let _ = writer.write_all(&line).await;
+            let _ = writer.write_all(b"\n").await;
+        });
+
+        let mut reader = BufReader::new(reader);
+        let mut response = String::new();
+        reader.read_line(&mut response).await.unwrap();
+        let response: Response = serde_json::from_str(&response).unwrap();
+        assert_eq!(error_code_of(&response), error_code::INVALID_REQUEST);
+
+        let err = served.await.unwrap().unwrap_err();
+        assert!(err.to_string().contains("exceeds"), "{:#}", err);
+        let mut rest = String::new();
+        assert_eq!(reader.read_line(&mut rest).await.unwrap(), 0);
+        writing.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_handles_removed_after_exit() {
+        let mut dispatcher = dispatcher();
+        dispatcher.retention = Duration::ZERO;
+        let spec = ContainerSpec::new("alpine", "true", vec![]);
+
+        let start = Request::new(1, "start", StartRequest::from(spec)).unwrap();
+        let response = handle(&dispatcher, &serde_json::to_string(&start).unwrap()).await;
+        assert!(response.error.is_none(), "error: {:?}", response.error);
+
+        for _ in 0..500 {
+            if dispatcher.handles.lock().await.is_empty() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("handle was not removed after the container exited");
+    }
+}
@@ -7,10 +7,9 @@
 //! cargo test -p enviro-core --test benchmarks -- --ignored --nocapture
 //! ```
 
-use std::collections::HashMap;
 use std::time::Instant;
 
-use enviro_core::executor::{ExecutionContext, NetworkConfig, ResourceLimits};
+use enviro_core::executor::ExecutionContext;
 use enviro_core::{
     BufferPool, ContextPool, NamespaceCache, NamespaceTemplate, OptimizedResourceLimits,
     ResourceLimitBatch, ResourceProfile,
@@ -21,21 +20,12 @@ use enviro_core::Executor;
 
 /// Helper to build a default [`ExecutionContext`] for benchmarks.
 fn bench_context(id: &str) -> ExecutionContext {
-    ExecutionContext {
-        container_id: id.to_string(),
-        env: HashMap::new(),
-        workdir: "/tmp".to_string(),
-        limits: ResourceLimits {
-            cpu_cores: 1.0,
-            memory_bytes: 256 * 1024 * 1024,
-            pid_limit: 128,
-        },
-        network: NetworkConfig {
-            isolated: true,
-            ip_address: None,
-            dns_servers: vec![],
-        },
-    }
+    ExecutionContext::builder(id)
+        .workdir("/tmp")
+        .memory_bytes(256 * 1024 * 1024)
+        .pid_limit(128)
+        .build()
+        .unwrap()
 }
 
 // ---------------------------------------------------------------------------
@@ -233,7 +223,7 @@ fn bench_resource_profile_apply() {
 #[ignore]
 fn bench_context_pool() {
     const ITERATIONS: usize = 1000;
-    let mut pool = ContextPool::new(16);
+    let mut pool = ContextPool::new(16, 16);
 
     let start = Instant::now();
     for i in 0..ITERATIONS {
@@ -0,0 +1,318 @@
+//! Integration tests for the `enviro` command-line interface.
+//!
+//! These invoke the compiled binary and check what a user would see: the
+//! container's output on stdout and its exit code as the process exit code.
+//! `ps` and `stop` are run against an `enviro serve` started for the test.
+
+use nix::errno::Errno;
+use nix::sys::signal::{kill, Signal};
+use nix::unistd::Pid;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::path::Path;
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+mod common;
+use common::Server;
+
+fn enviro(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_enviro"))
+        .args(args)
+        .output()
+        .expect("failed to run the enviro binary")
+}
+
+fn stdout_lines(output: &Output) -> Vec<String> {
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn test_run_echo() {
+    let output = enviro(&["run", "alpine", "--", "echo", "hello", "world"]);
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    // Nothing but the container's output, logs included
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "hello world\n");
+}
+
+#[test]
+fn test_run_spawns_the_command() {
+    let dir = tempfile::tempdir().unwrap();
+    let marker = dir.path().join("ran");
+    let script = format!("echo $$ > {}", marker.display());
+    let output = enviro(&["run", "alpine", "--", "sh", "-c", &script]);
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    // Written by a real process with a PID of its own
+    let pid: u32 = std::fs::read_to_string(&marker).unwrap().trim().parse().unwrap();
+    assert_ne!(pid, 0);
+}
+
+#[test]
+fn test_run_propagates_exit_code() {
+    let output = enviro(&["run", "alpine", "--", "sh", "-c", "exit 7"]);
+    assert_eq!(output.status.code(), Some(7));
+}
+
+#[test]
+fn test_run_signalled_command_exits_128_plus_signal() {
+    let output = enviro(&["run", "alpine", "--", "sh", "-c", "kill -TERM $$"]);
+    assert_eq!(output.status.code(), Some(128 + libc::SIGTERM));
+}
+
+#[test]
+fn test_run_env_and_workdir() {
+    let output = enviro(&[
+        "run", "-e", "GREETING=hi", "-w", "/tmp", "alpine", "--", "sh", "-c", "echo $GREETING $(pwd)",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "hi /tmp\n");
+}
+
+#[test]
+fn test_run_unknown_flag() {
+    let output = enviro(&["run", "--bogus", "alpine", "--", "true"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unrecognized argument '--bogus'"));
+}
+
+#[test]
+fn test_run_missing_command() {
+    let output = enviro(&["run", "alpine"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("requires a command"));
+}
+
+#[test]
+fn test_unknown_argument() {
+    let output = enviro(&["--bogus"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unrecognized argument '--bogus'"));
+}
+
+#[test]
+fn test_stats_json() {
+    let output = enviro(&["stats", "--format", "json"]);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    for key in ["snapshot", "docker_container_start_ms", "speedup"] {
+        assert!(report.get(key).is_some(), "missing '{}' in {}", key, report);
+    }
+    let snapshot = &report["snapshot"];
+    for key in [
+        "container_starts",
+        "avg_container_start_ms",
+        "container_start_latency",
+        "buffer_reuse_rate",
+    ] {
+        assert!(snapshot.get(key).is_some(), "missing '{}' in {}", key, snapshot);
+    }
+    assert_eq!(snapshot["container_starts"], 10);
+}
+
+impl Server {
+    /// Run `enviro <command> --addr <server> [args...]`
+    fn enviro(&self, command: &str, args: &[&str]) -> Output {
+        let mut argv = vec![command, "--addr", &self.addr];
+        argv.extend_from_slice(args);
+        enviro(&argv)
+    }
+
+    /// Send one JSON-RPC request and return its result
+    fn call(&self, method: &str, params: serde_json::Value) -> serde_json::Value {
+        let mut stream = TcpStream::connect(&self.addr).unwrap();
+        let request =
+            serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params});
+        writeln!(stream, "{}", request).unwrap();
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line).unwrap();
+        let mut response: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert!(response["error"].is_null(), "error: {}", response["error"]);
+        response["result"].take()
+    }
+}
+
+#[test]
+fn test_ps_prints_header() {
+    let server = Server::start();
+    let output = server.enviro("ps", &["--all"]);
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(stdout_lines(&output)[0].starts_with("CONTAINER ID"));
+}
+
+#[test]
+fn test_ps_json() {
+    let server = Server::start();
+    let output = server.enviro("ps", &["--format", "json"]);
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "[]\n");
+}
+
+#[test]
+fn test_ps_without_server() {
+    // Nothing listens on port 1
+    let output = enviro(&["ps", "--addr", "127.0.0.1:1"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("No enviro server at 127.0.0.1:1"), "stderr: {}", stderr);
+}
+
+#[test]
+fn test_ps_and_stop_reach_server() {
+    let server = Server::start();
+    let started = server.call(
+        "start",
+        serde_json::json!({
+            "id": "cli-ps-1",
+            "image": "alpine",
+            "command": "sleep",
+            "args": ["30"],
+        }),
+    );
+    assert_eq!(started["id"], "cli-ps-1");
+
+    let output = server.enviro("ps", &[]);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let lines = stdout_lines(&output);
+    assert_eq!(lines.len(), 2, "stdout: {:?}", lines);
+    assert!(lines[1].starts_with("cli-ps-1"), "stdout: {:?}", lines);
+
+    let output = server.enviro("stop", &["cli-ps-1"]);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+
+    // Stopped containers are only listed with --all
+    let output = server.enviro("ps", &["--format", "json"]);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "[]\n");
+    let output = server.enviro("ps", &["--all", "--format", "json"]);
+    let containers: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(containers[0]["id"], "cli-ps-1");
+}
+
+#[test]
+fn test_ps_bad_format() {
+    let output = enviro(&["ps", "--format", "yaml"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown format 'yaml'"));
+}
+
+#[test]
+fn test_version() {
+    let output = enviro(&["-v"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        format!("enviro {}\n", env!("CARGO_PKG_VERSION"))
+    );
+}
+
+#[test]
+fn test_stop_unknown_container() {
+    let server = Server::start();
+    let output = server.enviro("stop", &["no-such-container"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No such container"));
+}
+
+/// Start `enviro run` with a shell script that records its PID in `dir`,
+/// returning once the script is running
+fn spawn_recording_pid(dir: &Path, script: &str) -> (Child, Pid) {
+    let pid_file = dir.join("pid");
+    let script = format!("echo $$ > {}; {}", pid_file.display(), script);
+    let mut child = Command::new(env!("CARGO_BIN_EXE_enviro"))
+        .args(["run", "alpine", "--", "sh", "-c", &script])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run the enviro binary");
+
+    match read_pid(&pid_file, Duration::from_secs(10)) {
+        Some(pid) => (child, pid),
+        None => {
+            child.kill().ok();
+            child.wait().ok();
+            panic!("container never started");
+        }
+    }
+}
+
+/// Poll `path` until it holds a PID
+fn read_pid(path: &Path, timeout: Duration) -> Option<Pid> {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if let Ok(pid) = std::fs::read_to_string(path) {
+            if let Ok(pid) = pid.trim().parse() {
+                return Some(Pid::from_raw(pid));
+            }
+        }
+        sleep(Duration::from_millis(10));
+    }
+    None
+}
+
+fn wait_timeout(child: &mut Child, timeout: Duration) -> Option<ExitStatus> {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if let Some(status) = child.try_wait().unwrap() {
+            return Some(status);
+        }
+        sleep(Duration::from_millis(10));
+    }
+    None
+}
+
+fn assert_exits(pid: Pid) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while kill(pid, None) != Err(Errno::ESRCH) {
+        assert!(Instant::now() < deadline, "container command {} still running", pid);
+        sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn test_run_sigint_stops_container() {
+    let dir = tempfile::tempdir().unwrap();
+    let (mut child, workload) = spawn_recording_pid(dir.path(), "exec sleep 30");
+
+    kill(Pid::from_raw(child.id() as i32), Signal::SIGINT).unwrap();
+
+    let status = wait_timeout(&mut child, Duration::from_secs(5)).expect("enviro did not exit");
+    assert_eq!(status.code(), Some(130));
+    assert_exits(workload);
+    let output = child.wait_with_output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Stopping container"));
+}
+
+#[test]
+fn test_run_second_signal_kills_container() {
+    let dir = tempfile::tempdir().unwrap();
+    // An ignored SIGTERM stays ignored across exec
+    let (mut child, workload) = spawn_recording_pid(dir.path(), "trap '' TERM; exec sleep 30");
+    let enviro = Pid::from_raw(child.id() as i32);
+
+    kill(enviro, Signal::SIGINT).unwrap();
+    // Still inside the grace period
+    assert!(wait_timeout(&mut child, Duration::from_millis(300)).is_none());
+
+    kill(enviro, Signal::SIGTERM).unwrap();
+    let status = wait_timeout(&mut child, Duration::from_secs(5)).expect("enviro did not exit");
+    assert_eq!(status.code(), Some(130));
+    assert_exits(workload);
+    let output = child.wait_with_output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Killing container"));
+}
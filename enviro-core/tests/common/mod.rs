@@ -0,0 +1,49 @@
+//! Fixtures shared by the integration test suites.
+
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+
+/// `enviro serve` started for a test, killed when dropped
+pub struct Server {
+    child: Child,
+    /// Address the server printed it is listening on
+    pub addr: String,
+}
+
+impl Server {
+    /// Start `enviro serve` on a free loopback port
+    pub fn start() -> Self {
+        Self::start_with(&["--addr", "127.0.0.1:0"])
+    }
+
+    /// Start `enviro serve` with `args` and wait until it is listening
+    pub fn start_with(args: &[&str]) -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_enviro"))
+            .arg("serve")
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("failed to run the enviro binary");
+        let stdout = child.stdout.take().unwrap();
+        let addr = BufReader::new(stdout)
+            .lines()
+            .map_while(Result::ok)
+            .find_map(|line| line.strip_prefix("Listening on ").map(str::to_string));
+        match addr {
+            Some(addr) => Self { child, addr },
+            None => {
+                child.kill().ok();
+                child.wait().ok();
+                panic!("server exited before listening");
+            }
+        }
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
@@ -0,0 +1,239 @@
+//! Integration tests for dynamic plugin loading
+//!
+//! These load the sample executor plugin from `tests/sample-plugin`, which
+//! build.rs compiles when the `sample-plugin` feature is enabled:
+//!
+//! ```text
+//! cargo test --features sample-plugin --test plugin_loading
+//! ```
+
+#![cfg(feature = "sample-plugin")]
+
+use enviro_core::executor::ExecutionContext;
+use enviro_core::plugin::{PluginKind, PluginRegistry, CORE_VERSION};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+fn sample_plugin_path() -> PathBuf {
+    PathBuf::from(env!("ENVIRO_SAMPLE_PLUGIN"))
+}
+
+fn test_context() -> ExecutionContext {
+    ExecutionContext::builder("plugin-test")
+        .workdir("/tmp")
+        .memory_bytes(64 * 1024 * 1024)
+        .pid_limit(16)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_load_sample_plugin() {
+    let mut registry = PluginRegistry::new();
+    registry
+        .load_plugin("sample".to_string(), sample_plugin_path())
+        .unwrap();
+
+    let info = registry.get_plugin_info("sample").unwrap();
+    assert_eq!(info.name, "sample");
+    assert_eq!(info.core_version, CORE_VERSION);
+    assert_eq!(registry.list_plugins(), vec!["sample".to_string()]);
+}
+
+#[test]
+fn test_load_sample_plugin_verified() {
+    use sha2::{Digest, Sha256};
+
+    let path = sample_plugin_path();
+    let digest: String = Sha256::digest(std::fs::read(&path).unwrap())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+
+    let mut registry = PluginRegistry::new();
+    registry
+        .load_plugin_verified("sample".to_string(), path, &digest)
+        .unwrap();
+
+    let info = registry.get_plugin_info("sample").unwrap();
+    assert_eq!(info.kind, PluginKind::Native);
+    assert_eq!(info.sha256.as_deref(), Some(digest.as_str()));
+
+    // The library was loaded from the verified copy and is fully usable
+    let executor = registry.instantiate("sample").unwrap();
+    assert_eq!(executor.executor_type(), "sample");
+}
+
+#[tokio::test]
+async fn test_instantiate_and_execute() {
+    let mut registry = PluginRegistry::new();
+    registry
+        .load_plugin("sample".to_string(), sample_plugin_path())
+        .unwrap();
+
+    let executor = registry.instantiate("sample").unwrap();
+    assert_eq!(executor.executor_type(), "sample");
+
+    let result = executor
+        .execute(&test_context(), "echo", &["hello".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(result.exit_code, 0);
+    assert_eq!(result.stdout, "echo hello");
+}
+
+#[tokio::test]
+async fn test_executor_outlives_unload() {
+    let mut registry = PluginRegistry::new();
+    registry
+        .load_plugin("sample".to_string(), sample_plugin_path())
+        .unwrap();
+
+    let executor = registry.instantiate("sample").unwrap();
+    registry.unload_plugin("sample").unwrap();
+    assert!(registry.list_plugins().is_empty());
+
+    // The executor keeps the library mapped after the registry lets go
+    let result = executor
+        .execute(&test_context(), "still", &["loaded".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(result.stdout, "still loaded");
+    drop(executor);
+}
+
+#[test]
+fn test_abi_mismatch_rejected() {
+    let mut registry = PluginRegistry::new();
+    let err = registry
+        .load_plugin(
+            "bad-abi".to_string(),
+            PathBuf::from(env!("ENVIRO_SAMPLE_PLUGIN_BAD_ABI")),
+        )
+        .unwrap_err();
+
+    assert!(err.to_string().contains("ABI version"));
+    assert!(registry.list_plugins().is_empty());
+    assert!(registry.get_plugin_info("bad-abi").is_none());
+}
+
+#[test]
+fn test_discover_many_plugins() {
+    const PLUGIN_COUNT: usize = 64;
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut expected = Vec::new();
+    for i in 0..PLUGIN_COUNT {
+        let name = format!("sample{:02}", i);
+        std::fs::copy(sample_plugin_path(), dir.path().join(format!("{}.so", name))).unwrap();
+        expected.push(name);
+    }
+
+    let mut registry = PluginRegistry::new();
+    registry.add_search_path(dir.path().to_path_buf());
+
+    let mut discovered = registry.discover_plugins().unwrap();
+    discovered.sort();
+    assert_eq!(discovered, expected);
+
+    let mut listed = registry.list_plugins();
+    listed.sort();
+    assert_eq!(listed, expected);
+}
+
+#[tokio::test]
+async fn test_reload_drains_outstanding_executor() {
+    let mut registry = PluginRegistry::new();
+    registry
+        .load_plugin("sample".to_string(), sample_plugin_path())
+        .unwrap();
+
+    let executor = registry.instantiate("sample").unwrap();
+    assert_eq!(registry.executor_count("sample"), Some(1));
+
+    // The replacement is swapped in without waiting for the old executor
+    let drain = registry
+        .reload_plugin("sample".to_string(), sample_plugin_path())
+        .unwrap();
+    assert_eq!(drain.outstanding(), 1);
+    assert_eq!(registry.executor_count("sample"), Some(0));
+
+    let reloaded = registry.instantiate("sample").unwrap();
+    let result = reloaded
+        .execute(&test_context(), "reloaded", &[])
+        .await
+        .unwrap();
+    assert_eq!(result.stdout, "reloaded");
+
+    let holder = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let result = executor
+            .execute(&test_context(), "still", &["here".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(result.stdout, "still here");
+    });
+
+    let start = Instant::now();
+    drain.wait(Duration::from_secs(10)).await.unwrap();
+    holder.await.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    assert_eq!(drain.outstanding(), 0);
+}
+
+#[tokio::test]
+async fn test_reload_drain_timeout_keeps_old_executor() {
+    let mut registry = PluginRegistry::new();
+    registry
+        .load_plugin("sample".to_string(), sample_plugin_path())
+        .unwrap();
+
+    let executor = registry.instantiate("sample").unwrap();
+    let drain = registry
+        .reload_plugin("sample".to_string(), sample_plugin_path())
+        .unwrap();
+    let err = drain.wait(Duration::from_millis(50)).await.unwrap_err();
+    assert!(err.to_string().contains("Timed out"));
+
+    // The outstanding executor is untouched
+    let result = executor
+        .execute(&test_context(), "still", &["here".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(result.stdout, "still here");
+}
+
+#[test]
+fn test_failed_reload_keeps_old_plugin() {
+    let dir = tempfile::tempdir().unwrap();
+    let broken = dir.path().join("broken.so");
+    std::fs::write(&broken, b"not an elf").unwrap();
+
+    let mut registry = PluginRegistry::new();
+    registry
+        .load_plugin("sample".to_string(), sample_plugin_path())
+        .unwrap();
+
+    assert!(registry.reload_plugin("sample".to_string(), broken).is_err());
+    assert_eq!(registry.list_plugins(), vec!["sample".to_string()]);
+    assert_eq!(registry.instantiate("sample").unwrap().executor_type(), "sample");
+}
+
+#[test]
+fn test_discover_native_and_wasm() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::copy(sample_plugin_path(), dir.path().join("native.so")).unwrap();
+    std::fs::write(dir.path().join("module.wasm"), b"\0asm\x01\0\0\0").unwrap();
+
+    let mut registry = PluginRegistry::new();
+    registry.add_search_path(dir.path().to_path_buf());
+
+    let mut discovered = registry.discover_plugins().unwrap();
+    discovered.sort();
+    assert_eq!(discovered, vec!["module".to_string(), "native".to_string()]);
+
+    assert_eq!(registry.get_plugin_info("native").unwrap().kind, PluginKind::Native);
+    assert_eq!(registry.get_plugin_info("module").unwrap().kind, PluginKind::Wasm);
+    assert_eq!(registry.instantiate("native").unwrap().executor_type(), "sample");
+    assert_eq!(registry.instantiate("module").unwrap().executor_type(), "wasm");
+}
@@ -0,0 +1,79 @@
+//! Sample Executor Plugin
+//!
+//! A minimal plugin exporting `plugin_abi_version`, `get_plugin_info` and
+//! `init_plugin`, used by the enviro-core integration tests to exercise
+//! `PluginRegistry`. `execute` echoes the command line back on stdout without
+//! spawning anything. With the `bad-abi` feature the plugin reports an ABI
+//! version the host rejects.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use enviro_core::executor::{ExecutionContext, ExecutionResult, Executor, Termination};
+use enviro_core::plugin::{PluginInfo, PluginKind, CORE_VERSION, PLUGIN_ABI_VERSION};
+
+struct SampleExecutor;
+
+#[async_trait]
+impl Executor for SampleExecutor {
+    async fn prepare(&mut self, _ctx: &ExecutionContext) -> Result<()> {
+        Ok(())
+    }
+
+    async fn execute(
+        &self,
+        _ctx: &ExecutionContext,
+        command: &str,
+        args: &[String],
+    ) -> Result<ExecutionResult> {
+        let mut stdout = command.to_string();
+        for arg in args {
+            stdout.push(' ');
+            stdout.push_str(arg);
+        }
+
+        Ok(ExecutionResult {
+            exit_code: 0,
+            stdout,
+            stderr: String::new(),
+            duration_ms: 0,
+            termination: Termination::Exited(0),
+        })
+    }
+
+    async fn cleanup(&mut self, _ctx: &ExecutionContext) -> Result<()> {
+        Ok(())
+    }
+
+    fn executor_type(&self) -> &str {
+        "sample"
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn plugin_abi_version() -> u32 {
+    if cfg!(feature = "bad-abi") {
+        PLUGIN_ABI_VERSION + 1
+    } else {
+        PLUGIN_ABI_VERSION
+    }
+}
+
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub extern "C" fn get_plugin_info() -> PluginInfo {
+    PluginInfo {
+        name: "sample".to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        author: "Enviro Contributors".to_string(),
+        description: "Echo executor used by the plugin tests".to_string(),
+        core_version: CORE_VERSION.to_string(),
+        kind: PluginKind::Native,
+        sha256: None,
+    }
+}
+
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub extern "C" fn init_plugin() -> *mut dyn Executor {
+    Box::into_raw(Box::new(SampleExecutor))
+}
@@ -0,0 +1,85 @@
+//! Integration tests for `enviro serve`.
+//!
+//! These start the compiled binary on an ephemeral port and talk to it the
+//! way a client would: one JSON-RPC request per line over TCP.
+
+use enviro_core::server::{error_code, AuthRequest, HandleInfo, Request, Response, StartRequest};
+use enviro_core::{ContainerInfo, ContainerSpec};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::process::Command;
+
+mod common;
+use common::Server;
+
+fn call(stream: &mut TcpStream, request: &Request) -> Response {
+    let mut line = serde_json::to_string(request).unwrap();
+    line.push('\n');
+    stream.write_all(line.as_bytes()).unwrap();
+
+    let mut response = String::new();
+    BufReader::new(stream.try_clone().unwrap())
+        .read_line(&mut response)
+        .unwrap();
+    serde_json::from_str(&response).unwrap()
+}
+
+#[test]
+fn test_serve_start_returns_handle() {
+    let server = Server::start();
+    let mut stream = TcpStream::connect(&server.addr).unwrap();
+
+    let mut spec = ContainerSpec::new("alpine", "sleep", vec!["5".to_string()]);
+    spec.id = "served-1".to_string();
+    let id = spec.id.clone();
+    let response = call(
+        &mut stream,
+        &Request::new(7, "start", StartRequest::from(spec)).unwrap(),
+    );
+
+    assert_eq!(response.id, 7);
+    assert!(response.error.is_none(), "error: {:?}", response.error);
+    let handle: HandleInfo = serde_json::from_value(response.result.unwrap()).unwrap();
+    assert_eq!(handle.id, id);
+    assert_eq!(handle.restarts, 0);
+
+    // The started container is in the server's registry
+    let response = call(&mut stream, &Request::new(8, "list", ()).unwrap());
+    let containers: Vec<ContainerInfo> = serde_json::from_value(response.result.unwrap()).unwrap();
+    assert_eq!(containers.len(), 1);
+    assert_eq!(containers[0].id, id);
+    assert!(containers[0].state.is_running());
+}
+
+#[test]
+fn test_serve_refuses_public_address_without_token() {
+    let output = Command::new(env!("CARGO_BIN_EXE_enviro"))
+        .args(["serve", "--addr", "0.0.0.0:0"])
+        .output()
+        .expect("failed to run the enviro binary");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("non-loopback"), "stderr: {}", stderr);
+}
+
+#[test]
+fn test_serve_with_token_requires_auth() {
+    let dir = tempfile::tempdir().unwrap();
+    let token_file = dir.path().join("token");
+    std::fs::write(&token_file, "s3cret\n").unwrap();
+    let server =
+        Server::start_with(&["--addr", "127.0.0.1:0", "--token-file", token_file.to_str().unwrap()]);
+    let mut stream = TcpStream::connect(&server.addr).unwrap();
+
+    let response = call(&mut stream, &Request::new(1, "list", ()).unwrap());
+    assert_eq!(response.error.unwrap().code, error_code::UNAUTHORIZED);
+
+    let auth = AuthRequest {
+        token: "s3cret".to_string(),
+    };
+    let response = call(&mut stream, &Request::new(2, "auth", auth).unwrap());
+    assert!(response.error.is_none(), "error: {:?}", response.error);
+    let response = call(&mut stream, &Request::new(3, "list", ()).unwrap());
+    assert!(response.error.is_none(), "error: {:?}", response.error);
+}